@@ -1,15 +1,36 @@
 mod message_sink;
+mod object_search;
+mod params;
+mod read_only;
+mod row_sink;
+mod row_source;
+mod statement_analysis;
+mod structure_changes;
 mod traits;
+mod value_search;
+mod where_expr;
 pub mod mongo;
+pub mod mysql;
 pub mod postgres;
 pub mod redisdb;
+pub mod sqlite;
+pub mod tls;
 
 pub use message_sink::*;
+pub use object_search::*;
+pub use params::*;
+pub use read_only::*;
+pub use row_sink::*;
+pub use row_source::*;
+pub use statement_analysis::*;
+pub use structure_changes::*;
 pub use traits::*;
+pub use value_search::*;
+pub use where_expr::*;
 
 use std::sync::Arc;
 
-use crate::error::{Error, Result};
+use crate::error::Result;
 use crate::models::{DatabaseType, Server};
 
 /// Create an adapter for the given server and database
@@ -31,10 +52,12 @@ pub async fn create_adapter(
             Ok(Arc::new(adapter))
         }
         DatabaseType::Mysql => {
-            Err(Error::UnsupportedDatabase("MySQL support coming soon".into()))
+            let adapter = mysql::MysqlAdapter::new(server, database)?;
+            Ok(Arc::new(adapter))
         }
         DatabaseType::Sqlite => {
-            Err(Error::UnsupportedDatabase("SQLite support coming soon".into()))
+            let adapter = sqlite::SqliteAdapter::new(server, database)?;
+            Ok(Arc::new(adapter))
         }
     }
 }
\ No newline at end of file
@@ -3,25 +3,18 @@ use std::time::Instant;
 use mongodb::bson::{doc, Document};
 use mongodb::Database;
 
+use crate::adapters::{RowSink, STREAM_BATCH_ROWS};
 use crate::error::{Error, Result};
 use crate::models::{QueryResult, SortDirection, TableDataRequest};
 
-use super::executor::{collect_cursor, documents_to_table, editable_for};
+use super::executor::{collect_cursor, documents_to_table, editable_for, stream_cursor};
 
 pub async fn fetch_table_data(
     db: &Database,
     database_name: &str,
     request: TableDataRequest,
 ) -> Result<QueryResult> {
-    if request
-        .where_expr
-        .as_deref()
-        .is_some_and(|s| !s.trim().is_empty())
-    {
-        return Err(Error::InvalidQuery(
-            "Raw WHERE is only supported for PostgreSQL".into(),
-        ));
-    }
+    reject_where_expr(&request)?;
 
     let coll = db.collection::<Document>(&request.table);
 
@@ -68,6 +61,39 @@ pub async fn fetch_table_data(
     })
 }
 
+/// Every document of the collection, in browse order, through the cursor.
+pub async fn stream_table_data(
+    db: &Database,
+    request: TableDataRequest,
+    sink: &mut dyn RowSink,
+) -> Result<u64> {
+    reject_where_expr(&request)?;
+
+    let coll = db.collection::<Document>(&request.table);
+    let mut find = coll
+        .find(Document::new())
+        .batch_size(STREAM_BATCH_ROWS as u32);
+    let sort = build_sort(&request);
+    if !sort.is_empty() {
+        find = find.sort(sort);
+    }
+
+    stream_cursor(find.await?, sink).await
+}
+
+fn reject_where_expr(request: &TableDataRequest) -> Result<()> {
+    if request
+        .where_expr
+        .as_deref()
+        .is_some_and(|s| !s.trim().is_empty())
+    {
+        return Err(Error::InvalidQuery(
+            "Raw WHERE is only supported for PostgreSQL".into(),
+        ));
+    }
+    Ok(())
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 // Sort translation (pure, unit-testable)
 // ─────────────────────────────────────────────────────────────────────────────
@@ -138,8 +164,11 @@ mod tests {
             username: String::new(),
             password: String::new(),
             default_database: None,
-            ssl_enabled: false,
+            tls: Default::default(),
             connection_uri: None,
+            ssh_tunnel: None,
+            read_only: false,
+            confirm_writes: false,
             created_at: 0,
         };
 
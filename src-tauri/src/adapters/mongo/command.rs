@@ -1,6 +1,7 @@
 use mongodb::bson::{Bson, Document};
 
 use crate::error::{Error, Result};
+use crate::models::{StatementWarning, StructureChange, WarningKind, WarningSeverity};
 
 /// A parsed MongoDB shell-style command (`db.<collection>.<method>(...)`).
 #[derive(Debug, Clone, PartialEq)]
@@ -60,15 +61,101 @@ pub enum MongoCommand {
 }
 
 impl MongoCommand {
+    /// Pipelines ending in `$out`/`$merge` write their result to a
+    /// collection, so they count as writes too.
     pub fn is_write(&self) -> bool {
-        !matches!(
-            self,
+        match self {
+            Self::Aggregate { pipeline, .. } => has_output_stage(pipeline),
             Self::Find { .. }
-                | Self::FindOne { .. }
-                | Self::Aggregate { .. }
-                | Self::CountDocuments { .. }
-                | Self::Distinct { .. }
-        )
+            | Self::FindOne { .. }
+            | Self::CountDocuments { .. }
+            | Self::Distinct { .. } => false,
+            _ => true,
+        }
+    }
+
+    pub fn collection(&self) -> &str {
+        match self {
+            Self::Find { collection, .. }
+            | Self::FindOne { collection, .. }
+            | Self::Aggregate { collection, .. }
+            | Self::CountDocuments { collection, .. }
+            | Self::Distinct { collection, .. }
+            | Self::InsertOne { collection, .. }
+            | Self::InsertMany { collection, .. }
+            | Self::UpdateOne { collection, .. }
+            | Self::UpdateMany { collection, .. }
+            | Self::DeleteOne { collection, .. }
+            | Self::DeleteMany { collection, .. }
+            | Self::Drop { collection } => collection,
+        }
+    }
+
+    /// The Mongo side of the dangerous statement check: bulk writes with an
+    /// empty filter hit the whole collection, like SQL without WHERE.
+    pub fn warning(&self) -> Option<StatementWarning> {
+        let (kind, collection, message) = match self {
+            Self::UpdateMany { collection, filter, .. } if filter.is_empty() => (
+                WarningKind::UpdateWithoutWhere,
+                collection,
+                format!("updateMany with an empty filter changes every document of {collection}"),
+            ),
+            Self::DeleteMany { collection, filter } if filter.is_empty() => (
+                WarningKind::DeleteWithoutWhere,
+                collection,
+                format!("deleteMany with an empty filter removes every document of {collection}"),
+            ),
+            Self::Drop { collection } => (
+                WarningKind::Drop,
+                collection,
+                format!("drop removes the collection {collection} and its indexes"),
+            ),
+            _ => return None,
+        };
+        Some(StatementWarning {
+            kind,
+            severity: WarningSeverity::Danger,
+            statement_index: 0,
+            object: Some(collection.clone()),
+            message,
+        })
+    }
+
+    /// Columns of a collection are sampled from its documents, so any write
+    /// may change them (and the document count), not just `drop`. For
+    /// `$out`/`$merge` that's the collection written to, when it is in the
+    /// same database.
+    pub fn structure_change(&self) -> Option<StructureChange> {
+        let table = match self {
+            Self::Aggregate { pipeline, .. } => output_collection(pipeline)?,
+            _ if self.is_write() => self.collection(),
+            _ => return None,
+        };
+        Some(StructureChange::Table {
+            schema: None,
+            table: table.to_string(),
+        })
+    }
+}
+
+fn has_output_stage(pipeline: &[Document]) -> bool {
+    pipeline
+        .iter()
+        .any(|stage| stage.contains_key("$out") || stage.contains_key("$merge"))
+}
+
+/// Target of a `$out` (`"coll"` or `{ db, coll }`) or `$merge` (`"coll"` or
+/// `{ into: "coll" | { db, coll } }`) stage. `None` for another database.
+fn output_collection(pipeline: &[Document]) -> Option<&str> {
+    let target = pipeline.iter().find_map(|stage| match stage.get("$merge") {
+        Some(Bson::Document(merge)) => merge.get("into"),
+        Some(into) => Some(into),
+        None => stage.get("$out"),
+    })?;
+    match target {
+        Bson::String(collection) => Some(collection),
+        Bson::Document(target) if !target.contains_key("db") => target.get_str("coll").ok(),
+        _ => None,
     }
 }
 
@@ -407,4 +494,40 @@ mod tests {
         assert!(parse_command("db.u.deleteMany({})").unwrap().is_write());
         assert!(!parse_command("db.u.find({})").unwrap().is_write());
     }
+
+    #[test]
+    fn aggregate_with_output_stage_is_a_write() {
+        let read = parse_command("db.u.aggregate([{ $match: { a: 1 } }])").unwrap();
+        assert!(!read.is_write());
+        assert_eq!(read.structure_change(), None);
+
+        let table = |cmd: &str| {
+            let command = parse_command(cmd).unwrap();
+            assert!(command.is_write(), "{cmd}");
+            match command.structure_change() {
+                Some(StructureChange::Table { table, .. }) => Some(table),
+                _ => None,
+            }
+        };
+        assert_eq!(table("db.u.aggregate([{ $out: 'y' }])").as_deref(), Some("y"));
+        assert_eq!(
+            table("db.u.aggregate([{ $match: {} }, { $merge: { into: 'y', on: '_id' } }])")
+                .as_deref(),
+            Some("y")
+        );
+        assert_eq!(table("db.u.aggregate([{ $merge: 'y' }])").as_deref(), Some("y"));
+        assert_eq!(table("db.u.aggregate([{ $out: { db: 'other', coll: 'y' } }])"), None);
+    }
+
+    #[test]
+    fn empty_filter_bulk_writes_warn() {
+        let warning = |cmd: &str| parse_command(cmd).unwrap().warning().map(|w| w.kind);
+        assert_eq!(warning("db.u.deleteMany({})"), Some(WarningKind::DeleteWithoutWhere));
+        assert_eq!(
+            warning("db.u.updateMany({}, {$set: {a: 1}})"),
+            Some(WarningKind::UpdateWithoutWhere)
+        );
+        assert_eq!(warning("db.u.drop()"), Some(WarningKind::Drop));
+        assert_eq!(warning("db.u.deleteMany({a: 1})"), None);
+    }
 }
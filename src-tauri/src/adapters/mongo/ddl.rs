@@ -0,0 +1,156 @@
+use mongodb::bson::{doc, Bson, Document};
+use mongodb::Database;
+
+use crate::error::{Error, Result};
+use crate::models::{DdlObjectKind, DdlRequest};
+
+/// Index options that `createIndex` derives itself (or takes as its first
+/// argument) and that mongosh rejects in the options document.
+const INDEX_RESERVED_OPTIONS: [&str; 3] = ["v", "key", "ns"];
+
+/// mongosh script that recreates a collection (options and validator
+/// included) and its indexes, or a view. The app's own command parser
+/// doesn't run `createCollection`/`createIndex`: the script is for mongosh.
+pub async fn get_object_ddl(db: &Database, request: &DdlRequest) -> Result<String> {
+    if !matches!(
+        request.kind,
+        DdlObjectKind::Collection | DdlObjectKind::Table | DdlObjectKind::View
+    ) {
+        return Err(Error::UnsupportedType(
+            "MongoDB only scripts collections and views".into(),
+        ));
+    }
+    let name = request.name.as_str();
+
+    let reply = db
+        .run_command(doc! { "listCollections": 1, "filter": { "name": name } })
+        .await?;
+    let spec = first_batch(&reply)
+        .into_iter()
+        .next()
+        .ok_or_else(|| Error::NotFound(format!("Collection {name}")))?;
+
+    let indexes = if spec.get_str("type") == Ok("view") {
+        Vec::new()
+    } else {
+        let reply = db.run_command(doc! { "listIndexes": name }).await?;
+        first_batch(&reply)
+    };
+    Ok(render_script(name, &spec, &indexes))
+}
+
+fn first_batch(reply: &Document) -> Vec<Document> {
+    reply
+        .get_document("cursor")
+        .and_then(|cursor| cursor.get_array("firstBatch"))
+        .map(|batch| {
+            batch
+                .iter()
+                .filter_map(|item| item.as_document().cloned())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Script for a `listCollections` entry and its `listIndexes` output.
+fn render_script(name: &str, spec: &Document, indexes: &[Document]) -> String {
+    let mut options = spec.get_document("options").cloned().unwrap_or_default();
+    let mut statements = Vec::new();
+
+    if spec.get_str("type") == Ok("view") {
+        let source = options.remove("viewOn").unwrap_or(Bson::Null);
+        let pipeline = options
+            .remove("pipeline")
+            .unwrap_or(Bson::Array(Vec::new()));
+        let mut create = format!(
+            "db.createView({}, {}, {}",
+            quoted(name),
+            json(&source),
+            json(&pipeline)
+        );
+        if !options.is_empty() {
+            create.push_str(&format!(", {}", json(&Bson::Document(options))));
+        }
+        create.push_str(");");
+        statements.push(create);
+    } else if options.is_empty() {
+        statements.push(format!("db.createCollection({});", quoted(name)));
+    } else {
+        statements.push(format!(
+            "db.createCollection({}, {});",
+            quoted(name),
+            json(&Bson::Document(options))
+        ));
+    }
+
+    for index in indexes {
+        if index.get_str("name") == Ok("_id_") {
+            continue;
+        }
+        let key = index
+            .get("key")
+            .cloned()
+            .unwrap_or(Bson::Document(Document::new()));
+        let mut options = index.clone();
+        for reserved in INDEX_RESERVED_OPTIONS {
+            options.remove(reserved);
+        }
+        statements.push(format!(
+            "db.getCollection({}).createIndex({}, {});",
+            quoted(name),
+            json(&key),
+            json(&Bson::Document(options))
+        ));
+    }
+
+    statements.join("\n\n") + "\n"
+}
+
+fn json(value: &Bson) -> String {
+    serde_json::to_string_pretty(&value.clone().into_relaxed_extjson()).unwrap_or_default()
+}
+
+fn quoted(name: &str) -> String {
+    serde_json::to_string(name).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collection_script_has_validator_and_secondary_indexes() {
+        let spec = doc! {
+            "name": "users",
+            "type": "collection",
+            "options": {
+                "validator": { "$jsonSchema": { "required": ["email"] } },
+                "validationLevel": "strict",
+            },
+        };
+        let indexes = [
+            doc! { "v": 2, "key": { "_id": 1 }, "name": "_id_" },
+            doc! { "v": 2, "key": { "email": 1 }, "name": "email_1", "unique": true },
+        ];
+
+        let script = render_script("users", &spec, &indexes);
+        assert!(script.starts_with("db.createCollection(\"users\", {"));
+        assert!(script.contains("\"$jsonSchema\""));
+        assert!(!script.contains("_id_"));
+        assert!(script.contains("db.getCollection(\"users\").createIndex({\n  \"email\": 1\n}"));
+        assert!(script.contains("\"unique\": true"));
+        assert!(!script.contains("\"v\""));
+    }
+
+    #[test]
+    fn view_script_uses_create_view() {
+        let spec = doc! {
+            "name": "active",
+            "type": "view",
+            "options": { "viewOn": "users", "pipeline": [{ "$match": { "active": true } }] },
+        };
+        let script = render_script("active", &spec, &[]);
+        assert!(script.starts_with("db.createView(\"active\", \"users\", ["));
+        assert!(script.trim_end().ends_with("]);"));
+    }
+}
@@ -3,6 +3,7 @@ use std::time::Instant;
 use mongodb::bson::{doc, Bson, Document};
 use mongodb::{Cursor, Database};
 
+use crate::adapters::{bind_mongo, RowSink, STREAM_BATCH_ROWS};
 use crate::error::{Error, Result};
 use crate::models::{
     EditableInfo, QueryColumnInfo, QueryOptions, QueryResult, RowEdit, RowInsert, StatementResult,
@@ -17,7 +18,11 @@ pub async fn execute_query(
     query: &str,
     options: QueryOptions,
 ) -> Result<QueryResult> {
-    let command = parse_command(query)?;
+    let command = if options.params.is_empty() {
+        parse_command(query)?
+    } else {
+        parse_command(&bind_mongo(query, &options.params)?)?
+    };
     let start = Instant::now();
 
     let result = match command {
@@ -191,6 +196,52 @@ pub async fn execute_query(
     })
 }
 
+/// Stream a find/aggregate through its cursor. Other commands have a bounded
+/// reply and go through [`execute_query`] as a single batch.
+pub async fn stream_query(
+    db: &Database,
+    database_name: &str,
+    query: &str,
+    sink: &mut dyn RowSink,
+) -> Result<u64> {
+    let cursor = match parse_command(query)? {
+        MongoCommand::Find {
+            collection,
+            filter,
+            projection,
+        } => {
+            let coll = db.collection::<Document>(&collection);
+            let mut find = coll.find(filter);
+            if let Some(p) = projection {
+                find = find.projection(p);
+            }
+            find.batch_size(STREAM_BATCH_ROWS as u32).await?
+        }
+        MongoCommand::Aggregate {
+            collection,
+            pipeline,
+        } => {
+            let coll = db.collection::<Document>(&collection);
+            coll.aggregate(pipeline)
+                .batch_size(STREAM_BATCH_ROWS as u32)
+                .await?
+        }
+        _ => {
+            let options = QueryOptions {
+                unlimited: true,
+                ..Default::default()
+            };
+            let result = execute_query(db, database_name, query, options).await?;
+            let sent = result.rows.len() as u64;
+            sink.columns(&result.columns).await?;
+            sink.rows(result.rows).await?;
+            return Ok(sent);
+        }
+    };
+
+    stream_cursor(cursor, sink).await
+}
+
 pub async fn execute_statement(db: &Database, statement: &str) -> Result<StatementResult> {
     let command = parse_command(statement)?;
 
@@ -420,6 +471,54 @@ pub async fn collect_cursor(
     Ok(docs)
 }
 
+/// Drain a cursor into `sink` in batches of [`STREAM_BATCH_ROWS`].
+///
+/// Documents have no fixed shape, so the columns are those of the first
+/// batch (as in [`documents_to_table`]); keys that only show up in later
+/// documents are not exported.
+pub async fn stream_cursor(mut cursor: Cursor<Document>, sink: &mut dyn RowSink) -> Result<u64> {
+    let mut columns: Option<Vec<QueryColumnInfo>> = None;
+    let mut batch: Vec<Document> = Vec::with_capacity(STREAM_BATCH_ROWS);
+    let mut sent: u64 = 0;
+
+    loop {
+        let more = cursor.advance().await?;
+        if more {
+            batch.push(cursor.deserialize_current()?);
+            if batch.len() < STREAM_BATCH_ROWS {
+                continue;
+            }
+        }
+
+        let rows = match &columns {
+            Some(columns) => batch
+                .iter()
+                .map(|doc| {
+                    columns
+                        .iter()
+                        .map(|col| doc.get(&col.name).and_then(bson_to_string))
+                        .collect()
+                })
+                .collect(),
+            None => {
+                let (first_columns, rows) = documents_to_table(&batch);
+                sink.columns(&first_columns).await?;
+                columns = Some(first_columns);
+                rows
+            }
+        };
+        batch.clear();
+
+        if !rows.is_empty() {
+            sent += rows.len() as u64;
+            sink.rows(rows).await?;
+        }
+        if !more {
+            return Ok(sent);
+        }
+    }
+}
+
 fn single_value_result(name: &str, type_name: &str, value: Option<String>) -> QueryResult {
     QueryResult {
         columns: vec![QueryColumnInfo {
@@ -0,0 +1,265 @@
+use mongodb::bson::{doc, Array, Bson, Document};
+use mongodb::Database;
+
+use crate::error::{Error, Result};
+use crate::models::{ExplainOptions, ExplainPlan, PlanCondition, PlanNode};
+
+use super::command::{parse_command, MongoCommand};
+
+/// Stage fields shown as conditions, as relaxed extended JSON.
+const CONDITION_KEYS: &[&str] = &["filter", "indexBounds", "sortPattern", "transformBy"];
+
+/// Where the children of a stage live: classic stages use `inputStage(s)`,
+/// slot-based (SBE) execution stages name their branches.
+const CHILD_KEYS: &[&str] = &[
+    "inputStage",
+    "outerStage",
+    "innerStage",
+    "thenStage",
+    "elseStage",
+];
+
+/// Run the `explain` command for a find/findOne/aggregate from the editor.
+/// `analyze` asks for `executionStats`, which runs the query (reads only:
+/// writes can't be explained here, so nothing needs undoing).
+pub async fn explain_query(
+    db: &Database,
+    query: &str,
+    options: ExplainOptions,
+) -> Result<ExplainPlan> {
+    let explained = match parse_command(query)? {
+        MongoCommand::Find {
+            collection,
+            filter,
+            projection,
+        } => find_command(collection, filter, projection, None),
+        MongoCommand::FindOne {
+            collection,
+            filter,
+            projection,
+        } => find_command(collection, filter, projection, Some(1)),
+        MongoCommand::Aggregate {
+            collection,
+            pipeline,
+        } => doc! { "aggregate": collection, "pipeline": pipeline, "cursor": {} },
+        _ => {
+            return Err(Error::InvalidQuery(
+                "Only find, findOne and aggregate can be explained".into(),
+            ))
+        }
+    };
+
+    let verbosity = if options.analyze {
+        "executionStats"
+    } else {
+        "queryPlanner"
+    };
+    let reply = db
+        .run_command(doc! { "explain": explained, "verbosity": verbosity })
+        .await?;
+    parse_explain(reply)
+}
+
+fn find_command(
+    collection: String,
+    filter: Document,
+    projection: Option<Document>,
+    limit: Option<i64>,
+) -> Document {
+    let mut find = doc! { "find": collection, "filter": filter };
+    if let Some(projection) = projection {
+        find.insert("projection", projection);
+    }
+    if let Some(limit) = limit {
+        find.insert("limit", limit);
+    }
+    find
+}
+
+/// A pushed-down query has `queryPlanner` at the top; an aggregation that
+/// runs stages of its own lists them in `stages` instead.
+fn parse_explain(reply: Document) -> Result<ExplainPlan> {
+    let raw = serde_json::to_string_pretty(&Bson::Document(reply.clone()).into_relaxed_extjson())
+        .unwrap_or_default();
+
+    let (root, execution_time_ms) = if reply.contains_key("queryPlanner") {
+        cursor_plan(&reply)?
+    } else if let Ok(stages) = reply.get_array("stages") {
+        pipeline_plan(stages)?
+    } else {
+        return Err(Error::Query("Unexpected explain output: no plan".into()));
+    };
+
+    Ok(ExplainPlan {
+        root,
+        planning_time_ms: None,
+        execution_time_ms,
+        raw,
+    })
+}
+
+/// A find (or the `$cursor` stage of an aggregation): the execution stages
+/// when the query ran, the winning plan otherwise.
+fn cursor_plan(explain: &Document) -> Result<(PlanNode, Option<f64>)> {
+    let planner = explain
+        .get_document("queryPlanner")
+        .map_err(|_| Error::Query("Unexpected explain output: no queryPlanner".into()))?;
+    let stats = explain.get_document("executionStats").ok();
+
+    let tree = match stats.and_then(|s| s.get_document("executionStages").ok()) {
+        Some(stages) => stages,
+        None => {
+            let winning = planner
+                .get_document("winningPlan")
+                .map_err(|_| Error::Query("Unexpected explain output: no winningPlan".into()))?;
+            // SBE (6.0+) nests the classic tree under `queryPlan`
+            winning.get_document("queryPlan").unwrap_or(winning)
+        }
+    };
+
+    let mut root = stage_node(tree);
+    root.relation = planner
+        .get_str("namespace")
+        .ok()
+        .map(|ns| ns.split_once('.').map_or(ns, |(_, coll)| coll).to_string());
+    Ok((root, stats.and_then(|s| number(s, "executionTimeMillis"))))
+}
+
+/// Pipeline stages in order; each one becomes the parent of the previous,
+/// so the last stage is the root, like a Postgres plan.
+fn pipeline_plan(stages: &Array) -> Result<(PlanNode, Option<f64>)> {
+    let mut root: Option<PlanNode> = None;
+    let mut cursor_time = None;
+
+    for stage in stages.iter().filter_map(Bson::as_document) {
+        let Some((name, spec)) = stage.iter().find(|(key, _)| key.starts_with('$')) else {
+            continue;
+        };
+
+        let mut node = match spec {
+            Bson::Document(cursor) if name == "$cursor" => {
+                let (node, time) = cursor_plan(cursor)?;
+                cursor_time = time;
+                node
+            }
+            _ => PlanNode {
+                node_type: name.clone(),
+                conditions: vec![condition(name, spec)],
+                actual_rows: number(stage, "nReturned"),
+                actual_total_time_ms: number(stage, "executionTimeMillisEstimate"),
+                ..Default::default()
+            },
+        };
+        node.children.extend(root.take());
+        root = Some(node);
+    }
+
+    let root = root.ok_or_else(|| Error::Query("Unexpected explain output: no stages".into()))?;
+    let time = root.actual_total_time_ms.or(cursor_time);
+    Ok((root, time))
+}
+
+fn stage_node(stage: &Document) -> PlanNode {
+    let mut children: Vec<PlanNode> = CHILD_KEYS
+        .iter()
+        .filter_map(|key| stage.get_document(key).ok())
+        .map(stage_node)
+        .collect();
+    if let Ok(inputs) = stage.get_array("inputStages") {
+        children.extend(inputs.iter().filter_map(Bson::as_document).map(stage_node));
+    }
+
+    PlanNode {
+        node_type: stage.get_str("stage").unwrap_or("UNKNOWN").to_string(),
+        index_name: stage.get_str("indexName").ok().map(str::to_string),
+        conditions: CONDITION_KEYS
+            .iter()
+            .filter_map(|key| stage.get(key).map(|value| condition(key, value)))
+            .collect(),
+        actual_rows: number(stage, "nReturned"),
+        actual_total_time_ms: number(stage, "executionTimeMillisEstimate"),
+        keys_examined: number(stage, "keysExamined"),
+        docs_examined: number(stage, "docsExamined"),
+        children,
+        ..Default::default()
+    }
+}
+
+fn condition(kind: &str, value: &Bson) -> PlanCondition {
+    PlanCondition {
+        kind: kind.to_string(),
+        expression: value.clone().into_relaxed_extjson().to_string(),
+    }
+}
+
+fn number(document: &Document, key: &str) -> Option<f64> {
+    match document.get(key)? {
+        Bson::Int32(v) => Some(*v as f64),
+        Bson::Int64(v) => Some(*v as f64),
+        Bson::Double(v) => Some(*v),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_uses_execution_stages_when_analyzed() {
+        let reply = doc! {
+            "queryPlanner": {
+                "namespace": "shop.orders",
+                "winningPlan": { "stage": "COLLSCAN" },
+            },
+            "executionStats": {
+                "executionTimeMillis": 3,
+                "executionStages": {
+                    "stage": "FETCH",
+                    "nReturned": 2,
+                    "docsExamined": 2,
+                    "inputStage": {
+                        "stage": "IXSCAN",
+                        "indexName": "status_1",
+                        "keysExamined": 2,
+                        "indexBounds": { "status": ["[\"paid\", \"paid\"]"] },
+                    },
+                },
+            },
+        };
+
+        let plan = parse_explain(reply).unwrap();
+        assert_eq!(plan.execution_time_ms, Some(3.0));
+        assert_eq!(plan.root.node_type, "FETCH");
+        assert_eq!(plan.root.relation.as_deref(), Some("orders"));
+        assert_eq!(plan.root.docs_examined, Some(2.0));
+
+        let scan = &plan.root.children[0];
+        assert_eq!(scan.index_name.as_deref(), Some("status_1"));
+        assert_eq!(scan.keys_examined, Some(2.0));
+        assert_eq!(scan.conditions[0].kind, "indexBounds");
+    }
+
+    #[test]
+    fn aggregation_stages_nest_over_the_cursor() {
+        let reply = doc! {
+            "stages": [
+                { "$cursor": {
+                    "queryPlanner": {
+                        "namespace": "shop.orders",
+                        "winningPlan": { "queryPlan": { "stage": "COLLSCAN" } },
+                    },
+                } },
+                { "$group": { "_id": "$status" } },
+                { "$sort": { "sortKey": { "_id": 1 } } },
+            ],
+        };
+
+        let root = parse_explain(reply).unwrap().root;
+        assert_eq!(root.node_type, "$sort");
+        assert_eq!(root.children[0].node_type, "$group");
+        let scan = &root.children[0].children[0];
+        assert_eq!(scan.node_type, "COLLSCAN");
+        assert_eq!(scan.relation.as_deref(), Some("orders"));
+    }
+}
@@ -0,0 +1,116 @@
+use mongodb::bson::{Bson, Document};
+use mongodb::error::ErrorKind;
+use mongodb::Database;
+
+use crate::adapters::{RowSource, SourceRow};
+use crate::error::{Error, Result};
+use crate::models::ImportTarget;
+
+use super::types::parse_scalar;
+
+/// Bulk-load `source` with one `insert_many` per batch.
+///
+/// There is no transaction to roll back: in stop mode the documents before
+/// the bad one stay inserted (`ordered`), in skip mode the server inserts
+/// every valid document of the batch (`ordered(false)`) and reports the rest.
+/// A dry run only converts the rows, without writing.
+pub async fn import_rows(
+    db: &Database,
+    target: &ImportTarget,
+    source: &mut dyn RowSource,
+) -> Result<u64> {
+    let coll = db.collection::<Document>(&target.table);
+    let columns = source.columns().to_vec();
+    let mut total: u64 = 0;
+
+    while let Some(batch) = source.next_batch().await? {
+        let documents: Vec<Document> = batch.iter().map(|row| to_document(&columns, row)).collect();
+        let count = documents.len() as u64;
+
+        if source.dry_run() || documents.is_empty() {
+            total += count;
+            source.imported(count);
+            continue;
+        }
+
+        // Unordered keeps going past a bad document, which is skip mode
+        let ordered = source.stop_on_error();
+        let imported = match coll.insert_many(documents).ordered(ordered).await {
+            Ok(result) => result.inserted_ids.len() as u64,
+            Err(e) => match *e.kind {
+                ErrorKind::InsertMany(ref failure) => {
+                    let errors = failure.write_errors.as_deref().unwrap_or_default();
+                    for error in errors {
+                        let line = batch.get(error.index).map(|r| r.line).unwrap_or_default();
+                        source.reject(line, error.message.clone())?;
+                    }
+                    if let Some(concern) = &failure.write_concern_error {
+                        return Err(Error::Query(concern.message.clone()));
+                    }
+                    count - errors.len() as u64
+                }
+                _ => return Err(e.into()),
+            },
+        };
+
+        total += imported;
+        source.imported(imported);
+    }
+
+    Ok(total)
+}
+
+/// NULL fields are left out (a missing `_id` is generated by the server).
+/// Values are typed with [`parse_scalar`], except JSON objects and arrays
+/// (nested NDJSON values), which become embedded documents and arrays;
+/// Extended JSON such as `{"$oid": "..."}` is understood.
+fn to_document(columns: &[String], row: &SourceRow) -> Document {
+    let mut document = Document::new();
+    for (column, value) in columns.iter().zip(&row.values) {
+        if let Some(value) = value {
+            document.insert(column.clone(), to_bson(value));
+        }
+    }
+    document
+}
+
+fn to_bson(value: &str) -> Bson {
+    if value.starts_with(['{', '[']) {
+        if let Some(bson) = serde_json::from_str::<serde_json::Value>(value)
+            .ok()
+            .and_then(|json| Bson::try_from(json).ok())
+        {
+            return bson;
+        }
+    }
+    parse_scalar(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mongodb::bson::doc;
+
+    #[test]
+    fn nested_json_becomes_documents() {
+        let row = SourceRow {
+            line: 2,
+            values: vec![
+                Some("42".into()),
+                None,
+                Some("{\"city\":\"Recife\",\"tags\":[1,2]}".into()),
+                Some("{not json".into()),
+            ],
+        };
+        let columns = ["n", "missing", "address", "raw"].map(String::from);
+
+        assert_eq!(
+            to_document(&columns, &row),
+            doc! {
+                "n": 42_i64,
+                "address": { "city": "Recife", "tags": [1, 2] },
+                "raw": "{not json",
+            }
+        );
+    }
+}
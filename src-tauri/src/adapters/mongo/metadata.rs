@@ -4,10 +4,11 @@ use chrono::Utc;
 use mongodb::bson::{Bson, Document};
 use mongodb::{Client, Database};
 
+use crate::adapters::rank_matches;
 use crate::error::Result;
 use crate::models::{
-    ColumnInfo, DatabaseInfo, DatabaseStructure, IndexInfo, SchemaStructure, TableInfo,
-    TableStructure, TableType,
+    ColumnInfo, DatabaseInfo, DatabaseStructure, IndexInfo, ObjectKind, ObjectMatch,
+    ObjectSearchRequest, SchemaStructure, TableInfo, TableStructure, TableType,
 };
 
 use super::executor::collect_cursor;
@@ -16,6 +17,10 @@ use super::types::bson_type_name;
 /// Number of documents sampled to infer a collection's "columns".
 const COLUMN_SAMPLE_SIZE: i64 = 100;
 
+/// Collections whose fields `search_objects` samples: past this, a search
+/// would mean hundreds of `find`s, so only collection names are matched.
+const SEARCH_SAMPLED_COLLECTIONS: usize = 200;
+
 pub async fn list_databases(client: &Client) -> Result<Vec<DatabaseInfo>> {
     let specs = client.list_databases().await?;
 
@@ -181,3 +186,45 @@ pub async fn list_schemas_with_tables(
         fetched_at: Utc::now().timestamp_millis(),
     })
 }
+
+/// Collection names plus the fields sampled from each collection (the same
+/// inference as [`list_columns`]), ranked against the query.
+pub async fn search_objects(
+    db: &Database,
+    schema_name: &str,
+    request: &ObjectSearchRequest,
+) -> Result<Vec<ObjectMatch>> {
+    let mut names = db.list_collection_names().await?;
+    names.sort();
+
+    let mut candidates: Vec<ObjectMatch> = names
+        .iter()
+        .map(|name| ObjectMatch {
+            kind: ObjectKind::Collection,
+            schema: Some(schema_name.to_string()),
+            parent: None,
+            name: name.clone(),
+            detail: None,
+            score: 0,
+        })
+        .collect();
+
+    if request.wants(ObjectKind::Column) && names.len() <= SEARCH_SAMPLED_COLLECTIONS {
+        for collection in &names {
+            // Views and system collections may refuse the find: skip them.
+            let Ok(columns) = list_columns(db, collection).await else {
+                continue;
+            };
+            candidates.extend(columns.into_iter().map(|column| ObjectMatch {
+                kind: ObjectKind::Column,
+                schema: Some(schema_name.to_string()),
+                parent: Some(collection.clone()),
+                name: column.name,
+                detail: Some(column.data_type),
+                score: 0,
+            }));
+        }
+    }
+
+    Ok(rank_matches(request, candidates))
+}
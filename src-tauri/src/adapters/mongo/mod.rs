@@ -1,9 +1,14 @@
 mod browse;
 mod command;
+mod ddl;
 mod executor;
+mod explain;
+mod import;
 mod metadata;
+mod search;
 mod types;
 
+use std::path::PathBuf;
 use std::time::Duration;
 
 use async_trait::async_trait;
@@ -11,7 +16,7 @@ use mongodb::bson::doc;
 use mongodb::options::{ClientOptions, Credential, ServerAddress, Tls, TlsOptions};
 use mongodb::{Client, Database};
 
-use crate::adapters::DatabaseAdapter;
+use crate::adapters::{tls, without_mongo_params, DatabaseAdapter, RowSink, RowSource};
 use crate::error::{Error, Result};
 use crate::models::*;
 
@@ -47,17 +52,21 @@ impl MongoAdapter {
                 );
             }
 
-            if server.ssl_enabled {
-                options.tls = Some(Tls::Enabled(
-                    TlsOptions::builder()
-                        .allow_invalid_certificates(true)
-                        .build(),
-                ));
-            }
-
             options
         };
 
+        // A TLS mode overrides the URI's tls options; with TLS disabled the
+        // URI (e.g. mongodb+srv://, TLS on by default) decides.
+        if server.tls.mode.is_enabled() {
+            options.tls = Some(Tls::Enabled(tls_options(&server.tls)?));
+        }
+
+        if server.ssh_tunnel.is_some() {
+            // The tunnel forwards a single host: talk to it directly instead
+            // of discovering replica set members it can't reach.
+            options.direct_connection = Some(true);
+        }
+
         options.connect_timeout = Some(CONNECT_TIMEOUT);
         options.server_selection_timeout = Some(CONNECT_TIMEOUT);
         options.app_name = Some("octapus_db".to_string());
@@ -76,12 +85,80 @@ impl MongoAdapter {
     }
 }
 
+/// rustls can't skip just the hostname check, so verify-ca also verifies the
+/// hostname (stricter, never weaker). The client certificate and key must be
+/// one PEM file, as with mongosh's `--tlsCertificateKeyFile`.
+fn tls_options(tls: &TlsConfig) -> Result<TlsOptions> {
+    let path = |p: &Option<String>| {
+        p.as_deref()
+            .filter(|p| !p.trim().is_empty())
+            .map(str::to_string)
+    };
+    let cert = path(&tls.client_cert_path);
+    let key = path(&tls.client_key_path);
+
+    if tls.mode.verifies_certificate() && tls.server_name.is_some() {
+        return Err(Error::Connection(
+            "MongoDB can't verify the server certificate through an SSH tunnel (the driver \
+             checks it against 127.0.0.1); use TLS mode require"
+                .into(),
+        ));
+    }
+    if cert.as_deref().is_some_and(tls::is_pkcs12) {
+        return Err(Error::Connection(
+            "MongoDB client certificates must be PEM, not PKCS#12".into(),
+        ));
+    }
+    if key.is_some() && key != cert {
+        return Err(Error::Connection(
+            "MongoDB needs the client certificate and key in one PEM file \
+             (e.g. `cat client.crt client.key > client.pem`); leave the key path empty"
+                .into(),
+        ));
+    }
+
+    Ok(TlsOptions::builder()
+        .allow_invalid_certificates(tls.mode == TlsMode::Require)
+        .ca_file_path(path(&tls.ca_cert_path).map(PathBuf::from))
+        .cert_key_file_path(cert.map(PathBuf::from))
+        .tls_certificate_key_file_password(
+            tls.client_key_passphrase
+                .clone()
+                .filter(|p| !p.is_empty())
+                .map(String::into_bytes),
+        )
+        .build())
+}
+
 #[async_trait]
 impl DatabaseAdapter for MongoAdapter {
     fn capabilities(&self) -> AdapterCapabilities {
         AdapterCapabilities::mongodb()
     }
 
+    /// Placeholders aren't JSON: the checks see them as `null`, which keeps
+    /// the command's shape (verb, collection, filter keys).
+    fn is_read_only_query(&self, query: &str) -> bool {
+        command::parse_command(&without_mongo_params(query))
+            .is_ok_and(|command| !command.is_write())
+    }
+
+    fn structure_changes(&self, query: &str) -> Vec<StructureChange> {
+        match command::parse_command(&without_mongo_params(query)) {
+            Ok(command) => command.structure_change().into_iter().collect(),
+            Err(_) => Vec::new(),
+        }
+    }
+
+    async fn analyze_query(&self, query: &str) -> Result<Vec<StatementWarning>> {
+        // Comando inválido não gera aviso: a execução vai recusá-lo mesmo.
+        Ok(command::parse_command(&without_mongo_params(query))
+            .ok()
+            .and_then(|command| command.warning())
+            .into_iter()
+            .collect())
+    }
+
     async fn execute_query(
         &self,
         query: &str,
@@ -94,6 +171,23 @@ impl DatabaseAdapter for MongoAdapter {
         browse::fetch_table_data(&self.db(), &self.database, request).await
     }
 
+    async fn explain_query(&self, query: &str, options: ExplainOptions) -> Result<ExplainPlan> {
+        explain::explain_query(&self.db(), query, options).await
+    }
+
+    async fn stream_query(
+        &self,
+        query: &str,
+        _query_id: Option<&str>,
+        sink: &mut dyn RowSink,
+    ) -> Result<u64> {
+        executor::stream_query(&self.db(), &self.database, query, sink).await
+    }
+
+    async fn stream_table(&self, request: TableDataRequest, sink: &mut dyn RowSink) -> Result<u64> {
+        browse::stream_table_data(&self.db(), request, sink).await
+    }
+
     async fn apply_row_edits(
         &self,
         editable: &EditableInfo,
@@ -110,6 +204,14 @@ impl DatabaseAdapter for MongoAdapter {
         executor::insert_rows(&self.db(), editable, rows).await
     }
 
+    async fn import_rows(
+        &self,
+        target: &ImportTarget,
+        source: &mut dyn RowSource,
+    ) -> Result<u64> {
+        import::import_rows(&self.db(), target, source).await
+    }
+
     async fn delete_rows(
         &self,
         editable: &EditableInfo,
@@ -142,6 +244,22 @@ impl DatabaseAdapter for MongoAdapter {
         metadata::list_schemas_with_tables(&self.db(), &self.database).await
     }
 
+    async fn search_objects(&self, request: &ObjectSearchRequest) -> Result<Vec<ObjectMatch>> {
+        metadata::search_objects(&self.db(), &self.database, request).await
+    }
+
+    async fn get_object_ddl(&self, request: &DdlRequest) -> Result<String> {
+        ddl::get_object_ddl(&self.db(), request).await
+    }
+
+    async fn search_values(
+        &self,
+        request: &ValueSearchRequest,
+        events: tokio::sync::mpsc::Sender<ValueSearchEvent>,
+    ) -> Result<()> {
+        search::search_values(&self.db(), &self.database, request, events).await
+    }
+
     async fn test_connection(&self) -> Result<()> {
         self.db().run_command(doc! { "ping": 1 }).await?;
         Ok(())
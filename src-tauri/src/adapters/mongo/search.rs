@@ -0,0 +1,170 @@
+use std::time::Duration;
+
+use mongodb::bson::{doc, Bson, Document};
+use mongodb::error::ErrorKind;
+use mongodb::Database;
+use tokio::sync::mpsc;
+
+use crate::adapters::{scan_tables, ScanTarget, TableScan};
+use crate::error::Result;
+use crate::models::{ValueHit, ValueMatchMode, ValueSearchEvent, ValueSearchRequest};
+
+use super::executor::scalar_variants;
+use super::metadata::list_columns;
+use super::types::bson_to_string;
+
+/// Server error code of a `find` that ran past its `maxTimeMS`.
+const MAX_TIME_EXPIRED: i32 = 50;
+
+/// Every collection of the database, each searched over the top-level fields
+/// seen in a sample of its documents (as in [`list_columns`]): fields that
+/// only show up past the sample aren't searched.
+pub async fn search_values(
+    db: &Database,
+    schema_name: &str,
+    request: &ValueSearchRequest,
+    events: mpsc::Sender<ValueSearchEvent>,
+) -> Result<()> {
+    let mut names = db.list_collection_names().await?;
+    names.retain(|name| !name.starts_with("system."));
+    names.sort();
+
+    let targets = names
+        .into_iter()
+        .map(|table| ScanTarget {
+            schema: Some(schema_name.to_string()),
+            table,
+        })
+        .collect();
+
+    scan_tables(request, targets, request.concurrency, events, |target| {
+        let db = db.clone();
+        let request = request.clone();
+        async move { scan_collection(&db, &target, &request).await }
+    })
+    .await
+}
+
+async fn scan_collection(
+    db: &Database,
+    target: &ScanTarget,
+    request: &ValueSearchRequest,
+) -> Result<TableScan> {
+    // Nomes com `.`/`$` virariam caminho/operador dentro do filtro.
+    let fields: Vec<String> = list_columns(db, &target.table)
+        .await?
+        .into_iter()
+        .map(|column| column.name)
+        .filter(|name| !name.contains('.') && !name.starts_with('$'))
+        .collect();
+    if fields.is_empty() {
+        return Ok(TableScan::Hits(Vec::new()));
+    }
+
+    let condition = match request.mode {
+        ValueMatchMode::Exact => Bson::Document(doc! { "$in": scalar_variants(&request.value) }),
+        ValueMatchMode::Contains => Bson::Document(doc! {
+            "$regex": regex_escape(&request.value),
+            "$options": "i",
+        }),
+    };
+    let any_field: Vec<Bson> = fields
+        .iter()
+        .map(|field| {
+            let mut clause = Document::new();
+            clause.insert(field.clone(), condition.clone());
+            Bson::Document(clause)
+        })
+        .collect();
+
+    let found = db
+        .collection::<Document>(&target.table)
+        .find(doc! { "$or": any_field })
+        .limit(request.max_hits as i64)
+        .max_time(Duration::from_millis(request.table_timeout_ms.max(1)))
+        .await;
+    let mut cursor = match found {
+        Ok(cursor) => cursor,
+        Err(e) if is_max_time_expired(&e) => return Ok(TableScan::TimedOut),
+        Err(e) => return Err(e.into()),
+    };
+    let mut docs = Vec::new();
+    while docs.len() < request.max_hits {
+        match cursor.advance().await {
+            Ok(true) => docs.push(cursor.deserialize_current()?),
+            Ok(false) => break,
+            Err(e) if is_max_time_expired(&e) => return Ok(TableScan::TimedOut),
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    let mut hits = Vec::new();
+    for doc in &docs {
+        let id = doc.get("_id").and_then(bson_to_string);
+        for field in &fields {
+            let Some(value) = doc.get(field) else {
+                continue;
+            };
+            if bson_matches(request, value) {
+                hits.push(ValueHit {
+                    schema: target.schema.clone(),
+                    table: target.table.clone(),
+                    column: field.clone(),
+                    key_columns: vec!["_id".to_string()],
+                    key_values: vec![id.clone()],
+                    value: bson_to_string(value),
+                });
+            }
+        }
+    }
+    Ok(TableScan::Hits(hits))
+}
+
+/// Whether the field is one the filter matched: an array matches through
+/// any of its elements, as `$in`/`$regex` do.
+fn bson_matches(request: &ValueSearchRequest, value: &Bson) -> bool {
+    match value {
+        Bson::Array(items) => items.iter().any(|item| bson_matches(request, item)),
+        other => bson_to_string(other).is_some_and(|text| request.matches(&text)),
+    }
+}
+
+fn regex_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if "\\^$.|?*+()[]{}".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// `true` for the error a `find` gets when `maxTimeMS` runs out.
+fn is_max_time_expired(error: &mongodb::error::Error) -> bool {
+    matches!(&*error.kind, ErrorKind::Command(command) if command.code == MAX_TIME_EXPIRED)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arrays_match_through_their_elements() {
+        let request = ValueSearchRequest {
+            value: "42".into(),
+            schema: None,
+            mode: ValueMatchMode::Exact,
+            concurrency: 1,
+            table_timeout_ms: 1000,
+            max_hits: 10,
+        };
+        assert!(bson_matches(&request, &Bson::Int32(42)));
+        assert!(bson_matches(
+            &request,
+            &Bson::Array(vec![Bson::String("42".into())])
+        ));
+        assert!(!bson_matches(&request, &Bson::String("420".into())));
+        assert_eq!(regex_escape("a.b*(c)"), "a\\.b\\*\\(c\\)");
+    }
+}
@@ -0,0 +1,512 @@
+use std::time::Instant;
+
+use mysql_async::prelude::Queryable;
+use mysql_async::{Conn, Pool};
+use sqlparser::dialect::MySqlDialect;
+
+use crate::adapters::{normalize_where_expr, RowSink};
+use crate::error::{Error, Result};
+use crate::models::{
+    EditableInfo, QueryColumnInfo, QueryResult, SortDirection, SortSpec, TableDataRequest,
+};
+
+use super::executor::{run_text, stream_text};
+use super::pool::get_conn;
+use super::util::{get_columns_ordered, get_pk_columns, quote_ident, rows_to_strings};
+
+pub async fn fetch_table_data(
+    pool: &Pool,
+    database: &str,
+    request: TableDataRequest,
+) -> Result<QueryResult> {
+    let mut conn = get_conn(pool).await?;
+
+    // MySQL's "schema" is the database itself.
+    let schema = request.schema.as_deref().unwrap_or(database);
+    let plan = plan_table_select(&mut conn, schema, &request).await?;
+    let base = &plan.base;
+
+    // +1 row to detect has_more without a second query
+    let select = format!(
+        "SELECT {} {base}{} LIMIT {} OFFSET {}",
+        plan.select_list,
+        plan.order_clause,
+        request.limit + 1,
+        request.offset,
+    );
+
+    let exec_started = Instant::now();
+    let (result_set, _) = run_text(&mut conn, &select, 0, None).await?;
+    let execution_time_ms = exec_started.elapsed().as_millis() as u64;
+
+    let mut rows = match result_set {
+        Some(set) => rows_to_strings(&set.columns, set.rows),
+        None => Vec::new(),
+    };
+
+    let total_count = if request.count_total {
+        conn.query_first::<i64, _>(format!("SELECT COUNT(*) {base}"))
+            .await?
+    } else {
+        None
+    };
+
+    let has_more = rows.len() as i64 > request.limit;
+    if has_more {
+        rows.truncate(request.limit as usize);
+    }
+
+    let editable_info =
+        detect_editable_info(schema, &request.table, &plan.pk_columns, &plan.columns);
+
+    Ok(QueryResult {
+        row_count: rows.len(),
+        columns: plan.columns,
+        rows,
+        total_count,
+        has_more,
+        execution_time_ms,
+        editable_info,
+    })
+}
+
+/// Every row of the browse (same filter and order), streamed unbuffered.
+pub async fn stream_table_data(
+    pool: &Pool,
+    database: &str,
+    request: TableDataRequest,
+    sink: &mut dyn RowSink,
+) -> Result<u64> {
+    let mut conn = get_conn(pool).await?;
+
+    let schema = request.schema.as_deref().unwrap_or(database);
+    let plan = plan_table_select(&mut conn, schema, &request).await?;
+
+    let select = format!("SELECT {} {}{}", plan.select_list, plan.base, plan.order_clause);
+    stream_text(&mut conn, &select, Some(plan.columns), sink).await
+}
+
+/// The parts of a browse query shared by the paged fetch and the stream.
+struct TableSelect {
+    columns: Vec<QueryColumnInfo>,
+    pk_columns: Vec<String>,
+    /// `FROM schema.table[ WHERE (...)]`
+    base: String,
+    /// Leading-space `" ORDER BY ..."` or empty
+    order_clause: String,
+    select_list: String,
+}
+
+async fn plan_table_select(
+    conn: &mut Conn,
+    schema: &str,
+    request: &TableDataRequest,
+) -> Result<TableSelect> {
+    // Ordered column metadata validates that the table exists and gives us
+    // the column names for rejecting unknown sort columns. PK columns drive
+    // the default ordering (stable pagination) and editability.
+    let columns_meta = get_columns_ordered(conn, schema, &request.table).await?;
+    let pk_columns = get_pk_columns(conn, schema, &request.table).await?;
+    let column_names: Vec<String> = columns_meta.iter().map(|c| c.name.clone()).collect();
+
+    let clauses = build_clauses(request, &column_names, &pk_columns)?;
+
+    let base = format!(
+        "FROM {}.{}{}",
+        quote_ident(schema),
+        quote_ident(&request.table),
+        clauses.where_clause,
+    );
+
+    let select_list = column_names
+        .iter()
+        .map(|c| quote_ident(c))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let columns = columns_meta
+        .iter()
+        .map(|c| QueryColumnInfo {
+            name: c.name.clone(),
+            type_name: c.data_type.clone(),
+            type_oid: None,
+        })
+        .collect();
+
+    Ok(TableSelect {
+        columns,
+        pk_columns,
+        base,
+        order_clause: clauses.order_clause,
+        select_list,
+    })
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// SQL building (pure, unit-testable)
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Debug)]
+struct BuiltClauses {
+    /// Leading-space `" WHERE (...)"` or empty
+    where_clause: String,
+    /// Leading-space `" ORDER BY ..."` or empty
+    order_clause: String,
+}
+
+fn build_clauses(
+    request: &TableDataRequest,
+    columns: &[String],
+    pk_columns: &[String],
+) -> Result<BuiltClauses> {
+    let where_expr = normalize_where_expr(request.where_expr.as_deref(), &MySqlDialect {})?;
+    let where_clause = match where_expr {
+        Some(expr) => format!(" WHERE ({expr})"),
+        None => String::new(),
+    };
+
+    let order_clause = build_order_clause(&request.sort, columns, pk_columns)?;
+
+    Ok(BuiltClauses {
+        where_clause,
+        order_clause,
+    })
+}
+
+fn build_order_clause(
+    sort: &[SortSpec],
+    columns: &[String],
+    pk_columns: &[String],
+) -> Result<String> {
+    // Default ordering: PK desc, keeping OFFSET pagination deterministic;
+    // tables/views without a PK get no ordering.
+    if sort.is_empty() {
+        if pk_columns.is_empty() {
+            return Ok(String::new());
+        }
+        let parts: Vec<String> = pk_columns
+            .iter()
+            .map(|col| format!("{} DESC", quote_ident(col)))
+            .collect();
+        return Ok(format!(" ORDER BY {}", parts.join(", ")));
+    }
+
+    let parts: Vec<String> = sort
+        .iter()
+        .map(|s| {
+            if !columns.contains(&s.column) {
+                return Err(Error::InvalidQuery(format!("Unknown column: {}", s.column)));
+            }
+            let dir = match s.direction {
+                SortDirection::Asc => "ASC",
+                SortDirection::Desc => "DESC",
+            };
+            Ok(format!("{} {}", quote_ident(&s.column), dir))
+        })
+        .collect::<Result<_>>()?;
+
+    Ok(format!(" ORDER BY {}", parts.join(", ")))
+}
+
+fn detect_editable_info(
+    schema: &str,
+    table: &str,
+    pk_columns: &[String],
+    result_columns: &[QueryColumnInfo],
+) -> Option<EditableInfo> {
+    if pk_columns.is_empty() {
+        return None;
+    }
+
+    let pk_indices: Vec<usize> = pk_columns
+        .iter()
+        .filter_map(|pk_name| result_columns.iter().position(|c| c.name == *pk_name))
+        .collect();
+
+    if pk_indices.len() != pk_columns.len() {
+        return None;
+    }
+
+    Some(EditableInfo {
+        schema: schema.to_string(),
+        table: table.to_string(),
+        primary_key_columns: pk_columns.to_vec(),
+        primary_key_column_indices: pk_indices,
+    })
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn req(where_expr: Option<&str>, sort: Vec<SortSpec>) -> TableDataRequest {
+        TableDataRequest {
+            schema: None,
+            table: "users".into(),
+            where_expr: where_expr.map(str::to_string),
+            sort,
+            limit: 100,
+            offset: 0,
+            count_total: false,
+        }
+    }
+
+    fn columns() -> Vec<String> {
+        vec!["id".to_string(), "name".to_string()]
+    }
+
+    #[test]
+    fn no_sort_defaults_to_pk_desc() {
+        let built = build_clauses(&req(None, vec![]), &columns(), &["id".to_string()]).unwrap();
+        assert_eq!(built.where_clause, "");
+        assert_eq!(built.order_clause, " ORDER BY `id` DESC");
+    }
+
+    #[test]
+    fn sort_uses_backtick_quoting() {
+        let built = build_clauses(
+            &req(
+                None,
+                vec![SortSpec {
+                    column: "name".into(),
+                    direction: SortDirection::Asc,
+                }],
+            ),
+            &columns(),
+            &[],
+        )
+        .unwrap();
+        assert_eq!(built.order_clause, " ORDER BY `name` ASC");
+    }
+
+    #[test]
+    fn where_expr_is_validated_with_mysql_dialect() {
+        // Backtick identifiers only parse under the MySQL dialect
+        let built = build_clauses(
+            &req(Some("WHERE `name` LIKE 'a%'"), vec![]),
+            &columns(),
+            &[],
+        )
+        .unwrap();
+        assert_eq!(built.where_clause, " WHERE (`name` LIKE 'a%')");
+
+        let err = build_clauses(
+            &req(Some("1 = 1; DROP TABLE users"), vec![]),
+            &columns(),
+            &[],
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("single WHERE expression"));
+    }
+
+    #[test]
+    fn unknown_sort_column_rejected() {
+        let err = build_clauses(
+            &req(
+                None,
+                vec![SortSpec {
+                    column: "nope".into(),
+                    direction: SortDirection::Asc,
+                }],
+            ),
+            &columns(),
+            &[],
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("Unknown column"));
+    }
+
+    // ── End-to-end (requires a local MariaDB; run with `cargo test -- --ignored`) ──
+
+    #[tokio::test]
+    #[ignore = "requires local mariadb on localhost:3306 (root/1234)"]
+    async fn e2e_mysql_adapter() {
+        use std::sync::Arc;
+        use std::time::Duration;
+
+        use crate::adapters::mysql::MysqlAdapter;
+        use crate::adapters::DatabaseAdapter;
+        use crate::models::{DatabaseType, QueryOptions, RowEdit, RowInsert, Server};
+
+        let server = Server {
+            id: Some(1),
+            name: "e2e".into(),
+            db_type: DatabaseType::Mysql,
+            host: "localhost".into(),
+            port: 3306,
+            username: "root".into(),
+            password: "1234".into(),
+            default_database: None,
+            tls: Default::default(),
+            connection_uri: None,
+            ssh_tunnel: None,
+            read_only: false,
+            confirm_writes: false,
+            created_at: 0,
+        };
+
+        let setup = MysqlAdapter::new(&server, "mysql").unwrap();
+        setup
+            .execute_statement("DROP DATABASE IF EXISTS octapus_e2e; CREATE DATABASE octapus_e2e")
+            .await
+            .unwrap();
+
+        let adapter = Arc::new(MysqlAdapter::new(&server, "octapus_e2e").unwrap());
+        adapter.test_connection().await.unwrap();
+
+        adapter
+            .execute_statement(
+                "CREATE TABLE users (id INT AUTO_INCREMENT PRIMARY KEY, name VARCHAR(50), \
+                    age INT, avatar VARBINARY(8), INDEX idx_users_age (age)); \
+                 CREATE TABLE logs (message TEXT)",
+            )
+            .await
+            .unwrap();
+        adapter
+            .execute_transaction(
+                (1..=30)
+                    .map(|i| {
+                        format!(
+                            "INSERT INTO users (name, age) VALUES ('user{i}', {})",
+                            i % 10
+                        )
+                    })
+                    .chain(["UPDATE users SET avatar = 0xCAFE WHERE id = 1".to_string()])
+                    .collect(),
+            )
+            .await
+            .unwrap();
+
+        // Structure
+        let databases = adapter.list_databases().await.unwrap();
+        assert!(databases.iter().any(|d| d.name == "octapus_e2e"));
+        let tables = adapter.list_tables("octapus_e2e").await.unwrap();
+        assert_eq!(
+            tables.iter().map(|t| t.name.as_str()).collect::<Vec<_>>(),
+            vec!["logs", "users"]
+        );
+        let columns = adapter.list_columns("octapus_e2e", "users").await.unwrap();
+        assert!(columns[0].is_primary_key);
+        let indexes = adapter.list_indexes("octapus_e2e", "users").await.unwrap();
+        assert!(indexes.iter().any(|i| i.name == "idx_users_age"));
+        assert!(indexes.iter().any(|i| i.is_primary));
+
+        // Browse with filter, sort and pagination
+        let result = adapter
+            .fetch_table_data(TableDataRequest {
+                where_expr: Some("age >= 5".into()),
+                sort: vec![SortSpec {
+                    column: "id".into(),
+                    direction: SortDirection::Desc,
+                }],
+                limit: 10,
+                count_total: true,
+                ..req(None, vec![])
+            })
+            .await
+            .unwrap();
+        assert_eq!(result.total_count, Some(15));
+        assert_eq!(result.row_count, 10);
+        assert!(result.has_more);
+        assert_eq!(result.rows[0][0].as_deref(), Some("29"));
+        let editable = result.editable_info.expect("PK table should be editable");
+
+        // Edit + insert + delete through the PK
+        let edited = adapter
+            .apply_row_edits(
+                &editable,
+                vec![RowEdit {
+                    pk_values: vec![Some("29".into())],
+                    changes: vec![("name".into(), Some("renamed".into()))],
+                }],
+            )
+            .await
+            .unwrap();
+        assert_eq!(edited.affected_rows, 1);
+        adapter
+            .insert_rows(
+                &editable,
+                vec![RowInsert {
+                    values: vec![("name".into(), Some("new".into()))],
+                }],
+            )
+            .await
+            .unwrap();
+        let deleted = adapter
+            .delete_rows(&editable, vec![vec![Some("2".into())]])
+            .await
+            .unwrap();
+        assert_eq!(deleted.affected_rows, 1);
+
+        // Free-form editor: paginated, editable from column origin metadata
+        let q = adapter
+            .execute_query(
+                "SELECT id, name, avatar FROM users WHERE name LIKE 'user%' ORDER BY id",
+                QueryOptions {
+                    limit: 5,
+                    count_total: true,
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(q.row_count, 5);
+        assert!(q.has_more);
+        assert_eq!(q.total_count, Some(28));
+        assert_eq!(q.rows[0][2].as_deref(), Some("\\xcafe"));
+        let q_editable = q
+            .editable_info
+            .expect("single-table select should be editable");
+        assert_eq!(q_editable.table, "users");
+        assert_eq!(q_editable.primary_key_column_indices, vec![0]);
+
+        // Duplicate column names still paginate
+        let dup = adapter
+            .execute_query(
+                "SELECT a.id, b.id FROM users a JOIN users b ON a.id = b.id",
+                QueryOptions {
+                    limit: 3,
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(dup.row_count, 3);
+        assert!(dup.has_more);
+        assert!(dup.editable_info.is_none());
+
+        // KILL QUERY interrupts a running query
+        let runner = adapter.clone();
+        let handle = tokio::spawn(async move {
+            runner
+                .execute_query(
+                    "SELECT SLEEP(30)",
+                    QueryOptions {
+                        query_id: Some("e2e-cancel".into()),
+                        ..Default::default()
+                    },
+                )
+                .await
+        });
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        adapter.cancel_query("e2e-cancel").await.unwrap();
+        let outcome = tokio::time::timeout(Duration::from_secs(5), handle)
+            .await
+            .expect("query should stop after KILL QUERY")
+            .unwrap();
+        // MariaDB aborts with an error; MySQL makes SLEEP() return 1
+        match outcome {
+            Err(e) => assert!(e.to_string().contains("interrupted")),
+            Ok(r) => assert_eq!(r.rows[0][0].as_deref(), Some("1")),
+        }
+
+        setup
+            .execute_statement("DROP DATABASE octapus_e2e")
+            .await
+            .unwrap();
+    }
+}
@@ -0,0 +1,647 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use mysql_async::prelude::Queryable;
+use mysql_async::{Column, Conn, Pool, Row, TxOpts, Value};
+use sqlparser::ast::{SetExpr, Statement};
+use sqlparser::dialect::MySqlDialect;
+use sqlparser::parser::Parser;
+
+use super::pool::{get_conn, get_conn_within};
+use super::util::{
+    column_type_name, get_columns_ordered, get_pk_columns, quote_ident, row_to_strings,
+    rows_to_strings,
+};
+use super::QueryRegistry;
+
+use crate::adapters::{RowSink, STREAM_BATCH_ROWS};
+use crate::error::{Error, Result};
+use crate::models::{
+    EditableInfo, QueryColumnInfo, QueryOptions, QueryResult, RowEdit, RowInsert, StatementResult,
+};
+
+/// Quanto o COUNT espera por uma conexão livre antes de desistir do total.
+const COUNT_WAIT_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Removes a query_id → connection id entry when the execution finishes,
+/// including early returns and cancelled/failed queries.
+struct ConnIdGuard<'a> {
+    registry: &'a QueryRegistry,
+    query_id: String,
+}
+
+impl Drop for ConnIdGuard<'_> {
+    fn drop(&mut self) {
+        self.registry.lock().unwrap().remove(&self.query_id);
+    }
+}
+
+pub async fn execute_query(
+    pool: &Pool,
+    query: &str,
+    options: QueryOptions,
+    registry: &QueryRegistry,
+) -> Result<QueryResult> {
+    let mut conn = get_conn(pool).await?;
+    let trimmed = query.trim().trim_end_matches(';');
+
+    if trimmed.is_empty() {
+        return Err(Error::InvalidQuery("Empty query".into()));
+    }
+
+    // The connection id is the target of `KILL QUERY` in cancel_query.
+    let _id_guard = options.query_id.as_deref().map(|query_id| {
+        registry
+            .lock()
+            .unwrap()
+            .insert(query_id.to_string(), conn.id());
+        ConnIdGuard {
+            registry,
+            query_id: query_id.to_string(),
+        }
+    });
+
+    let is_select = is_select_query(trimmed);
+
+    // Prepare (without executing) the statement for its column metadata:
+    // real types even for an empty result, plus the origin schema/table of
+    // each column.
+    let stmt_columns: Option<Vec<Column>> = if is_select {
+        match conn.prep(trimmed).await {
+            Ok(stmt) => {
+                let columns = stmt.columns().to_vec();
+                let _ = conn.close(stmt).await;
+                Some(columns)
+            }
+            Err(_) => None,
+        }
+    } else {
+        None
+    };
+
+    let paginate = is_select && !options.unlimited;
+    let limit = options.limit.max(0) as usize;
+    let offset = options.offset.max(0) as usize;
+
+    // Data and the optional COUNT(*) run concurrently; the COUNT on its own
+    // connection, since a connection serves one query at a time. Only the
+    // data query is timed, so `executionTimeMs` doesn't include the count scan.
+    let exec_started = Instant::now();
+    let data_fut = async {
+        let result = if paginate && takes_limit(trimmed) {
+            // +1 to detect has_more; on a new line, so a trailing
+            // `-- comment` doesn't swallow it
+            let paged = format!("{trimmed}\nLIMIT {} OFFSET {offset}", limit + 1);
+            run_text(&mut conn, &paged, 0, None).await
+        } else if paginate {
+            // Paged on the client: the remaining rows are still read, just
+            // not kept.
+            run_text(&mut conn, trimmed, offset, Some(limit + 1)).await
+        } else {
+            run_text(&mut conn, trimmed, 0, None).await
+        };
+        (result, exec_started.elapsed())
+    };
+    let count_fut = async {
+        if !(options.count_total && is_select) {
+            return None;
+        }
+
+        // Espera curta: esta segunda conexão é pedida com a primeira já na mão,
+        // e num pool saturado a espera padrão seguraria a query inteira.
+        // Sem conexão livre a gente abre mão do total, não do resultado.
+        let mut count_conn = get_conn_within(pool, COUNT_WAIT_TIMEOUT).await.ok()?;
+        count_conn
+            .query_first::<i64, _>(format!("SELECT COUNT(*) FROM ({trimmed}) AS __c"))
+            .await
+            .ok()
+            .flatten()
+    };
+    let ((result, exec_elapsed), total_count) = tokio::join!(data_fut, count_fut);
+
+    let (result_set, _) = result?;
+    let execution_time_ms = exec_elapsed.as_millis() as u64;
+
+    let (result_columns, mut rows) = match result_set {
+        Some(set) => {
+            let rows = rows_to_strings(&set.columns, set.rows);
+            (set.columns.to_vec(), rows)
+        }
+        None => (Vec::new(), Vec::new()),
+    };
+
+    // Determine if more rows exist
+    let has_more = paginate && rows.len() > limit;
+    if has_more {
+        rows.truncate(limit);
+    }
+
+    // Column metadata: prefer the prepared statement (present even for an
+    // empty result set); fall back to the result set's own description.
+    let meta_columns = stmt_columns.as_deref().unwrap_or(&result_columns);
+    let columns: Vec<QueryColumnInfo> = meta_columns
+        .iter()
+        .map(|c| QueryColumnInfo {
+            name: c.name_str().into_owned(),
+            type_name: column_type_name(c),
+            type_oid: None,
+        })
+        .collect();
+
+    // Detect if the result is editable (single source table with a primary key)
+    let editable_info = match &stmt_columns {
+        Some(stmt_columns) if !columns.is_empty() => {
+            detect_editable_info(&mut conn, stmt_columns, &columns).await
+        }
+        _ => None,
+    };
+
+    Ok(QueryResult {
+        row_count: rows.len(),
+        columns,
+        rows,
+        total_count,
+        has_more,
+        execution_time_ms,
+        editable_info,
+    })
+}
+
+/// Streams the result of `query` row by row: mysql_async reads the wire
+/// unbuffered, so memory stays at one batch whatever the result size.
+pub async fn stream_query(
+    pool: &Pool,
+    query: &str,
+    query_id: Option<&str>,
+    registry: &QueryRegistry,
+    sink: &mut dyn RowSink,
+) -> Result<u64> {
+    let mut conn = get_conn(pool).await?;
+    let trimmed = query.trim().trim_end_matches(';');
+
+    if trimmed.is_empty() {
+        return Err(Error::InvalidQuery("Empty query".into()));
+    }
+
+    let _id_guard = query_id.map(|query_id| {
+        registry
+            .lock()
+            .unwrap()
+            .insert(query_id.to_string(), conn.id());
+        ConnIdGuard {
+            registry,
+            query_id: query_id.to_string(),
+        }
+    });
+
+    stream_text(&mut conn, trimmed, None, sink).await
+}
+
+/// Sends the first result set of `sql` to `sink` in [`STREAM_BATCH_ROWS`]
+/// batches. `columns` overrides the wire description (the table browser has
+/// the information_schema types).
+pub(super) async fn stream_text(
+    conn: &mut Conn,
+    sql: &str,
+    columns: Option<Vec<QueryColumnInfo>>,
+    sink: &mut dyn RowSink,
+) -> Result<u64> {
+    let mut result = conn.query_iter(sql).await?;
+
+    loop {
+        let Some(set_columns) = result.columns() else {
+            // No statement of the script returned rows
+            sink.columns(&columns.unwrap_or_default()).await?;
+            return Ok(0);
+        };
+        // Statements without a result set (a leading SET, an INSERT)
+        if set_columns.is_empty() {
+            result.collect::<Row>().await?;
+            continue;
+        }
+
+        let columns = columns.unwrap_or_else(|| {
+            set_columns
+                .iter()
+                .map(|c| QueryColumnInfo {
+                    name: c.name_str().into_owned(),
+                    type_name: column_type_name(c),
+                    type_oid: None,
+                })
+                .collect()
+        });
+        sink.columns(&columns).await?;
+
+        let mut sent: u64 = 0;
+        let mut batch = Vec::with_capacity(STREAM_BATCH_ROWS);
+        while let Some(row) = result.next().await? {
+            batch.push(row_to_strings(&set_columns, row));
+            if batch.len() == STREAM_BATCH_ROWS {
+                sent += batch.len() as u64;
+                sink.rows(std::mem::take(&mut batch)).await?;
+            }
+        }
+        if !batch.is_empty() {
+            sent += batch.len() as u64;
+            sink.rows(batch).await?;
+        }
+
+        // Later statements of a script still run and surface their errors.
+        result.drop_result().await?;
+        return Ok(sent);
+    }
+}
+
+pub async fn apply_row_edits(
+    pool: &Pool,
+    editable: &EditableInfo,
+    edits: Vec<RowEdit>,
+) -> Result<StatementResult> {
+    if edits.is_empty() {
+        return Ok(StatementResult {
+            affected_rows: 0,
+            execution_time_ms: 0,
+        });
+    }
+
+    let mut conn = get_conn(pool).await?;
+    let known = known_columns(&mut conn, editable).await?;
+
+    let start = Instant::now();
+    let mut tx = conn.start_transaction(TxOpts::default()).await?;
+    let mut total_affected: u64 = 0;
+
+    for edit in &edits {
+        if edit.changes.is_empty() {
+            continue;
+        }
+
+        if edit.pk_values.len() != editable.primary_key_columns.len() {
+            return Err(Error::InvalidQuery(
+                "Primary key value count does not match primary key columns".into(),
+            ));
+        }
+
+        let mut set_clauses: Vec<String> = Vec::new();
+        let mut params: Vec<Value> = Vec::new();
+
+        // Values are bound as strings and MySQL converts them to the column
+        // type on assignment/comparison.
+        for (col_name, value) in &edit.changes {
+            check_column(&known, col_name)?;
+            set_clauses.push(format!("{} = ?", quote_ident(col_name)));
+            params.push(Value::from(value.as_deref()));
+        }
+
+        let where_clause = pk_where_clause(editable, &known)?;
+        params.extend(edit.pk_values.iter().map(|v| Value::from(v.as_deref())));
+
+        let query = format!(
+            "UPDATE {}.{} SET {} WHERE {}",
+            quote_ident(&editable.schema),
+            quote_ident(&editable.table),
+            set_clauses.join(", "),
+            where_clause,
+        );
+
+        tx.exec_drop(query.as_str(), params)
+            .await
+            .map_err(|e| Error::Query(format!("Failed to update row: {e}")))?;
+
+        total_affected += tx.affected_rows();
+    }
+
+    tx.commit().await?;
+
+    Ok(StatementResult {
+        affected_rows: total_affected,
+        execution_time_ms: start.elapsed().as_millis() as u64,
+    })
+}
+
+pub async fn insert_rows(
+    pool: &Pool,
+    editable: &EditableInfo,
+    rows: Vec<RowInsert>,
+) -> Result<StatementResult> {
+    if rows.is_empty() {
+        return Ok(StatementResult {
+            affected_rows: 0,
+            execution_time_ms: 0,
+        });
+    }
+
+    let mut conn = get_conn(pool).await?;
+    let known = known_columns(&mut conn, editable).await?;
+
+    let start = Instant::now();
+    let mut tx = conn.start_transaction(TxOpts::default()).await?;
+    let mut total_affected: u64 = 0;
+
+    for row in &rows {
+        // Only the filled columns are sent; omitted ones fall back to the
+        // column DEFAULT (or AUTO_INCREMENT).
+        if row.values.is_empty() {
+            continue;
+        }
+
+        let mut columns: Vec<String> = Vec::new();
+        let mut params: Vec<Value> = Vec::new();
+
+        for (col_name, value) in &row.values {
+            check_column(&known, col_name)?;
+            columns.push(quote_ident(col_name));
+            params.push(Value::from(value.as_deref()));
+        }
+
+        let query = format!(
+            "INSERT INTO {}.{} ({}) VALUES ({})",
+            quote_ident(&editable.schema),
+            quote_ident(&editable.table),
+            columns.join(", "),
+            vec!["?"; columns.len()].join(", "),
+        );
+
+        tx.exec_drop(query.as_str(), params)
+            .await
+            .map_err(|e| Error::Query(format!("Failed to insert row: {e}")))?;
+
+        total_affected += tx.affected_rows();
+    }
+
+    tx.commit().await?;
+
+    Ok(StatementResult {
+        affected_rows: total_affected,
+        execution_time_ms: start.elapsed().as_millis() as u64,
+    })
+}
+
+pub async fn delete_rows(
+    pool: &Pool,
+    editable: &EditableInfo,
+    pk_values: Vec<Vec<Option<String>>>,
+) -> Result<StatementResult> {
+    if pk_values.is_empty() {
+        return Ok(StatementResult {
+            affected_rows: 0,
+            execution_time_ms: 0,
+        });
+    }
+
+    let mut conn = get_conn(pool).await?;
+    let known = known_columns(&mut conn, editable).await?;
+    let where_clause = pk_where_clause(editable, &known)?;
+    let query = format!(
+        "DELETE FROM {}.{} WHERE {}",
+        quote_ident(&editable.schema),
+        quote_ident(&editable.table),
+        where_clause,
+    );
+
+    let start = Instant::now();
+    let mut tx = conn.start_transaction(TxOpts::default()).await?;
+    let mut total_affected: u64 = 0;
+
+    for pk in &pk_values {
+        if pk.len() != editable.primary_key_columns.len() {
+            return Err(Error::InvalidQuery(
+                "Primary key value count does not match primary key columns".into(),
+            ));
+        }
+
+        let params: Vec<Value> = pk.iter().map(|v| Value::from(v.as_deref())).collect();
+        tx.exec_drop(query.as_str(), params)
+            .await
+            .map_err(|e| Error::Query(format!("Failed to delete row: {e}")))?;
+
+        total_affected += tx.affected_rows();
+    }
+
+    tx.commit().await?;
+
+    Ok(StatementResult {
+        affected_rows: total_affected,
+        execution_time_ms: start.elapsed().as_millis() as u64,
+    })
+}
+
+pub async fn execute_statement(pool: &Pool, statement: &str) -> Result<StatementResult> {
+    let mut conn = get_conn(pool).await?;
+    let start = Instant::now();
+
+    let (_, affected) = run_text(&mut conn, statement, 0, Some(0)).await?;
+
+    Ok(StatementResult {
+        affected_rows: affected,
+        execution_time_ms: start.elapsed().as_millis() as u64,
+    })
+}
+
+pub async fn execute_transaction(
+    pool: &Pool,
+    statements: Vec<String>,
+) -> Result<Vec<StatementResult>> {
+    let mut conn = get_conn(pool).await?;
+    let mut tx = conn.start_transaction(TxOpts::default()).await?;
+
+    let mut results = Vec::with_capacity(statements.len());
+
+    for stmt in &statements {
+        let start = Instant::now();
+        let (_, affected) = run_text(&mut tx, stmt, 0, Some(0)).await?;
+        results.push(StatementResult {
+            affected_rows: affected,
+            execution_time_ms: start.elapsed().as_millis() as u64,
+        });
+    }
+
+    tx.commit().await?;
+    Ok(results)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Whether LIMIT/OFFSET can be appended to `query`: a single SELECT (or
+/// UNION) without its own LIMIT, locking or INTO clause. Pages aren't cut
+/// from a derived table instead, as MariaDB drops the ORDER BY of a
+/// subquery without LIMIT and the pages would come unordered.
+fn takes_limit(query: &str) -> bool {
+    let Ok(statements) = Parser::parse_sql(&MySqlDialect {}, query) else {
+        return false;
+    };
+    match statements.as_slice() {
+        [Statement::Query(query)] => {
+            query.limit_clause.is_none()
+                && query.fetch.is_none()
+                && query.locks.is_empty()
+                && query.for_clause.is_none()
+                && match query.body.as_ref() {
+                    SetExpr::Select(select) => select.into.is_none(),
+                    SetExpr::SetOperation { .. } | SetExpr::Query(_) => true,
+                    _ => false,
+                }
+        }
+        _ => false,
+    }
+}
+
+fn is_select_query(query: &str) -> bool {
+    let first_word = query.split_whitespace().next().unwrap_or("");
+    matches!(
+        first_word.to_uppercase().as_str(),
+        "SELECT" | "WITH" | "TABLE" | "VALUES"
+    )
+}
+
+pub(super) struct ResultSet {
+    pub columns: Arc<[Column]>,
+    pub rows: Vec<Row>,
+}
+
+/// Runs `sql` (one or more statements) over the text protocol, so the server
+/// renders every value as text. Returns the last result set that has
+/// columns — keeping only the rows after `skip`, up to `max_rows` — and the
+/// rows affected by the statements that returned none.
+pub(super) async fn run_text<Q: Queryable>(
+    conn: &mut Q,
+    sql: &str,
+    skip: usize,
+    max_rows: Option<usize>,
+) -> mysql_async::Result<(Option<ResultSet>, u64)> {
+    let mut result = conn.query_iter(sql).await?;
+    let mut last_set = None;
+    let mut affected: u64 = 0;
+
+    loop {
+        // Read before consuming the set: moving to the next statement
+        // overwrites the connection's affected-rows counter.
+        let columns = result.columns();
+        let set_affected = result.affected_rows();
+
+        // Also surfaces the error of a failed later statement of a script.
+        let mut seen = 0usize;
+        let rows: Vec<Row> = result
+            .reduce(Vec::new(), |mut rows: Vec<Row>, row: Row| {
+                seen += 1;
+                if seen > skip && max_rows.is_none_or(|max| rows.len() < max) {
+                    rows.push(row);
+                }
+                rows
+            })
+            .await?;
+
+        match columns {
+            None => break,
+            Some(columns) if columns.is_empty() => affected += set_affected,
+            Some(columns) => last_set = Some(ResultSet { columns, rows }),
+        }
+    }
+
+    Ok((last_set, affected))
+}
+
+/// Column names of the edited table, used to reject unknown columns before
+/// they are spliced (quoted) into the statement.
+async fn known_columns(conn: &mut Conn, editable: &EditableInfo) -> Result<Vec<String>> {
+    Ok(get_columns_ordered(conn, &editable.schema, &editable.table)
+        .await?
+        .into_iter()
+        .map(|c| c.name)
+        .collect())
+}
+
+fn check_column(known: &[String], column: &str) -> Result<()> {
+    if known.iter().any(|c| c == column) {
+        Ok(())
+    } else {
+        Err(Error::InvalidQuery(format!("Unknown column: {column}")))
+    }
+}
+
+/// `` `pk1` = ? AND `pk2` = ? ...``
+fn pk_where_clause(editable: &EditableInfo, known: &[String]) -> Result<String> {
+    let clauses = editable
+        .primary_key_columns
+        .iter()
+        .map(|pk_col| {
+            check_column(known, pk_col).map_err(|_| {
+                Error::InvalidQuery(format!("Unknown primary key column: {pk_col}"))
+            })?;
+            Ok(format!("{} = ?", quote_ident(pk_col)))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(clauses.join(" AND "))
+}
+
+/// Detects if the query result is editable by checking:
+/// 1. All columns come from the same source table (origin schema + table
+///    from the column definitions)
+/// 2. The source table has a primary key
+/// 3. All PK columns are present in the result
+///
+/// Takes the columns of the prepared statement, present even when the
+/// result is empty.
+async fn detect_editable_info(
+    conn: &mut Conn,
+    stmt_columns: &[Column],
+    result_columns: &[QueryColumnInfo],
+) -> Option<EditableInfo> {
+    // Expressions have no origin table; ignore them like Postgres' oid 0
+    let tables: HashSet<(String, String)> = stmt_columns
+        .iter()
+        .filter(|c| !c.org_table_str().is_empty())
+        .map(|c| (c.schema_str().into_owned(), c.org_table_str().into_owned()))
+        .collect();
+
+    // All real columns must come from exactly one table
+    if tables.len() != 1 {
+        return None;
+    }
+
+    let (schema, table) = tables.into_iter().next()?;
+
+    let pk_column_names = get_pk_columns(conn, &schema, &table).await.ok()?;
+    if pk_column_names.is_empty() {
+        return None;
+    }
+
+    // Find PK column indices in the result set
+    let pk_indices: Vec<usize> = pk_column_names
+        .iter()
+        .filter_map(|pk_name| result_columns.iter().position(|c| c.name == *pk_name))
+        .collect();
+
+    // All PK columns must be present in the result
+    if pk_indices.len() != pk_column_names.len() {
+        return None;
+    }
+
+    Some(EditableInfo {
+        schema,
+        table,
+        primary_key_columns: pk_column_names,
+        primary_key_column_indices: pk_indices,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limit_is_appended_only_to_plain_selects() {
+        assert!(takes_limit("SELECT * FROM t ORDER BY created_at DESC"));
+        assert!(takes_limit("SELECT a FROM t UNION SELECT b FROM u"));
+        assert!(takes_limit("WITH c AS (SELECT 1 AS x) SELECT x FROM c"));
+
+        assert!(!takes_limit("SELECT * FROM t LIMIT 5"));
+        assert!(!takes_limit("SELECT * FROM t FOR UPDATE"));
+        assert!(!takes_limit("SELECT 1; SELECT 2"));
+        assert!(!takes_limit("SHOW TABLES"));
+    }
+}
@@ -0,0 +1,112 @@
+use mysql_async::prelude::Queryable;
+use mysql_async::{Pool, Transaction, TxOpts, Value};
+
+use crate::adapters::{RowSource, SourceRow};
+use crate::error::{Error, Result};
+use crate::models::ImportTarget;
+
+use super::pool::get_conn;
+use super::util::{get_columns_ordered, quote_ident};
+
+/// Placeholders per prepared statement are capped at 65535 by the protocol.
+const MAX_PLACEHOLDERS: usize = 65_535;
+
+/// Bulk-load `source` with multi-row `INSERT`s, all in one transaction
+/// (rolled back on a dry run or on error).
+///
+/// InnoDB undoes a failed statement on its own, so a rejected batch is
+/// simply replayed row by row to find the bad lines.
+pub async fn import_rows(
+    pool: &Pool,
+    database: &str,
+    target: &ImportTarget,
+    source: &mut dyn RowSource,
+) -> Result<u64> {
+    // MySQL's "schema" is the database itself.
+    let schema = target.schema.as_deref().unwrap_or(database);
+    let mut conn = get_conn(pool).await?;
+
+    let known: Vec<String> = get_columns_ordered(&mut conn, schema, &target.table)
+        .await?
+        .into_iter()
+        .map(|c| c.name)
+        .collect();
+    if let Some(unknown) = source.columns().iter().find(|c| !known.contains(c)) {
+        return Err(Error::InvalidQuery(format!("Unknown column: {unknown}")));
+    }
+
+    let width = source.columns().len();
+    let insert_prefix = format!(
+        "INSERT INTO {}.{} ({}) VALUES ",
+        quote_ident(schema),
+        quote_ident(&target.table),
+        source
+            .columns()
+            .iter()
+            .map(|c| quote_ident(c))
+            .collect::<Vec<_>>()
+            .join(", "),
+    );
+    let row_placeholders = format!("({})", vec!["?"; width].join(", "));
+    let chunk_rows = (MAX_PLACEHOLDERS / width.max(1)).max(1);
+
+    let mut tx = conn.start_transaction(TxOpts::default()).await?;
+    let mut total: u64 = 0;
+
+    while let Some(batch) = source.next_batch().await? {
+        let mut imported: u64 = 0;
+        for chunk in batch.chunks(chunk_rows) {
+            let sql = format!(
+                "{insert_prefix}{}",
+                vec![row_placeholders.as_str(); chunk.len()].join(", ")
+            );
+            let params: Vec<Value> = chunk
+                .iter()
+                .flat_map(|row| row.values.iter().map(|v| Value::from(v.as_deref())))
+                .collect();
+
+            imported += match tx.exec_drop(sql.as_str(), params).await {
+                Ok(()) => chunk.len() as u64,
+                Err(_) => {
+                    insert_each(&mut tx, &insert_prefix, &row_placeholders, chunk, source).await?
+                }
+            };
+        }
+
+        total += imported;
+        source.imported(imported);
+    }
+
+    if source.dry_run() {
+        tx.rollback().await?;
+    } else {
+        tx.commit().await?;
+    }
+    Ok(total)
+}
+
+async fn insert_each(
+    tx: &mut Transaction<'_>,
+    insert_prefix: &str,
+    row_placeholders: &str,
+    rows: &[SourceRow],
+    source: &mut dyn RowSource,
+) -> Result<u64> {
+    let sql = format!("{insert_prefix}{row_placeholders}");
+    let mut imported: u64 = 0;
+
+    for row in rows {
+        let params: Vec<Value> = row
+            .values
+            .iter()
+            .map(|v| Value::from(v.as_deref()))
+            .collect();
+        match tx.exec_drop(sql.as_str(), params).await {
+            Ok(()) => imported += 1,
+            Err(mysql_async::Error::Server(e)) => source.reject(row.line, e.message)?,
+            Err(e) => source.reject(row.line, e.to_string())?,
+        }
+    }
+
+    Ok(imported)
+}
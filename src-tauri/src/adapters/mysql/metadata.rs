@@ -0,0 +1,177 @@
+use chrono::Utc;
+use mysql_async::prelude::Queryable;
+use mysql_async::Pool;
+
+use crate::error::Result;
+use crate::models::{
+    ColumnInfo, DatabaseInfo, DatabaseStructure, IndexInfo, SchemaStructure, TableInfo,
+    TableStructure, TableType,
+};
+
+use super::pool::get_conn;
+use super::util::get_columns_ordered;
+
+pub async fn list_databases(pool: &Pool) -> Result<Vec<DatabaseInfo>> {
+    let mut conn = get_conn(pool).await?;
+
+    let rows: Vec<(String, Option<i64>)> = conn
+        .query(
+            r#"
+            SELECT s.SCHEMA_NAME,
+                   (SELECT CAST(SUM(t.DATA_LENGTH + t.INDEX_LENGTH) AS SIGNED)
+                    FROM information_schema.TABLES t
+                    WHERE t.TABLE_SCHEMA = s.SCHEMA_NAME) AS size_bytes
+            FROM information_schema.SCHEMATA s
+            ORDER BY s.SCHEMA_NAME
+            "#,
+        )
+        .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(name, size_bytes)| DatabaseInfo { name, size_bytes })
+        .collect())
+}
+
+pub async fn list_tables(pool: &Pool, schema: &str) -> Result<Vec<TableInfo>> {
+    let mut conn = get_conn(pool).await?;
+
+    let rows: Vec<(String, String, Option<u64>)> = conn
+        .exec(
+            r#"
+            SELECT TABLE_NAME, TABLE_TYPE, TABLE_ROWS
+            FROM information_schema.TABLES
+            WHERE TABLE_SCHEMA = ?
+            ORDER BY TABLE_NAME
+            "#,
+            (schema,),
+        )
+        .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(name, kind, row_estimate)| {
+            let is_view = kind.ends_with("VIEW");
+            TableInfo {
+                name,
+                schema: schema.to_string(),
+                table_type: if is_view {
+                    TableType::View
+                } else {
+                    TableType::Table
+                },
+                // InnoDB's TABLE_ROWS is an estimate, like reltuples; views
+                // report NULL.
+                row_estimate: row_estimate.filter(|_| !is_view).map(|n| n as i64),
+            }
+        })
+        .collect())
+}
+
+/// InnoDB's `TABLE_ROWS` estimate of a table, for the ALTER warning.
+/// Without a schema, the connection's database.
+pub async fn row_estimate(pool: &Pool, schema: Option<&str>, table: &str) -> Result<Option<i64>> {
+    let mut conn = get_conn(pool).await?;
+
+    let rows: Option<Option<u64>> = conn
+        .exec_first(
+            r#"
+            SELECT TABLE_ROWS
+            FROM information_schema.TABLES
+            WHERE TABLE_SCHEMA = COALESCE(?, DATABASE()) AND TABLE_NAME = ?
+            "#,
+            (schema, table),
+        )
+        .await?;
+
+    Ok(rows.flatten().map(|n| n as i64))
+}
+
+pub async fn list_columns(pool: &Pool, schema: &str, table: &str) -> Result<Vec<ColumnInfo>> {
+    let mut conn = get_conn(pool).await?;
+
+    let columns = get_columns_ordered(&mut conn, schema, table).await?;
+
+    let fk_columns: Vec<String> = conn
+        .exec(
+            r#"
+            SELECT DISTINCT COLUMN_NAME
+            FROM information_schema.KEY_COLUMN_USAGE
+            WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ?
+              AND REFERENCED_TABLE_NAME IS NOT NULL
+            "#,
+            (schema, table),
+        )
+        .await?;
+
+    Ok(columns
+        .into_iter()
+        .enumerate()
+        .map(|(i, c)| ColumnInfo {
+            is_foreign_key: fk_columns.contains(&c.name),
+            name: c.name,
+            ordinal: i as i32 + 1,
+            data_type: c.column_type,
+            is_nullable: c.is_nullable,
+            default_value: c.default_value,
+            is_primary_key: c.is_primary_key,
+        })
+        .collect())
+}
+
+pub async fn list_indexes(pool: &Pool, schema: &str, table: &str) -> Result<Vec<IndexInfo>> {
+    let mut conn = get_conn(pool).await?;
+
+    // One row per indexed column; functional key parts (MySQL 8) have no
+    // column name.
+    let rows: Vec<(String, i64, Option<String>, String)> = conn
+        .exec(
+            r#"
+            SELECT INDEX_NAME, NON_UNIQUE, COLUMN_NAME, INDEX_TYPE
+            FROM information_schema.STATISTICS
+            WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ?
+            ORDER BY INDEX_NAME, SEQ_IN_INDEX
+            "#,
+            (schema, table),
+        )
+        .await?;
+
+    let mut indexes: Vec<IndexInfo> = Vec::new();
+
+    for (name, non_unique, column, index_type) in rows {
+        if indexes.last().is_none_or(|i| i.name != name) {
+            indexes.push(IndexInfo {
+                is_primary: name == "PRIMARY",
+                name,
+                columns: Vec::new(),
+                is_unique: non_unique == 0,
+                index_type: index_type.to_lowercase(),
+            });
+        }
+        if let (Some(index), Some(column)) = (indexes.last_mut(), column) {
+            index.columns.push(column);
+        }
+    }
+
+    Ok(indexes)
+}
+
+pub async fn list_schemas_with_tables(pool: &Pool, database: &str) -> Result<DatabaseStructure> {
+    let tables = list_tables(pool, database)
+        .await?
+        .into_iter()
+        .map(|t| TableStructure {
+            name: t.name,
+            table_type: t.table_type,
+        })
+        .collect();
+
+    // No schema level: the database is shown as its only schema.
+    Ok(DatabaseStructure {
+        schemas: vec![SchemaStructure {
+            name: database.to_string(),
+            tables,
+        }],
+        fetched_at: Utc::now().timestamp_millis(),
+    })
+}
@@ -0,0 +1,172 @@
+mod browse;
+mod executor;
+mod import;
+mod metadata;
+mod pool;
+mod util;
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use mysql_async::prelude::Queryable;
+use mysql_async::Pool;
+use sqlparser::dialect::MySqlDialect;
+
+use crate::adapters::{
+    analyze_sql, is_read_only_sql, sql_structure_changes, DatabaseAdapter, RowSink, RowSource,
+};
+use crate::error::Result;
+use crate::models::*;
+
+/// Maps in-flight query ids to the id of the connection running them, so
+/// cancel_query can issue `KILL QUERY` from another connection.
+type QueryRegistry = Mutex<HashMap<String, u32>>;
+
+/// MySQL and MariaDB (same wire protocol and information_schema).
+pub struct MysqlAdapter {
+    pool: Pool,
+    /// MySQL has no schema level: the database doubles as the schema of
+    /// every object.
+    database: String,
+    active_queries: QueryRegistry,
+}
+
+impl MysqlAdapter {
+    pub fn new(server: &Server, database: &str) -> Result<Self> {
+        Ok(Self {
+            pool: pool::create_pool(server, database)?,
+            database: database.to_string(),
+            active_queries: Mutex::new(HashMap::new()),
+        })
+    }
+}
+
+#[async_trait]
+impl DatabaseAdapter for MysqlAdapter {
+    fn capabilities(&self) -> AdapterCapabilities {
+        AdapterCapabilities::mysql()
+    }
+
+    fn is_read_only_query(&self, query: &str) -> bool {
+        is_read_only_sql(&MySqlDialect {}, query)
+    }
+
+    fn structure_changes(&self, query: &str) -> Vec<StructureChange> {
+        sql_structure_changes(&MySqlDialect {}, query)
+    }
+
+    async fn analyze_query(&self, query: &str) -> Result<Vec<StatementWarning>> {
+        let mut analysis = analyze_sql(&MySqlDialect {}, query);
+        for altered in std::mem::take(&mut analysis.altered_tables) {
+            let rows =
+                metadata::row_estimate(&self.pool, altered.schema.as_deref(), &altered.table)
+                    .await?;
+            analysis.flag_if_large(&altered, rows);
+        }
+        Ok(analysis.into_warnings())
+    }
+
+    async fn execute_query(&self, query: &str, options: QueryOptions) -> Result<QueryResult> {
+        executor::execute_query(&self.pool, query, options, &self.active_queries).await
+    }
+
+    async fn fetch_table_data(&self, request: TableDataRequest) -> Result<QueryResult> {
+        browse::fetch_table_data(&self.pool, &self.database, request).await
+    }
+
+    async fn stream_query(
+        &self,
+        query: &str,
+        query_id: Option<&str>,
+        sink: &mut dyn RowSink,
+    ) -> Result<u64> {
+        executor::stream_query(&self.pool, query, query_id, &self.active_queries, sink).await
+    }
+
+    async fn stream_table(&self, request: TableDataRequest, sink: &mut dyn RowSink) -> Result<u64> {
+        browse::stream_table_data(&self.pool, &self.database, request, sink).await
+    }
+
+    async fn apply_row_edits(
+        &self,
+        editable: &EditableInfo,
+        edits: Vec<RowEdit>,
+    ) -> Result<StatementResult> {
+        executor::apply_row_edits(&self.pool, editable, edits).await
+    }
+
+    async fn insert_rows(
+        &self,
+        editable: &EditableInfo,
+        rows: Vec<RowInsert>,
+    ) -> Result<StatementResult> {
+        executor::insert_rows(&self.pool, editable, rows).await
+    }
+
+    async fn import_rows(
+        &self,
+        target: &ImportTarget,
+        source: &mut dyn RowSource,
+    ) -> Result<u64> {
+        import::import_rows(&self.pool, &self.database, target, source).await
+    }
+
+    async fn delete_rows(
+        &self,
+        editable: &EditableInfo,
+        pk_values: Vec<Vec<Option<String>>>,
+    ) -> Result<StatementResult> {
+        executor::delete_rows(&self.pool, editable, pk_values).await
+    }
+
+    async fn execute_statement(&self, statement: &str) -> Result<StatementResult> {
+        executor::execute_statement(&self.pool, statement).await
+    }
+
+    async fn execute_transaction(&self, statements: Vec<String>) -> Result<Vec<StatementResult>> {
+        executor::execute_transaction(&self.pool, statements).await
+    }
+
+    async fn list_databases(&self) -> Result<Vec<DatabaseInfo>> {
+        metadata::list_databases(&self.pool).await
+    }
+
+    async fn list_tables(&self, schema: &str) -> Result<Vec<TableInfo>> {
+        metadata::list_tables(&self.pool, schema).await
+    }
+
+    async fn list_columns(&self, schema: &str, table: &str) -> Result<Vec<ColumnInfo>> {
+        metadata::list_columns(&self.pool, schema, table).await
+    }
+
+    async fn list_indexes(&self, schema: &str, table: &str) -> Result<Vec<IndexInfo>> {
+        metadata::list_indexes(&self.pool, schema, table).await
+    }
+
+    async fn list_schemas_with_tables(&self) -> Result<DatabaseStructure> {
+        metadata::list_schemas_with_tables(&self.pool, &self.database).await
+    }
+
+    async fn test_connection(&self) -> Result<()> {
+        let mut conn = pool::get_conn(&self.pool).await?;
+        conn.query_drop("SELECT 1").await?;
+        Ok(())
+    }
+
+    async fn cancel_query(&self, query_id: &str) -> Result<()> {
+        let conn_id = self.active_queries.lock().unwrap().get(query_id).copied();
+
+        // Unknown id means the query already finished (or never registered a
+        // query_id); cancelling is then a benign no-op.
+        let Some(conn_id) = conn_id else {
+            return Ok(());
+        };
+
+        // KILL QUERY stops the statement but keeps the connection, which
+        // goes back to the pool once the cancelled execution returns.
+        let mut conn = pool::get_conn(&self.pool).await?;
+        conn.query_drop(format!("KILL QUERY {conn_id}")).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,108 @@
+use std::time::Duration;
+
+use mysql_async::{Conn, Opts, OptsBuilder, Pool, PoolConstraints, PoolOpts, SslOpts};
+
+use crate::adapters::tls::{self, ClientIdentity};
+use crate::error::{Error, Result};
+use crate::models::{Server, TlsConfig, TlsMode};
+
+const POOL_MAX_SIZE: usize = 16;
+/// mysql_async has no connect/checkout timeout of its own; this bounds both
+/// (a dead host would otherwise hang until the OS gives up on the socket).
+const GET_CONN_TIMEOUT: Duration = Duration::from_secs(30);
+const TCP_KEEPALIVE_MS: u32 = 60_000;
+
+pub fn create_pool(server: &Server, database: &str) -> Result<Pool> {
+    let builder = match server.connection_uri.as_deref() {
+        // The URI provides host/port/user/password; the selected database
+        // still takes precedence so one server can open several databases.
+        Some(uri) => OptsBuilder::from_opts(
+            Opts::from_url(uri).map_err(|e| Error::Connection(format!("Invalid URI: {e}")))?,
+        ),
+        None => OptsBuilder::default()
+            .ip_or_hostname(server.host.clone())
+            .tcp_port(server.port)
+            .user(Some(server.username.clone()))
+            .pass(Some(server.password.clone())),
+    };
+
+    let constraints = PoolConstraints::new(0, POOL_MAX_SIZE)
+        .ok_or_else(|| Error::Connection("Invalid pool size".into()))?;
+
+    let mut builder = builder
+        .db_name(Some(database.to_string()))
+        // Keepalive TCP: evita que firewalls/NAT derrubem conexões ociosas em
+        // silêncio enquanto o app fica aberto sem uso.
+        .tcp_keepalive(Some(TCP_KEEPALIVE_MS))
+        // UPDATE reports matched rows (like Postgres), not only changed ones:
+        // saving a cell with its current value still counts as 1 row.
+        .client_found_rows(true)
+        .pool_opts(PoolOpts::default().with_constraints(constraints));
+
+    // Server em modo read-only: o próprio MySQL recusa escritas, o que pega
+    // o que a análise do SQL não enxerga (funções que escrevem). `setup` (e
+    // não `init`) porque roda de novo depois do reset da conexão que o pool
+    // faz ao recebê-la de volta.
+    if server.read_only {
+        builder = builder.setup(vec!["SET SESSION TRANSACTION READ ONLY"]);
+    }
+
+    if server.tls.mode.is_enabled() {
+        builder = builder.ssl_opts(ssl_opts(&server.tls)?);
+    }
+
+    Ok(Pool::new(builder))
+}
+
+/// TLS options for the mode. mysql_async (native-tls) only takes client
+/// certificates as a PKCS#12 bundle.
+fn ssl_opts(tls: &TlsConfig) -> Result<SslOpts> {
+    let mut opts = match tls.mode {
+        TlsMode::Disable | TlsMode::Require => SslOpts::default()
+            .with_danger_accept_invalid_certs(true)
+            .with_danger_skip_domain_validation(true),
+        TlsMode::VerifyCa => SslOpts::default().with_danger_skip_domain_validation(true),
+        TlsMode::VerifyFull => SslOpts::default(),
+    };
+
+    if let Some(bundle) = tls::ca_bundle(tls)? {
+        opts = opts.with_root_certs(vec![bundle.into()]);
+    }
+
+    match tls::client_identity(tls)? {
+        Some(ClientIdentity::Pkcs12 { der, password }) => {
+            opts = opts.with_client_identity(Some(
+                mysql_async::ClientIdentity::new(der.into())
+                    .with_password(password),
+            ));
+        }
+        Some(ClientIdentity::Pem { .. }) => {
+            return Err(Error::Connection(
+                "MySQL client certificates must be a PKCS#12 bundle (.p12/.pfx); \
+                 create one with `openssl pkcs12 -export -in client.crt -inkey client.key`"
+                    .into(),
+            ));
+        }
+        None => {}
+    }
+
+    // Through an SSH tunnel the host is 127.0.0.1: verify the certificate
+    // against the database's real hostname instead.
+    if let Some(name) = tls.server_name.clone() {
+        opts = opts.with_danger_tls_hostname_override(Some(name));
+    }
+
+    Ok(opts)
+}
+
+/// Checks a connection out of the pool (opening one if needed).
+pub async fn get_conn(pool: &Pool) -> Result<Conn> {
+    get_conn_within(pool, GET_CONN_TIMEOUT).await
+}
+
+pub async fn get_conn_within(pool: &Pool, timeout: Duration) -> Result<Conn> {
+    match tokio::time::timeout(timeout, pool.get_conn()).await {
+        Ok(conn) => Ok(conn?),
+        Err(_) => Err(Error::ConnectionTimeout),
+    }
+}
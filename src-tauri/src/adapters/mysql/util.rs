@@ -0,0 +1,199 @@
+use mysql_async::consts::{ColumnFlags, ColumnType};
+use mysql_async::prelude::Queryable;
+use mysql_async::{Column, Conn, Row, Value};
+
+use crate::error::{Error, Result};
+
+/// Character set id MySQL reports for binary data (`BINARY`, `VARBINARY`,
+/// `BLOB`) — and also for numbers and temporals, so it only means "bytes"
+/// on string-family types.
+const BINARY_CHARSET: u16 = 63;
+
+/// Safely quotes a MySQL identifier (prevents SQL injection on names).
+pub fn quote_ident(ident: &str) -> String {
+    format!("`{}`", ident.replace('`', "``"))
+}
+
+/// Friendly lowercase type name for a result-set column, close to what
+/// `information_schema.COLUMNS.DATA_TYPE` reports for the same column.
+pub fn column_type_name(column: &Column) -> String {
+    use ColumnType::*;
+
+    let flags = column.flags();
+    let binary = column.character_set() == BINARY_CHARSET;
+    let unsigned = flags.contains(ColumnFlags::UNSIGNED_FLAG);
+
+    let name = match column.column_type() {
+        MYSQL_TYPE_DECIMAL | MYSQL_TYPE_NEWDECIMAL => "decimal",
+        MYSQL_TYPE_TINY => "tinyint",
+        MYSQL_TYPE_SHORT => "smallint",
+        MYSQL_TYPE_INT24 => "mediumint",
+        MYSQL_TYPE_LONG => "int",
+        MYSQL_TYPE_LONGLONG => "bigint",
+        MYSQL_TYPE_FLOAT => "float",
+        MYSQL_TYPE_DOUBLE => "double",
+        MYSQL_TYPE_NULL => "null",
+        MYSQL_TYPE_TIMESTAMP | MYSQL_TYPE_TIMESTAMP2 => "timestamp",
+        MYSQL_TYPE_DATE | MYSQL_TYPE_NEWDATE => "date",
+        MYSQL_TYPE_TIME | MYSQL_TYPE_TIME2 => "time",
+        MYSQL_TYPE_DATETIME | MYSQL_TYPE_DATETIME2 => "datetime",
+        MYSQL_TYPE_YEAR => "year",
+        MYSQL_TYPE_BIT => "bit",
+        MYSQL_TYPE_JSON => "json",
+        MYSQL_TYPE_GEOMETRY => "geometry",
+        MYSQL_TYPE_VECTOR => "vector",
+        MYSQL_TYPE_ENUM => "enum",
+        MYSQL_TYPE_SET => "set",
+        // ENUM/SET columns travel as strings, flagged
+        _ if flags.contains(ColumnFlags::ENUM_FLAG) => "enum",
+        _ if flags.contains(ColumnFlags::SET_FLAG) => "set",
+        MYSQL_TYPE_TINY_BLOB | MYSQL_TYPE_MEDIUM_BLOB | MYSQL_TYPE_LONG_BLOB | MYSQL_TYPE_BLOB => {
+            if binary {
+                "blob"
+            } else {
+                "text"
+            }
+        }
+        MYSQL_TYPE_VARCHAR | MYSQL_TYPE_VAR_STRING => {
+            if binary {
+                "varbinary"
+            } else {
+                "varchar"
+            }
+        }
+        MYSQL_TYPE_STRING => {
+            if binary {
+                "binary"
+            } else {
+                "char"
+            }
+        }
+        _ => "unknown",
+    };
+
+    if unsigned && column.column_type().is_numeric_type() {
+        format!("{name} unsigned")
+    } else {
+        name.to_string()
+    }
+}
+
+/// Whether values of this column are raw bytes rather than text.
+fn is_binary_column(column: &Column) -> bool {
+    use ColumnType::*;
+
+    column.character_set() == BINARY_CHARSET
+        && matches!(
+            column.column_type(),
+            MYSQL_TYPE_TINY_BLOB
+                | MYSQL_TYPE_MEDIUM_BLOB
+                | MYSQL_TYPE_LONG_BLOB
+                | MYSQL_TYPE_BLOB
+                | MYSQL_TYPE_VARCHAR
+                | MYSQL_TYPE_VAR_STRING
+                | MYSQL_TYPE_STRING
+                | MYSQL_TYPE_GEOMETRY
+        )
+}
+
+/// Render a MySQL value as text. Queries go over the text protocol, so most
+/// values already arrive as the server's own rendering; binary strings are
+/// shown as hex (like Postgres `bytea`) and `BIT` as its integer value.
+pub fn value_to_string(column: &Column, value: Value) -> Option<String> {
+    match value {
+        Value::NULL => None,
+        Value::Bytes(bytes) if column.column_type() == ColumnType::MYSQL_TYPE_BIT => Some(
+            bytes
+                .iter()
+                .fold(0u64, |acc, b| (acc << 8) | u64::from(*b))
+                .to_string(),
+        ),
+        Value::Bytes(bytes) if is_binary_column(column) => {
+            Some(format!("\\x{}", hex::encode(bytes)))
+        }
+        Value::Bytes(bytes) => Some(String::from_utf8_lossy(&bytes).into_owned()),
+        Value::Int(v) => Some(v.to_string()),
+        Value::UInt(v) => Some(v.to_string()),
+        Value::Float(v) => Some(v.to_string()),
+        Value::Double(v) => Some(v.to_string()),
+        // Temporals only come back typed from the binary protocol; render
+        // them the way the text protocol would.
+        other => Some(other.as_sql(true).trim_matches('\'').to_string()),
+    }
+}
+
+/// Text values of a result set, in column order.
+pub fn rows_to_strings(columns: &[Column], rows: Vec<Row>) -> Vec<Vec<Option<String>>> {
+    rows.into_iter()
+        .map(|row| row_to_strings(columns, row))
+        .collect()
+}
+
+pub fn row_to_strings(columns: &[Column], row: Row) -> Vec<Option<String>> {
+    row.unwrap()
+        .into_iter()
+        .zip(columns)
+        .map(|(value, column)| value_to_string(column, value))
+        .collect()
+}
+
+/// One row of `information_schema.COLUMNS`, in declaration order.
+pub struct ColumnMeta {
+    pub name: String,
+    /// `DATA_TYPE` (e.g. `int`, `varchar`)
+    pub data_type: String,
+    /// `COLUMN_TYPE` with length/precision/unsigned (e.g. `int(10) unsigned`)
+    pub column_type: String,
+    pub is_nullable: bool,
+    pub default_value: Option<String>,
+    pub is_primary_key: bool,
+}
+
+/// Fetches the ordered column metadata for a given table or view.
+pub async fn get_columns_ordered(
+    conn: &mut Conn,
+    schema: &str,
+    table: &str,
+) -> Result<Vec<ColumnMeta>> {
+    let rows: Vec<(String, String, String, String, Option<String>, String)> = conn
+        .exec(
+            "SELECT COLUMN_NAME, DATA_TYPE, COLUMN_TYPE, IS_NULLABLE, COLUMN_DEFAULT, COLUMN_KEY \
+             FROM information_schema.COLUMNS \
+             WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ? \
+             ORDER BY ORDINAL_POSITION",
+            (schema, table),
+        )
+        .await?;
+
+    if rows.is_empty() {
+        return Err(Error::InvalidQuery(format!(
+            "Table {schema}.{table} not found or has no columns"
+        )));
+    }
+
+    Ok(rows
+        .into_iter()
+        .map(
+            |(name, data_type, column_type, nullable, default_value, key)| ColumnMeta {
+                name,
+                data_type,
+                column_type,
+                is_nullable: nullable == "YES",
+                default_value,
+                is_primary_key: key == "PRI",
+            },
+        )
+        .collect())
+}
+
+/// Primary-key column names in key order, or empty if the table has none.
+pub async fn get_pk_columns(conn: &mut Conn, schema: &str, table: &str) -> Result<Vec<String>> {
+    Ok(conn
+        .exec(
+            "SELECT COLUMN_NAME FROM information_schema.KEY_COLUMN_USAGE \
+             WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ? AND CONSTRAINT_NAME = 'PRIMARY' \
+             ORDER BY ORDINAL_POSITION",
+            (schema, table),
+        )
+        .await?)
+}
@@ -0,0 +1,195 @@
+use crate::models::{ObjectKind, ObjectMatch, ObjectSearchRequest};
+
+/// How many candidates an adapter pulls before ranking: the pattern the
+/// database filters with only says the letters appear in order, so the
+/// shortest names are kept (they rank best).
+pub const MAX_SEARCH_CANDIDATES: usize = 5000;
+
+/// Fuzzy score of `candidate` for `query`, case-insensitive; `None` when the
+/// letters of `query` don't all appear in it, in order. Exact names rank
+/// first, then prefixes, substrings and finally scattered letters, with
+/// bonuses for matches at word starts (`user_id`, `userId`).
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<u32> {
+    let query: Vec<char> = query.trim().chars().map(lower).collect();
+    let original: Vec<char> = candidate.chars().collect();
+    let name: Vec<char> = original.iter().copied().map(lower).collect();
+    if query.is_empty() || query.len() > name.len() {
+        return None;
+    }
+
+    let extra = (name.len() - query.len()).min(99) as u32;
+    if name == query {
+        return Some(1000);
+    }
+    if name.starts_with(&query) {
+        return Some(900 - extra);
+    }
+    if let Some(at) = name
+        .windows(query.len())
+        .position(|w| w == query.as_slice())
+    {
+        let bonus = if is_word_start(&original, at) { 50 } else { 0 };
+        return Some(700 + bonus - (at as u32).min(99) - extra / 2);
+    }
+
+    let mut score: i64 = 300;
+    let mut next = 0;
+    let mut previous: Option<usize> = None;
+    for (i, c) in name.iter().enumerate() {
+        if next == query.len() {
+            break;
+        }
+        if *c != query[next] {
+            continue;
+        }
+        if is_word_start(&original, i) {
+            score += 15;
+        }
+        match previous {
+            Some(p) if p + 1 == i => score += 10,
+            Some(p) => score -= (i - p - 1).min(10) as i64,
+            None => score -= i.min(10) as i64,
+        }
+        previous = Some(i);
+        next += 1;
+    }
+    (next == query.len()).then(|| score.clamp(1, 499) as u32)
+}
+
+/// Score `candidates` by name, keep the matches, best first (tables before
+/// their columns on ties), at most `limit`.
+pub fn rank_matches(
+    request: &ObjectSearchRequest,
+    candidates: impl IntoIterator<Item = ObjectMatch>,
+) -> Vec<ObjectMatch> {
+    let mut matches: Vec<ObjectMatch> = candidates
+        .into_iter()
+        .filter(|candidate| request.wants(candidate.kind))
+        .filter_map(|mut candidate| {
+            candidate.score = fuzzy_score(&request.query, &candidate.name)?;
+            Some(candidate)
+        })
+        .collect();
+
+    matches.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then_with(|| kind_order(a.kind).cmp(&kind_order(b.kind)))
+            .then_with(|| a.name.len().cmp(&b.name.len()))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    matches.truncate(request.limit);
+    matches
+}
+
+/// `%u%s%r%` for `ILIKE ... ESCAPE '\'`: the database-side prefilter with
+/// the same "letters in order" rule as [`fuzzy_score`].
+pub fn like_subsequence(query: &str) -> String {
+    let mut pattern = String::from("%");
+    for c in query.trim().chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+        pattern.push('%');
+    }
+    pattern
+}
+
+/// `*u*s*r*` for Redis `SCAN MATCH`. The glob is case-sensitive, so each
+/// letter becomes a `[uU]` class.
+pub fn glob_subsequence(query: &str) -> String {
+    let mut pattern = String::from("*");
+    for c in query.trim().chars() {
+        let (low, up) = (lower(c), c.to_uppercase().next().unwrap_or(c));
+        if low != up {
+            pattern.push_str(&format!("[{low}{up}]"));
+        } else {
+            if matches!(c, '*' | '?' | '[' | ']' | '\\') {
+                pattern.push('\\');
+            }
+            pattern.push(c);
+        }
+        pattern.push('*');
+    }
+    pattern
+}
+
+fn lower(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+fn is_word_start(name: &[char], at: usize) -> bool {
+    at == 0
+        || matches!(name[at - 1], '_' | '-' | '.' | ':' | ' ' | '/')
+        || (name[at - 1].is_lowercase() && name[at].is_uppercase())
+}
+
+fn kind_order(kind: ObjectKind) -> u8 {
+    match kind {
+        ObjectKind::Table | ObjectKind::Collection | ObjectKind::KeyGroup => 0,
+        ObjectKind::View => 1,
+        ObjectKind::Function => 2,
+        ObjectKind::Column => 3,
+        ObjectKind::Index => 4,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(kind: ObjectKind, name: &str) -> ObjectMatch {
+        ObjectMatch {
+            kind,
+            schema: None,
+            parent: None,
+            name: name.into(),
+            detail: None,
+            score: 0,
+        }
+    }
+
+    #[test]
+    fn exact_beats_prefix_beats_substring_beats_scattered() {
+        let scores: Vec<Option<u32>> = ["users", "users_archive", "app_users", "u_se_rs", "orders"]
+            .iter()
+            .map(|name| fuzzy_score("Users", name))
+            .collect();
+
+        assert!(scores[0] > scores[1]);
+        assert!(scores[1] > scores[2]);
+        assert!(scores[2] > scores[3]);
+        assert_eq!(scores[4], None);
+        assert!(fuzzy_score("id", "user_id") > fuzzy_score("id", "valid_x"));
+        assert!(fuzzy_score("ui", "userId") > fuzzy_score("ui", "usxi"));
+    }
+
+    #[test]
+    fn ranking_filters_kinds_and_limits() {
+        let request = ObjectSearchRequest {
+            query: "ord".into(),
+            kinds: vec![ObjectKind::Table, ObjectKind::Column],
+            limit: 2,
+        };
+        let ranked = rank_matches(
+            &request,
+            [
+                candidate(ObjectKind::Column, "order_id"),
+                candidate(ObjectKind::Table, "orders"),
+                candidate(ObjectKind::Index, "orders_pkey"),
+                candidate(ObjectKind::Table, "customer_orders"),
+                candidate(ObjectKind::Table, "users"),
+            ],
+        );
+
+        let names: Vec<&str> = ranked.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, ["orders", "order_id"]);
+    }
+
+    #[test]
+    fn database_patterns_escape_wildcards() {
+        assert_eq!(like_subsequence("a_b"), "%a%\\_%b%");
+        assert_eq!(glob_subsequence("u:1"), "*[uU]*:*1*");
+    }
+}
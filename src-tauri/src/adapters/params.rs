@@ -0,0 +1,432 @@
+use std::ops::Range;
+
+use serde_json::Value;
+
+use crate::error::{Error, Result};
+use crate::models::{QueryParam, QueryParamInfo};
+
+/// Where placeholders may appear, which decides what the scanner skips.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParamSyntax {
+    /// Postgres SQL: strings (`'...'`, `E'...'`, `$tag$...$tag$`), quoted
+    /// identifiers and comments are skipped, and `::` is a cast.
+    Sql,
+    /// Mongo shell commands: JSON5 strings are skipped, and a placeholder
+    /// only counts in a value position (after `:`, `,`, `[` or `(`), so the
+    /// `key:` of `{key:true}` is never read as one.
+    Mongo,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Placeholder {
+    Named(String),
+    Positional(usize),
+}
+
+/// The placeholders of `query` (`:name` or `$1`), each once, in order of
+/// first appearance: what the UI should prompt for.
+pub fn detect_params(syntax: ParamSyntax, query: &str) -> Vec<QueryParamInfo> {
+    let mut found: Vec<QueryParamInfo> = Vec::new();
+    for (_, placeholder) in scan(syntax, query) {
+        let (name, position) = match placeholder {
+            Placeholder::Named(name) => (Some(name), None),
+            Placeholder::Positional(n) => (None, Some(n)),
+        };
+        match found.iter_mut().find(|p| p.name == name && p.position == position) {
+            Some(existing) => existing.occurrences += 1,
+            None => found.push(QueryParamInfo {
+                name,
+                position,
+                occurrences: 1,
+            }),
+        }
+    }
+    found
+}
+
+/// A Postgres query with every placeholder as `$n` (named ones numbered in
+/// order of first appearance, a type hint appended as a cast) and the bind
+/// values in `$n` order, as text (`None` = NULL) for the server to parse.
+pub fn bind_sql(query: &str, params: &[QueryParam]) -> Result<(String, Vec<Option<String>>)> {
+    let placeholders = scan(ParamSyntax::Sql, query);
+    let names = named_order(&placeholders)?;
+
+    let mut sql = String::with_capacity(query.len());
+    let mut last = 0;
+    let mut values: Vec<Option<String>> = Vec::new();
+    for (range, placeholder) in &placeholders {
+        let (position, param) = match placeholder {
+            Placeholder::Named(name) => {
+                let position = names.iter().position(|n| n == name).unwrap_or_default() + 1;
+                (position, named_param(params, name)?)
+            }
+            Placeholder::Positional(n) => (*n, positional_param(params, *n)?),
+        };
+        if values.len() < position {
+            values.resize(position, None);
+        }
+        values[position - 1] = sql_text(&param.value);
+
+        sql.push_str(&query[last..range.start]);
+        sql.push_str(&format!("${position}"));
+        if let Some(hint) = param.type_hint.as_deref() {
+            sql.push_str("::");
+            sql.push_str(validated_type(hint)?);
+        }
+        last = range.end;
+    }
+    sql.push_str(&query[last..]);
+
+    Ok((sql, values))
+}
+
+/// A Mongo command with each placeholder replaced by its value as JSON (so a
+/// string can never break out of its literal). Type hints map to extended
+/// JSON: `objectId`, `date`, `long`, `int`, `double`, `decimal`.
+pub fn bind_mongo(query: &str, params: &[QueryParam]) -> Result<String> {
+    let placeholders = scan(ParamSyntax::Mongo, query);
+    named_order(&placeholders)?;
+
+    let mut bound = String::with_capacity(query.len());
+    let mut last = 0;
+    for (range, placeholder) in &placeholders {
+        let param = match placeholder {
+            Placeholder::Named(name) => named_param(params, name)?,
+            Placeholder::Positional(n) => positional_param(params, *n)?,
+        };
+        bound.push_str(&query[last..range.start]);
+        bound.push_str(&mongo_value(param)?.to_string());
+        last = range.end;
+    }
+    bound.push_str(&query[last..]);
+
+    Ok(bound)
+}
+
+/// The command with every placeholder as `null`, for the checks that only
+/// need its shape (read or write, empty filter) before values are bound.
+pub fn without_mongo_params(query: &str) -> String {
+    let mut shaped = String::with_capacity(query.len());
+    let mut last = 0;
+    for (range, _) in scan(ParamSyntax::Mongo, query) {
+        shaped.push_str(&query[last..range.start]);
+        shaped.push_str("null");
+        last = range.end;
+    }
+    shaped.push_str(&query[last..]);
+    shaped
+}
+
+/// Distinct names in order of first appearance; a query mixing `:name` and
+/// `$1` is refused (there would be no sane numbering).
+fn named_order(placeholders: &[(Range<usize>, Placeholder)]) -> Result<Vec<String>> {
+    let mut names: Vec<String> = Vec::new();
+    let mut positional = false;
+    for (_, placeholder) in placeholders {
+        match placeholder {
+            Placeholder::Named(name) if !names.contains(name) => names.push(name.clone()),
+            Placeholder::Named(_) => {}
+            Placeholder::Positional(_) => positional = true,
+        }
+    }
+    if positional && !names.is_empty() {
+        return Err(Error::InvalidQuery(
+            "Use either named (:name) or positional ($1) parameters, not both".into(),
+        ));
+    }
+    Ok(names)
+}
+
+fn named_param<'a>(params: &'a [QueryParam], name: &str) -> Result<&'a QueryParam> {
+    params
+        .iter()
+        .find(|p| p.name.as_deref() == Some(name))
+        .ok_or_else(|| Error::InvalidQuery(format!("Missing value for parameter :{name}")))
+}
+
+/// `$n` takes the n-th parameter sent without a name.
+fn positional_param(params: &[QueryParam], n: usize) -> Result<&QueryParam> {
+    params
+        .iter()
+        .filter(|p| p.name.is_none())
+        .nth(n.saturating_sub(1))
+        .filter(|_| n > 0)
+        .ok_or_else(|| Error::InvalidQuery(format!("Missing value for parameter ${n}")))
+}
+
+/// Text the Postgres input function of the parameter's type will parse.
+/// Objects and arrays go as JSON (fine for json/jsonb; a Postgres array
+/// wants its literal as a string, e.g. `"{1,2}"`).
+fn sql_text(value: &Value) -> Option<String> {
+    match value {
+        Value::Null => None,
+        Value::String(s) => Some(s.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+/// The hint is spliced into the SQL as a cast, so it must look like a type
+/// name (`int4`, `varchar(20)`, `timestamp with time zone`, `text[]`).
+fn validated_type(hint: &str) -> Result<&str> {
+    let hint = hint.trim();
+    let valid = !hint.is_empty()
+        && hint.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && hint
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || " _.,()[]".contains(c));
+    if !valid {
+        return Err(Error::InvalidQuery(format!("Invalid parameter type '{hint}'")));
+    }
+    Ok(hint)
+}
+
+fn mongo_value(param: &QueryParam) -> Result<Value> {
+    let value = &param.value;
+    let Some(hint) = param.type_hint.as_deref() else {
+        return Ok(value.clone());
+    };
+    if value.is_null() {
+        return Ok(Value::Null);
+    }
+    // $numberLong & cia. querem o número como string.
+    let text = match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    let wrapper = match hint {
+        "objectId" => "$oid",
+        "date" => "$date",
+        "long" => "$numberLong",
+        "int" => "$numberInt",
+        "double" => "$numberDouble",
+        "decimal" => "$numberDecimal",
+        other => {
+            return Err(Error::InvalidQuery(format!(
+                "Invalid parameter type '{other}' (objectId, date, long, int, double, decimal)"
+            )))
+        }
+    };
+    // Um `$date` numérico são milissegundos desde a época, não string.
+    if hint == "date" && value.is_number() {
+        return Ok(serde_json::json!({ wrapper: { "$numberLong": text } }));
+    }
+    Ok(serde_json::json!({ wrapper: text }))
+}
+
+/// Byte ranges of the placeholders outside literals and comments.
+fn scan(syntax: ParamSyntax, query: &str) -> Vec<(Range<usize>, Placeholder)> {
+    let bytes = query.as_bytes();
+    let mut found = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let c = bytes[i];
+        match (syntax, c) {
+            (ParamSyntax::Sql, b'\'') => {
+                let escapes = i > 0 && matches!(bytes[i - 1], b'e' | b'E') && !ident_before(bytes, i - 1);
+                i = skip_quoted(bytes, i, b'\'', escapes);
+            }
+            (ParamSyntax::Sql, b'"') => i = skip_quoted(bytes, i, b'"', false),
+            (ParamSyntax::Mongo, b'\'' | b'"') => i = skip_quoted(bytes, i, c, true),
+            (ParamSyntax::Sql, b'-') if bytes.get(i + 1) == Some(&b'-') => {
+                i = query[i..].find('\n').map_or(bytes.len(), |n| i + n + 1);
+            }
+            (ParamSyntax::Sql, b'/') if bytes.get(i + 1) == Some(&b'*') => {
+                i = skip_block_comment(bytes, i);
+            }
+            (ParamSyntax::Sql, b':') if bytes.get(i + 1) == Some(&b':') => i += 2,
+            (_, b':') if starts_ident(bytes.get(i + 1)) && value_position(syntax, bytes, i) => {
+                let end = ident_end(bytes, i + 1);
+                found.push((i..end, Placeholder::Named(query[i + 1..end].to_string())));
+                i = end;
+            }
+            (_, b'$') if bytes.get(i + 1).is_some_and(u8::is_ascii_digit) => {
+                let end = (i + 1..bytes.len())
+                    .find(|&j| !bytes[j].is_ascii_digit())
+                    .unwrap_or(bytes.len());
+                if value_position(syntax, bytes, i) && !ident_before(bytes, i) {
+                    let n = query[i + 1..end].parse().unwrap_or(0);
+                    found.push((i..end, Placeholder::Positional(n)));
+                }
+                i = end;
+            }
+            (ParamSyntax::Sql, b'$') if !ident_before(bytes, i) => {
+                i = skip_dollar_quoted(query, i);
+            }
+            _ => i += 1,
+        }
+    }
+    found
+}
+
+/// In SQL anything goes (`WHERE a = :a`, `(:a, :b)`); Mongo needs a value
+/// position, see [`ParamSyntax::Mongo`].
+fn value_position(syntax: ParamSyntax, bytes: &[u8], at: usize) -> bool {
+    match syntax {
+        ParamSyntax::Sql => !ident_before(bytes, at),
+        ParamSyntax::Mongo => bytes[..at]
+            .iter()
+            .rev()
+            .find(|b| !b.is_ascii_whitespace())
+            .is_some_and(|b| matches!(b, b':' | b',' | b'[' | b'(')),
+    }
+}
+
+fn ident_before(bytes: &[u8], at: usize) -> bool {
+    at > 0 && (bytes[at - 1].is_ascii_alphanumeric() || bytes[at - 1] == b'_')
+}
+
+fn starts_ident(byte: Option<&u8>) -> bool {
+    byte.is_some_and(|b| b.is_ascii_alphabetic() || *b == b'_')
+}
+
+fn ident_end(bytes: &[u8], start: usize) -> usize {
+    (start..bytes.len())
+        .find(|&j| !(bytes[j].is_ascii_alphanumeric() || bytes[j] == b'_'))
+        .unwrap_or(bytes.len())
+}
+
+/// Index after the closing quote. A doubled quote is an escaped one; with
+/// `escapes` a backslash escapes the next byte too.
+fn skip_quoted(bytes: &[u8], start: usize, quote: u8, escapes: bool) -> usize {
+    let mut i = start + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' if escapes => i += 2,
+            b if b == quote && bytes.get(i + 1) == Some(&quote) => i += 2,
+            b if b == quote => return i + 1,
+            _ => i += 1,
+        }
+    }
+    bytes.len()
+}
+
+/// Postgres block comments nest.
+fn skip_block_comment(bytes: &[u8], start: usize) -> usize {
+    let mut depth = 0;
+    let mut i = start;
+    while i + 1 < bytes.len() {
+        match (bytes[i], bytes[i + 1]) {
+            (b'/', b'*') => {
+                depth += 1;
+                i += 2;
+            }
+            (b'*', b'/') => {
+                depth -= 1;
+                i += 2;
+                if depth == 0 {
+                    return i;
+                }
+            }
+            _ => i += 1,
+        }
+    }
+    bytes.len()
+}
+
+/// `$tag$ ... $tag$` (or `$$ ... $$`); a lone `$` is just skipped.
+fn skip_dollar_quoted(query: &str, start: usize) -> usize {
+    let bytes = query.as_bytes();
+    let tag_end = if bytes.get(start + 1) == Some(&b'$') {
+        start + 1
+    } else if starts_ident(bytes.get(start + 1)) {
+        let end = ident_end(bytes, start + 1);
+        if bytes.get(end) != Some(&b'$') {
+            return start + 1;
+        }
+        end
+    } else {
+        return start + 1;
+    };
+
+    let tag = &query[start..=tag_end];
+    let body = tag_end + 1;
+    query[body..]
+        .find(tag)
+        .map_or(bytes.len(), |n| body + n + tag.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn named(name: &str, value: Value) -> QueryParam {
+        QueryParam {
+            name: Some(name.into()),
+            value,
+            type_hint: None,
+        }
+    }
+
+    #[test]
+    fn detects_sql_placeholders_outside_literals() {
+        let sql = "SELECT :id::text, ':skip', \"a:b\", $$ :body $$ -- :comment\n\
+                   FROM t /* :block */ WHERE x = :id OR y = :other";
+        let found = detect_params(ParamSyntax::Sql, sql);
+
+        let names: Vec<_> = found.iter().map(|p| p.name.as_deref().unwrap()).collect();
+        assert_eq!(names, ["id", "other"]);
+        assert_eq!(found[0].occurrences, 2);
+        assert!(detect_params(ParamSyntax::Sql, "SELECT a::int FROM t").is_empty());
+    }
+
+    #[test]
+    fn named_sql_params_become_numbered_binds() {
+        let params = [
+            named("id", json!(42)),
+            QueryParam {
+                type_hint: Some("date".into()),
+                ..named("since", json!("2024-01-01"))
+            },
+            named("unused", json!(null)),
+        ];
+        let (sql, values) = bind_sql(
+            "SELECT * FROM t WHERE id = :id AND created > :since OR parent = :id",
+            &params,
+        )
+        .unwrap();
+
+        assert_eq!(
+            sql,
+            "SELECT * FROM t WHERE id = $1 AND created > $2::date OR parent = $1"
+        );
+        assert_eq!(values, [Some("42".into()), Some("2024-01-01".into())]);
+
+        let missing = bind_sql("SELECT :nope", &params).unwrap_err();
+        assert!(missing.to_string().contains(":nope"));
+        assert!(bind_sql("SELECT :id, $1", &params).is_err());
+        let bad_hint = QueryParam {
+            type_hint: Some("int); DROP TABLE t; --".into()),
+            ..named("id", json!(1))
+        };
+        assert!(bind_sql("SELECT :id", &[bad_hint]).is_err());
+    }
+
+    #[test]
+    fn mongo_values_are_substituted_as_json() {
+        let params = [
+            named("status", json!("paid\", $where: \"1")),
+            QueryParam {
+                type_hint: Some("objectId".into()),
+                ..named("id", json!("65a1f0c2e4b0a1b2c3d4e5f6"))
+            },
+        ];
+        let bound = bind_mongo(
+            "db.orders.find({ status: :status, _id: :id, note: 'a :status', ok:true })",
+            &params,
+        )
+        .unwrap();
+
+        assert_eq!(
+            bound,
+            "db.orders.find({ status: \"paid\\\", $where: \\\"1\", \
+             _id: {\"$oid\":\"65a1f0c2e4b0a1b2c3d4e5f6\"}, note: 'a :status', ok:true })"
+        );
+        assert_eq!(
+            without_mongo_params("db.t.deleteMany({ a: :a, b: [$1] })"),
+            "db.t.deleteMany({ a: null, b: [null] })"
+        );
+    }
+}
@@ -8,63 +8,29 @@ use crate::models::{
     EditableInfo, QueryColumnInfo, QueryResult, SortDirection, SortSpec, TableDataRequest,
 };
 use sqlparser::dialect::PostgreSqlDialect;
-use sqlparser::parser::Parser;
-use sqlparser::tokenizer::Token;
 
-use super::executor::extract_text_rows_typed;
+use crate::adapters::{normalize_where_expr, RowSink};
+
+use super::executor::{extract_text_rows_typed, stream_cursor};
 use super::util::{get_columns_ordered, quote_ident};
 
-const DEFAULT_SCHEMA: &str = "public";
+pub(super) const DEFAULT_SCHEMA: &str = "public";
 
 pub async fn fetch_table_data(pool: &Pool, request: TableDataRequest) -> Result<QueryResult> {
     let client = pool.get().await?;
 
     let schema = request.schema.as_deref().unwrap_or(DEFAULT_SCHEMA);
-
-    // Fetch column metadata and PK columns in parallel (independent round-trips).
-    // Ordered column metadata also validates that the table exists and gives us
-    // the column names for rejecting unknown sort columns. PK columns drive the
-    // default ordering (stable pagination) and editability.
-    let (columns_meta, pk_columns) = tokio::join!(
-        get_columns_ordered(&client, schema, &request.table),
-        fetch_pk_columns(&client, schema, &request.table),
-    );
-    let columns_meta = columns_meta?;
-    let type_map: HashMap<String, String> = columns_meta
-        .iter()
-        .map(|c| (c.name.clone(), c.format_type.clone()))
-        .collect();
-
-    let clauses = build_clauses(&request, &type_map, &pk_columns)?;
-
-    let base = format!(
-        "FROM {}.{}{}",
-        quote_ident(schema),
-        quote_ident(&request.table),
-        clauses.where_clause,
-    );
-
-    // Cast every column to text in SQL so Postgres does all type→text rendering
-    // (handles jsonb/numeric/uuid/arrays/enums) and Rust only reads strings.
-    // The cast happens in an OUTER layer: the inner query keeps the real typed
-    // columns so WHERE/ORDER BY operate on them (e.g. numeric sort, not text).
-    let select_list = columns_meta
-        .iter()
-        .map(|c| {
-            let ident = quote_ident(&c.name);
-            format!("{ident}::text AS {ident}")
-        })
-        .collect::<Vec<_>>()
-        .join(", ");
+    let plan = plan_table_select(&client, schema, &request).await?;
+    let base = &plan.base;
 
     // +1 row to detect has_more without a second query
     let inner = format!(
         "SELECT * {base}{} LIMIT {} OFFSET {}",
-        clauses.order_clause,
+        plan.order_clause,
         request.limit + 1,
         request.offset,
     );
-    let select = format!("SELECT {select_list} FROM ({inner}) AS __q");
+    let select = format!("SELECT {} FROM ({inner}) AS __q", plan.select_list);
 
     // Run the data query and the optional COUNT(*) pipelined on the same
     // connection so the count scan overlaps the fetch instead of running after
@@ -97,22 +63,14 @@ pub async fn fetch_table_data(pool: &Pool, request: TableDataRequest) -> Result<
         &rows[..]
     };
 
-    let columns: Vec<QueryColumnInfo> = columns_meta
-        .iter()
-        .map(|c| QueryColumnInfo {
-            name: c.name.clone(),
-            type_name: c.type_name.clone(),
-            type_oid: Some(c.type_oid),
-        })
-        .collect();
     let result_rows = extract_text_rows_typed(rows_to_process);
 
     let editable_info =
-        detect_editable_info(schema, &request.table, &pk_columns, &columns);
+        detect_editable_info(schema, &request.table, &plan.pk_columns, &plan.columns);
 
     Ok(QueryResult {
         row_count: rows_to_process.len(),
-        columns,
+        columns: plan.columns,
         rows: result_rows,
         total_count,
         has_more,
@@ -121,6 +79,95 @@ pub async fn fetch_table_data(pool: &Pool, request: TableDataRequest) -> Result<
     })
 }
 
+/// Every row of the browse (same filter and order), through a cursor.
+pub async fn stream_table_data(
+    pool: &Pool,
+    request: TableDataRequest,
+    sink: &mut dyn RowSink,
+) -> Result<u64> {
+    let mut client = pool.get().await?;
+
+    let schema = request.schema.as_deref().unwrap_or(DEFAULT_SCHEMA);
+    let plan = plan_table_select(&client, schema, &request).await?;
+
+    let select = format!(
+        "SELECT {} FROM (SELECT * {}{}) AS __q",
+        plan.select_list, plan.base, plan.order_clause
+    );
+    stream_cursor(&mut client, &select, &plan.columns, sink).await
+}
+
+/// The parts of a browse query shared by the paged fetch and the stream.
+struct TableSelect {
+    columns: Vec<QueryColumnInfo>,
+    pk_columns: Vec<String>,
+    /// `FROM schema.table[ WHERE (...)]`
+    base: String,
+    /// Leading-space `" ORDER BY ..."` or empty
+    order_clause: String,
+    /// Every column cast to text, in table order
+    select_list: String,
+}
+
+async fn plan_table_select(
+    client: &deadpool_postgres::Object,
+    schema: &str,
+    request: &TableDataRequest,
+) -> Result<TableSelect> {
+    // Fetch column metadata and PK columns in parallel (independent round-trips).
+    // Ordered column metadata also validates that the table exists and gives us
+    // the column names for rejecting unknown sort columns. PK columns drive the
+    // default ordering (stable pagination) and editability.
+    let (columns_meta, pk_columns) = tokio::join!(
+        get_columns_ordered(client, schema, &request.table),
+        fetch_pk_columns(client, schema, &request.table),
+    );
+    let columns_meta = columns_meta?;
+    let type_map: HashMap<String, String> = columns_meta
+        .iter()
+        .map(|c| (c.name.clone(), c.format_type.clone()))
+        .collect();
+
+    let clauses = build_clauses(request, &type_map, &pk_columns)?;
+
+    let base = format!(
+        "FROM {}.{}{}",
+        quote_ident(schema),
+        quote_ident(&request.table),
+        clauses.where_clause,
+    );
+
+    // Cast every column to text in SQL so Postgres does all type→text rendering
+    // (handles jsonb/numeric/uuid/arrays/enums) and Rust only reads strings.
+    // The cast happens in an OUTER layer: the inner query keeps the real typed
+    // columns so WHERE/ORDER BY operate on them (e.g. numeric sort, not text).
+    let select_list = columns_meta
+        .iter()
+        .map(|c| {
+            let ident = quote_ident(&c.name);
+            format!("{ident}::text AS {ident}")
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let columns = columns_meta
+        .iter()
+        .map(|c| QueryColumnInfo {
+            name: c.name.clone(),
+            type_name: c.type_name.clone(),
+            type_oid: Some(c.type_oid),
+        })
+        .collect();
+
+    Ok(TableSelect {
+        columns,
+        pk_columns,
+        base,
+        order_clause: clauses.order_clause,
+        select_list,
+    })
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 // SQL building (pure, unit-testable)
 // ─────────────────────────────────────────────────────────────────────────────
@@ -138,7 +185,8 @@ fn build_clauses(
     type_map: &HashMap<String, String>,
     pk_columns: &[String],
 ) -> Result<BuiltClauses> {
-    let where_clause = match normalize_where_expr(request.where_expr.as_deref())? {
+    let where_expr = normalize_where_expr(request.where_expr.as_deref(), &PostgreSqlDialect {})?;
+    let where_clause = match where_expr {
         Some(expr) => format!(" WHERE ({expr})"),
         None => String::new(),
     };
@@ -151,52 +199,6 @@ fn build_clauses(
     })
 }
 
-/// Parse `raw` as a single SQL expression. Empty/whitespace → `None`.
-/// A leading `WHERE` keyword is stripped so the input can match the UI label.
-fn normalize_where_expr(raw: Option<&str>) -> Result<Option<String>> {
-    let Some(raw) = raw else {
-        return Ok(None);
-    };
-    let trimmed = raw.trim();
-    if trimmed.is_empty() {
-        return Ok(None);
-    }
-
-    let expr = strip_leading_where(trimmed);
-    if expr.is_empty() {
-        return Ok(None);
-    }
-
-    let dialect = PostgreSqlDialect {};
-    let mut parser = Parser::new(&dialect)
-        .try_with_sql(expr)
-        .map_err(|e| Error::InvalidQuery(format!("Invalid WHERE expression: {e}")))?;
-    parser
-        .parse_expr()
-        .map_err(|e| Error::InvalidQuery(format!("Invalid WHERE expression: {e}")))?;
-
-    if !matches!(parser.peek_token().token, Token::EOF) {
-        return Err(Error::InvalidQuery(
-            "Filter must be a single WHERE expression".into(),
-        ));
-    }
-
-    Ok(Some(expr.to_string()))
-}
-
-fn strip_leading_where(s: &str) -> &str {
-    let trimmed = s.trim_start();
-    let Some(rest) = trimmed.get(5..) else {
-        return trimmed;
-    };
-    if trimmed[..5].eq_ignore_ascii_case("where")
-        && (rest.is_empty() || rest.starts_with(char::is_whitespace))
-    {
-        return rest.trim_start();
-    }
-    trimmed
-}
-
 fn build_order_clause(
     sort: &[SortSpec],
     type_map: &HashMap<String, String>,
@@ -441,8 +443,11 @@ mod tests {
             username: "postgres".into(),
             password: "1234".into(),
             default_database: None,
-            ssl_enabled: false,
+            tls: Default::default(),
             connection_uri: None,
+            ssh_tunnel: None,
+            read_only: false,
+            confirm_writes: false,
             created_at: 0,
         };
 
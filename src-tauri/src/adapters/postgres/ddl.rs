@@ -0,0 +1,729 @@
+use deadpool_postgres::{Object, Pool};
+
+use crate::error::{Error, Result};
+use crate::models::{DdlObjectKind, DdlRequest};
+
+use super::browse::DEFAULT_SCHEMA;
+use super::util::quote_literal;
+
+/// A `pg_class` entry, with its name already quoted as `schema.name`.
+struct Relation {
+    oid: u32,
+    qualified: String,
+}
+
+/// Rebuild the object's DDL from pg_catalog. Statements come separated by a
+/// blank line, in an order that can be replayed: a table's owned sequences
+/// come before it, foreign keys, indexes, triggers, partitions and comments
+/// after.
+pub async fn get_object_ddl(pool: &Pool, request: &DdlRequest) -> Result<String> {
+    let client = pool.get().await?;
+    let schema = request.schema.as_deref().unwrap_or(DEFAULT_SCHEMA);
+    let name = request.name.as_str();
+
+    let statements = match request.kind {
+        DdlObjectKind::Table => table_ddl(&client, schema, name).await?,
+        DdlObjectKind::View => view_ddl(&client, schema, name, false).await?,
+        DdlObjectKind::MaterializedView => view_ddl(&client, schema, name, true).await?,
+        DdlObjectKind::Index => index_ddl(&client, schema, name).await?,
+        DdlObjectKind::Function => {
+            function_ddl(&client, schema, name, request.arguments.as_deref()).await?
+        }
+        DdlObjectKind::Sequence => {
+            let relation = relation(&client, schema, name, &["S"], "Sequence").await?;
+            sequence_ddl(&client, relation.oid).await?
+        }
+        DdlObjectKind::Trigger => {
+            trigger_ddl(&client, schema, name, request.table.as_deref()).await?
+        }
+        DdlObjectKind::Type => type_ddl(&client, schema, name).await?,
+        DdlObjectKind::Collection => {
+            return Err(Error::UnsupportedType(
+                "Collections only exist in MongoDB".into(),
+            ))
+        }
+    };
+    Ok(statements.join("\n\n") + "\n")
+}
+
+async fn relation(
+    client: &Object,
+    schema: &str,
+    name: &str,
+    kinds: &[&str],
+    label: &str,
+) -> Result<Relation> {
+    let row = client
+        .query_opt(
+            "SELECT c.oid, format('%I.%I', n.nspname, c.relname), c.relkind::text \
+             FROM pg_class c \
+             JOIN pg_namespace n ON n.oid = c.relnamespace \
+             WHERE n.nspname = $1 AND c.relname = $2",
+            &[&schema, &name],
+        )
+        .await?;
+
+    match row {
+        Some(row) if kinds.contains(&row.get::<_, &str>(2)) => Ok(Relation {
+            oid: row.get(0),
+            qualified: row.get(1),
+        }),
+        _ => Err(Error::NotFound(format!("{label} {schema}.{name}"))),
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tables
+// ─────────────────────────────────────────────────────────────────────────────
+
+async fn table_ddl(client: &Object, schema: &str, name: &str) -> Result<Vec<String>> {
+    let table = relation(client, schema, name, &["r", "p"], "Table").await?;
+    let oid = table.oid;
+    let q = &table.qualified;
+
+    let info = client
+        .query_one(
+            "SELECT c.relpersistence::text, pg_get_partkeydef(c.oid), c.relispartition, \
+                    pg_get_expr(c.relpartbound, c.oid), \
+                    (SELECT string_agg(format('%I.%I', pn.nspname, p.relname), ', ' \
+                                       ORDER BY i.inhseqno) \
+                     FROM pg_inherits i \
+                     JOIN pg_class p ON p.oid = i.inhparent \
+                     JOIN pg_namespace pn ON pn.oid = p.relnamespace \
+                     WHERE i.inhrelid = c.oid), \
+                    array_to_string(c.reloptions, ', '), \
+                    obj_description(c.oid, 'pg_class') \
+             FROM pg_class c WHERE c.oid = $1",
+            &[&oid],
+        )
+        .await?;
+    let persistence: &str = info.get(0);
+    let partition_key: Option<String> = info.get(1);
+    let is_partition: bool = info.get(2);
+    let partition_bound: Option<String> = info.get(3);
+    let parents: Option<String> = info.get(4);
+    let options: Option<String> = info.get(5);
+    let table_comment: Option<String> = info.get(6);
+
+    let mut statements = Vec::new();
+
+    // serial: the sequence has to exist before the column default uses it.
+    let owned = client
+        .query(
+            "SELECT s.oid, format('%I.%I', sn.nspname, s.relname), format('%I', a.attname) \
+             FROM pg_depend d \
+             JOIN pg_class s ON s.oid = d.objid AND s.relkind = 'S' \
+             JOIN pg_namespace sn ON sn.oid = s.relnamespace \
+             JOIN pg_attribute a ON a.attrelid = d.refobjid AND a.attnum = d.refobjsubid \
+             WHERE d.refobjid = $1 AND d.classid = 'pg_class'::regclass AND d.deptype = 'a' \
+             ORDER BY s.relname",
+            &[&oid],
+        )
+        .await?;
+    for row in &owned {
+        statements.extend(sequence_ddl(client, row.get(0)).await?);
+    }
+
+    let columns = client
+        .query(
+            "SELECT format('%I', a.attname), format_type(a.atttypid, a.atttypmod), \
+                    a.attnotnull, pg_get_expr(d.adbin, d.adrelid), a.attidentity::text, \
+                    a.attgenerated::text, \
+                    CASE WHEN a.attcollation <> t.typcollation \
+                         THEN format('%I.%I', cn.nspname, co.collname) END, \
+                    col_description(a.attrelid, a.attnum), a.attislocal \
+             FROM pg_attribute a \
+             JOIN pg_type t ON t.oid = a.atttypid \
+             LEFT JOIN pg_attrdef d ON d.adrelid = a.attrelid AND d.adnum = a.attnum \
+             LEFT JOIN pg_collation co ON co.oid = a.attcollation \
+             LEFT JOIN pg_namespace cn ON cn.oid = co.collnamespace \
+             WHERE a.attrelid = $1 AND a.attnum > 0 AND NOT a.attisdropped \
+             ORDER BY a.attnum",
+            &[&oid],
+        )
+        .await?;
+
+    let constraints = client
+        .query(
+            "SELECT format('%I', conname), contype::text, pg_get_constraintdef(oid, true) \
+             FROM pg_constraint \
+             WHERE conrelid = $1 AND conislocal AND conparentid = 0 AND contype <> 'n' \
+             ORDER BY CASE contype WHEN 'p' THEN 0 WHEN 'u' THEN 1 WHEN 'c' THEN 2 \
+                                   WHEN 'x' THEN 3 ELSE 4 END, conname",
+            &[&oid],
+        )
+        .await?;
+
+    let mut lines: Vec<String> = Vec::new();
+    if !is_partition {
+        for column in columns.iter().filter(|c| c.get::<_, bool>(8)) {
+            lines.push(column_line(column));
+        }
+    }
+    let mut foreign_keys = Vec::new();
+    for constraint in &constraints {
+        let (name, kind, definition): (String, &str, String) =
+            (constraint.get(0), constraint.get(1), constraint.get(2));
+        if kind == "f" {
+            foreign_keys.push(format!(
+                "ALTER TABLE {q} ADD CONSTRAINT {name} {definition};"
+            ));
+        } else {
+            lines.push(format!("CONSTRAINT {name} {definition}"));
+        }
+    }
+
+    let unlogged = if persistence == "u" { "UNLOGGED " } else { "" };
+    let mut create = match (is_partition, parents.as_deref()) {
+        (true, Some(parent)) => {
+            let mut create = format!("CREATE {unlogged}TABLE {q} PARTITION OF {parent}");
+            if !lines.is_empty() {
+                create.push_str(&format!(" (\n    {}\n)", lines.join(",\n    ")));
+            }
+            create.push_str(&format!(" {}", partition_bound.unwrap_or_default()));
+            create
+        }
+        (_, parents) => {
+            let mut create = format!(
+                "CREATE {unlogged}TABLE {q} (\n    {}\n)",
+                lines.join(",\n    ")
+            );
+            if let Some(parents) = parents {
+                create.push_str(&format!("\nINHERITS ({parents})"));
+            }
+            create
+        }
+    };
+    if let Some(key) = &partition_key {
+        create.push_str(&format!("\nPARTITION BY {key}"));
+    }
+    if let Some(options) = options.filter(|o| !o.is_empty()) {
+        create.push_str(&format!("\nWITH ({options})"));
+    }
+    create.push(';');
+    statements.push(create);
+    statements.extend(foreign_keys);
+
+    for row in &owned {
+        let (sequence, column): (String, String) = (row.get(1), row.get(2));
+        statements.push(format!("ALTER SEQUENCE {sequence} OWNED BY {q}.{column};"));
+    }
+
+    if partition_key.is_some() {
+        let partitions = client
+            .query(
+                "SELECT format('%I.%I', n.nspname, c.relname), \
+                        pg_get_expr(c.relpartbound, c.oid), pg_get_partkeydef(c.oid) \
+                 FROM pg_inherits i \
+                 JOIN pg_class c ON c.oid = i.inhrelid \
+                 JOIN pg_namespace n ON n.oid = c.relnamespace \
+                 WHERE i.inhparent = $1 \
+                 ORDER BY c.relname",
+                &[&oid],
+            )
+            .await?;
+        for partition in &partitions {
+            let (child, bound): (String, String) = (partition.get(0), partition.get(1));
+            let sub_key: Option<String> = partition.get(2);
+            let sub_key = sub_key
+                .map(|key| format!(" PARTITION BY {key}"))
+                .unwrap_or_default();
+            statements.push(format!(
+                "CREATE TABLE {child} PARTITION OF {q} {bound}{sub_key};"
+            ));
+        }
+    }
+
+    statements.extend(index_statements(client, oid).await?);
+
+    let triggers = client
+        .query(
+            "SELECT pg_get_triggerdef(oid, true) FROM pg_trigger \
+             WHERE tgrelid = $1 AND NOT tgisinternal ORDER BY tgname",
+            &[&oid],
+        )
+        .await?;
+    statements.extend(
+        triggers
+            .iter()
+            .map(|t| format!("{};", t.get::<_, String>(0))),
+    );
+
+    push_comment(&mut statements, &format!("TABLE {q}"), table_comment);
+    for column in &columns {
+        let name: String = column.get(0);
+        push_comment(
+            &mut statements,
+            &format!("COLUMN {q}.{name}"),
+            column.get(7),
+        );
+    }
+    Ok(statements)
+}
+
+/// `name type [COLLATE] [DEFAULT | GENERATED ...] [NOT NULL]`, from a row of
+/// the column query in [`table_ddl`].
+fn column_line(column: &tokio_postgres::Row) -> String {
+    let name: String = column.get(0);
+    let data_type: String = column.get(1);
+    let not_null: bool = column.get(2);
+    let default: Option<String> = column.get(3);
+    let identity: &str = column.get(4);
+    let generated: &str = column.get(5);
+    let collation: Option<String> = column.get(6);
+
+    let mut line = format!("{name} {data_type}");
+    if let Some(collation) = collation {
+        line.push_str(&format!(" COLLATE {collation}"));
+    }
+    match (identity, generated, default) {
+        ("a", _, _) => line.push_str(" GENERATED ALWAYS AS IDENTITY"),
+        ("d", _, _) => line.push_str(" GENERATED BY DEFAULT AS IDENTITY"),
+        (_, "s", Some(expression)) => {
+            line.push_str(&format!(" GENERATED ALWAYS AS ({expression}) STORED"))
+        }
+        (_, _, Some(default)) => line.push_str(&format!(" DEFAULT {default}")),
+        _ => {}
+    }
+    if not_null {
+        line.push_str(" NOT NULL");
+    }
+    line
+}
+
+/// Indexes of a table or materialized view that no constraint creates, and
+/// that aren't a partition's copy of its parent's index.
+async fn index_statements(client: &Object, oid: u32) -> Result<Vec<String>> {
+    let rows = client
+        .query(
+            "SELECT pg_get_indexdef(x.indexrelid) \
+             FROM pg_index x \
+             JOIN pg_class i ON i.oid = x.indexrelid \
+             WHERE x.indrelid = $1 \
+               AND NOT EXISTS (SELECT 1 FROM pg_constraint k \
+                               WHERE k.conindid = x.indexrelid AND k.conrelid = x.indrelid) \
+               AND NOT EXISTS (SELECT 1 FROM pg_inherits h WHERE h.inhrelid = x.indexrelid) \
+             ORDER BY i.relname",
+            &[&oid],
+        )
+        .await?;
+    Ok(rows
+        .iter()
+        .map(|r| format!("{};", r.get::<_, String>(0)))
+        .collect())
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Views, indexes, sequences
+// ─────────────────────────────────────────────────────────────────────────────
+
+async fn view_ddl(
+    client: &Object,
+    schema: &str,
+    name: &str,
+    materialized: bool,
+) -> Result<Vec<String>> {
+    let (kinds, label) = if materialized {
+        (["m"], "Materialized view")
+    } else {
+        (["v"], "View")
+    };
+    let view = relation(client, schema, name, &kinds, label).await?;
+    let q = &view.qualified;
+
+    let row = client
+        .query_one(
+            "SELECT pg_get_viewdef($1::oid, true), obj_description($1::oid, 'pg_class')",
+            &[&view.oid],
+        )
+        .await?;
+    let definition: String = row.get(0);
+    let definition = definition.trim_end().trim_end_matches(';');
+
+    let mut statements = vec![if materialized {
+        format!("CREATE MATERIALIZED VIEW {q} AS\n{definition}\nWITH DATA;")
+    } else {
+        format!("CREATE OR REPLACE VIEW {q} AS\n{definition};")
+    }];
+    if materialized {
+        statements.extend(index_statements(client, view.oid).await?);
+    }
+
+    let kind = if materialized {
+        "MATERIALIZED VIEW"
+    } else {
+        "VIEW"
+    };
+    push_comment(&mut statements, &format!("{kind} {q}"), row.get(1));
+    let columns = client
+        .query(
+            "SELECT format('%I', attname), col_description(attrelid, attnum) \
+             FROM pg_attribute WHERE attrelid = $1 AND attnum > 0 ORDER BY attnum",
+            &[&view.oid],
+        )
+        .await?;
+    for column in &columns {
+        let name: String = column.get(0);
+        push_comment(
+            &mut statements,
+            &format!("COLUMN {q}.{name}"),
+            column.get(1),
+        );
+    }
+    Ok(statements)
+}
+
+async fn index_ddl(client: &Object, schema: &str, name: &str) -> Result<Vec<String>> {
+    let index = relation(client, schema, name, &["i", "I"], "Index").await?;
+    let row = client
+        .query_one(
+            "SELECT pg_get_indexdef($1::oid), obj_description($1::oid, 'pg_class')",
+            &[&index.oid],
+        )
+        .await?;
+
+    let mut statements = vec![format!("{};", row.get::<_, String>(0))];
+    push_comment(
+        &mut statements,
+        &format!("INDEX {}", index.qualified),
+        row.get(1),
+    );
+    Ok(statements)
+}
+
+async fn sequence_ddl(client: &Object, oid: u32) -> Result<Vec<String>> {
+    let row = client
+        .query_one(
+            "SELECT format('%I.%I', n.nspname, c.relname), format_type(s.seqtypid, NULL), \
+                    s.seqstart, s.seqincrement, s.seqmin, s.seqmax, s.seqcache, s.seqcycle, \
+                    obj_description(c.oid, 'pg_class') \
+             FROM pg_sequence s \
+             JOIN pg_class c ON c.oid = s.seqrelid \
+             JOIN pg_namespace n ON n.oid = c.relnamespace \
+             WHERE s.seqrelid = $1",
+            &[&oid],
+        )
+        .await?;
+    let q: String = row.get(0);
+    let data_type: String = row.get(1);
+    let (start, increment, min, max, cache): (i64, i64, i64, i64, i64) =
+        (row.get(2), row.get(3), row.get(4), row.get(5), row.get(6));
+    let cycle = if row.get::<_, bool>(7) {
+        "\n    CYCLE"
+    } else {
+        ""
+    };
+
+    let mut statements = vec![format!(
+        "CREATE SEQUENCE {q}\n    AS {data_type}\n    START WITH {start}\n    \
+         INCREMENT BY {increment}\n    MINVALUE {min}\n    MAXVALUE {max}\n    \
+         CACHE {cache}{cycle};"
+    )];
+    push_comment(&mut statements, &format!("SEQUENCE {q}"), row.get(8));
+    Ok(statements)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Functions, triggers, types
+// ─────────────────────────────────────────────────────────────────────────────
+
+async fn function_ddl(
+    client: &Object,
+    schema: &str,
+    name: &str,
+    arguments: Option<&str>,
+) -> Result<Vec<String>> {
+    let rows = client
+        .query(
+            "SELECT pg_get_functiondef(p.oid), pg_get_function_identity_arguments(p.oid), \
+                    format('%I.%I', n.nspname, p.proname), p.prokind::text, \
+                    obj_description(p.oid, 'pg_proc') \
+             FROM pg_proc p \
+             JOIN pg_namespace n ON n.oid = p.pronamespace \
+             WHERE n.nspname = $1 AND p.proname = $2 AND p.prokind IN ('f', 'p', 'w') \
+             ORDER BY 2",
+            &[&schema, &name],
+        )
+        .await?;
+
+    let mut statements = Vec::new();
+    for row in &rows {
+        let identity: String = row.get(1);
+        if arguments.is_some_and(|wanted| wanted.trim() != identity) {
+            continue;
+        }
+        let definition: String = row.get(0);
+        let q: String = row.get(2);
+        let kind = if row.get::<_, &str>(3) == "p" {
+            "PROCEDURE"
+        } else {
+            "FUNCTION"
+        };
+        statements.push(format!("{};", definition.trim_end()));
+        push_comment(
+            &mut statements,
+            &format!("{kind} {q}({identity})"),
+            row.get(4),
+        );
+    }
+
+    if statements.is_empty() {
+        let signature = arguments.map(|a| format!("({a})")).unwrap_or_default();
+        return Err(Error::NotFound(format!(
+            "Function {schema}.{name}{signature}"
+        )));
+    }
+    Ok(statements)
+}
+
+async fn trigger_ddl(
+    client: &Object,
+    schema: &str,
+    name: &str,
+    table: Option<&str>,
+) -> Result<Vec<String>> {
+    let rows = client
+        .query(
+            "SELECT pg_get_triggerdef(t.oid, true), format('%I', t.tgname), \
+                    format('%I.%I', n.nspname, c.relname), obj_description(t.oid, 'pg_trigger') \
+             FROM pg_trigger t \
+             JOIN pg_class c ON c.oid = t.tgrelid \
+             JOIN pg_namespace n ON n.oid = c.relnamespace \
+             WHERE n.nspname = $1 AND t.tgname = $2 AND NOT t.tgisinternal \
+               AND ($3::text IS NULL OR c.relname = $3) \
+             ORDER BY c.relname",
+            &[&schema, &name, &table],
+        )
+        .await?;
+    if rows.is_empty() {
+        return Err(Error::NotFound(format!("Trigger {schema}.{name}")));
+    }
+
+    let mut statements = Vec::new();
+    for row in &rows {
+        let (definition, trigger, on): (String, String, String) =
+            (row.get(0), row.get(1), row.get(2));
+        statements.push(format!("{definition};"));
+        push_comment(
+            &mut statements,
+            &format!("TRIGGER {trigger} ON {on}"),
+            row.get(3),
+        );
+    }
+    Ok(statements)
+}
+
+async fn type_ddl(client: &Object, schema: &str, name: &str) -> Result<Vec<String>> {
+    let row = client
+        .query_opt(
+            "SELECT t.oid, t.typtype::text, format('%I.%I', n.nspname, t.typname), \
+                    t.typrelid, format_type(t.typbasetype, t.typtypmod), t.typnotnull, \
+                    t.typdefault, obj_description(t.oid, 'pg_type'), \
+                    (SELECT relkind::text FROM pg_class WHERE oid = t.typrelid) \
+             FROM pg_type t \
+             JOIN pg_namespace n ON n.oid = t.typnamespace \
+             WHERE n.nspname = $1 AND t.typname = $2",
+            &[&schema, &name],
+        )
+        .await?
+        .ok_or_else(|| Error::NotFound(format!("Type {schema}.{name}")))?;
+    let oid: u32 = row.get(0);
+    let q: String = row.get(2);
+
+    let create = match row.get::<_, &str>(1) {
+        "e" => {
+            let labels: Vec<String> = client
+                .query(
+                    "SELECT enumlabel::text FROM pg_enum WHERE enumtypid = $1 \
+                     ORDER BY enumsortorder",
+                    &[&oid],
+                )
+                .await?
+                .iter()
+                .map(|r| quote_literal(r.get(0)))
+                .collect();
+            format!(
+                "CREATE TYPE {q} AS ENUM (\n    {}\n);",
+                labels.join(",\n    ")
+            )
+        }
+        "c" if row.get::<_, Option<&str>>(8) == Some("c") => {
+            let relid: u32 = row.get(3);
+            let attributes: Vec<String> = client
+                .query(
+                    "SELECT format('%I', attname) || ' ' || format_type(atttypid, atttypmod) \
+                     FROM pg_attribute \
+                     WHERE attrelid = $1 AND attnum > 0 AND NOT attisdropped \
+                     ORDER BY attnum",
+                    &[&relid],
+                )
+                .await?
+                .iter()
+                .map(|r| r.get(0))
+                .collect();
+            format!(
+                "CREATE TYPE {q} AS (\n    {}\n);",
+                attributes.join(",\n    ")
+            )
+        }
+        "d" => {
+            let mut create = format!("CREATE DOMAIN {q} AS {}", row.get::<_, String>(4));
+            if let Some(default) = row.get::<_, Option<String>>(6) {
+                create.push_str(&format!("\n    DEFAULT {default}"));
+            }
+            if row.get::<_, bool>(5) {
+                create.push_str("\n    NOT NULL");
+            }
+            let checks = client
+                .query(
+                    "SELECT format('%I', conname), pg_get_constraintdef(oid, true) \
+                     FROM pg_constraint WHERE contypid = $1 AND contype = 'c' \
+                     ORDER BY conname",
+                    &[&oid],
+                )
+                .await?;
+            for check in &checks {
+                let (name, definition): (String, String) = (check.get(0), check.get(1));
+                create.push_str(&format!("\n    CONSTRAINT {name} {definition}"));
+            }
+            create.push(';');
+            create
+        }
+        "r" => {
+            let subtype: String = client
+                .query_one(
+                    "SELECT format_type(rngsubtype, NULL) FROM pg_range WHERE rngtypid = $1",
+                    &[&oid],
+                )
+                .await?
+                .get(0);
+            format!("CREATE TYPE {q} AS RANGE (\n    SUBTYPE = {subtype}\n);")
+        }
+        "c" => {
+            return Err(Error::InvalidQuery(format!(
+                "{schema}.{name} is the row type of a table: script the table instead"
+            )))
+        }
+        _ => {
+            return Err(Error::UnsupportedType(format!(
+                "{schema}.{name} is a base or pseudo type, which can't be scripted"
+            )))
+        }
+    };
+
+    let kind = if row.get::<_, &str>(1) == "d" {
+        "DOMAIN"
+    } else {
+        "TYPE"
+    };
+    let mut statements = vec![create];
+    push_comment(&mut statements, &format!("{kind} {q}"), row.get(7));
+    Ok(statements)
+}
+
+fn push_comment(statements: &mut Vec<String>, target: &str, comment: Option<String>) {
+    if let Some(comment) = comment {
+        statements.push(format!(
+            "COMMENT ON {target} IS {};",
+            quote_literal(&comment)
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::adapters::postgres::PostgresAdapter;
+    use crate::adapters::DatabaseAdapter;
+    use crate::models::{DatabaseType, Server};
+
+    use super::*;
+
+    fn adapter() -> PostgresAdapter {
+        let server = Server {
+            id: Some(1),
+            name: "e2e".into(),
+            db_type: DatabaseType::Postgres,
+            host: "localhost".into(),
+            port: 5432,
+            username: "postgres".into(),
+            password: "1234".into(),
+            default_database: None,
+            tls: Default::default(),
+            connection_uri: None,
+            ssh_tunnel: None,
+            read_only: false,
+            confirm_writes: false,
+            created_at: 0,
+        };
+        PostgresAdapter::new(&server, "postgres").unwrap()
+    }
+
+    async fn table(adapter: &PostgresAdapter, name: &str) -> String {
+        adapter
+            .get_object_ddl(&DdlRequest {
+                kind: DdlObjectKind::Table,
+                schema: Some("e2e_ddl".into()),
+                name: name.into(),
+                table: None,
+                arguments: None,
+            })
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    #[ignore = "requires local postgres on localhost:5432 (postgres/1234)"]
+    async fn e2e_table_scripts_round_trip() {
+        let adapter = adapter();
+        let client = adapter.pool.get().await.unwrap();
+        client
+            .batch_execute(
+                "DROP SCHEMA IF EXISTS e2e_ddl CASCADE; CREATE SCHEMA e2e_ddl;
+                 CREATE TABLE e2e_ddl.customers (
+                     id int GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+                     email text NOT NULL UNIQUE);
+                 CREATE TABLE e2e_ddl.orders (
+                     id serial PRIMARY KEY,
+                     customer_id int NOT NULL REFERENCES e2e_ddl.customers ON DELETE CASCADE,
+                     total numeric(10, 2) DEFAULT 0 CHECK (total >= 0),
+                     note text);
+                 CREATE INDEX orders_customer ON e2e_ddl.orders (customer_id);
+                 COMMENT ON TABLE e2e_ddl.orders IS 'Customer''s orders';
+                 COMMENT ON COLUMN e2e_ddl.orders.note IS 'free text';
+                 CREATE TABLE e2e_ddl.events (
+                     id bigint GENERATED BY DEFAULT AS IDENTITY,
+                     at date NOT NULL,
+                     order_id int REFERENCES e2e_ddl.orders,
+                     PRIMARY KEY (id, at)) PARTITION BY RANGE (at);
+                 CREATE TABLE e2e_ddl.events_2026 PARTITION OF e2e_ddl.events
+                     FOR VALUES FROM ('2026-01-01') TO ('2027-01-01');",
+            )
+            .await
+            .unwrap();
+
+        let mut scripts = Vec::new();
+        for name in ["customers", "orders", "events"] {
+            scripts.push(table(&adapter, name).await);
+        }
+        assert!(scripts[1].contains("CREATE SEQUENCE e2e_ddl.orders_id_seq"));
+        assert!(scripts[1].contains("ON DELETE CASCADE"));
+        assert!(scripts[1].contains("COMMENT ON TABLE e2e_ddl.orders IS 'Customer''s orders';"));
+        assert!(scripts[2].contains("PARTITION OF e2e_ddl.events"));
+
+        client
+            .batch_execute("DROP SCHEMA e2e_ddl CASCADE; CREATE SCHEMA e2e_ddl;")
+            .await
+            .unwrap();
+        for script in &scripts {
+            client.batch_execute(script).await.unwrap();
+        }
+        for (name, script) in ["customers", "orders", "events"].iter().zip(&scripts) {
+            assert_eq!(&table(&adapter, name).await, script, "{name}");
+        }
+
+        client
+            .batch_execute("DROP SCHEMA e2e_ddl CASCADE")
+            .await
+            .unwrap();
+    }
+}
@@ -1,25 +1,68 @@
 use std::collections::HashSet;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
+use bytes::BytesMut;
 use deadpool_postgres::{Pool, Timeouts};
+use tokio_postgres::types::{to_sql_checked, Format, IsNull, ToSql, Type};
 use tokio_postgres::{Column, Row, SimpleQueryMessage, SimpleQueryRow, Statement};
 
 use super::notices::{message_from_db_error, NoticeHub};
+use super::session::Session;
 use super::QueryRegistry;
 
-use crate::adapters::MessageSink;
+use crate::adapters::{bind_sql, MessageSink, RowSink, STREAM_BATCH_ROWS};
 use crate::error::{Error, Result};
 use crate::models::{
     EditableInfo, QueryColumnInfo, QueryMessage, QueryOptions, QueryResult, RowEdit, RowInsert,
     StatementResult,
 };
 
-use super::util::{get_column_types, quote_ident};
+use super::util::{get_column_types, quote_ident, quote_literal};
 
 /// Quanto o COUNT espera por uma conexão livre antes de desistir do total.
 const COUNT_WAIT_TIMEOUT: Duration = Duration::from_secs(3);
 
+/// Cursor through which a SELECT with bind parameters is read back as text.
+const PARAMS_CURSOR: &str = "__params";
+
+/// Prefix of the SQL-level prepared statements of [`execute_prepared`].
+const PARAMS_STATEMENT: &str = "__params_";
+static PARAMS_STATEMENT_SEQ: AtomicU64 = AtomicU64::new(0);
+
+/// A bind value sent in text format: Postgres parses it with the input
+/// function of whatever type it inferred (or the `::hint` cast) for the
+/// placeholder, exactly as if the literal had been typed in the query.
+#[derive(Debug)]
+struct TextParam(Option<String>);
+
+impl ToSql for TextParam {
+    fn to_sql(
+        &self,
+        _ty: &Type,
+        out: &mut BytesMut,
+    ) -> std::result::Result<IsNull, Box<dyn std::error::Error + Sync + Send>> {
+        match &self.0 {
+            Some(text) => {
+                out.extend_from_slice(text.as_bytes());
+                Ok(IsNull::No)
+            }
+            None => Ok(IsNull::Yes),
+        }
+    }
+
+    fn accepts(_ty: &Type) -> bool {
+        true
+    }
+
+    fn encode_format(&self, _ty: &Type) -> Format {
+        Format::Text
+    }
+
+    to_sql_checked!();
+}
+
 /// Removes a query_id → backend PID entry when the execution finishes,
 /// including early returns and cancelled/failed queries.
 struct PidGuard<'a> {
@@ -42,24 +85,82 @@ pub async fn execute_query(
     sink: Option<Arc<dyn MessageSink>>,
 ) -> Result<QueryResult> {
     let client = pool.get().await?;
+    run_query(&client, None, Some(pool), query, options, registry, hub, sink).await
+}
+
+/// [`execute_query`] on the connection pinned by a session. Nothing else is
+/// sent on it: no `prepare` for column types and no editability lookup (an
+/// error in either would abort the user's transaction), and no COUNT (another
+/// connection can't see the uncommitted rows). Column types come back empty.
+pub async fn execute_in_session(
+    session: &Session,
+    query: &str,
+    options: QueryOptions,
+    registry: &QueryRegistry,
+    hub: &Arc<NoticeHub>,
+    sink: Option<Arc<dyn MessageSink>>,
+) -> Result<QueryResult> {
+    run_query(
+        session.client()?,
+        Some(session.pid),
+        None,
+        query,
+        options,
+        registry,
+        hub,
+        sink,
+    )
+    .await
+}
+
+/// `pool` is where the COUNT gets its own connection; `None` means `client`
+/// belongs to a session (see [`execute_in_session`]). `known_pid` saves the
+/// lookup when the caller already has it.
+#[allow(clippy::too_many_arguments)]
+async fn run_query(
+    client: &deadpool_postgres::Object,
+    known_pid: Option<i32>,
+    pool: Option<&Pool>,
+    query: &str,
+    options: QueryOptions,
+    registry: &QueryRegistry,
+    hub: &Arc<NoticeHub>,
+    sink: Option<Arc<dyn MessageSink>>,
+) -> Result<QueryResult> {
     let trimmed = query.trim().trim_end_matches(';');
 
     if trimmed.is_empty() {
         return Err(Error::InvalidQuery("Empty query".into()));
     }
 
+    // Com parâmetros, `:nome` vira `$n` e os valores vão como binds; daqui em
+    // diante (prepare, COUNT, paginação) tudo usa a query reescrita.
+    let (bound, binds) = if options.params.is_empty() {
+        (None, None)
+    } else {
+        let (sql, values) = bind_sql(trimmed, &options.params)?;
+        let binds: Vec<TextParam> = values.into_iter().map(TextParam).collect();
+        (Some(sql), Some(binds))
+    };
+    let trimmed = bound.as_deref().unwrap_or(trimmed);
+    let bind_refs: Vec<&(dyn ToSql + Sync)> = binds
+        .iter()
+        .flatten()
+        .map(|b| b as &(dyn ToSql + Sync))
+        .collect();
+
     // O PID identifica a conexão física que o pool entregou. Serve para duas
     // coisas: mirar o pg_cancel_backend e rotear os notices que chegarem por
     // ela enquanto esta query roda.
-    let pid: Option<i32> = if sink.is_some() || options.query_id.is_some() {
-        Some(
+    let pid: Option<i32> = match known_pid {
+        Some(pid) => Some(pid),
+        None if sink.is_some() || options.query_id.is_some() => Some(
             client
                 .query_one("SELECT pg_backend_pid()", &[])
                 .await?
                 .get(0),
-        )
-    } else {
-        None
+        ),
+        None => None,
     };
 
     let _pid_guard = match (options.query_id.as_deref(), pid) {
@@ -111,10 +212,21 @@ pub async fn execute_query(
     // count scan. `prepare` only accepts a single statement (None for scripts).
     let exec_started = Instant::now();
     let data_fut = async {
-        let messages = client.simple_query(&exec_query).await;
+        let messages = if let Some(binds) = &binds {
+            query_with_binds(client, &exec_query, is_select, binds).await
+        } else {
+            client.simple_query(&exec_query).await
+        };
         (messages, exec_started.elapsed())
     };
+    let prepare_fut = async {
+        match pool {
+            Some(_) => client.prepare(trimmed).await.ok(),
+            None => None,
+        }
+    };
     let count_fut = async {
+        let pool = pool?;
         if !(options.count_total && is_select) {
             return None;
         }
@@ -134,15 +246,14 @@ pub async fn execute_query(
         };
         let count_client = pool.timeout_get(&timeouts).await.ok()?;
         count_client
-            .query_one(&count_query, &[])
+            .query_one(&count_query, &bind_refs)
             .await
             .ok()?
             .get::<_, Option<i64>>(0)
     };
     let (stmt, (messages, exec_elapsed), total_count) =
-        tokio::join!(client.prepare(trimmed), data_fut, count_fut);
+        tokio::join!(prepare_fut, data_fut, count_fut);
 
-    let stmt = stmt.ok();
     let messages = match messages {
         Ok(messages) => messages,
         Err(err) => {
@@ -193,7 +304,7 @@ pub async fn execute_query(
     // Detect if the result is editable (single source table with a primary key)
     let editable_info = match &stmt {
         Some(stmt) if is_select && !columns.is_empty() => {
-            detect_editable_info(&client, stmt.columns(), &columns).await
+            detect_editable_info(client, stmt.columns(), &columns).await
         }
         _ => None,
     };
@@ -209,6 +320,75 @@ pub async fn execute_query(
     })
 }
 
+/// The extended-protocol counterpart of `simple_query`, for queries with
+/// binds. A plain `query` would bring rows back in binary, decoded per type;
+/// a SELECT instead goes through a cursor whose `FETCH` is a simple query, so
+/// the rows keep Postgres' own text rendering. Whether anything else returns
+/// rows is up to the prepared statement, not the verb: an `INSERT ...
+/// RETURNING` goes through `PREPARE`/`EXECUTE`, whose rows are text too.
+async fn query_with_binds(
+    client: &deadpool_postgres::Object,
+    query: &str,
+    is_select: bool,
+    binds: &[TextParam],
+) -> std::result::Result<Vec<SimpleQueryMessage>, tokio_postgres::Error> {
+    if !is_select {
+        let statement = client.prepare(query).await?;
+        if statement.columns().is_empty() {
+            let affected = client.execute_raw(&statement, binds).await?;
+            return Ok(vec![SimpleQueryMessage::CommandComplete(affected)]);
+        }
+        return execute_prepared(client, query, binds).await;
+    }
+
+    // WITH HOLD: fora de uma transação o cursor sobrevive ao commit implícito
+    // do DECLARE; dentro de uma sessão ele simplesmente vive até o CLOSE.
+    let declare = format!("DECLARE {PARAMS_CURSOR} NO SCROLL CURSOR WITH HOLD FOR {query}");
+    client.execute_raw(&declare, binds).await?;
+    let fetched = client
+        .simple_query(&format!("FETCH ALL FROM {PARAMS_CURSOR}"))
+        .await;
+    // Best-effort: se a transação da sessão abortou, o ROLLBACK fecha o cursor.
+    let _ = client
+        .batch_execute(&format!("CLOSE {PARAMS_CURSOR}"))
+        .await;
+    fetched
+}
+
+/// Runs a statement that returns rows but can't sit in a cursor (DML with
+/// `RETURNING`) through a SQL-level `PREPARE`, with the binds as literals of
+/// the `EXECUTE`: Postgres coerces them exactly like protocol binds, and the
+/// rows come back as text. Each call gets its own name because a failed
+/// `EXECUTE` inside a session transaction leaves the `DEALLOCATE` ignored
+/// until the `DISCARD ALL` that ends the session.
+async fn execute_prepared(
+    client: &deadpool_postgres::Object,
+    query: &str,
+    binds: &[TextParam],
+) -> std::result::Result<Vec<SimpleQueryMessage>, tokio_postgres::Error> {
+    let name = format!(
+        "{PARAMS_STATEMENT}{}",
+        PARAMS_STATEMENT_SEQ.fetch_add(1, Ordering::Relaxed)
+    );
+    client
+        .batch_execute(&format!("PREPARE {name} AS {query}"))
+        .await?;
+
+    let args: Vec<String> = binds
+        .iter()
+        .map(|b| b.0.as_deref().map_or_else(|| "NULL".into(), quote_literal))
+        .collect();
+    let executed = if args.is_empty() {
+        client.simple_query(&format!("EXECUTE {name}")).await
+    } else {
+        client
+            .simple_query(&format!("EXECUTE {name}({})", args.join(", ")))
+            .await
+    };
+    let _ = client.batch_execute(&format!("DEALLOCATE {name}")).await;
+    executed
+}
+
 /// Texto da linha de conclusão. O `CommandComplete` do tokio-postgres carrega
 /// só o número de linhas, não a tag textual do Postgres ("INSERT 0 5"), então o
 /// texto é montado aqui.
@@ -236,6 +416,87 @@ fn completion_status(
     }
 }
 
+/// Runs a read query through a server-side cursor, handing each `FETCH` to
+/// `sink` before asking for the next one: memory stays at one batch no matter
+/// how many rows the query returns.
+pub async fn stream_query(
+    pool: &Pool,
+    query: &str,
+    query_id: Option<&str>,
+    registry: &QueryRegistry,
+    sink: &mut dyn RowSink,
+) -> Result<u64> {
+    let mut client = pool.get().await?;
+    let trimmed = query.trim().trim_end_matches(';');
+
+    if trimmed.is_empty() {
+        return Err(Error::InvalidQuery("Empty query".into()));
+    }
+    // DECLARE ... CURSOR só aceita consultas; um INSERT ... RETURNING não vira cursor.
+    if !is_select_query(trimmed) {
+        return Err(Error::InvalidQuery(
+            "Only queries that return rows (SELECT, WITH, TABLE) can be streamed".into(),
+        ));
+    }
+
+    // Cancelar durante um FETCH lento (sort grande antes da primeira linha)
+    // precisa do pg_cancel_backend; entre lotes o próprio sink interrompe.
+    let _pid_guard = match query_id {
+        Some(query_id) => {
+            let pid: i32 = client.query_one("SELECT pg_backend_pid()", &[]).await?.get(0);
+            registry.lock().unwrap().insert(query_id.to_string(), pid);
+            Some(PidGuard {
+                registry,
+                query_id: query_id.to_string(),
+            })
+        }
+        None => None,
+    };
+
+    let stmt = client.prepare(trimmed).await?;
+    let columns = columns_from_statement(&stmt);
+
+    stream_cursor(&mut client, trimmed, &columns, sink).await
+}
+
+/// `DECLARE` a cursor for `sql` and drain it in [`STREAM_BATCH_ROWS`] chunks.
+/// Values come through the simple protocol, so they're already text, same as
+/// `execute_query`. The transaction is rolled back if the sink bails out.
+pub(super) async fn stream_cursor(
+    client: &mut deadpool_postgres::Object,
+    sql: &str,
+    columns: &[QueryColumnInfo],
+    sink: &mut dyn RowSink,
+) -> Result<u64> {
+    sink.columns(columns).await?;
+
+    let tx = client.transaction().await?;
+    tx.batch_execute(&format!("DECLARE __stream NO SCROLL CURSOR FOR {sql}"))
+        .await?;
+
+    let fetch = format!("FETCH {STREAM_BATCH_ROWS} FROM __stream");
+    let mut sent: u64 = 0;
+    loop {
+        let messages = tx.simple_query(&fetch).await?;
+        let rows: Vec<&SimpleQueryRow> = messages
+            .iter()
+            .filter_map(|m| match m {
+                SimpleQueryMessage::Row(row) => Some(row),
+                _ => None,
+            })
+            .collect();
+        if rows.is_empty() {
+            break;
+        }
+
+        sent += rows.len() as u64;
+        sink.rows(extract_text_rows(&rows)).await?;
+    }
+
+    tx.commit().await?;
+    Ok(sent)
+}
+
 pub async fn apply_row_edits(
     pool: &Pool,
     editable: &EditableInfo,
@@ -678,8 +939,11 @@ mod tests {
             username: "postgres".into(),
             password: "1234".into(),
             default_database: None,
-            ssl_enabled: false,
+            tls: Default::default(),
             connection_uri: None,
+            ssh_tunnel: None,
+            read_only: false,
+            confirm_writes: false,
             created_at: 0,
         };
 
@@ -725,6 +989,173 @@ mod tests {
         adapter.cancel_query(query_id).await.unwrap();
     }
 
+    #[tokio::test]
+    #[ignore = "requires local postgres on localhost:5432 (postgres/1234)"]
+    async fn e2e_params_are_bound() {
+        use serde_json::json;
+
+        use crate::adapters::postgres::PostgresAdapter;
+        use crate::adapters::DatabaseAdapter;
+        use crate::models::{DatabaseType, QueryOptions, QueryParam, Server};
+
+        let server = Server {
+            id: Some(1),
+            name: "e2e".into(),
+            db_type: DatabaseType::Postgres,
+            host: "localhost".into(),
+            port: 5432,
+            username: "postgres".into(),
+            password: "1234".into(),
+            default_database: None,
+            tls: Default::default(),
+            connection_uri: None,
+            ssh_tunnel: None,
+            read_only: false,
+            confirm_writes: false,
+            created_at: 0,
+        };
+        let adapter = PostgresAdapter::new(&server, "postgres").unwrap();
+        let param = |name: &str, value, type_hint: Option<&str>| QueryParam {
+            name: Some(name.into()),
+            value,
+            type_hint: type_hint.map(str::to_string),
+        };
+
+        let result = adapter
+            .execute_query(
+                "SELECT n, :label AS label FROM generate_series(1, 10) n \
+                 WHERE n > :min AND :flag",
+                QueryOptions {
+                    limit: 3,
+                    count_total: true,
+                    params: vec![
+                        param("label", json!("it's; DROP TABLE x"), Some("text")),
+                        param("min", json!(4), Some("int")),
+                        param("flag", json!(true), None),
+                    ],
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        // Text rendering, pagination and COUNT all see the bound values.
+        assert_eq!(result.rows[0], vec![Some("5".into()), Some("it's; DROP TABLE x".into())]);
+        assert_eq!(result.row_count, 3);
+        assert!(result.has_more);
+        assert_eq!(result.total_count, Some(6));
+
+        let null = adapter
+            .execute_query(
+                "SELECT $1::text IS NULL",
+                QueryOptions {
+                    params: vec![QueryParam {
+                        name: None,
+                        value: json!(null),
+                        type_hint: None,
+                    }],
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(null.rows[0], vec![Some("t".into())]);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires local postgres on localhost:5432 (postgres/1234)"]
+    async fn e2e_params_keep_returning_rows() {
+        use serde_json::json;
+
+        use crate::adapters::postgres::PostgresAdapter;
+        use crate::adapters::DatabaseAdapter;
+        use crate::models::{DatabaseType, QueryOptions, QueryParam, Server};
+
+        let server = Server {
+            id: Some(1),
+            name: "e2e".into(),
+            db_type: DatabaseType::Postgres,
+            host: "localhost".into(),
+            port: 5432,
+            username: "postgres".into(),
+            password: "1234".into(),
+            default_database: None,
+            tls: Default::default(),
+            connection_uri: None,
+            ssh_tunnel: None,
+            read_only: false,
+            confirm_writes: false,
+            created_at: 0,
+        };
+        let adapter = PostgresAdapter::new(&server, "postgres").unwrap();
+        for sql in [
+            "DROP SCHEMA IF EXISTS e2e_returning CASCADE",
+            "CREATE SCHEMA e2e_returning",
+            "CREATE TABLE e2e_returning.items (id serial PRIMARY KEY, label text, data jsonb)",
+        ] {
+            adapter.execute_statement(sql).await.unwrap();
+        }
+        let params = |label, data| QueryOptions {
+            params: vec![
+                QueryParam {
+                    name: Some("label".into()),
+                    value: json!(label),
+                    type_hint: None,
+                },
+                QueryParam {
+                    name: Some("data".into()),
+                    value: data,
+                    type_hint: None,
+                },
+            ],
+            ..Default::default()
+        };
+
+        let inserted = adapter
+            .execute_query(
+                "INSERT INTO e2e_returning.items (label, data) \
+                 VALUES (:label, :data) RETURNING id, label, data",
+                params("it's", json!({"a": 1})),
+            )
+            .await
+            .unwrap();
+        assert_eq!(inserted.columns[2].type_name, "jsonb");
+        assert_eq!(
+            inserted.rows,
+            vec![vec![
+                Some("1".into()),
+                Some("it's".into()),
+                Some(r#"{"a": 1}"#.into()),
+            ]]
+        );
+
+        let updated = adapter
+            .execute_query(
+                "UPDATE e2e_returning.items SET label = :label \
+                 WHERE data = :data RETURNING label",
+                params("renamed", json!({"a": 1})),
+            )
+            .await
+            .unwrap();
+        assert_eq!(updated.rows, vec![vec![Some("renamed".into())]]);
+
+        // Without RETURNING the statement still only reports its row count.
+        let deleted = adapter
+            .execute_query(
+                "DELETE FROM e2e_returning.items WHERE label = :label AND :data::jsonb IS NULL",
+                params("renamed", json!(null)),
+            )
+            .await
+            .unwrap();
+        assert!(deleted.columns.is_empty());
+        assert!(deleted.rows.is_empty());
+
+        adapter
+            .execute_statement("DROP SCHEMA e2e_returning CASCADE")
+            .await
+            .unwrap();
+    }
+
     /// Regression: rich types (jsonb/json/numeric/uuid/arrays/bool/timestamptz)
     /// used to come back as NULL because tokio-postgres can't decode them as
     /// String. Now Postgres renders everything as text, so every column must
@@ -745,8 +1176,11 @@ mod tests {
             username: "postgres".into(),
             password: "1234".into(),
             default_database: None,
-            ssl_enabled: false,
+            tls: Default::default(),
             connection_uri: None,
+            ssh_tunnel: None,
+            read_only: false,
+            confirm_writes: false,
             created_at: 0,
         };
 
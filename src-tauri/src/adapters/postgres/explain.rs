@@ -0,0 +1,229 @@
+use deadpool_postgres::Pool;
+use serde_json::Value;
+use tokio_postgres::SimpleQueryMessage;
+
+use crate::error::{Error, Result};
+use crate::models::{ExplainOptions, ExplainPlan, PlanBuffers, PlanCondition, PlanNode};
+
+/// Node keys that hold a filter or condition expression.
+const CONDITION_KEYS: &[&str] = &[
+    "Filter",
+    "Index Cond",
+    "Recheck Cond",
+    "Join Filter",
+    "Hash Cond",
+    "Merge Cond",
+    "TID Cond",
+    "One-Time Filter",
+];
+
+/// Node keys that hold a list of expressions, shown joined.
+const KEY_LIST_KEYS: &[&str] = &["Sort Key", "Presorted Key", "Group Key"];
+
+/// `EXPLAIN (FORMAT JSON, ...)` and the plan parsed into a [`PlanNode`] tree.
+///
+/// ANALYZE executes the statement, so it runs inside a transaction that is
+/// always rolled back: an `UPDATE` is measured without changing anything, and
+/// so is a `WITH ... DELETE` that only looks like a read.
+pub async fn explain_query(
+    pool: &Pool,
+    query: &str,
+    options: ExplainOptions,
+) -> Result<ExplainPlan> {
+    let mut client = pool.get().await?;
+    let trimmed = query.trim().trim_end_matches(';');
+
+    if trimmed.is_empty() {
+        return Err(Error::InvalidQuery("Empty query".into()));
+    }
+
+    let sql = format!(
+        "EXPLAIN (FORMAT JSON, ANALYZE {}, BUFFERS {}, VERBOSE {}) {trimmed}",
+        options.analyze, options.buffers, options.verbose
+    );
+    // O simple_query aceitaria um script: `SELECT 1; DELETE ...` explicaria o
+    // SELECT e rodaria o DELETE de verdade. O prepare recusa mais de um
+    // statement (e não executa nada).
+    client.prepare(&sql).await?;
+
+    let messages = if options.analyze {
+        let tx = client.transaction().await?;
+        let messages = tx.simple_query(&sql).await?;
+        tx.rollback().await?;
+        messages
+    } else {
+        client.simple_query(&sql).await?
+    };
+
+    let raw = messages
+        .iter()
+        .find_map(|m| match m {
+            SimpleQueryMessage::Row(row) => row.get(0).map(str::to_string),
+            _ => None,
+        })
+        .ok_or_else(|| Error::Query("EXPLAIN returned no plan".into()))?;
+
+    parse_plan(raw)
+}
+
+/// Parse the `[{"Plan": ..., "Planning Time": ..., "Execution Time": ...}]`
+/// document EXPLAIN returns.
+fn parse_plan(raw: String) -> Result<ExplainPlan> {
+    let document: Value = serde_json::from_str(&raw)
+        .map_err(|e| Error::Query(format!("Unexpected EXPLAIN output: {e}")))?;
+    let top = document
+        .get(0)
+        .filter(|top| top.get("Plan").is_some())
+        .ok_or_else(|| Error::Query("Unexpected EXPLAIN output: no plan".into()))?;
+
+    Ok(ExplainPlan {
+        root: plan_node(&top["Plan"]),
+        planning_time_ms: number(top, "Planning Time"),
+        execution_time_ms: number(top, "Execution Time"),
+        raw,
+    })
+}
+
+fn plan_node(plan: &Value) -> PlanNode {
+    let mut conditions: Vec<PlanCondition> = CONDITION_KEYS
+        .iter()
+        .filter_map(|key| {
+            text(plan, key).map(|expression| PlanCondition {
+                kind: key.to_string(),
+                expression,
+            })
+        })
+        .collect();
+    conditions.extend(KEY_LIST_KEYS.iter().filter_map(|key| {
+        let keys = strings(plan, key);
+        (!keys.is_empty()).then(|| PlanCondition {
+            kind: key.to_string(),
+            expression: keys.join(", "),
+        })
+    }));
+
+    PlanNode {
+        node_type: text(plan, "Node Type").unwrap_or_default(),
+        relation: text(plan, "Relation Name"),
+        schema: text(plan, "Schema"),
+        alias: text(plan, "Alias"),
+        index_name: text(plan, "Index Name"),
+        join_type: text(plan, "Join Type"),
+        startup_cost: number(plan, "Startup Cost"),
+        total_cost: number(plan, "Total Cost"),
+        estimated_rows: number(plan, "Plan Rows"),
+        actual_rows: number(plan, "Actual Rows"),
+        loops: number(plan, "Actual Loops"),
+        actual_startup_time_ms: number(plan, "Actual Startup Time"),
+        actual_total_time_ms: number(plan, "Actual Total Time"),
+        buffers: buffers(plan),
+        conditions,
+        rows_removed_by_filter: number(plan, "Rows Removed by Filter")
+            .or_else(|| number(plan, "Rows Removed by Join Filter")),
+        keys_examined: None,
+        docs_examined: None,
+        output: strings(plan, "Output"),
+        children: plan
+            .get("Plans")
+            .and_then(Value::as_array)
+            .map(|plans| plans.iter().map(plan_node).collect())
+            .unwrap_or_default(),
+    }
+}
+
+/// `None` unless BUFFERS was requested (the keys are absent otherwise).
+fn buffers(plan: &Value) -> Option<PlanBuffers> {
+    plan.get("Shared Hit Blocks")?;
+    let blocks = |key: &str| plan.get(key).and_then(Value::as_u64).unwrap_or(0);
+    Some(PlanBuffers {
+        shared_hit: blocks("Shared Hit Blocks"),
+        shared_read: blocks("Shared Read Blocks"),
+        shared_dirtied: blocks("Shared Dirtied Blocks"),
+        shared_written: blocks("Shared Written Blocks"),
+        local_hit: blocks("Local Hit Blocks"),
+        local_read: blocks("Local Read Blocks"),
+        temp_read: blocks("Temp Read Blocks"),
+        temp_written: blocks("Temp Written Blocks"),
+    })
+}
+
+fn text(plan: &Value, key: &str) -> Option<String> {
+    plan.get(key).and_then(Value::as_str).map(str::to_string)
+}
+
+fn number(plan: &Value, key: &str) -> Option<f64> {
+    plan.get(key).and_then(Value::as_f64)
+}
+
+fn strings(plan: &Value, key: &str) -> Vec<String> {
+    plan.get(key)
+        .and_then(Value::as_array)
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(Value::as_str)
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_analyze_buffers_tree() {
+        let raw = r#"[{
+            "Plan": {
+                "Node Type": "Hash Join", "Join Type": "Inner",
+                "Startup Cost": 1.09, "Total Cost": 2.3, "Plan Rows": 4,
+                "Actual Startup Time": 0.05, "Actual Total Time": 0.08,
+                "Actual Rows": 3, "Actual Loops": 1,
+                "Hash Cond": "(o.user_id = u.id)",
+                "Shared Hit Blocks": 2, "Shared Read Blocks": 1,
+                "Plans": [
+                    {"Node Type": "Seq Scan", "Relation Name": "orders", "Alias": "o",
+                     "Plan Rows": 4, "Actual Rows": 4, "Actual Loops": 1,
+                     "Filter": "(total > 10)", "Rows Removed by Filter": 6,
+                     "Shared Hit Blocks": 1},
+                    {"Node Type": "Hash", "Plan Rows": 2, "Actual Rows": 2,
+                     "Actual Loops": 1, "Shared Hit Blocks": 1}
+                ]
+            },
+            "Planning Time": 0.2,
+            "Execution Time": 0.11
+        }]"#;
+
+        let plan = parse_plan(raw.to_string()).unwrap();
+        assert_eq!(plan.planning_time_ms, Some(0.2));
+        assert_eq!(plan.execution_time_ms, Some(0.11));
+
+        let root = &plan.root;
+        assert_eq!(root.node_type, "Hash Join");
+        assert_eq!(root.join_type.as_deref(), Some("Inner"));
+        assert_eq!(root.estimated_rows, Some(4.0));
+        assert_eq!(root.actual_rows, Some(3.0));
+        assert_eq!(root.conditions[0].kind, "Hash Cond");
+        assert_eq!(root.buffers.as_ref().map(|b| b.shared_read), Some(1));
+        assert_eq!(root.children.len(), 2);
+
+        let scan = &root.children[0];
+        assert_eq!(scan.relation.as_deref(), Some("orders"));
+        assert_eq!(scan.rows_removed_by_filter, Some(6.0));
+        assert_eq!(scan.conditions[0].expression, "(total > 10)");
+    }
+
+    #[test]
+    fn plain_explain_has_no_actuals() {
+        let raw = r#"[{"Plan": {"Node Type": "Sort", "Plan Rows": 10,
+            "Sort Key": ["name", "id DESC"]}, "Planning Time": 0.1}]"#;
+        let plan = parse_plan(raw.to_string()).unwrap();
+
+        assert_eq!(plan.execution_time_ms, None);
+        assert_eq!(plan.root.actual_rows, None);
+        assert_eq!(plan.root.buffers, None);
+        assert_eq!(plan.root.conditions[0].expression, "name, id DESC");
+        assert!(parse_plan("[]".into()).is_err());
+    }
+}
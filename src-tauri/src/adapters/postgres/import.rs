@@ -0,0 +1,173 @@
+use bytes::Bytes;
+use deadpool_postgres::{Pool, Transaction};
+use futures_util::SinkExt;
+use tokio_postgres::Statement;
+
+use crate::adapters::{RowSource, SourceRow};
+use crate::error::{Error, Result};
+use crate::models::ImportTarget;
+
+use super::browse::DEFAULT_SCHEMA;
+use super::util::{get_columns_ordered, quote_ident};
+
+/// Bulk-load `source` with `COPY ... FROM STDIN`, one COPY per batch, all in
+/// a single transaction (rolled back on a dry run or on error).
+///
+/// A COPY is all-or-nothing, so a batch it rejects is replayed row by row
+/// with `INSERT`s, each under its own savepoint: that pinpoints the bad lines
+/// and, in skip mode, keeps the good rows of the batch.
+pub async fn import_rows(
+    pool: &Pool,
+    target: &ImportTarget,
+    source: &mut dyn RowSource,
+) -> Result<u64> {
+    let schema = target.schema.as_deref().unwrap_or(DEFAULT_SCHEMA);
+    let mut client = pool.get().await?;
+
+    let table_columns = get_columns_ordered(&client, schema, &target.table).await?;
+    let casts = source
+        .columns()
+        .iter()
+        .map(|name| {
+            table_columns
+                .iter()
+                .find(|c| c.name == *name)
+                .map(|c| c.format_type.clone())
+                .ok_or_else(|| Error::InvalidQuery(format!("Unknown column: {name}")))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let table = format!("{}.{}", quote_ident(schema), quote_ident(&target.table));
+    let column_list = source
+        .columns()
+        .iter()
+        .map(|c| quote_ident(c))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let copy_sql = format!("COPY {table} ({column_list}) FROM STDIN");
+    let placeholders = casts
+        .iter()
+        .enumerate()
+        .map(|(i, cast)| format!("${}::text::{cast}", i + 1))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let insert_sql = format!("INSERT INTO {table} ({column_list}) VALUES ({placeholders})");
+
+    let tx = client.transaction().await?;
+    let insert = tx.prepare(&insert_sql).await?;
+    let mut total: u64 = 0;
+
+    while let Some(batch) = source.next_batch().await? {
+        tx.batch_execute("SAVEPOINT import_batch").await?;
+        let imported = match copy_batch(&tx, &copy_sql, &batch).await {
+            Ok(n) => n,
+            Err(_) => {
+                tx.batch_execute("ROLLBACK TO SAVEPOINT import_batch")
+                    .await?;
+                insert_each(&tx, &insert, &batch, source).await?
+            }
+        };
+        tx.batch_execute("RELEASE SAVEPOINT import_batch").await?;
+
+        total += imported;
+        source.imported(imported);
+    }
+
+    if source.dry_run() {
+        tx.rollback().await?;
+    } else {
+        tx.commit().await?;
+    }
+    Ok(total)
+}
+
+async fn copy_batch(tx: &Transaction<'_>, copy_sql: &str, batch: &[SourceRow]) -> Result<u64> {
+    let mut data = String::new();
+    for row in batch {
+        encode_copy_row(&row.values, &mut data);
+    }
+
+    let mut sink = Box::pin(tx.copy_in::<_, Bytes>(copy_sql).await?);
+    sink.send(Bytes::from(data)).await?;
+    Ok(sink.as_mut().finish().await?)
+}
+
+async fn insert_each(
+    tx: &Transaction<'_>,
+    insert: &Statement,
+    batch: &[SourceRow],
+    source: &mut dyn RowSource,
+) -> Result<u64> {
+    let mut imported: u64 = 0;
+
+    for row in batch {
+        let params: Vec<&(dyn tokio_postgres::types::ToSql + Sync)> = row
+            .values
+            .iter()
+            .map(|v| v as &(dyn tokio_postgres::types::ToSql + Sync))
+            .collect();
+
+        tx.batch_execute("SAVEPOINT import_row").await?;
+        match tx.execute(insert, &params).await {
+            Ok(_) => {
+                tx.batch_execute("RELEASE SAVEPOINT import_row").await?;
+                imported += 1;
+            }
+            Err(e) => {
+                tx.batch_execute("ROLLBACK TO SAVEPOINT import_row").await?;
+                let message = e
+                    .as_db_error()
+                    .map(|db| db.message().to_string())
+                    .unwrap_or_else(|| e.to_string());
+                source.reject(row.line, message)?;
+            }
+        }
+    }
+
+    Ok(imported)
+}
+
+/// One line of COPY's text format: tab-separated, `\N` for NULL, and
+/// backslash escapes for the characters that would break the framing.
+fn encode_copy_row(values: &[Option<String>], out: &mut String) {
+    for (i, value) in values.iter().enumerate() {
+        if i > 0 {
+            out.push('\t');
+        }
+        match value {
+            None => out.push_str("\\N"),
+            Some(v) => {
+                for ch in v.chars() {
+                    match ch {
+                        '\\' => out.push_str("\\\\"),
+                        '\t' => out.push_str("\\t"),
+                        '\n' => out.push_str("\\n"),
+                        '\r' => out.push_str("\\r"),
+                        _ => out.push(ch),
+                    }
+                }
+            }
+        }
+    }
+    out.push('\n');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn copy_text_escapes_framing_characters() {
+        let mut out = String::new();
+        encode_copy_row(
+            &[
+                Some("plain".into()),
+                None,
+                Some("tab\there".into()),
+                Some("line\nbreak \\N".into()),
+            ],
+            &mut out,
+        );
+        assert_eq!(out, "plain\t\\N\ttab\\there\tline\\nbreak \\\\N\n");
+    }
+}
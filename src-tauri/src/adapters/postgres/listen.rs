@@ -0,0 +1,205 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use deadpool_postgres::{ClientWrapper, Object, Pool};
+use tokio::sync::mpsc;
+
+use crate::error::{Error, Result};
+use crate::models::{ChannelNotification, ChannelSubscription};
+
+use super::notices::{ListenSub, NoticeHub};
+use super::util::quote_ident;
+
+/// Assinaturas abertas ao mesmo tempo por adapter. Cada uma tira de vez uma
+/// conexão do pool (16), que já divide espaço com as sessões.
+const MAX_LISTENERS: usize = 4;
+
+/// A connection taken out of the pool for good, so its `LISTEN` never leaks
+/// into someone else's checkout. Dropping it closes the connection (ending
+/// the `LISTEN`) and the routing of its notifications.
+pub struct Listener {
+    client: ClientWrapper,
+    _routing: ListenSub,
+}
+
+pub type Listeners = Mutex<HashMap<String, Listener>>;
+
+/// `LISTEN channel` on a dedicated connection, sending what arrives to
+/// `events` until [`unsubscribe`] or the connection closing.
+pub async fn subscribe(
+    pool: &Pool,
+    hub: &Arc<NoticeHub>,
+    listeners: &Listeners,
+    subscription_id: &str,
+    request: &ChannelSubscription,
+    events: mpsc::Sender<ChannelNotification>,
+) -> Result<()> {
+    if request.pattern || request.keyspace {
+        return Err(Error::UnsupportedType(
+            "Postgres listens on channel names only, not patterns or keyspace events".into(),
+        ));
+    }
+    let channel = request.channel.as_str();
+    check_free(listeners, subscription_id)?;
+
+    let client = Object::take(pool.get().await?);
+    let pid: i32 = client
+        .query_one("SELECT pg_backend_pid()", &[])
+        .await?
+        .get(0);
+    // Roteia antes do LISTEN: nada que chegue logo depois se perde.
+    let routing = hub.listen(pid, events);
+    client
+        .batch_execute(&format!("LISTEN {}", quote_ident(channel)))
+        .await?;
+
+    // O lock foi solto durante os awaits: outra chamada pode ter ocupado o id.
+    check_free(listeners, subscription_id)?;
+    listeners.lock().unwrap().insert(
+        subscription_id.to_string(),
+        Listener {
+            client,
+            _routing: routing,
+        },
+    );
+    Ok(())
+}
+
+pub fn unsubscribe(listeners: &Listeners, subscription_id: &str) -> Result<()> {
+    match listeners.lock().unwrap().remove(subscription_id) {
+        Some(_) => Ok(()),
+        None => Err(Error::NotFound(format!("Subscription {subscription_id}"))),
+    }
+}
+
+/// `pg_notify` takes the channel as a value, so any name works and matches
+/// the quoted identifier [`subscribe`] listens on.
+pub async fn notify(pool: &Pool, channel: &str, payload: &str) -> Result<()> {
+    let client = pool.get().await?;
+    client
+        .execute("SELECT pg_notify($1, $2)", &[&channel, &payload])
+        .await?;
+    Ok(())
+}
+
+fn check_free(listeners: &Listeners, subscription_id: &str) -> Result<()> {
+    let mut open = listeners.lock().unwrap();
+    // Conexões que caíram já soltaram o sender: liberam o id e a vaga.
+    open.retain(|_, listener| !listener.client.is_closed());
+    if open.contains_key(subscription_id) {
+        return Err(Error::AlreadyExists(format!(
+            "Subscription {subscription_id}"
+        )));
+    }
+    if open.len() >= MAX_LISTENERS {
+        return Err(Error::InvalidState(format!(
+            "Too many channel subscriptions ({MAX_LISTENERS}): unsubscribe one first"
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::adapters::postgres::PostgresAdapter;
+    use crate::adapters::DatabaseAdapter;
+    use crate::models::{DatabaseType, Server};
+
+    use super::*;
+
+    fn server() -> Server {
+        Server {
+            id: Some(1),
+            name: "e2e".into(),
+            db_type: DatabaseType::Postgres,
+            host: "localhost".into(),
+            port: 5432,
+            username: "postgres".into(),
+            password: "1234".into(),
+            default_database: None,
+            tls: Default::default(),
+            connection_uri: None,
+            ssh_tunnel: None,
+            read_only: false,
+            confirm_writes: false,
+            created_at: 0,
+        }
+    }
+
+    fn channel(name: &str) -> ChannelSubscription {
+        ChannelSubscription {
+            channel: name.into(),
+            pattern: false,
+            keyspace: false,
+            enable_keyspace_events: false,
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "requires local postgres on localhost:5432 (postgres/1234)"]
+    async fn e2e_notifications_reach_the_subscriber_until_unsubscribe() {
+        let adapter = PostgresAdapter::new(&server(), "postgres").unwrap();
+
+        let request = channel("Orders Created");
+        let (events, mut received) = mpsc::channel(8);
+        adapter
+            .subscribe_channel("sub-1", &request, events)
+            .await
+            .unwrap();
+        adapter
+            .notify_channel("Orders Created", "{\"id\": 1}")
+            .await
+            .unwrap();
+
+        let notification = tokio::time::timeout(Duration::from_secs(5), received.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(notification.channel, "Orders Created");
+        assert_eq!(notification.payload, "{\"id\": 1}");
+        assert!(notification.sender_pid.is_some());
+
+        adapter.unsubscribe_channel("sub-1").await.unwrap();
+        assert!(received.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    #[ignore = "requires local postgres on localhost:5432 (postgres/1234)"]
+    async fn e2e_a_dropped_connection_ends_the_subscription() {
+        let adapter = PostgresAdapter::new(&server(), "postgres").unwrap();
+
+        let (events, mut received) = mpsc::channel(8);
+        adapter
+            .subscribe_channel("sub-dropped", &channel("e2e_dropped"), events)
+            .await
+            .unwrap();
+
+        let killer = adapter.pool.get().await.unwrap();
+        let killed = killer
+            .query_one(
+                "SELECT count(pg_terminate_backend(pid)) FROM pg_stat_activity \
+                 WHERE query = 'LISTEN \"e2e_dropped\"'",
+                &[],
+            )
+            .await
+            .unwrap()
+            .get::<_, i64>(0);
+        assert_eq!(killed, 1);
+
+        // The sender goes with the connection, not with the unsubscribe
+        let closed = tokio::time::timeout(Duration::from_secs(5), received.recv())
+            .await
+            .unwrap();
+        assert!(closed.is_none());
+
+        // ...and so do the id and the slot
+        let (events, _received) = mpsc::channel(8);
+        adapter
+            .subscribe_channel("sub-dropped", &channel("e2e_dropped"), events)
+            .await
+            .unwrap();
+        adapter.unsubscribe_channel("sub-dropped").await.unwrap();
+    }
+}
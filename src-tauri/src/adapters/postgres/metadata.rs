@@ -3,12 +3,17 @@ use chrono::Utc;
 
 use deadpool_postgres::Pool;
 
+use crate::adapters::{like_subsequence, rank_matches, MAX_SEARCH_CANDIDATES};
 use crate::error::Result;
 use crate::models::{
     ColumnInfo, DatabaseInfo, IndexInfo, SchemaInfo, TableInfo, TableType, DatabaseStructure, SchemaStructure,
-    TableStructure,
+    TableStructure, ObjectKind, ObjectMatch, ObjectSearchRequest, ColumnSnapshot,
+    ConstraintSnapshot, FunctionSnapshot, IndexSnapshot, SchemaSnapshot, TableSnapshot,
+    ViewSnapshot, ConstraintInfo, ConstraintType, ForeignKeyAction, ForeignKeyInfo,
 };
 
+use super::util::quote_ident;
+
 pub async fn list_databases(pool: &Pool) -> Result<Vec<DatabaseInfo>> {
     let client = pool.get().await?;
 
@@ -105,6 +110,30 @@ pub async fn list_tables(pool: &Pool, schema: &str) -> Result<Vec<TableInfo>> {
         .collect())
 }
 
+/// `reltuples` of a table, for the ALTER warning. `None` when it doesn't
+/// exist or was never analyzed. Without a schema, the first one in the
+/// search_path; unquoted names fold to lower case, hence the second try.
+pub async fn row_estimate(pool: &Pool, schema: Option<&str>, table: &str) -> Result<Option<i64>> {
+    let client = pool.get().await?;
+
+    let row = client
+        .query_opt(
+            r#"
+            SELECT c.reltuples::bigint
+            FROM pg_class c
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            WHERE c.relname IN ($2, lower($2))
+              AND n.nspname = COALESCE($1, current_schema())
+              AND c.relkind IN ('r', 'm', 'p')
+            LIMIT 1
+            "#,
+            &[&schema, &table],
+        )
+        .await?;
+
+    Ok(row.map(|r| r.get::<_, i64>(0)).filter(|rows| *rows >= 0))
+}
+
 pub async fn list_columns(pool: &Pool, schema: &str, table: &str) -> Result<Vec<ColumnInfo>> {
     let client = pool.get().await?;
 
@@ -195,6 +224,156 @@ pub async fn list_indexes(pool: &Pool, schema: &str, table: &str) -> Result<Vec<
         .collect())
 }
 
+/// Shared by [`list_foreign_keys`] and [`list_referencing_tables`], which
+/// add the side (`c` referencing, `r` referenced) to filter on, and which of
+/// the partition copies of a key (`conparentid`) to keep.
+const FOREIGN_KEYS_SQL: &str = r#"
+    SELECT
+        k.conname::text,
+        n.nspname::text,
+        c.relname::text,
+        ARRAY(
+            SELECT a.attname::text
+            FROM unnest(k.conkey) WITH ORDINALITY AS u(attnum, ord)
+            JOIN pg_attribute a ON a.attrelid = k.conrelid AND a.attnum = u.attnum
+            ORDER BY u.ord
+        ),
+        rn.nspname::text,
+        r.relname::text,
+        ARRAY(
+            SELECT a.attname::text
+            FROM unnest(k.confkey) WITH ORDINALITY AS u(attnum, ord)
+            JOIN pg_attribute a ON a.attrelid = k.confrelid AND a.attnum = u.attnum
+            ORDER BY u.ord
+        ),
+        k.confupdtype::text,
+        k.confdeltype::text,
+        k.condeferrable,
+        k.condeferred
+    FROM pg_constraint k
+    JOIN pg_class c ON c.oid = k.conrelid
+    JOIN pg_namespace n ON n.oid = c.relnamespace
+    JOIN pg_class r ON r.oid = k.confrelid
+    JOIN pg_namespace rn ON rn.oid = r.relnamespace
+    WHERE k.contype = 'f'
+"#;
+
+pub async fn list_foreign_keys(
+    pool: &Pool,
+    schema: &str,
+    table: &str,
+) -> Result<Vec<ForeignKeyInfo>> {
+    // A partition keeps the keys inherited from its parent, but not the
+    // copies made for each partition of a partitioned referenced table.
+    let sql = format!(
+        "{FOREIGN_KEYS_SQL} AND n.nspname = $1 AND c.relname = $2 \
+         AND NOT EXISTS (SELECT 1 FROM pg_constraint p \
+                         WHERE p.oid = k.conparentid AND p.conrelid = k.conrelid) \
+         ORDER BY k.conname"
+    );
+    query_foreign_keys(pool, &sql, schema, table).await
+}
+
+pub async fn list_referencing_tables(
+    pool: &Pool,
+    schema: &str,
+    table: &str,
+) -> Result<Vec<ForeignKeyInfo>> {
+    let sql = format!(
+        "{FOREIGN_KEYS_SQL} AND rn.nspname = $1 AND r.relname = $2 AND k.conparentid = 0 \
+         ORDER BY n.nspname, c.relname, k.conname"
+    );
+    query_foreign_keys(pool, &sql, schema, table).await
+}
+
+async fn query_foreign_keys(
+    pool: &Pool,
+    sql: &str,
+    schema: &str,
+    table: &str,
+) -> Result<Vec<ForeignKeyInfo>> {
+    let client = pool.get().await?;
+    let rows = client.query(sql, &[&schema, &table]).await?;
+
+    Ok(rows
+        .iter()
+        .map(|r| ForeignKeyInfo {
+            name: r.get(0),
+            schema: r.get(1),
+            table: r.get(2),
+            columns: r.get(3),
+            referenced_schema: r.get(4),
+            referenced_table: r.get(5),
+            referenced_columns: r.get(6),
+            on_update: foreign_key_action(r.get(7)),
+            on_delete: foreign_key_action(r.get(8)),
+            is_deferrable: r.get(9),
+            is_initially_deferred: r.get(10),
+        })
+        .collect())
+}
+
+/// `pg_constraint.confupdtype`/`confdeltype`.
+fn foreign_key_action(code: &str) -> ForeignKeyAction {
+    match code {
+        "r" => ForeignKeyAction::Restrict,
+        "c" => ForeignKeyAction::Cascade,
+        "n" => ForeignKeyAction::SetNull,
+        "d" => ForeignKeyAction::SetDefault,
+        _ => ForeignKeyAction::NoAction,
+    }
+}
+
+pub async fn list_constraints(
+    pool: &Pool,
+    schema: &str,
+    table: &str,
+) -> Result<Vec<ConstraintInfo>> {
+    let client = pool.get().await?;
+
+    let rows = client
+        .query(
+            r#"
+            SELECT
+                k.conname::text,
+                k.contype::text,
+                ARRAY(
+                    SELECT a.attname::text
+                    FROM unnest(k.conkey) WITH ORDINALITY AS u(attnum, ord)
+                    JOIN pg_attribute a ON a.attrelid = k.conrelid AND a.attnum = u.attnum
+                    ORDER BY u.ord
+                ),
+                pg_get_constraintdef(k.oid, true),
+                k.condeferrable,
+                k.condeferred
+            FROM pg_constraint k
+            JOIN pg_class c ON c.oid = k.conrelid
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            WHERE n.nspname = $1 AND c.relname = $2 AND k.contype IN ('p', 'u', 'c', 'x')
+            ORDER BY position(k.contype::text IN 'pucx'), k.conname
+            "#,
+            &[&schema, &table],
+        )
+        .await?;
+
+    Ok(rows
+        .iter()
+        .map(|r| ConstraintInfo {
+            name: r.get(0),
+            constraint_type: match r.get::<_, &str>(1) {
+                "p" => ConstraintType::PrimaryKey,
+                "u" => ConstraintType::Unique,
+                "x" => ConstraintType::Exclusion,
+                _ => ConstraintType::Check,
+            },
+            columns: r.get(2),
+            definition: r.get(3),
+            is_deferrable: r.get(4),
+            is_initially_deferred: r.get(5),
+        })
+        .collect())
+}
+
 pub async fn list_schemas_with_tables(pool: &Pool) -> Result<DatabaseStructure> {
     let client = pool.get().await?;
 
@@ -249,4 +428,402 @@ pub async fn list_schemas_with_tables(pool: &Pool) -> Result<DatabaseStructure>
         schemas,
         fetched_at: Utc::now().timestamp_millis(),
     })
-}
\ No newline at end of file
+}
+
+/// Candidates for `search_objects` straight from pg_catalog: tables, views,
+/// columns, functions and indexes whose name has the letters of the query in
+/// order (`ILIKE '%u%s%r%'`), shortest names first; ranking happens in Rust.
+pub async fn search_objects(
+    pool: &Pool,
+    request: &ObjectSearchRequest,
+) -> Result<Vec<ObjectMatch>> {
+    let client = pool.get().await?;
+    let pattern = like_subsequence(&request.query);
+
+    let rows = client
+        .query(
+            r#"
+            SELECT kind, schema_name, parent, name, detail FROM (
+                SELECT
+                    CASE WHEN c.relkind IN ('v', 'm') THEN 'view' ELSE 'table' END AS kind,
+                    n.nspname::text AS schema_name,
+                    NULL::text AS parent,
+                    c.relname::text AS name,
+                    NULL::text AS detail
+                FROM pg_class c
+                JOIN pg_namespace n ON n.oid = c.relnamespace
+                WHERE c.relkind IN ('r', 'p', 'v', 'm', 'f')
+                  AND c.relname ILIKE $1 ESCAPE '\'
+                UNION ALL
+                SELECT 'column', n.nspname::text, c.relname::text, a.attname::text,
+                       format_type(a.atttypid, a.atttypmod)
+                FROM pg_attribute a
+                JOIN pg_class c ON c.oid = a.attrelid
+                JOIN pg_namespace n ON n.oid = c.relnamespace
+                WHERE c.relkind IN ('r', 'p', 'v', 'm', 'f')
+                  AND a.attnum > 0 AND NOT a.attisdropped
+                  AND a.attname ILIKE $1 ESCAPE '\'
+                UNION ALL
+                SELECT 'function', n.nspname::text, NULL, p.proname::text,
+                       pg_get_function_identity_arguments(p.oid)
+                FROM pg_proc p
+                JOIN pg_namespace n ON n.oid = p.pronamespace
+                WHERE p.proname ILIKE $1 ESCAPE '\'
+                UNION ALL
+                SELECT 'index', n.nspname::text, t.relname::text, i.relname::text, NULL
+                FROM pg_index x
+                JOIN pg_class i ON i.oid = x.indexrelid
+                JOIN pg_class t ON t.oid = x.indrelid
+                JOIN pg_namespace n ON n.oid = i.relnamespace
+                WHERE i.relname ILIKE $1 ESCAPE '\'
+            ) o
+            WHERE schema_name NOT IN ('pg_toast', 'pg_catalog', 'information_schema')
+              AND schema_name NOT LIKE 'pg\_temp\_%'
+              AND schema_name NOT LIKE 'pg\_toast\_temp\_%'
+            ORDER BY length(name)
+            LIMIT $2
+            "#,
+            &[&pattern, &(MAX_SEARCH_CANDIDATES as i64)],
+        )
+        .await?;
+
+    let candidates = rows.iter().map(|r| ObjectMatch {
+        kind: match r.get::<_, &str>(0) {
+            "view" => ObjectKind::View,
+            "column" => ObjectKind::Column,
+            "function" => ObjectKind::Function,
+            "index" => ObjectKind::Index,
+            _ => ObjectKind::Table,
+        },
+        schema: r.get(1),
+        parent: r.get(2),
+        name: r.get(3),
+        detail: r.get(4),
+        score: 0,
+    });
+    Ok(rank_matches(request, candidates))
+}
+
+/// Tables (partitions aside), views and functions of `schema`, for
+/// `compare_schemas`. Read in one transaction with `search_path` set to the
+/// schema, so the `pg_get_*` definitions leave it out; index and function
+/// headers, which always carry it, get it stripped here.
+pub async fn schema_snapshot(pool: &Pool, schema: &str) -> Result<SchemaSnapshot> {
+    let mut client = pool.get().await?;
+    let tx = client.transaction().await?;
+    tx.batch_execute(&format!("SET LOCAL search_path TO {}", quote_ident(schema)))
+        .await?;
+    let prefix: String = tx
+        .query_one("SELECT quote_ident($1) || '.'", &[&schema])
+        .await?
+        .get(0);
+
+    let mut tables: BTreeMap<String, TableSnapshot> = tx
+        .query(
+            r#"
+            SELECT c.relname::text
+            FROM pg_class c
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            WHERE n.nspname = $1 AND c.relkind IN ('r', 'p') AND NOT c.relispartition
+            "#,
+            &[&schema],
+        )
+        .await?
+        .iter()
+        .map(|r| {
+            let name: String = r.get(0);
+            let table = TableSnapshot {
+                name: name.clone(),
+                columns: Vec::new(),
+                indexes: Vec::new(),
+                constraints: Vec::new(),
+            };
+            (name, table)
+        })
+        .collect();
+
+    let columns = tx
+        .query(
+            r#"
+            SELECT c.relname::text, a.attname::text, format_type(a.atttypid, a.atttypmod),
+                   NOT a.attnotnull, pg_get_expr(d.adbin, d.adrelid)
+            FROM pg_attribute a
+            JOIN pg_class c ON c.oid = a.attrelid
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            LEFT JOIN pg_attrdef d ON d.adrelid = a.attrelid AND d.adnum = a.attnum
+            WHERE n.nspname = $1 AND c.relkind IN ('r', 'p')
+              AND a.attnum > 0 AND NOT a.attisdropped
+            ORDER BY c.relname, a.attnum
+            "#,
+            &[&schema],
+        )
+        .await?;
+    for r in &columns {
+        if let Some(table) = tables.get_mut(r.get::<_, &str>(0)) {
+            table.columns.push(ColumnSnapshot {
+                name: r.get(1),
+                data_type: r.get(2),
+                nullable: r.get(3),
+                default_value: r.get(4),
+            });
+        }
+    }
+
+    // NOT NULL (contype 'n' no PG 18) já aparece na coluna.
+    let constraints = tx
+        .query(
+            r#"
+            SELECT c.relname::text, k.conname::text, k.contype::text,
+                   pg_get_constraintdef(k.oid, true)
+            FROM pg_constraint k
+            JOIN pg_class c ON c.oid = k.conrelid
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            WHERE n.nspname = $1 AND k.contype <> 'n'
+            ORDER BY c.relname, k.conname
+            "#,
+            &[&schema],
+        )
+        .await?;
+    for r in &constraints {
+        if let Some(table) = tables.get_mut(r.get::<_, &str>(0)) {
+            table.constraints.push(ConstraintSnapshot {
+                name: r.get(1),
+                kind: r.get(2),
+                definition: r.get(3),
+            });
+        }
+    }
+
+    let indexes = tx
+        .query(
+            r#"
+            SELECT t.relname::text, i.relname::text, pg_get_indexdef(x.indexrelid)
+            FROM pg_index x
+            JOIN pg_class i ON i.oid = x.indexrelid
+            JOIN pg_class t ON t.oid = x.indrelid
+            JOIN pg_namespace n ON n.oid = t.relnamespace
+            WHERE n.nspname = $1
+              AND NOT EXISTS (
+                  SELECT 1 FROM pg_constraint k
+                  WHERE k.conindid = x.indexrelid AND k.conrelid = x.indrelid
+              )
+            ORDER BY t.relname, i.relname
+            "#,
+            &[&schema],
+        )
+        .await?;
+    for r in &indexes {
+        if let Some(table) = tables.get_mut(r.get::<_, &str>(0)) {
+            let definition: String = r.get(2);
+            let definition = definition
+                .replacen(&format!(" ON ONLY {prefix}"), " ON ONLY ", 1)
+                .replacen(&format!(" ON {prefix}"), " ON ", 1);
+            table.indexes.push(IndexSnapshot {
+                name: r.get(1),
+                definition,
+            });
+        }
+    }
+
+    let views = tx
+        .query(
+            r#"
+            SELECT c.relname::text, c.relkind = 'm', pg_get_viewdef(c.oid, true),
+                   ARRAY(
+                       SELECT DISTINCT r.relname::text
+                       FROM pg_rewrite w
+                       JOIN pg_depend d ON d.classid = 'pg_rewrite'::regclass
+                                       AND d.objid = w.oid
+                                       AND d.refclassid = 'pg_class'::regclass
+                       JOIN pg_class r ON r.oid = d.refobjid
+                       WHERE w.ev_class = c.oid AND r.oid <> c.oid
+                         AND r.relnamespace = c.relnamespace
+                       ORDER BY 1
+                   )
+            FROM pg_class c
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            WHERE n.nspname = $1 AND c.relkind IN ('v', 'm')
+            ORDER BY c.relname
+            "#,
+            &[&schema],
+        )
+        .await?
+        .iter()
+        .map(|r| ViewSnapshot {
+            name: r.get(0),
+            materialized: r.get(1),
+            definition: r
+                .get::<_, &str>(2)
+                .trim_end()
+                .trim_end_matches(';')
+                .to_string(),
+            dependencies: r.get(3),
+        })
+        .collect();
+
+    // Funções de extensões vêm com a extensão, não com a migração.
+    let functions = tx
+        .query(
+            r#"
+            SELECT p.proname::text, pg_get_function_identity_arguments(p.oid),
+                   p.prokind = 'p', pg_get_functiondef(p.oid),
+                   oidvectortypes(p.proargtypes), pg_get_function_result(p.oid)
+            FROM pg_proc p
+            JOIN pg_namespace n ON n.oid = p.pronamespace
+            WHERE n.nspname = $1 AND p.prokind IN ('f', 'p', 'w')
+              AND NOT EXISTS (
+                  SELECT 1 FROM pg_depend d
+                  WHERE d.classid = 'pg_proc'::regclass AND d.objid = p.oid AND d.deptype = 'e'
+              )
+            ORDER BY 1, 2
+            "#,
+            &[&schema],
+        )
+        .await?
+        .iter()
+        .map(|r| {
+            let is_procedure: bool = r.get(2);
+            let keyword = if is_procedure { "PROCEDURE" } else { "FUNCTION" };
+            FunctionSnapshot {
+                name: r.get(0),
+                arguments: r.get(1),
+                argument_types: r.get(4),
+                result: r.get(5),
+                is_procedure,
+                definition: r
+                    .get::<_, &str>(3)
+                    .replacen(&format!("{keyword} {prefix}"), &format!("{keyword} "), 1)
+                    .trim_end()
+                    .to_string(),
+            }
+        })
+        .collect();
+
+    tx.commit().await?;
+    Ok(SchemaSnapshot {
+        tables: tables.into_values().collect(),
+        views,
+        functions,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::adapters::postgres::PostgresAdapter;
+    use crate::models::{DatabaseType, Server};
+
+    use super::*;
+
+    fn adapter() -> PostgresAdapter {
+        let server = Server {
+            id: Some(1),
+            name: "e2e".into(),
+            db_type: DatabaseType::Postgres,
+            host: "localhost".into(),
+            port: 5432,
+            username: "postgres".into(),
+            password: "1234".into(),
+            default_database: None,
+            tls: Default::default(),
+            connection_uri: None,
+            ssh_tunnel: None,
+            read_only: false,
+            confirm_writes: false,
+            created_at: 0,
+        };
+        PostgresAdapter::new(&server, "postgres").unwrap()
+    }
+
+    #[tokio::test]
+    #[ignore = "requires local postgres on localhost:5432 (postgres/1234)"]
+    async fn e2e_foreign_keys_and_constraints() {
+        let pool = &adapter().pool;
+        pool.get()
+            .await
+            .unwrap()
+            .batch_execute(
+                "DROP SCHEMA IF EXISTS e2e_keys CASCADE; CREATE SCHEMA e2e_keys;
+                 CREATE TABLE e2e_keys.accounts (
+                     id int, region text, PRIMARY KEY (id, region));
+                 CREATE TABLE e2e_keys.transfers (
+                     id int PRIMARY KEY,
+                     account_id int,
+                     account_region text,
+                     amount numeric CONSTRAINT positive CHECK (amount > 0),
+                     CONSTRAINT one_per_account UNIQUE (account_id, id)
+                         DEFERRABLE INITIALLY DEFERRED,
+                     CONSTRAINT transfers_account FOREIGN KEY (account_region, account_id)
+                         REFERENCES e2e_keys.accounts (region, id)
+                         ON UPDATE CASCADE ON DELETE SET NULL DEFERRABLE INITIALLY DEFERRED);
+                 CREATE TABLE e2e_keys.ledger (
+                     at date, account_id int, account_region text,
+                     CONSTRAINT ledger_account FOREIGN KEY (account_id, account_region)
+                         REFERENCES e2e_keys.accounts ON DELETE CASCADE)
+                     PARTITION BY RANGE (at);
+                 CREATE TABLE e2e_keys.ledger_2026 PARTITION OF e2e_keys.ledger
+                     FOR VALUES FROM ('2026-01-01') TO ('2027-01-01');",
+            )
+            .await
+            .unwrap();
+
+        let keys = list_foreign_keys(pool, "e2e_keys", "transfers")
+            .await
+            .unwrap();
+        assert_eq!(keys.len(), 1);
+        let key = &keys[0];
+        assert_eq!(key.name, "transfers_account");
+        assert_eq!(key.columns, ["account_region", "account_id"]);
+        assert_eq!(key.referenced_table, "accounts");
+        assert_eq!(key.referenced_columns, ["region", "id"]);
+        assert_eq!(key.on_update, ForeignKeyAction::Cascade);
+        assert_eq!(key.on_delete, ForeignKeyAction::SetNull);
+        assert!(key.is_deferrable && key.is_initially_deferred);
+
+        // A partition lists the key it inherits from its parent
+        let keys = list_foreign_keys(pool, "e2e_keys", "ledger_2026")
+            .await
+            .unwrap();
+        assert_eq!(keys.len(), 1);
+        assert_eq!(keys[0].columns, ["account_id", "account_region"]);
+        assert_eq!(keys[0].referenced_columns, ["id", "region"]);
+        assert_eq!(keys[0].on_delete, ForeignKeyAction::Cascade);
+        assert_eq!(keys[0].on_update, ForeignKeyAction::NoAction);
+        assert!(!keys[0].is_deferrable);
+
+        // ...while the referenced table only lists the parent's
+        let referencing = list_referencing_tables(pool, "e2e_keys", "accounts")
+            .await
+            .unwrap();
+        let tables: Vec<&str> = referencing.iter().map(|k| k.table.as_str()).collect();
+        assert_eq!(tables, ["ledger", "transfers"]);
+
+        let constraints = list_constraints(pool, "e2e_keys", "transfers")
+            .await
+            .unwrap();
+        let kinds: Vec<(&str, ConstraintType)> = constraints
+            .iter()
+            .map(|c| (c.name.as_str(), c.constraint_type))
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                ("transfers_pkey", ConstraintType::PrimaryKey),
+                ("one_per_account", ConstraintType::Unique),
+                ("positive", ConstraintType::Check),
+            ]
+        );
+        assert_eq!(constraints[0].columns, ["id"]);
+        assert!(!constraints[0].is_deferrable);
+        assert_eq!(constraints[1].columns, ["account_id", "id"]);
+        assert!(constraints[1].is_deferrable && constraints[1].is_initially_deferred);
+        assert_eq!(constraints[2].columns, ["amount"]);
+        assert_eq!(constraints[2].definition, "CHECK (amount > 0::numeric)");
+
+        pool.get()
+            .await
+            .unwrap()
+            .batch_execute("DROP SCHEMA e2e_keys CASCADE")
+            .await
+            .unwrap();
+    }
+}
@@ -1,19 +1,29 @@
 mod browse;
+mod ddl;
 mod pool;
 mod executor;
+mod explain;
+mod import;
+mod listen;
 mod metadata;
 mod notices;
-mod util;
+mod search;
+mod session;
+pub(crate) mod util;
 
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
 use async_trait::async_trait;
 use deadpool_postgres::Pool;
+use sqlparser::dialect::PostgreSqlDialect;
 
 use crate::error::{Error, Result};
 use crate::models::*;
-use crate::adapters::{DatabaseAdapter, MessageSink, PoolStats};
+use crate::adapters::{
+    analyze_sql, is_read_only_sql, sql_structure_changes, DatabaseAdapter, MessageSink, PoolStats,
+    RowSink, RowSource,
+};
 
 use self::notices::NoticeHub;
 
@@ -28,6 +38,10 @@ pub struct PostgresAdapter {
     /// Roteia os notices que chegam pelas conexões do pool para a execução
     /// que estiver rodando em cada uma (ver `notices.rs`).
     notice_hub: Arc<NoticeHub>,
+    /// Conexões presas por `open_session`, por session id.
+    sessions: session::Sessions,
+    /// Conexões dedicadas de `subscribe_channel`, por subscription id.
+    listeners: listen::Listeners,
 }
 
 impl PostgresAdapter {
@@ -39,6 +53,8 @@ impl PostgresAdapter {
             _database: database.to_string(),
             active_queries: Mutex::new(HashMap::new()),
             notice_hub,
+            sessions: Mutex::new(HashMap::new()),
+            listeners: Mutex::new(HashMap::new()),
         })
     }
 }
@@ -49,10 +65,46 @@ impl DatabaseAdapter for PostgresAdapter {
         AdapterCapabilities::postgres()
     }
 
+    fn is_read_only_query(&self, query: &str) -> bool {
+        is_read_only_sql(&PostgreSqlDialect {}, query)
+    }
+
+    fn structure_changes(&self, query: &str) -> Vec<StructureChange> {
+        sql_structure_changes(&PostgreSqlDialect {}, query)
+    }
+
+    async fn analyze_query(&self, query: &str) -> Result<Vec<StatementWarning>> {
+        let mut analysis = analyze_sql(&PostgreSqlDialect {}, query);
+        for altered in std::mem::take(&mut analysis.altered_tables) {
+            let rows =
+                metadata::row_estimate(&self.pool, altered.schema.as_deref(), &altered.table)
+                    .await?;
+            analysis.flag_if_large(&altered, rows);
+        }
+        Ok(analysis.into_warnings())
+    }
+
     async fn fetch_table_data(&self, request: TableDataRequest) -> Result<QueryResult> {
         browse::fetch_table_data(&self.pool, request).await
     }
 
+    async fn explain_query(&self, query: &str, options: ExplainOptions) -> Result<ExplainPlan> {
+        explain::explain_query(&self.pool, query, options).await
+    }
+
+    async fn stream_query(
+        &self,
+        query: &str,
+        query_id: Option<&str>,
+        sink: &mut dyn RowSink,
+    ) -> Result<u64> {
+        executor::stream_query(&self.pool, query, query_id, &self.active_queries, sink).await
+    }
+
+    async fn stream_table(&self, request: TableDataRequest, sink: &mut dyn RowSink) -> Result<u64> {
+        browse::stream_table_data(&self.pool, request, sink).await
+    }
+
     async fn execute_query(
         &self,
         query: &str,
@@ -67,6 +119,20 @@ impl DatabaseAdapter for PostgresAdapter {
         options: QueryOptions,
         sink: Option<Arc<dyn MessageSink>>,
     ) -> Result<QueryResult> {
+        if let Some(session_id) = options.session_id.as_deref() {
+            let session = session::get(&self.sessions, session_id)?;
+            let session = session.lock().await;
+            return executor::execute_in_session(
+                &session,
+                query,
+                options,
+                &self.active_queries,
+                &self.notice_hub,
+                sink,
+            )
+            .await;
+        }
+
         executor::execute_query(
             &self.pool,
             query,
@@ -94,6 +160,14 @@ impl DatabaseAdapter for PostgresAdapter {
         executor::insert_rows(&self.pool, editable, rows).await
     }
 
+    async fn import_rows(
+        &self,
+        target: &ImportTarget,
+        source: &mut dyn RowSource,
+    ) -> Result<u64> {
+        import::import_rows(&self.pool, target, source).await
+    }
+
     async fn delete_rows(
         &self,
         editable: &EditableInfo,
@@ -133,10 +207,46 @@ impl DatabaseAdapter for PostgresAdapter {
         metadata::list_indexes(&self.pool, schema, table).await
     }
 
+    async fn list_foreign_keys(&self, schema: &str, table: &str) -> Result<Vec<ForeignKeyInfo>> {
+        metadata::list_foreign_keys(&self.pool, schema, table).await
+    }
+
+    async fn list_constraints(&self, schema: &str, table: &str) -> Result<Vec<ConstraintInfo>> {
+        metadata::list_constraints(&self.pool, schema, table).await
+    }
+
+    async fn list_referencing_tables(
+        &self,
+        schema: &str,
+        table: &str,
+    ) -> Result<Vec<ForeignKeyInfo>> {
+        metadata::list_referencing_tables(&self.pool, schema, table).await
+    }
+
     async fn list_schemas_with_tables(&self) -> Result<DatabaseStructure> {
         metadata::list_schemas_with_tables(&self.pool).await
     }
 
+    async fn search_objects(&self, request: &ObjectSearchRequest) -> Result<Vec<ObjectMatch>> {
+        metadata::search_objects(&self.pool, request).await
+    }
+
+    async fn get_object_ddl(&self, request: &DdlRequest) -> Result<String> {
+        ddl::get_object_ddl(&self.pool, request).await
+    }
+
+    async fn schema_snapshot(&self, schema: &str) -> Result<SchemaSnapshot> {
+        metadata::schema_snapshot(&self.pool, schema).await
+    }
+
+    async fn search_values(
+        &self,
+        request: &ValueSearchRequest,
+        events: tokio::sync::mpsc::Sender<ValueSearchEvent>,
+    ) -> Result<()> {
+        search::search_values(&self.pool, request, events).await
+    }
+
     async fn test_connection(&self) -> Result<()> {
         let client = self.pool.get().await?;
         client.query_one("SELECT 1", &[]).await?;
@@ -167,6 +277,45 @@ impl DatabaseAdapter for PostgresAdapter {
         Ok(())
     }
 
+    async fn open_session(&self, session_id: &str) -> Result<TransactionState> {
+        session::open(&self.pool, &self.sessions, session_id).await
+    }
+
+    async fn session_state(&self, session_id: &str) -> Result<TransactionState> {
+        let session = session::get(&self.sessions, session_id)?;
+        let session = session.lock().await;
+        session::state(&session).await
+    }
+
+    async fn end_session(&self, session_id: &str, commit: bool) -> Result<()> {
+        session::end(&self.sessions, session_id, commit).await
+    }
+
+    async fn subscribe_channel(
+        &self,
+        subscription_id: &str,
+        request: &ChannelSubscription,
+        events: tokio::sync::mpsc::Sender<ChannelNotification>,
+    ) -> Result<()> {
+        listen::subscribe(
+            &self.pool,
+            &self.notice_hub,
+            &self.listeners,
+            subscription_id,
+            request,
+            events,
+        )
+        .await
+    }
+
+    async fn unsubscribe_channel(&self, subscription_id: &str) -> Result<()> {
+        listen::unsubscribe(&self.listeners, subscription_id)
+    }
+
+    async fn notify_channel(&self, channel: &str, payload: &str) -> Result<()> {
+        listen::notify(&self.pool, channel, payload).await
+    }
+
     fn pool_stats(&self) -> Option<PoolStats> {
         let status = self.pool.status();
         Some(PoolStats {
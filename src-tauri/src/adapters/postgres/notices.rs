@@ -10,6 +10,10 @@
 //! ser criada, e o executor inscreve o PID da conexão que pegou do pool
 //! enquanto a query dura. Notices de um PID sem inscrição (o COUNT paralelo, o
 //! recycle do pool, metadados) são descartados.
+//!
+//! As notificações de `LISTEN` chegam pelo mesmo caminho e também são
+//! roteadas por PID: o da conexão dedicada que deu o `LISTEN` (ver
+//! `listen.rs`).
 
 use std::collections::HashMap;
 use std::future::Future;
@@ -18,13 +22,14 @@ use std::sync::atomic::{AtomicI32, Ordering};
 use std::sync::{Arc, Mutex};
 
 use deadpool_postgres::Connect;
+use tokio::sync::mpsc;
 use tokio::task::JoinHandle;
 use tokio_postgres::error::{DbError, ErrorPosition};
 use tokio_postgres::tls::{MakeTlsConnect, TlsConnect};
-use tokio_postgres::{AsyncMessage, Client, Config as PgConfig, Socket};
+use tokio_postgres::{AsyncMessage, Client, Config as PgConfig, Notification, Socket};
 
 use crate::adapters::MessageSink;
-use crate::models::{now_ms, QueryMessage, QueryMessageKind};
+use crate::models::{now_ms, ChannelNotification, QueryMessage, QueryMessageKind};
 
 /// Converte um `DbError` (notices e erros usam o mesmo formato no protocolo).
 /// `force_error` marca a mensagem como erro mesmo que a severidade venha
@@ -63,6 +68,7 @@ pub fn message_from_db_error(err: &DbError, force_error: bool) -> QueryMessage {
 #[derive(Default)]
 pub struct NoticeHub {
     sinks: Mutex<HashMap<i32, Arc<dyn MessageSink>>>,
+    listeners: Mutex<HashMap<i32, mpsc::Sender<ChannelNotification>>>,
 }
 
 impl NoticeHub {
@@ -76,6 +82,29 @@ impl NoticeHub {
         }
     }
 
+    /// Passa a entregar as notificações recebidas pela conexão `pid` (a que
+    /// deu `LISTEN`) em `events`, enquanto o guard viver.
+    pub fn listen(
+        self: &Arc<Self>,
+        pid: i32,
+        events: mpsc::Sender<ChannelNotification>,
+    ) -> ListenSub {
+        self.listeners.lock().unwrap().insert(pid, events);
+        ListenSub {
+            hub: Arc::clone(self),
+            pid,
+        }
+    }
+
+    /// A conexão `pid` acabou: um `LISTEN` nela não recebe mais nada, então
+    /// o sender sai já (fechando o canal de quem repassa ao frontend) em vez
+    /// de esperar o `unsubscribe`.
+    fn connection_closed(&self, pid: i32) {
+        if pid != 0 {
+            self.listeners.lock().unwrap().remove(&pid);
+        }
+    }
+
     fn dispatch(&self, pid: i32, notice: &DbError) {
         // Solta o lock antes de entregar: o push atravessa o IPC do Tauri e a
         // task da conexão não pode ficar presa nele.
@@ -104,6 +133,19 @@ impl Drop for NoticeSub {
     }
 }
 
+/// Como o [`NoticeSub`], para as notificações. Soltar o sender fecha o canal
+/// de quem as repassa ao frontend.
+pub struct ListenSub {
+    hub: Arc<NoticeHub>,
+    pid: i32,
+}
+
+impl Drop for ListenSub {
+    fn drop(&mut self) {
+        self.hub.listeners.lock().unwrap().remove(&self.pid);
+    }
+}
+
 /// `Connect` do deadpool que, em vez de descartar os `AsyncMessage`, encaminha
 /// os notices para o [`NoticeHub`].
 pub struct NoticeConnect<T> {
@@ -145,12 +187,15 @@ where
                         Ok(AsyncMessage::Notice(notice)) => {
                             dispatch_notice(&hub, &task_pid, &notice);
                         }
-                        // LISTEN/NOTIFY: sem uso no app hoje.
+                        Ok(AsyncMessage::Notification(notification)) => {
+                            dispatch_notification(&hub, &task_pid, &notification);
+                        }
                         Ok(_) => {}
                         // Conexão morreu; o pool a descarta no próximo checkout.
                         Err(_) => break,
                     }
                 }
+                hub.connection_closed(task_pid.load(Ordering::Relaxed));
             });
 
             if let Ok(row) = client.query_one("SELECT pg_backend_pid()", &[]).await {
@@ -171,3 +216,20 @@ fn dispatch_notice(hub: &NoticeHub, pid: &AtomicI32, notice: &DbError) {
         hub.dispatch(pid, notice);
     }
 }
+
+fn dispatch_notification(hub: &NoticeHub, pid: &AtomicI32, notification: &Notification) {
+    let pid = pid.load(Ordering::Relaxed);
+    let events = hub.listeners.lock().unwrap().get(&pid).cloned();
+
+    // Sem await na task da conexão: com o buffer cheio (o frontend não dá
+    // conta), a notificação é descartada.
+    if let Some(events) = events {
+        let _ = events.try_send(ChannelNotification {
+            pattern: None,
+            channel: notification.channel().to_string(),
+            payload: notification.payload().to_string(),
+            sender_pid: Some(notification.process_id()),
+            timestamp_ms: now_ms(),
+        });
+    }
+}
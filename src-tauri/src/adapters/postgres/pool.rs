@@ -6,6 +6,7 @@ use postgres_native_tls::MakeTlsConnector;
 use tokio_postgres::tls::{MakeTlsConnect, TlsConnect};
 use tokio_postgres::{NoTls, Socket};
 
+use crate::adapters::tls;
 use crate::error::{Error, Result};
 use crate::models::Server;
 
@@ -32,7 +33,14 @@ pub fn create_pool(server: &Server, database: &str, hub: &Arc<NoticeHub>) -> Res
 
     // Garante a entrega dos RAISE NOTICE mesmo em servidores cujo default de
     // client_min_messages seja mais restritivo que o do Postgres.
-    cfg.options = Some("-c client_min_messages=notice".to_string());
+    let mut options = "-c client_min_messages=notice".to_string();
+    // Server em modo read-only: além da checagem nos commands, o próprio
+    // Postgres recusa escritas em toda conexão do pool (sessões incluídas),
+    // o que pega o que a análise do SQL não enxerga (funções que escrevem).
+    if server.read_only {
+        options.push_str(" -c default_transaction_read_only=on");
+    }
+    cfg.options = Some(options);
 
     // Keepalive TCP: evita que firewalls/NAT derrubem conexões ociosas em
     // silêncio enquanto o app fica aberto sem uso.
@@ -56,18 +64,51 @@ pub fn create_pool(server: &Server, database: &str, hub: &Arc<NoticeHub>) -> Res
         ..Default::default()
     });
 
-    if server.ssl_enabled {
-        // sslmode=require semantics: encrypt the connection without
-        // verifying the certificate (common for self-signed DB servers)
-        let connector = native_tls::TlsConnector::builder()
-            .danger_accept_invalid_certs(true)
-            .danger_accept_invalid_hostnames(true)
-            .build()
-            .map_err(|e| Error::Connection(format!("TLS setup failed: {e}")))?;
+    if !server.tls.mode.is_enabled() {
+        return build_pool(&cfg, NoTls, hub);
+    }
 
-        build_pool(&cfg, MakeTlsConnector::new(connector), hub)
-    } else {
-        build_pool(&cfg, NoTls, hub)
+    // Never fall back to plaintext when a TLS mode was chosen (libpq's
+    // default, prefer, would).
+    cfg.ssl_mode = Some(deadpool_postgres::SslMode::Require);
+    let connector = tls::native_connector(&server.tls)?;
+
+    match server.tls.server_name.as_deref() {
+        // Through an SSH tunnel `host` is 127.0.0.1: verify the certificate
+        // against the database's real hostname instead.
+        Some(name) => build_pool(
+            &cfg,
+            FixedServerName {
+                connector,
+                name: name.to_string(),
+            },
+            hub,
+        ),
+        None => build_pool(&cfg, MakeTlsConnector::new(connector), hub),
+    }
+}
+
+/// `MakeTlsConnect` that ignores the host being connected to and always
+/// checks the certificate (and sends SNI) for `name`.
+#[derive(Clone)]
+struct FixedServerName {
+    connector: native_tls::TlsConnector,
+    name: String,
+}
+
+impl MakeTlsConnect<Socket> for FixedServerName {
+    type Stream = postgres_native_tls::TlsStream<Socket>;
+    type TlsConnect = postgres_native_tls::TlsConnector;
+    type Error = native_tls::Error;
+
+    fn make_tls_connect(
+        &mut self,
+        _host: &str,
+    ) -> std::result::Result<Self::TlsConnect, Self::Error> {
+        Ok(postgres_native_tls::TlsConnector::new(
+            self.connector.clone(),
+            &self.name,
+        ))
     }
 }
 
@@ -0,0 +1,285 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use deadpool_postgres::Pool;
+use tokio::sync::mpsc;
+use tokio_postgres::error::SqlState;
+use tokio_postgres::SimpleQueryMessage;
+
+use crate::adapters::{like_contains, scan_tables, ScanTarget, TableScan};
+use crate::error::{Error, Result};
+use crate::models::{ValueHit, ValueMatchMode, ValueSearchEvent, ValueSearchRequest};
+
+use super::browse::DEFAULT_SCHEMA;
+use super::util::{quote_ident, quote_literal};
+
+/// What a table scan selects: the key to report and the columns to test.
+struct TablePlan {
+    /// Primary key, or `ctid` for tables without one.
+    key_columns: Vec<String>,
+    columns: Vec<String>,
+}
+
+/// Every table (and materialized view) of the schema, one query each on its
+/// own pooled connection. Columns are compared as `::text`, so any type
+/// matches its text form; `bytea` is skipped.
+pub async fn search_values(
+    pool: &Pool,
+    request: &ValueSearchRequest,
+    events: mpsc::Sender<ValueSearchEvent>,
+) -> Result<()> {
+    if request.value.contains('\0') {
+        return Err(Error::InvalidQuery(
+            "The value cannot contain NUL characters".into(),
+        ));
+    }
+    let schema = request
+        .schema
+        .clone()
+        .unwrap_or_else(|| DEFAULT_SCHEMA.to_string());
+    let plans = Arc::new(table_plans(pool, &schema).await?);
+
+    let mut targets: Vec<ScanTarget> = plans
+        .keys()
+        .map(|table| ScanTarget {
+            schema: Some(schema.clone()),
+            table: table.clone(),
+        })
+        .collect();
+    targets.sort_by(|a, b| a.table.cmp(&b.table));
+
+    // Uma conexão do pool fica livre para o resto do app.
+    let concurrency = request
+        .concurrency
+        .min(pool.status().max_size.saturating_sub(1));
+
+    scan_tables(request, targets, concurrency, events, |target| {
+        let pool = pool.clone();
+        let plans = Arc::clone(&plans);
+        let request = request.clone();
+        async move {
+            let plan = &plans[&target.table];
+            scan_table(&pool, &target, plan, &request).await
+        }
+    })
+    .await
+}
+
+async fn table_plans(pool: &Pool, schema: &str) -> Result<HashMap<String, TablePlan>> {
+    let client = pool.get().await?;
+    let rows = client
+        .query(
+            r#"
+            SELECT
+                c.relname::text,
+                array_agg(a.attname::text ORDER BY a.attnum)
+                    FILTER (WHERE t.typname <> 'bytea') AS columns,
+                (
+                    SELECT array_agg(ka.attname::text ORDER BY k.ord)
+                    FROM pg_index x
+                    CROSS JOIN LATERAL unnest(x.indkey) WITH ORDINALITY AS k(attnum, ord)
+                    JOIN pg_attribute ka ON ka.attrelid = x.indrelid AND ka.attnum = k.attnum
+                    WHERE x.indrelid = c.oid AND x.indisprimary
+                ) AS pk
+            FROM pg_class c
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            JOIN pg_attribute a ON a.attrelid = c.oid AND a.attnum > 0 AND NOT a.attisdropped
+            JOIN pg_type t ON t.oid = a.atttypid
+            WHERE n.nspname = $1
+              AND c.relkind IN ('r', 'p', 'm')
+              AND NOT c.relispartition
+            GROUP BY c.oid, c.relname
+            "#,
+            &[&schema],
+        )
+        .await?;
+
+    Ok(rows
+        .iter()
+        .filter_map(|r| {
+            let columns: Vec<String> = r.get::<_, Option<Vec<String>>>(1)?;
+            let key_columns = r
+                .get::<_, Option<Vec<String>>>(2)
+                .unwrap_or_else(|| vec!["ctid".to_string()]);
+            Some((
+                r.get(0),
+                TablePlan {
+                    key_columns,
+                    columns,
+                },
+            ))
+        })
+        .collect())
+}
+
+/// One `SELECT` per table, on the simple protocol so `SET LOCAL
+/// statement_timeout` covers just it (both run in one implicit transaction).
+/// Each column comes back only where it matched (`CASE WHEN ... END`).
+async fn scan_table(
+    pool: &Pool,
+    target: &ScanTarget,
+    plan: &TablePlan,
+    request: &ValueSearchRequest,
+) -> Result<TableScan> {
+    let schema = target.schema.as_deref().unwrap_or(DEFAULT_SCHEMA);
+    let comparisons: Vec<String> = plan
+        .columns
+        .iter()
+        .map(|column| match request.mode {
+            ValueMatchMode::Exact => format!(
+                "{}::text = {}",
+                quote_ident(column),
+                quote_literal(&request.value)
+            ),
+            ValueMatchMode::Contains => format!(
+                "{}::text ILIKE {}",
+                quote_ident(column),
+                quote_literal(&like_contains(&request.value))
+            ),
+        })
+        .collect();
+
+    let select_list: Vec<String> =
+        plan.key_columns
+            .iter()
+            .map(|key| format!("{}::text", quote_ident(key)))
+            .chain(plan.columns.iter().zip(&comparisons).map(|(column, cmp)| {
+                format!("CASE WHEN {cmp} THEN {}::text END", quote_ident(column))
+            }))
+            .collect();
+
+    let sql = format!(
+        "SET LOCAL statement_timeout = {}; SELECT {} FROM {}.{} WHERE {} LIMIT {}",
+        request.table_timeout_ms.max(1),
+        select_list.join(", "),
+        quote_ident(schema),
+        quote_ident(&target.table),
+        comparisons.join(" OR "),
+        request.max_hits,
+    );
+
+    let client = pool.get().await?;
+    let messages = match client.simple_query(&sql).await {
+        Ok(messages) => messages,
+        Err(e) if e.code() == Some(&SqlState::QUERY_CANCELED) => return Ok(TableScan::TimedOut),
+        Err(e) => return Err(e.into()),
+    };
+
+    let keys = plan.key_columns.len();
+    let mut hits = Vec::new();
+    for message in messages {
+        let SimpleQueryMessage::Row(row) = message else {
+            continue;
+        };
+        let key_values: Vec<Option<String>> =
+            (0..keys).map(|i| row.get(i).map(str::to_string)).collect();
+        for (i, column) in plan.columns.iter().enumerate() {
+            if let Some(value) = row.get(keys + i) {
+                hits.push(ValueHit {
+                    schema: target.schema.clone(),
+                    table: target.table.clone(),
+                    column: column.clone(),
+                    key_columns: plan.key_columns.clone(),
+                    key_values: key_values.clone(),
+                    value: Some(value.to_string()),
+                });
+            }
+        }
+    }
+    Ok(TableScan::Hits(hits))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    #[ignore = "requires local postgres on localhost:5432 (postgres/1234)"]
+    async fn e2e_search_values_reports_key_and_column() {
+        use crate::adapters::postgres::PostgresAdapter;
+        use crate::adapters::DatabaseAdapter;
+        use crate::models::{DatabaseType, Server};
+
+        let server = Server {
+            id: Some(1),
+            name: "e2e".into(),
+            db_type: DatabaseType::Postgres,
+            host: "localhost".into(),
+            port: 5432,
+            username: "postgres".into(),
+            password: "1234".into(),
+            default_database: None,
+            tls: Default::default(),
+            connection_uri: None,
+            ssh_tunnel: None,
+            read_only: false,
+            confirm_writes: false,
+            created_at: 0,
+        };
+        let adapter = PostgresAdapter::new(&server, "postgres").unwrap();
+        let setup = [
+            "DROP SCHEMA IF EXISTS e2e_search CASCADE",
+            "CREATE SCHEMA e2e_search",
+            "CREATE TABLE e2e_search.users (id int PRIMARY KEY, email text, note text)",
+            "CREATE TABLE e2e_search.logs (line text)",
+            "INSERT INTO e2e_search.users VALUES (1, 'o''neil@x.io', 'a\\b'), (2, 'b@x.io', NULL)",
+            "INSERT INTO e2e_search.logs VALUES ('login O''NEIL@x.io')",
+        ];
+        adapter
+            .execute_transaction(setup.iter().map(|s| s.to_string()).collect())
+            .await
+            .unwrap();
+
+        let search = |mode| ValueSearchRequest {
+            value: "o'neil@x.io".into(),
+            schema: Some("e2e_search".into()),
+            mode,
+            concurrency: 2,
+            table_timeout_ms: 5000,
+            max_hits: 100,
+        };
+        let hits = |request: ValueSearchRequest| {
+            let adapter = &adapter;
+            async move {
+                let (tx, mut rx) = mpsc::channel(64);
+                adapter.search_values(&request, tx).await.unwrap();
+                let mut hits = Vec::new();
+                while let Some(event) = rx.recv().await {
+                    if let ValueSearchEvent::Hit(hit) = event {
+                        hits.push((hit.table, hit.column, hit.key_columns, hit.key_values));
+                    }
+                }
+                hits.sort();
+                hits
+            }
+        };
+
+        let exact = hits(search(ValueMatchMode::Exact)).await;
+        assert_eq!(
+            exact,
+            [(
+                "users".to_string(),
+                "email".to_string(),
+                vec!["id".to_string()],
+                vec![Some("1".to_string())]
+            )]
+        );
+
+        let contains = hits(search(ValueMatchMode::Contains)).await;
+        assert_eq!(contains.len(), 2);
+        assert_eq!(contains[0].0, "logs");
+        assert_eq!(contains[0].2, ["ctid"]);
+
+        let backslash = hits(ValueSearchRequest {
+            value: "a\\b".into(),
+            ..search(ValueMatchMode::Exact)
+        })
+        .await;
+        assert_eq!(backslash.len(), 1);
+
+        adapter
+            .execute_statement("DROP SCHEMA e2e_search CASCADE")
+            .await
+            .unwrap();
+    }
+}
@@ -0,0 +1,356 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use deadpool_postgres::{Object, Pool};
+use tokio_postgres::error::SqlState;
+use tokio_postgres::SimpleQueryMessage;
+
+use crate::error::{Error, Result};
+use crate::models::TransactionState;
+
+/// Sessões abertas ao mesmo tempo por adapter. Cada uma segura uma conexão
+/// do pool (16) até o commit/rollback; o resto fica para o uso normal.
+const MAX_SESSIONS: usize = 8;
+
+/// A pooled connection pinned to a session id, so statements sent in
+/// separate `execute_query` calls share its transaction. The PID is read
+/// once, up front: in a failed transaction even `pg_backend_pid()` errors.
+pub struct Session {
+    /// `None` once the session ended: an execution that was waiting on it
+    /// finds it gone instead of running on a connection back in the pool.
+    client: Option<Object>,
+    pub pid: i32,
+}
+
+impl Session {
+    pub fn client(&self) -> Result<&Object> {
+        self.client.as_ref().ok_or_else(|| {
+            Error::NotFound("Session (already committed, rolled back or disconnected)".into())
+        })
+    }
+}
+
+/// The tokio mutex serialises executions on the same session (the editor
+/// running while the state is polled) without blocking the map.
+pub type Sessions = Mutex<HashMap<String, Arc<tokio::sync::Mutex<Session>>>>;
+
+pub async fn open(pool: &Pool, sessions: &Sessions, session_id: &str) -> Result<TransactionState> {
+    {
+        let open = sessions.lock().unwrap();
+        if open.contains_key(session_id) {
+            return Err(Error::AlreadyExists(format!("Session {session_id}")));
+        }
+        if open.len() >= MAX_SESSIONS {
+            return Err(Error::InvalidState(format!(
+                "Too many open sessions ({MAX_SESSIONS}): commit or roll back one first"
+            )));
+        }
+    }
+
+    let client = pool.get().await?;
+    let pid: i32 = client
+        .query_one("SELECT pg_backend_pid()", &[])
+        .await?
+        .get(0);
+
+    // A checagem acima soltou o lock durante o await: outra chamada com o
+    // mesmo id pode ter chegado antes.
+    let mut open = sessions.lock().unwrap();
+    if open.contains_key(session_id) {
+        return Err(Error::AlreadyExists(format!("Session {session_id}")));
+    }
+    open.insert(
+        session_id.to_string(),
+        Arc::new(tokio::sync::Mutex::new(Session {
+            client: Some(client),
+            pid,
+        })),
+    );
+    Ok(TransactionState::Idle)
+}
+
+pub fn get(sessions: &Sessions, session_id: &str) -> Result<Arc<tokio::sync::Mutex<Session>>> {
+    sessions
+        .lock()
+        .unwrap()
+        .get(session_id)
+        .cloned()
+        .ok_or_else(|| not_found(session_id))
+}
+
+/// The protocol's transaction status isn't exposed by tokio-postgres, so it
+/// is probed: inside a transaction block `now()` is frozen at its start while
+/// `statement_timestamp()` moves; outside, both belong to the probe itself.
+/// Only over the simple protocol, though: the extended one (parse, bind,
+/// execute) already moves `statement_timestamp()` within the probe's own
+/// implicit transaction. A failed transaction rejects any statement with
+/// `25P02`.
+pub async fn state(session: &Session) -> Result<TransactionState> {
+    match session
+        .client()?
+        .simple_query("SELECT now() <> statement_timestamp()")
+        .await
+    {
+        Ok(messages) => {
+            let in_transaction = messages.iter().any(|m| match m {
+                SimpleQueryMessage::Row(row) => row.get(0) == Some("t"),
+                _ => false,
+            });
+            Ok(if in_transaction {
+                TransactionState::InTransaction
+            } else {
+                TransactionState::Idle
+            })
+        }
+        Err(e) if e.code() == Some(&SqlState::IN_FAILED_SQL_TRANSACTION) => {
+            Ok(TransactionState::Failed)
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// COMMIT or ROLLBACK whatever is open and give the connection back to the
+/// pool, reset with `DISCARD ALL` so the session's `SET`s, role and temp
+/// tables don't leak into later queries. The session is gone even if this
+/// fails, and the connection with it: it may still be inside the transaction
+/// (or keep the session's settings), so it is closed rather than handed to
+/// the next checkout.
+///
+/// Committing a failed transaction rolls it back (that's what Postgres does
+/// with the COMMIT anyway) and reports it as an error.
+pub async fn end(sessions: &Sessions, session_id: &str, commit: bool) -> Result<()> {
+    let session = sessions
+        .lock()
+        .unwrap()
+        .remove(session_id)
+        .ok_or_else(|| not_found(session_id))?;
+    // Espera a execução que estiver rodando nela terminar.
+    let mut session = session.lock().await;
+
+    let result = finish(&session, commit).await;
+    let Some(client) = session.client.take() else {
+        return result;
+    };
+    if result.is_err() || reset(&client).await.is_err() {
+        drop(Object::take(client));
+    }
+    result
+}
+
+/// `DISCARD ALL` also deallocates the prepared statements the client caches
+/// (its own and the type lookups), so the caches go with them.
+async fn reset(client: &Object) -> Result<()> {
+    client.batch_execute("DISCARD ALL").await?;
+    client.statement_cache.clear();
+    client.clear_type_cache();
+    Ok(())
+}
+
+async fn finish(session: &Session, commit: bool) -> Result<()> {
+    let before = state(session).await?;
+    let failed = before == TransactionState::Failed;
+    if before != TransactionState::Idle {
+        let command = if commit && !failed {
+            "COMMIT"
+        } else {
+            "ROLLBACK"
+        };
+        session.client()?.batch_execute(command).await?;
+    }
+
+    if commit && failed {
+        return Err(Error::InvalidState(
+            "The transaction had failed and was rolled back instead of committed".into(),
+        ));
+    }
+    Ok(())
+}
+
+fn not_found(session_id: &str) -> Error {
+    Error::NotFound(format!(
+        "Session {session_id} (already committed, rolled back or disconnected)"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::adapters::postgres::PostgresAdapter;
+    use crate::adapters::DatabaseAdapter;
+    use crate::models::{DatabaseType, QueryOptions, Server};
+
+    use super::*;
+
+    fn adapter() -> PostgresAdapter {
+        let server = Server {
+            id: Some(1),
+            name: "e2e".into(),
+            db_type: DatabaseType::Postgres,
+            host: "localhost".into(),
+            port: 5432,
+            username: "postgres".into(),
+            password: "1234".into(),
+            default_database: None,
+            tls: Default::default(),
+            connection_uri: None,
+            ssh_tunnel: None,
+            read_only: false,
+            confirm_writes: false,
+            created_at: 0,
+        };
+        PostgresAdapter::new(&server, "postgres").unwrap()
+    }
+
+    async fn run(adapter: &PostgresAdapter, session_id: Option<&str>, sql: &str) -> Result<()> {
+        adapter
+            .execute_query(
+                sql,
+                QueryOptions {
+                    session_id: session_id.map(str::to_string),
+                    ..Default::default()
+                },
+            )
+            .await
+            .map(|_| ())
+    }
+
+    async fn count(adapter: &PostgresAdapter, table: &str) -> i64 {
+        let client = adapter.pool.get().await.unwrap();
+        client
+            .query_one(&format!("SELECT count(*) FROM {table}"), &[])
+            .await
+            .unwrap()
+            .get(0)
+    }
+
+    #[tokio::test]
+    #[ignore = "requires local postgres on localhost:5432 (postgres/1234)"]
+    async fn e2e_transaction_spans_calls_until_commit_or_rollback() {
+        let adapter = adapter();
+        run(
+            &adapter,
+            None,
+            "DROP TABLE IF EXISTS e2e_session; CREATE TABLE e2e_session (id int)",
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            adapter.open_session("s1").await.unwrap(),
+            TransactionState::Idle
+        );
+        assert_eq!(
+            adapter.session_state("s1").await.unwrap(),
+            TransactionState::Idle
+        );
+        run(&adapter, Some("s1"), "BEGIN").await.unwrap();
+        run(&adapter, Some("s1"), "INSERT INTO e2e_session VALUES (1)")
+            .await
+            .unwrap();
+        assert_eq!(
+            adapter.session_state("s1").await.unwrap(),
+            TransactionState::InTransaction
+        );
+        // Not visible outside the session yet
+        assert_eq!(count(&adapter, "e2e_session").await, 0);
+        run(&adapter, Some("s1"), "COMMIT").await.unwrap();
+        assert_eq!(
+            adapter.session_state("s1").await.unwrap(),
+            TransactionState::Idle
+        );
+        assert_eq!(count(&adapter, "e2e_session").await, 1);
+
+        run(
+            &adapter,
+            Some("s1"),
+            "BEGIN; INSERT INTO e2e_session VALUES (2)",
+        )
+        .await
+        .unwrap();
+        adapter.end_session("s1", true).await.unwrap();
+        assert_eq!(count(&adapter, "e2e_session").await, 2);
+        assert!(matches!(
+            adapter.session_state("s1").await,
+            Err(Error::NotFound(_))
+        ));
+
+        adapter.open_session("s2").await.unwrap();
+        run(
+            &adapter,
+            Some("s2"),
+            "BEGIN; INSERT INTO e2e_session VALUES (3)",
+        )
+        .await
+        .unwrap();
+        adapter.end_session("s2", false).await.unwrap();
+        assert_eq!(count(&adapter, "e2e_session").await, 2);
+
+        run(&adapter, None, "DROP TABLE e2e_session").await.unwrap();
+    }
+
+    #[tokio::test]
+    #[ignore = "requires local postgres on localhost:5432 (postgres/1234)"]
+    async fn e2e_failed_transaction_is_rolled_back_on_commit() {
+        let adapter = adapter();
+        run(
+            &adapter,
+            None,
+            "DROP TABLE IF EXISTS e2e_failed; CREATE TABLE e2e_failed (id int)",
+        )
+        .await
+        .unwrap();
+
+        adapter.open_session("s1").await.unwrap();
+        run(
+            &adapter,
+            Some("s1"),
+            "BEGIN; INSERT INTO e2e_failed VALUES (1)",
+        )
+        .await
+        .unwrap();
+        assert!(run(&adapter, Some("s1"), "SELECT 1 / 0").await.is_err());
+        assert_eq!(
+            adapter.session_state("s1").await.unwrap(),
+            TransactionState::Failed
+        );
+
+        assert!(matches!(
+            adapter.end_session("s1", true).await,
+            Err(Error::InvalidState(_))
+        ));
+        assert_eq!(count(&adapter, "e2e_failed").await, 0);
+
+        run(&adapter, None, "DROP TABLE e2e_failed").await.unwrap();
+    }
+
+    #[tokio::test]
+    #[ignore = "requires local postgres on localhost:5432 (postgres/1234)"]
+    async fn e2e_session_settings_dont_outlive_the_session() {
+        let adapter = adapter();
+        adapter.open_session("s1").await.unwrap();
+        run(
+            &adapter,
+            Some("s1"),
+            "SET search_path = e2e_nowhere; CREATE TEMP TABLE e2e_leak (id int)",
+        )
+        .await
+        .unwrap();
+        adapter.end_session("s1", true).await.unwrap();
+
+        // Whichever pooled connection the session had, none kept its state
+        let mut clients = Vec::new();
+        for _ in 0..adapter.pool.status().size {
+            let client = adapter.pool.get().await.unwrap();
+            let row = client
+                .query_one(
+                    "SELECT current_setting('search_path'), \
+                            to_regclass('pg_temp.e2e_leak') IS NULL",
+                    &[],
+                )
+                .await
+                .unwrap();
+            assert_ne!(row.get::<_, String>(0), "e2e_nowhere");
+            assert!(row.get::<_, bool>(1));
+            clients.push(client);
+        }
+    }
+}
@@ -7,6 +7,17 @@ pub fn quote_ident(ident: &str) -> String {
     format!("\"{}\"", ident.replace('"', "\"\""))
 }
 
+/// Quotes a string as a PostgreSQL literal, for SQL sent over the simple
+/// protocol (which has no bind parameters). Backslashes switch to `E''` so
+/// the result doesn't depend on `standard_conforming_strings`.
+pub fn quote_literal(value: &str) -> String {
+    if value.contains('\\') {
+        format!("E'{}'", value.replace('\\', "\\\\").replace('\'', "''"))
+    } else {
+        format!("'{}'", value.replace('\'', "''"))
+    }
+}
+
 /// Metadata for a single table column, in physical (`attnum`) order.
 pub struct ColumnMeta {
     pub name: String,
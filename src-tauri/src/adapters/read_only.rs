@@ -0,0 +1,112 @@
+use sqlparser::ast::{Query, SetExpr, Statement};
+use sqlparser::dialect::Dialect;
+use sqlparser::parser::Parser;
+
+/// SQLite pragmas whose argument names what to inspect rather than a new
+/// setting (`PRAGMA journal_mode(WAL)` has the same shape and writes).
+const INTROSPECTION_PRAGMAS: &[&str] = &[
+    "table_info",
+    "table_xinfo",
+    "index_list",
+    "index_info",
+    "index_xinfo",
+    "foreign_key_list",
+    "foreign_key_check",
+    "integrity_check",
+    "quick_check",
+];
+
+/// Whether `sql` (one statement or a whole script) only reads, for the
+/// read-only mode of the SQL adapters.
+///
+/// Errs on the side of "write": a script that doesn't parse, or any
+/// statement that isn't a plain query, EXPLAIN, SHOW or read PRAGMA, counts
+/// as a write. The first keyword isn't enough: `WITH d AS (DELETE ...)
+/// SELECT ...` and `SELECT 1; DROP TABLE t` both start like a read.
+pub fn is_read_only_sql(dialect: &dyn Dialect, sql: &str) -> bool {
+    let Ok(statements) = Parser::parse_sql(dialect, sql) else {
+        return false;
+    };
+    !statements.is_empty() && statements.iter().all(is_read_only_statement)
+}
+
+fn is_read_only_statement(statement: &Statement) -> bool {
+    match statement {
+        Statement::Query(query) => is_read_only_query(query),
+        // EXPLAIN ANALYZE executes the statement
+        Statement::Explain {
+            analyze, statement, ..
+        } => !analyze || is_read_only_statement(statement),
+        Statement::ExplainTable { .. }
+        | Statement::ShowVariable { .. }
+        | Statement::ShowVariables { .. }
+        | Statement::ShowTables { .. }
+        | Statement::ShowColumns { .. }
+        | Statement::ShowCreate { .. } => true,
+        // `PRAGMA x` reads, `PRAGMA x = y` sets
+        Statement::Pragma {
+            name, value, is_eq, ..
+        } => {
+            let name = name.to_string().to_lowercase();
+            let name = name.rsplit('.').next().unwrap_or_default();
+            value.is_none() || (!is_eq && INTROSPECTION_PRAGMAS.contains(&name))
+        }
+        _ => false,
+    }
+}
+
+/// `SELECT ... INTO` creates a table and `FOR UPDATE` takes row locks: both
+/// are refused along with data-modifying CTEs.
+fn is_read_only_query(query: &Query) -> bool {
+    let ctes_read = query
+        .with
+        .as_ref()
+        .is_none_or(|with| with.cte_tables.iter().all(|cte| is_read_only_query(&cte.query)));
+    ctes_read && query.locks.is_empty() && is_read_only_set_expr(&query.body)
+}
+
+fn is_read_only_set_expr(body: &SetExpr) -> bool {
+    match body {
+        SetExpr::Select(select) => select.into.is_none(),
+        SetExpr::Query(query) => is_read_only_query(query),
+        SetExpr::SetOperation { left, right, .. } => {
+            is_read_only_set_expr(left) && is_read_only_set_expr(right)
+        }
+        SetExpr::Values(_) | SetExpr::Table(_) => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlparser::dialect::{PostgreSqlDialect, SQLiteDialect};
+
+    use super::*;
+
+    #[test]
+    fn plain_reads_pass() {
+        let pg = PostgreSqlDialect {};
+        assert!(is_read_only_sql(&pg, "SELECT * FROM users WHERE id = 1"));
+        assert!(is_read_only_sql(&pg, "WITH t AS (SELECT 1) SELECT * FROM t; SELECT 2"));
+        assert!(is_read_only_sql(&pg, "EXPLAIN SELECT 1"));
+        assert!(is_read_only_sql(&pg, "SELECT * FROM t WHERE a = :a::int AND b = $1"));
+        assert!(is_read_only_sql(&SQLiteDialect {}, "PRAGMA table_info('users')"));
+    }
+
+    #[test]
+    fn writes_hidden_behind_reads_are_caught() {
+        let pg = PostgreSqlDialect {};
+        assert!(!is_read_only_sql(&pg, "SELECT 1; DROP TABLE users"));
+        assert!(!is_read_only_sql(
+            &pg,
+            "WITH d AS (DELETE FROM users RETURNING id) SELECT * FROM d"
+        ));
+        assert!(!is_read_only_sql(&pg, "SELECT * INTO backup FROM users"));
+        assert!(!is_read_only_sql(&pg, "SELECT * FROM users FOR UPDATE"));
+        assert!(!is_read_only_sql(&pg, "EXPLAIN ANALYZE DELETE FROM users"));
+        assert!(!is_read_only_sql(&pg, "SET default_transaction_read_only = off"));
+        assert!(!is_read_only_sql(&SQLiteDialect {}, "PRAGMA journal_mode(WAL)"));
+        assert!(!is_read_only_sql(&pg, "SELEC garbage"));
+        assert!(!is_read_only_sql(&pg, ""));
+    }
+}
@@ -3,12 +3,16 @@ use std::time::Instant;
 
 use redis::aio::ConnectionManager;
 
+use crate::adapters::{RowSink, STREAM_BATCH_ROWS};
 use crate::error::{Error, Result};
-use crate::models::{QueryColumnInfo, QueryResult, SortDirection, TableDataRequest};
+use crate::models::{
+    EditableInfo, QueryColumnInfo, QueryResult, SortDirection, TableDataRequest,
+};
 
 use super::metadata::{scan_keys, ROOT_GROUP, SCAN_CAP};
 
-/// Max elements rendered for collection values (lists, hashes, sets, zsets).
+/// Max elements rendered for collection values (lists, hashes, sets, zsets,
+/// stream entries).
 const VALUE_ELEMENT_CAP: isize = 100;
 
 const COLUMNS: [&str; 4] = ["key", "type", "ttl", "value"];
@@ -37,29 +41,14 @@ impl KeyEntry {
 /// capped at [`SCAN_CAP`] keys, so results over huge keyspaces are partial.
 pub async fn fetch_table_data(
     conn: &mut ConnectionManager,
+    schema_name: &str,
     request: TableDataRequest,
 ) -> Result<QueryResult> {
-    if request
-        .where_expr
-        .as_deref()
-        .is_some_and(|s| !s.trim().is_empty())
-    {
-        return Err(Error::InvalidQuery(
-            "Raw WHERE is only supported for PostgreSQL".into(),
-        ));
-    }
-
-    for s in &request.sort {
-        validate_column(&s.column)?;
-    }
+    validate_request(&request)?;
 
     let start = Instant::now();
 
-    let pattern = if request.table == ROOT_GROUP {
-        "*".to_string()
-    } else {
-        format!("{}:*", request.table)
-    };
+    let pattern = group_pattern(&request.table);
 
     let mut keys = scan_keys(conn, &pattern, SCAN_CAP).await?;
 
@@ -110,23 +99,104 @@ pub async fn fetch_table_data(
         .collect();
 
     Ok(QueryResult {
-        columns: COLUMNS
-            .iter()
-            .map(|name| QueryColumnInfo {
-                name: name.to_string(),
-                type_name: if *name == "ttl" { "long" } else { "string" }.to_string(),
-                type_oid: None,
-            })
-            .collect(),
+        columns: columns(),
         row_count: rows.len(),
         rows,
         total_count: request.count_total.then_some(total),
         has_more,
         execution_time_ms: start.elapsed().as_millis() as u64,
-        editable_info: None, // edits go through native commands
+        editable_info: Some(EditableInfo {
+            schema: schema_name.to_string(),
+            table: request.table,
+            primary_key_columns: vec!["key".to_string()],
+            primary_key_column_indices: vec![0],
+        }),
     })
 }
 
+/// Every key of the group, hydrated one SCAN batch at a time. Unlike the
+/// browse there is no [`SCAN_CAP`] and no sort: keys come in SCAN order, and
+/// SCAN may repeat a key that was rehashed while the sweep was running.
+pub async fn stream_table_data(
+    conn: &mut ConnectionManager,
+    request: TableDataRequest,
+    sink: &mut dyn RowSink,
+) -> Result<u64> {
+    validate_request(&request)?;
+
+    let pattern = group_pattern(&request.table);
+    sink.columns(&columns()).await?;
+
+    let mut sent: u64 = 0;
+    let mut cursor: u64 = 0;
+    loop {
+        let (next, mut keys): (u64, Vec<String>) = redis::cmd("SCAN")
+            .arg(cursor)
+            .arg("MATCH")
+            .arg(&pattern)
+            .arg("COUNT")
+            .arg(STREAM_BATCH_ROWS)
+            .query_async(conn)
+            .await?;
+
+        if request.table == ROOT_GROUP {
+            keys.retain(|k| !k.contains(':'));
+        }
+        if !keys.is_empty() {
+            let rows: Vec<Vec<Option<String>>> = hydrate(conn, &keys)
+                .await?
+                .iter()
+                .map(|e| COLUMNS.iter().map(|c| e.column(c)).collect())
+                .collect();
+            sent += rows.len() as u64;
+            sink.rows(rows).await?;
+        }
+
+        cursor = next;
+        if cursor == 0 {
+            return Ok(sent);
+        }
+    }
+}
+
+fn validate_request(request: &TableDataRequest) -> Result<()> {
+    if request
+        .where_expr
+        .as_deref()
+        .is_some_and(|s| !s.trim().is_empty())
+    {
+        return Err(Error::InvalidQuery(
+            "Raw WHERE is only supported for PostgreSQL".into(),
+        ));
+    }
+
+    for s in &request.sort {
+        validate_column(&s.column)?;
+    }
+    Ok(())
+}
+
+/// SCAN pattern of a key-prefix group. The root pattern also matches
+/// prefixed keys; callers keep only the keys without `:`.
+fn group_pattern(group: &str) -> String {
+    if group == ROOT_GROUP {
+        "*".to_string()
+    } else {
+        format!("{group}:*")
+    }
+}
+
+fn columns() -> Vec<QueryColumnInfo> {
+    COLUMNS
+        .iter()
+        .map(|name| QueryColumnInfo {
+            name: name.to_string(),
+            type_name: if *name == "ttl" { "long" } else { "string" }.to_string(),
+            type_oid: None,
+        })
+        .collect()
+}
+
 fn validate_column(column: &str) -> Result<()> {
     if COLUMNS.contains(&column) {
         Ok(())
@@ -180,7 +250,7 @@ async fn hydrate(conn: &mut ConnectionManager, keys: &[String]) -> Result<Vec<Ke
 }
 
 /// Fetch a displayable value for a key according to its type, capping
-/// collection types at [`VALUE_ELEMENT_CAP`] elements.
+/// collection types (but hashes) at [`VALUE_ELEMENT_CAP`] elements.
 async fn fetch_value(
     conn: &mut ConnectionManager,
     key: &str,
@@ -218,11 +288,21 @@ async fn fetch_value(
                 .query_async(conn)
                 .await?
         }
-        // stream and others: show type only
+        "stream" => {
+            redis::cmd("XRANGE")
+                .arg(key)
+                .arg("-")
+                .arg("+")
+                .arg("COUNT")
+                .arg(VALUE_ELEMENT_CAP)
+                .query_async(conn)
+                .await?
+        }
+        // module types and others: show type only
         _ => redis::Value::Nil,
     };
 
-    Ok(super::command::value_to_string(&value))
+    Ok(super::edit::render_value(type_name, &value))
 }
 
 // ─────────────────────────────────────────────────────────────────────────────
@@ -292,8 +372,11 @@ mod tests {
             username: String::new(),
             password: String::new(),
             default_database: None,
-            ssl_enabled: false,
+            tls: Default::default(),
             connection_uri: None,
+            ssh_tunnel: None,
+            read_only: false,
+            confirm_writes: false,
             created_at: 0,
         };
 
@@ -381,6 +464,27 @@ mod tests {
         let deleted = adapter.execute_statement("DEL rootkey").await.unwrap();
         assert_eq!(deleted.affected_rows, 1);
 
+        // Grid edits: the key is the primary key, the value goes as JSON
+        let editable = result.editable_info.expect("editable by key");
+        adapter
+            .apply_row_edits(
+                &editable,
+                vec![crate::models::RowEdit {
+                    pk_values: vec![Some("user:9".into())],
+                    changes: vec![
+                        ("value".into(), Some(r#"{"name":"Nine"}"#.into())),
+                        ("ttl".into(), Some("600".into())),
+                    ],
+                }],
+            )
+            .await
+            .unwrap();
+        let name = adapter
+            .execute_query("HGET user:9 name", QueryOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(name.rows[0][0].as_deref(), Some("Nine"));
+
         // Cleanup
         adapter.execute_statement("FLUSHDB").await.unwrap();
     }
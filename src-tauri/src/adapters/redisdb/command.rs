@@ -59,6 +59,50 @@ pub fn tokenize(line: &str) -> Result<Vec<String>> {
     Ok(tokens)
 }
 
+/// Commands that never write. Not on the list even though they look like
+/// reads: `SORT` (`STORE` option), `GEORADIUS` (`STORE`), `XREADGROUP`
+/// (moves the pending list), `PFCOUNT` (rewrites its cache) and `TOUCH`.
+const READ_ONLY_COMMANDS: &[&str] = &[
+    "GET", "MGET", "GETRANGE", "STRLEN", "GETBIT", "BITCOUNT", "BITPOS",
+    "EXISTS", "TYPE", "TTL", "PTTL", "EXPIRETIME", "PEXPIRETIME", "DUMP",
+    "KEYS", "SCAN", "RANDOMKEY", "DBSIZE",
+    "HGET", "HMGET", "HGETALL", "HKEYS", "HVALS", "HLEN", "HEXISTS", "HSTRLEN", "HSCAN",
+    "HRANDFIELD",
+    "LRANGE", "LINDEX", "LLEN", "LPOS",
+    "SMEMBERS", "SISMEMBER", "SMISMEMBER", "SCARD", "SRANDMEMBER", "SSCAN", "SINTER",
+    "SUNION", "SDIFF", "SINTERCARD",
+    "ZRANGE", "ZREVRANGE", "ZRANGEBYSCORE", "ZREVRANGEBYSCORE", "ZRANGEBYLEX",
+    "ZREVRANGEBYLEX", "ZSCORE", "ZMSCORE", "ZRANK", "ZREVRANK", "ZCARD", "ZCOUNT",
+    "ZLEXCOUNT", "ZSCAN", "ZRANDMEMBER", "ZINTER", "ZUNION", "ZDIFF", "ZINTERCARD",
+    "XRANGE", "XREVRANGE", "XLEN", "XREAD", "XINFO", "XPENDING",
+    "GEOPOS", "GEODIST", "GEOHASH", "GEOSEARCH",
+    "SORT_RO", "EVAL_RO", "EVALSHA_RO", "FCALL_RO",
+    "PING", "ECHO", "INFO", "TIME", "LASTSAVE", "OBJECT", "MEMORY", "COMMAND",
+];
+
+/// Admin commands whose subcommand decides (`CONFIG GET` vs `CONFIG SET`).
+const READ_ONLY_SUBCOMMANDS: &[(&str, &str)] = &[
+    ("CONFIG", "GET"),
+    ("CLIENT", "LIST"),
+    ("CLIENT", "INFO"),
+    ("CLIENT", "GETNAME"),
+    ("CLIENT", "ID"),
+    ("SLOWLOG", "GET"),
+    ("SLOWLOG", "LEN"),
+];
+
+/// Whether a tokenized command only reads; unknown commands count as writes.
+pub fn is_read_only(tokens: &[String]) -> bool {
+    let Some(name) = tokens.first().map(|t| t.to_uppercase()) else {
+        return false;
+    };
+    if READ_ONLY_COMMANDS.contains(&name.as_str()) {
+        return true;
+    }
+    let sub = tokens.get(1).map(|t| t.to_uppercase()).unwrap_or_default();
+    READ_ONLY_SUBCOMMANDS.contains(&(name.as_str(), sub.as_str()))
+}
+
 /// Convert a Redis reply value to a display string.
 pub fn value_to_string(value: &Value) -> Option<String> {
     match value {
@@ -178,6 +222,16 @@ mod tests {
         assert!(tokenize(r#"SET k "oops"#).is_err());
     }
 
+    #[test]
+    fn read_only_commands() {
+        let read_only = |line: &str| is_read_only(&tokenize(line).unwrap());
+        assert!(read_only("hgetall session:1"));
+        assert!(read_only("CONFIG GET maxmemory"));
+        assert!(!read_only("CONFIG SET maxmemory 1gb"));
+        assert!(!read_only("SORT list STORE dst"));
+        assert!(!read_only("FLUSHALL"));
+    }
+
     #[test]
     fn hgetall_flat_array_becomes_pairs() {
         let value = Value::Array(vec![
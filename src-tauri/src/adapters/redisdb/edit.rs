@@ -0,0 +1,598 @@
+//! Grid edits of a key-prefix group: the key is the primary key, `ttl` and
+//! `value` are editable. Collection values travel as JSON, the same format
+//! the browse renders them in.
+
+use std::time::Instant;
+
+use redis::aio::ConnectionManager;
+use redis::{Pipeline, Value};
+use serde_json::{Map, Value as Json};
+
+use crate::error::{Error, Result};
+use crate::models::{EditableInfo, RowEdit, RowInsert, StatementResult};
+
+use super::command::value_to_string;
+use super::metadata::ROOT_GROUP;
+
+/// Times a transaction is retried when a watched key changes under it.
+const MAX_WATCH_ATTEMPTS: usize = 3;
+
+/// How the browse shows a value of type `type_name`: hashes and sorted sets
+/// as JSON objects (`{field: value}`, `{member: score}`), lists and sets as
+/// JSON arrays, strings as they are.
+pub fn render_value(type_name: &str, value: &Value) -> Option<String> {
+    let json = match (type_name, value) {
+        ("list" | "set", Value::Array(items) | Value::Set(items)) => Json::Array(
+            items
+                .iter()
+                .map(|item| Json::String(value_to_string(item).unwrap_or_default()))
+                .collect(),
+        ),
+        ("hash", _) => Json::Object(
+            pairs(value)
+                .into_iter()
+                .map(|(field, value)| (field, Json::String(value)))
+                .collect(),
+        ),
+        ("zset", _) => Json::Object(
+            pairs(value)
+                .into_iter()
+                .map(|(member, score)| (member, score_json(score)))
+                .collect(),
+        ),
+        _ => return value_to_string(value),
+    };
+    Some(json.to_string())
+}
+
+/// Every edit of the batch in one `MULTI`/`EXEC`. The keys are `WATCH`ed
+/// while their types (and list lengths) are read to pick the commands, so
+/// a key changed meanwhile aborts the transaction instead of failing half
+/// way through it (Redis doesn't roll back); the batch is then retried.
+pub async fn apply_row_edits(
+    client: &redis::Client,
+    edits: Vec<RowEdit>,
+) -> Result<StatementResult> {
+    let start = Instant::now();
+    let edits: Vec<(String, RowEdit)> = edits
+        .into_iter()
+        .filter(|edit| !edit.changes.is_empty())
+        .map(|edit| Ok((edit_key(&edit.pk_values, "edit")?, edit)))
+        .collect::<Result<_>>()?;
+    if edits.is_empty() {
+        return Ok(StatementResult {
+            affected_rows: 0,
+            execution_time_ms: 0,
+        });
+    }
+
+    // WATCH belongs to the connection: one of its own, not the shared one.
+    let mut conn = client.get_multiplexed_async_connection().await?;
+    for _ in 0..MAX_WATCH_ATTEMPTS {
+        let mut watch = redis::cmd("WATCH");
+        for (key, _) in &edits {
+            watch.arg(key);
+        }
+        watch.query_async::<()>(&mut conn).await?;
+
+        let mut types = redis::pipe();
+        for (key, _) in &edits {
+            types.cmd("TYPE").arg(key);
+        }
+        let types: Vec<String> = types.query_async(&mut conn).await?;
+
+        let mut lengths = redis::pipe();
+        for ((key, _), type_name) in edits.iter().zip(&types) {
+            if type_name == "list" {
+                lengths.cmd("LLEN").arg(key);
+            }
+        }
+        let mut lengths = lengths
+            .query_async::<Vec<usize>>(&mut conn)
+            .await?
+            .into_iter();
+
+        let mut pipe = redis::pipe();
+        pipe.atomic();
+        for ((key, edit), type_name) in edits.iter().zip(&types) {
+            if type_name == "none" {
+                return Err(Error::NotFound(format!("Key {key}")));
+            }
+            let list_len = match type_name.as_str() {
+                "list" => lengths.next().unwrap_or_default(),
+                _ => 0,
+            };
+            for (column, value) in &edit.changes {
+                match column.as_str() {
+                    "ttl" => set_ttl(&mut pipe, key, value.as_deref())?,
+                    "value" => update_value(&mut pipe, key, type_name, list_len, value.as_deref())?,
+                    "key" => {
+                        return Err(Error::InvalidQuery(
+                            "Keys can't be renamed from the grid; use RENAME in the editor".into(),
+                        ))
+                    }
+                    "type" => {
+                        return Err(Error::InvalidQuery(
+                            "The type of a key can't be changed".into(),
+                        ))
+                    }
+                    other => return Err(Error::InvalidQuery(format!("Unknown column: {other}"))),
+                }
+            }
+        }
+
+        // EXEC answers nil when a watched key changed: nothing was applied.
+        if pipe
+            .query_async::<Option<Value>>(&mut conn)
+            .await?
+            .is_some()
+        {
+            return Ok(StatementResult {
+                affected_rows: edits.len() as u64,
+                execution_time_ms: start.elapsed().as_millis() as u64,
+            });
+        }
+    }
+    Err(watch_conflict())
+}
+
+/// New keys, in one `MULTI`/`EXEC`. `type` defaults to `string`; a key
+/// without the group's prefix gets it, as in the import. The keys are
+/// `WATCH`ed from the existence check on, so one created meanwhile aborts
+/// the insert rather than being overwritten.
+pub async fn insert_rows(
+    client: &redis::Client,
+    editable: &EditableInfo,
+    rows: Vec<RowInsert>,
+) -> Result<StatementResult> {
+    let start = Instant::now();
+    let prefix = (editable.table != ROOT_GROUP).then(|| format!("{}:", editable.table));
+
+    let mut keys = Vec::with_capacity(rows.len());
+    let mut pipe = redis::pipe();
+    pipe.atomic();
+    for row in rows.iter().filter(|row| !row.values.is_empty()) {
+        let column = |name: &str| {
+            row.values
+                .iter()
+                .find(|(column, _)| column == name)
+                .and_then(|(_, value)| value.as_deref())
+        };
+        if let Some((other, _)) = row
+            .values
+            .iter()
+            .find(|(column, _)| !matches!(column.as_str(), "key" | "type" | "ttl" | "value"))
+        {
+            return Err(Error::InvalidQuery(format!("Unknown column: {other}")));
+        }
+
+        let key = match (column("key"), &prefix) {
+            (None | Some(""), _) => {
+                return Err(Error::InvalidQuery("A new row needs a key".into()))
+            }
+            (Some(key), Some(prefix)) if !key.starts_with(prefix.as_str()) => {
+                format!("{prefix}{key}")
+            }
+            (Some(key), _) => key.to_string(),
+        };
+        create_value(
+            &mut pipe,
+            &key,
+            column("type").unwrap_or("string"),
+            column("value"),
+        )?;
+        if let Some(ttl) = column("ttl") {
+            set_ttl(&mut pipe, &key, Some(ttl))?;
+        }
+        keys.push(key);
+    }
+    if keys.is_empty() {
+        return Ok(StatementResult {
+            affected_rows: 0,
+            execution_time_ms: start.elapsed().as_millis() as u64,
+        });
+    }
+
+    let mut conn = client.get_multiplexed_async_connection().await?;
+    for _ in 0..MAX_WATCH_ATTEMPTS {
+        redis::cmd("WATCH")
+            .arg(&keys)
+            .query_async::<()>(&mut conn)
+            .await?;
+
+        // Um insert não sobrescreve: a checagem vem antes do MULTI.
+        let mut check = redis::pipe();
+        for key in &keys {
+            check.cmd("EXISTS").arg(key);
+        }
+        let existing: Vec<bool> = check.query_async(&mut conn).await?;
+        if let Some((key, _)) = keys.iter().zip(&existing).find(|(_, exists)| **exists) {
+            return Err(Error::AlreadyExists(format!("Key {key}")));
+        }
+
+        if pipe
+            .query_async::<Option<Value>>(&mut conn)
+            .await?
+            .is_some()
+        {
+            return Ok(StatementResult {
+                affected_rows: keys.len() as u64,
+                execution_time_ms: start.elapsed().as_millis() as u64,
+            });
+        }
+    }
+    Err(watch_conflict())
+}
+
+pub async fn delete_rows(
+    conn: &mut ConnectionManager,
+    pk_values: Vec<Vec<Option<String>>>,
+) -> Result<StatementResult> {
+    let start = Instant::now();
+    if pk_values.is_empty() {
+        return Ok(StatementResult {
+            affected_rows: 0,
+            execution_time_ms: 0,
+        });
+    }
+
+    let mut pipe = redis::pipe();
+    pipe.atomic();
+    for pk in &pk_values {
+        pipe.cmd("DEL").arg(edit_key(pk, "delete")?);
+    }
+    let deleted: Vec<u64> = pipe.query_async(conn).await?;
+
+    Ok(StatementResult {
+        affected_rows: deleted.iter().sum(),
+        execution_time_ms: start.elapsed().as_millis() as u64,
+    })
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Commands per type
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// A positive number of seconds sets the expiry; empty/NULL removes it.
+fn set_ttl(pipe: &mut Pipeline, key: &str, ttl: Option<&str>) -> Result<()> {
+    match ttl.map(str::trim).filter(|ttl| !ttl.is_empty()) {
+        None => {
+            pipe.cmd("PERSIST").arg(key);
+        }
+        Some(ttl) => match ttl.parse::<i64>() {
+            Ok(seconds) if seconds > 0 => {
+                pipe.cmd("EXPIRE").arg(key).arg(seconds);
+            }
+            _ => {
+                return Err(Error::InvalidQuery(format!(
+                    "TTL must be a positive number of seconds, got '{ttl}'"
+                )))
+            }
+        },
+    }
+    Ok(())
+}
+
+/// Changes what the JSON names and leaves the rest alone, so a value the
+/// browse cut at 100 elements can be edited without losing the others:
+/// hash fields and sorted set members set to `null` are removed, list
+/// elements are replaced by position (`LSET`) up to `list_len` and appended
+/// (`RPUSH`) past it, and set members are added (from an array) or
+/// added/removed (`{member: true | false}`).
+fn update_value(
+    pipe: &mut Pipeline,
+    key: &str,
+    type_name: &str,
+    list_len: usize,
+    value: Option<&str>,
+) -> Result<()> {
+    let Some(value) = value else {
+        return Err(Error::InvalidQuery(
+            "A value can't be NULL; delete the row to remove the key".into(),
+        ));
+    };
+
+    match type_name {
+        "string" => {
+            pipe.cmd("SET").arg(key).arg(value).arg("KEEPTTL");
+        }
+        "hash" => {
+            for (field, value) in json_object(value, type_name)? {
+                match value {
+                    Json::Null => pipe.cmd("HDEL").arg(key).arg(field),
+                    value => pipe.cmd("HSET").arg(key).arg(field).arg(text(&value)),
+                };
+            }
+        }
+        "list" => {
+            let elements = json_array(value, type_name)?;
+            if elements.iter().any(Json::is_null) {
+                return Err(Error::InvalidQuery("List elements can't be NULL".into()));
+            }
+            for (index, element) in elements.iter().enumerate().take(list_len) {
+                pipe.cmd("LSET").arg(key).arg(index).arg(text(element));
+            }
+            if elements.len() > list_len {
+                let mut push = redis::cmd("RPUSH");
+                push.arg(key);
+                for element in &elements[list_len..] {
+                    push.arg(text(element));
+                }
+                pipe.add_command(push);
+            }
+        }
+        "set" => match parse_json(value, type_name)? {
+            Json::Object(members) => {
+                for (member, keep) in members {
+                    let command = if keep.as_bool() == Some(true) {
+                        "SADD"
+                    } else {
+                        "SREM"
+                    };
+                    pipe.cmd(command).arg(key).arg(member);
+                }
+            }
+            Json::Array(members) => {
+                for member in &members {
+                    pipe.cmd("SADD").arg(key).arg(text(member));
+                }
+            }
+            _ => return Err(expected("set", "an array or an object")),
+        },
+        "zset" => {
+            for (member, score) in json_object(value, type_name)? {
+                match score {
+                    Json::Null => pipe.cmd("ZREM").arg(key).arg(member),
+                    score => pipe
+                        .cmd("ZADD")
+                        .arg(key)
+                        .arg(score_arg(&score)?)
+                        .arg(member),
+                };
+            }
+        }
+        other => {
+            return Err(Error::UnsupportedType(format!(
+                "Values of type {other} can't be edited from the grid"
+            )))
+        }
+    }
+    Ok(())
+}
+
+/// The whole value of a new key. Redis has no empty collections, so they
+/// need at least one element.
+fn create_value(
+    pipe: &mut Pipeline,
+    key: &str,
+    type_name: &str,
+    value: Option<&str>,
+) -> Result<()> {
+    if type_name == "string" {
+        pipe.cmd("SET").arg(key).arg(value.unwrap_or_default());
+        return Ok(());
+    }
+    let value = value.unwrap_or_default();
+
+    let cmd = match type_name {
+        "hash" => {
+            let mut cmd = redis::cmd("HSET");
+            cmd.arg(key);
+            for (field, value) in json_object(value, type_name)? {
+                if !value.is_null() {
+                    cmd.arg(field).arg(text(&value));
+                }
+            }
+            cmd
+        }
+        "list" | "set" => {
+            let mut cmd = redis::cmd(if type_name == "list" { "RPUSH" } else { "SADD" });
+            cmd.arg(key);
+            for element in json_array(value, type_name)? {
+                cmd.arg(text(&element));
+            }
+            cmd
+        }
+        "zset" => {
+            let mut cmd = redis::cmd("ZADD");
+            cmd.arg(key);
+            for (member, score) in json_object(value, type_name)? {
+                cmd.arg(score_arg(&score)?).arg(member);
+            }
+            cmd
+        }
+        other => {
+            return Err(Error::UnsupportedType(format!(
+                "Keys of type {other} can't be created from the grid"
+            )))
+        }
+    };
+    // Só o comando e a chave: a coleção veio vazia.
+    if cmd.args_iter().count() == 2 {
+        return Err(Error::InvalidQuery(format!(
+            "A new {type_name} needs at least one element"
+        )));
+    }
+    pipe.add_command(cmd);
+    Ok(())
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// JSON helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn watch_conflict() -> Error {
+    Error::InvalidState(
+        "The keys kept changing while the edit was applied; nothing was changed, try again".into(),
+    )
+}
+
+fn edit_key(pk_values: &[Option<String>], action: &str) -> Result<String> {
+    pk_values
+        .first()
+        .and_then(|key| key.clone())
+        .ok_or_else(|| Error::InvalidQuery(format!("Missing key value for {action}")))
+}
+
+fn parse_json(value: &str, type_name: &str) -> Result<Json> {
+    serde_json::from_str(value)
+        .map_err(|e| Error::InvalidQuery(format!("The value of a {type_name} must be JSON: {e}")))
+}
+
+fn json_object(value: &str, type_name: &str) -> Result<Map<String, Json>> {
+    match parse_json(value, type_name)? {
+        Json::Object(object) => Ok(object),
+        _ => Err(expected(type_name, "an object")),
+    }
+}
+
+fn json_array(value: &str, type_name: &str) -> Result<Vec<Json>> {
+    match parse_json(value, type_name)? {
+        Json::Array(array) => Ok(array),
+        _ => Err(expected(type_name, "an array")),
+    }
+}
+
+fn expected(type_name: &str, shape: &str) -> Error {
+    Error::InvalidQuery(format!("The value of a {type_name} must be {shape}"))
+}
+
+/// Strings as they are; numbers, booleans and nested JSON as their text.
+fn text(value: &Json) -> String {
+    match value {
+        Json::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn score_arg(score: &Json) -> Result<String> {
+    let text = text(score);
+    match text.parse::<f64>() {
+        Ok(score) if !score.is_nan() => Ok(text),
+        _ => Err(Error::InvalidQuery(format!(
+            "A sorted set score must be a number, got {score}"
+        ))),
+    }
+}
+
+/// Scores are numbers in the JSON, except `inf`/`-inf`, which JSON lacks.
+fn score_json(score: String) -> Json {
+    score
+        .parse::<f64>()
+        .ok()
+        .and_then(serde_json::Number::from_f64)
+        .map(Json::Number)
+        .unwrap_or(Json::String(score))
+}
+
+/// Field/value pairs of `HGETALL` or `ZRANGE ... WITHSCORES`: a flat array
+/// in RESP2; a map or an array of pairs in RESP3.
+pub fn pairs(value: &Value) -> Vec<(String, String)> {
+    let string = |value: &Value| value_to_string(value).unwrap_or_default();
+    match value {
+        Value::Map(pairs) => pairs.iter().map(|(k, v)| (string(k), string(v))).collect(),
+        Value::Array(items) if items.iter().all(|item| matches!(item, Value::Array(_))) => items
+            .iter()
+            .filter_map(|item| match item {
+                Value::Array(pair) if pair.len() == 2 => Some((string(&pair[0]), string(&pair[1]))),
+                _ => None,
+            })
+            .collect(),
+        Value::Array(items) => items
+            .chunks(2)
+            .filter(|pair| pair.len() == 2)
+            .map(|pair| (string(&pair[0]), string(&pair[1])))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn packed(pipe: &Pipeline) -> Vec<String> {
+        pipe.cmd_iter()
+            .map(|c| String::from_utf8_lossy(&c.get_packed_command()).replace("\r\n", " "))
+            .collect()
+    }
+
+    fn bulk(s: &str) -> Value {
+        Value::BulkString(s.as_bytes().to_vec())
+    }
+
+    #[test]
+    fn collections_render_as_json() {
+        let json = |rendered: Option<String>| -> Json {
+            serde_json::from_str(&rendered.unwrap()).unwrap()
+        };
+
+        let hash = Value::Array(vec![bulk("name"), bulk("Ana"), bulk("age"), bulk("7")]);
+        assert_eq!(
+            json(render_value("hash", &hash)),
+            serde_json::json!({ "name": "Ana", "age": "7" })
+        );
+
+        // RESP3: pares [membro, score]
+        let zset = Value::Array(vec![
+            Value::Array(vec![bulk("a"), Value::Double(1.5)]),
+            Value::Array(vec![bulk("b"), bulk("inf")]),
+        ]);
+        assert_eq!(
+            json(render_value("zset", &zset)),
+            serde_json::json!({ "a": 1.5, "b": "inf" })
+        );
+
+        let list = Value::Array(vec![bulk("x"), bulk("y")]);
+        assert_eq!(render_value("list", &list).as_deref(), Some(r#"["x","y"]"#));
+    }
+
+    #[test]
+    fn value_edits_only_touch_what_the_json_names() {
+        let mut pipe = redis::pipe();
+        update_value(
+            &mut pipe,
+            "u:1",
+            "hash",
+            0,
+            Some(r#"{"name":"Bia","old":null}"#),
+        )
+        .unwrap();
+        update_value(&mut pipe, "rank", "zset", 0, Some(r#"{"a":2,"b":null}"#)).unwrap();
+        update_value(&mut pipe, "tags", "set", 0, Some(r#"{"x":true,"y":false}"#)).unwrap();
+        update_value(&mut pipe, "s", "string", 0, Some("hi")).unwrap();
+        let cmds = packed(&pipe);
+
+        assert!(cmds[0].contains("HSET $3 u:1 $4 name $3 Bia"));
+        assert!(cmds[1].contains("HDEL $3 u:1 $3 old"));
+        assert!(cmds[2].contains("ZADD $4 rank $1 2 $1 a"));
+        assert!(cmds[3].contains("ZREM $4 rank $1 b"));
+        assert!(cmds[4].contains("SADD $4 tags $1 x"));
+        assert!(cmds[5].contains("SREM $4 tags $1 y"));
+        assert!(cmds[6].contains("SET $1 s $2 hi $7 KEEPTTL"));
+
+        assert!(update_value(&mut pipe, "l", "list", 1, Some(r#"{"0":"a"}"#)).is_err());
+        assert!(update_value(&mut pipe, "s", "string", 0, None).is_err());
+    }
+
+    #[test]
+    fn list_edits_append_past_the_end() {
+        let mut pipe = redis::pipe();
+        update_value(&mut pipe, "q", "list", 1, Some(r#"["a","b","c"]"#)).unwrap();
+        let cmds = packed(&pipe);
+
+        assert_eq!(cmds.len(), 2);
+        assert!(cmds[0].contains("LSET $1 q $1 0 $1 a"));
+        assert!(cmds[1].contains("RPUSH $1 q $1 b $1 c"));
+    }
+
+    #[test]
+    fn new_collections_need_an_element() {
+        let mut pipe = redis::pipe();
+        create_value(&mut pipe, "q", "list", Some(r#"["a","b"]"#)).unwrap();
+        assert!(packed(&pipe)[0].contains("RPUSH $1 q $1 a $1 b"));
+
+        assert!(create_value(&mut pipe, "h", "hash", Some("{}")).is_err());
+        assert!(create_value(&mut pipe, "z", "zset", Some(r#"{"a":"high"}"#)).is_err());
+    }
+}
@@ -0,0 +1,206 @@
+use redis::aio::ConnectionManager;
+use redis::Pipeline;
+
+use crate::adapters::{RowSource, SourceRow};
+use crate::error::{Error, Result};
+use crate::models::ImportTarget;
+
+use super::metadata::ROOT_GROUP;
+
+/// Bulk-load `source` as one pipeline per batch.
+///
+/// The row shape picks the command: `key` + `value` (+ `ttl`) is a string
+/// (`SET`); `key` + any other columns is a hash (`HSET`, one field per
+/// column, NULLs left out), expired with `EXPIRE` when `ttl` is given. A
+/// `type` column (as in the browse export) is ignored. Keys get the group
+/// prefix (`group:`) unless they already have it.
+///
+/// Redis has no rollback: when a pipeline fails, its rows are replayed one
+/// by one (SET/HSET are idempotent) to find the bad ones. A dry run only
+/// checks that every row converts.
+pub async fn import_rows(
+    conn: &mut ConnectionManager,
+    target: &ImportTarget,
+    source: &mut dyn RowSource,
+) -> Result<u64> {
+    let shape = RowShape::new(source.columns(), &target.table)?;
+    let mut total: u64 = 0;
+
+    while let Some(batch) = source.next_batch().await? {
+        let mut valid: Vec<(&SourceRow, Pipeline)> = Vec::with_capacity(batch.len());
+        for row in &batch {
+            match shape.commands(row) {
+                Ok(pipe) => valid.push((row, pipe)),
+                Err(message) => source.reject(row.line, message)?,
+            }
+        }
+
+        let mut imported = valid.len() as u64;
+        if !source.dry_run() && !valid.is_empty() {
+            let mut pipe = redis::pipe();
+            for (_, row_pipe) in &valid {
+                for cmd in row_pipe.cmd_iter() {
+                    pipe.add_command(cmd.clone()).ignore();
+                }
+            }
+
+            if pipe.query_async::<()>(conn).await.is_err() {
+                imported = 0;
+                for (row, row_pipe) in &valid {
+                    match row_pipe.query_async::<()>(conn).await {
+                        Ok(()) => imported += 1,
+                        Err(e) => source.reject(row.line, e.to_string())?,
+                    }
+                }
+            }
+        }
+
+        total += imported;
+        source.imported(imported);
+    }
+
+    Ok(total)
+}
+
+/// Where each command argument comes from, resolved once from the columns.
+struct RowShape {
+    prefix: Option<String>,
+    key: usize,
+    ttl: Option<usize>,
+    /// `Some` = string key (`SET`); `None` = hash of `fields`.
+    value: Option<usize>,
+    fields: Vec<(usize, String)>,
+}
+
+impl RowShape {
+    fn new(columns: &[String], group: &str) -> Result<Self> {
+        let position = |name: &str| columns.iter().position(|c| c == name);
+        let key = position("key").ok_or_else(|| {
+            Error::InvalidQuery("Redis import needs a column mapped to `key`".into())
+        })?;
+
+        let fields: Vec<(usize, String)> = columns
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| !matches!(c.as_str(), "key" | "ttl" | "type" | "value"))
+            .map(|(i, c)| (i, c.clone()))
+            .collect();
+        let value = position("value");
+        if value.is_some() && !fields.is_empty() {
+            return Err(Error::InvalidQuery(
+                "Map either `value` (string keys) or hash fields, not both".into(),
+            ));
+        }
+        if value.is_none() && fields.is_empty() {
+            return Err(Error::InvalidQuery(
+                "Redis import needs a `value` column or hash field columns".into(),
+            ));
+        }
+
+        Ok(Self {
+            prefix: (group != ROOT_GROUP).then(|| format!("{group}:")),
+            key,
+            ttl: position("ttl"),
+            value,
+            fields,
+        })
+    }
+
+    /// The commands that write one row, or why the row can't be written.
+    fn commands(&self, row: &SourceRow) -> std::result::Result<Pipeline, String> {
+        let key = match row.values[self.key].as_deref() {
+            Some(k) if !k.is_empty() => match &self.prefix {
+                Some(prefix) if !k.starts_with(prefix.as_str()) => format!("{prefix}{k}"),
+                _ => k.to_string(),
+            },
+            _ => return Err("Empty key".into()),
+        };
+        let ttl = match self.ttl.and_then(|i| row.values[i].as_deref()) {
+            None | Some("") | Some("-1") => None,
+            Some(t) => match t.parse::<u64>() {
+                Ok(secs) if secs > 0 => Some(secs),
+                _ => return Err(format!("Invalid ttl: {t}")),
+            },
+        };
+
+        let mut pipe = redis::pipe();
+        match self.value {
+            Some(i) => {
+                let value = row.values[i].as_deref().ok_or("NULL value")?;
+                let cmd = pipe.cmd("SET").arg(&key).arg(value);
+                if let Some(secs) = ttl {
+                    cmd.arg("EX").arg(secs);
+                }
+            }
+            None => {
+                let cmd = pipe.cmd("HSET").arg(&key);
+                let mut any = false;
+                for (i, field) in &self.fields {
+                    if let Some(v) = row.values[*i].as_deref() {
+                        cmd.arg(field).arg(v);
+                        any = true;
+                    }
+                }
+                if !any {
+                    return Err("Every hash field is NULL".into());
+                }
+                if let Some(secs) = ttl {
+                    pipe.cmd("EXPIRE").arg(&key).arg(secs);
+                }
+            }
+        }
+        Ok(pipe)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(values: &[Option<&str>]) -> SourceRow {
+        SourceRow {
+            line: 1,
+            values: values.iter().map(|v| v.map(String::from)).collect(),
+        }
+    }
+
+    fn packed(pipe: &Pipeline) -> Vec<String> {
+        pipe.cmd_iter()
+            .map(|c| String::from_utf8_lossy(&c.get_packed_command()).replace("\r\n", " "))
+            .collect()
+    }
+
+    #[test]
+    fn string_rows_use_set_with_group_prefix() {
+        let columns = ["key", "value", "ttl"].map(String::from);
+        let shape = RowShape::new(&columns, "session").unwrap();
+
+        let pipe = shape
+            .commands(&row(&[Some("42"), Some("abc"), Some("60")]))
+            .unwrap();
+        let cmds = packed(&pipe);
+        assert_eq!(cmds.len(), 1);
+        assert!(cmds[0].contains("SET $10 session:42 $3 abc $2 EX $2 60"));
+
+        let err = shape
+            .commands(&row(&[Some("1"), Some("x"), Some("soon")]))
+            .err();
+        assert_eq!(err.as_deref(), Some("Invalid ttl: soon"));
+    }
+
+    #[test]
+    fn other_columns_become_hash_fields() {
+        let columns = ["key", "name", "email"].map(String::from);
+        let shape = RowShape::new(&columns, ROOT_GROUP).unwrap();
+
+        let pipe = shape
+            .commands(&row(&[Some("u:1"), Some("Ana"), None]))
+            .unwrap();
+        let cmds = packed(&pipe);
+        assert!(cmds[0].contains("HSET $3 u:1 $4 name $3 Ana"));
+        assert!(!cmds[0].contains("email"));
+
+        assert!(shape.commands(&row(&[Some("u:2"), None, None])).is_err());
+        assert!(RowShape::new(&["name".to_string()], ROOT_GROUP).is_err());
+    }
+}
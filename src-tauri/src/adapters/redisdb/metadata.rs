@@ -3,10 +3,11 @@ use std::collections::BTreeMap;
 use chrono::Utc;
 use redis::aio::ConnectionManager;
 
+use crate::adapters::{glob_subsequence, rank_matches};
 use crate::error::Result;
 use crate::models::{
-    ColumnInfo, DatabaseInfo, DatabaseStructure, SchemaStructure, TableInfo, TableStructure,
-    TableType,
+    ColumnInfo, DatabaseInfo, DatabaseStructure, ObjectKind, ObjectMatch, ObjectSearchRequest,
+    SchemaStructure, TableInfo, TableStructure, TableType,
 };
 
 /// Group name for keys without a `:`-separated prefix.
@@ -92,15 +93,17 @@ pub async fn list_tables(
 pub fn group_by_prefix(keys: &[String]) -> BTreeMap<String, i64> {
     let mut groups: BTreeMap<String, i64> = BTreeMap::new();
     for key in keys {
-        let prefix = key
-            .split_once(':')
-            .map(|(p, _)| p.to_string())
-            .unwrap_or_else(|| ROOT_GROUP.to_string());
-        *groups.entry(prefix).or_insert(0) += 1;
+        *groups.entry(key_group(key).to_string()).or_insert(0) += 1;
     }
     groups
 }
 
+/// The prefix group a key is listed under: up to the first `:`, else
+/// [`ROOT_GROUP`].
+pub fn key_group(key: &str) -> &str {
+    key.split_once(':').map(|(p, _)| p).unwrap_or(ROOT_GROUP)
+}
+
 /// Synthetic columns: every key row exposes key / type / ttl / value.
 pub fn list_columns() -> Vec<ColumnInfo> {
     let spec: [(&str, &str, bool); 4] = [
@@ -146,6 +149,31 @@ pub async fn list_schemas_with_tables(
     })
 }
 
+/// Key groups for `search_objects`: `SCAN MATCH` with the letters of the
+/// query in order (so the scan only returns keys that can match), grouped by
+/// prefix and ranked by group name. A group whose name matches has every
+/// one of its keys matched by the scan, so the counts are exact.
+pub async fn search_objects(
+    conn: &mut ConnectionManager,
+    schema_name: &str,
+    request: &ObjectSearchRequest,
+) -> Result<Vec<ObjectMatch>> {
+    let keys = scan_keys(conn, &glob_subsequence(&request.query), SCAN_CAP).await?;
+
+    let candidates = group_by_prefix(&keys)
+        .into_iter()
+        .filter(|(prefix, _)| prefix != ROOT_GROUP)
+        .map(|(prefix, count)| ObjectMatch {
+            kind: ObjectKind::KeyGroup,
+            schema: Some(schema_name.to_string()),
+            parent: None,
+            name: prefix,
+            detail: Some(format!("{count} key(s)")),
+            score: 0,
+        });
+    Ok(rank_matches(request, candidates))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,15 +1,25 @@
 mod browse;
 mod command;
+mod edit;
+mod import;
+mod key_entries;
 mod metadata;
+mod pubsub;
+mod search;
+mod stream;
+mod streams;
 
+use std::collections::HashMap;
+use std::sync::Mutex;
 use std::time::Instant;
 
 use async_trait::async_trait;
 use redis::aio::ConnectionManager;
-use redis::{ConnectionAddr, ConnectionInfo, RedisConnectionInfo};
+use redis::{ClientTlsConfig, ConnectionAddr, ConnectionInfo, RedisConnectionInfo, TlsCertificates};
 use tokio::sync::OnceCell;
 
-use crate::adapters::DatabaseAdapter;
+use crate::adapters::tls::{self, ClientIdentity};
+use crate::adapters::{DatabaseAdapter, RowSink, RowSource};
 use crate::error::{Error, Result};
 use crate::models::*;
 
@@ -19,6 +29,8 @@ pub struct RedisAdapter {
     /// (adapter construction is sync).
     manager: OnceCell<ConnectionManager>,
     db_index: i64,
+    /// Conexões de pub/sub de `subscribe_channel`, por subscription id.
+    subscriptions: pubsub::Subscriptions,
 }
 
 impl RedisAdapter {
@@ -36,16 +48,27 @@ impl RedisAdapter {
                 .map_err(|e| Error::Connection(format!("Invalid Redis URI: {e}")))?;
             let mut info = base.get_connection_info().clone();
             info.redis.db = db_index;
-            redis::Client::open(info).map_err(|e| Error::Connection(e.to_string()))?
+
+            // A TLS mode overrides the URI scheme; with TLS disabled the
+            // URI decides.
+            if server.tls.mode.is_enabled() {
+                info.addr = match info.addr {
+                    ConnectionAddr::Tcp(host, port)
+                    | ConnectionAddr::TcpTls { host, port, .. } => {
+                        tls_addr(host, port, &server.tls)?
+                    }
+                    ConnectionAddr::Unix(_) => {
+                        return Err(Error::Connection(
+                            "TLS isn't available over a Unix socket".into(),
+                        ))
+                    }
+                };
+            }
+
+            build_client(info, &server.tls)?
         } else {
-            let addr = if server.ssl_enabled {
-                // Encrypt without certificate verification (self-signed servers)
-                ConnectionAddr::TcpTls {
-                    host: server.host.clone(),
-                    port: server.port,
-                    insecure: true,
-                    tls_params: None,
-                }
+            let addr = if server.tls.mode.is_enabled() {
+                tls_addr(server.host.clone(), server.port, &server.tls)?
             } else {
                 ConnectionAddr::Tcp(server.host.clone(), server.port)
             };
@@ -60,13 +83,14 @@ impl RedisAdapter {
                 },
             };
 
-            redis::Client::open(info).map_err(|e| Error::Connection(e.to_string()))?
+            build_client(info, &server.tls)?
         };
 
         Ok(Self {
             client,
             manager: OnceCell::new(),
             db_index,
+            subscriptions: Mutex::new(HashMap::new()),
         })
     }
 
@@ -87,12 +111,83 @@ impl RedisAdapter {
     }
 }
 
+/// `require` encrypts without verifying. redis-rs (rustls) can't skip just
+/// the hostname check, so verify-ca also verifies the hostname.
+fn tls_addr(host: String, port: u16, tls: &TlsConfig) -> Result<ConnectionAddr> {
+    if tls.mode.verifies_certificate() && tls.server_name.is_some() {
+        return Err(Error::Connection(
+            "Redis can't verify the server certificate through an SSH tunnel (the driver \
+             checks it against 127.0.0.1); use TLS mode require"
+                .into(),
+        ));
+    }
+
+    Ok(ConnectionAddr::TcpTls {
+        host,
+        port,
+        insecure: tls.mode == TlsMode::Require,
+        tls_params: None,
+    })
+}
+
+/// Custom CA (replaces the system roots) and client certificate, when set.
+fn build_client(info: ConnectionInfo, tls: &TlsConfig) -> Result<redis::Client> {
+    if !tls.mode.is_enabled() {
+        return redis::Client::open(info).map_err(|e| Error::Connection(e.to_string()));
+    }
+
+    let root_cert = tls::ca_bundle(tls)?;
+    let client_tls = match tls::client_identity(tls)? {
+        Some(ClientIdentity::Pem { cert, key }) => Some(ClientTlsConfig {
+            client_cert: cert,
+            client_key: key,
+        }),
+        Some(ClientIdentity::Pkcs12 { .. }) => {
+            return Err(Error::Connection(
+                "Redis client certificates must be PEM, not PKCS#12".into(),
+            ))
+        }
+        None => None,
+    };
+
+    if root_cert.is_none() && client_tls.is_none() {
+        return redis::Client::open(info).map_err(|e| Error::Connection(e.to_string()));
+    }
+
+    redis::Client::build_with_tls(
+        info,
+        TlsCertificates {
+            client_tls,
+            root_cert,
+        },
+    )
+    .map_err(|e| Error::Connection(e.to_string()))
+}
+
 #[async_trait]
 impl DatabaseAdapter for RedisAdapter {
     fn capabilities(&self) -> AdapterCapabilities {
         AdapterCapabilities::redis()
     }
 
+    fn is_read_only_query(&self, query: &str) -> bool {
+        command::tokenize(query).is_ok_and(|tokens| command::is_read_only(&tokens))
+    }
+
+    /// Key groups (and their key counts) come from the keys themselves: any
+    /// write may add, empty or resize one.
+    fn structure_changes(&self, query: &str) -> Vec<StructureChange> {
+        if self.is_read_only_query(query) {
+            Vec::new()
+        } else {
+            vec![StructureChange::Unknown]
+        }
+    }
+
+    fn row_writes_change_structure(&self) -> bool {
+        true
+    }
+
     /// Free-form editor: one native Redis command per query,
     /// e.g. `GET user:1`, `HGETALL session:abc`, `SCAN 0 MATCH user:*`.
     async fn execute_query(
@@ -120,7 +215,77 @@ impl DatabaseAdapter for RedisAdapter {
 
     async fn fetch_table_data(&self, request: TableDataRequest) -> Result<QueryResult> {
         let mut conn = self.conn().await?;
-        browse::fetch_table_data(&mut conn, request).await
+        browse::fetch_table_data(&mut conn, &self.schema_name(), request).await
+    }
+
+    async fn fetch_key_entries(&self, request: &KeyEntriesRequest) -> Result<KeyEntriesPage> {
+        let mut conn = self.conn().await?;
+        key_entries::fetch_key_entries(&mut conn, request).await
+    }
+
+    /// KEYS and the SCAN family sweep the cursor to the end, one batch per
+    /// reply; any other command runs once, as in the editor.
+    async fn stream_query(
+        &self,
+        query: &str,
+        query_id: Option<&str>,
+        sink: &mut dyn RowSink,
+    ) -> Result<u64> {
+        let tokens = command::tokenize(query)?;
+        let Some(scan) = stream::ScanCommand::parse(&tokens) else {
+            let options = QueryOptions {
+                unlimited: true,
+                query_id: query_id.map(str::to_string),
+                ..Default::default()
+            };
+            let result = self.execute_query(query, options).await?;
+            let sent = result.rows.len() as u64;
+            sink.columns(&result.columns).await?;
+            sink.rows(result.rows).await?;
+            return Ok(sent);
+        };
+
+        let mut conn = self.conn().await?;
+        scan.stream(&mut conn, sink).await
+    }
+
+    async fn stream_table(&self, request: TableDataRequest, sink: &mut dyn RowSink) -> Result<u64> {
+        let mut conn = self.conn().await?;
+        browse::stream_table_data(&mut conn, request, sink).await
+    }
+
+    async fn apply_row_edits(
+        &self,
+        _editable: &EditableInfo,
+        edits: Vec<RowEdit>,
+    ) -> Result<StatementResult> {
+        edit::apply_row_edits(&self.client, edits).await
+    }
+
+    async fn insert_rows(
+        &self,
+        editable: &EditableInfo,
+        rows: Vec<RowInsert>,
+    ) -> Result<StatementResult> {
+        edit::insert_rows(&self.client, editable, rows).await
+    }
+
+    async fn delete_rows(
+        &self,
+        _editable: &EditableInfo,
+        pk_values: Vec<Vec<Option<String>>>,
+    ) -> Result<StatementResult> {
+        let mut conn = self.conn().await?;
+        edit::delete_rows(&mut conn, pk_values).await
+    }
+
+    async fn import_rows(
+        &self,
+        target: &ImportTarget,
+        source: &mut dyn RowSource,
+    ) -> Result<u64> {
+        let mut conn = self.conn().await?;
+        import::import_rows(&mut conn, target, source).await
     }
 
     async fn execute_statement(&self, statement: &str) -> Result<StatementResult> {
@@ -166,6 +331,85 @@ impl DatabaseAdapter for RedisAdapter {
         metadata::list_schemas_with_tables(&mut conn, &self.schema_name()).await
     }
 
+    async fn search_objects(&self, request: &ObjectSearchRequest) -> Result<Vec<ObjectMatch>> {
+        let mut conn = self.conn().await?;
+        metadata::search_objects(&mut conn, &self.schema_name(), request).await
+    }
+
+    async fn search_values(
+        &self,
+        request: &ValueSearchRequest,
+        events: tokio::sync::mpsc::Sender<ValueSearchEvent>,
+    ) -> Result<()> {
+        let conn = self.conn().await?;
+        search::search_values(&conn, &self.schema_name(), request, events).await
+    }
+
+    async fn subscribe_channel(
+        &self,
+        subscription_id: &str,
+        request: &ChannelSubscription,
+        events: tokio::sync::mpsc::Sender<ChannelNotification>,
+    ) -> Result<()> {
+        let mut conn = self.conn().await?;
+        pubsub::subscribe(
+            &self.client,
+            &mut conn,
+            self.db_index,
+            &self.subscriptions,
+            subscription_id,
+            request,
+            events,
+        )
+        .await
+    }
+
+    async fn unsubscribe_channel(&self, subscription_id: &str) -> Result<()> {
+        pubsub::unsubscribe(&self.subscriptions, subscription_id)
+    }
+
+    async fn notify_channel(&self, channel: &str, payload: &str) -> Result<()> {
+        let mut conn = self.conn().await?;
+        pubsub::publish(&mut conn, channel, payload).await
+    }
+
+    async fn fetch_stream_entries(&self, request: &StreamRangeRequest) -> Result<StreamPage> {
+        let mut conn = self.conn().await?;
+        streams::fetch_entries(&mut conn, request).await
+    }
+
+    async fn stream_info(&self, key: &str) -> Result<StreamInfo> {
+        let mut conn = self.conn().await?;
+        streams::stream_info(&mut conn, key).await
+    }
+
+    async fn list_stream_groups(&self, key: &str) -> Result<Vec<StreamGroupInfo>> {
+        let mut conn = self.conn().await?;
+        streams::list_groups(&mut conn, key).await
+    }
+
+    async fn list_stream_consumers(
+        &self,
+        key: &str,
+        group: &str,
+    ) -> Result<Vec<StreamConsumerInfo>> {
+        let mut conn = self.conn().await?;
+        streams::list_consumers(&mut conn, key, group).await
+    }
+
+    async fn list_stream_pending(
+        &self,
+        request: &StreamPendingRequest,
+    ) -> Result<Vec<StreamPendingEntry>> {
+        let mut conn = self.conn().await?;
+        streams::list_pending(&mut conn, request).await
+    }
+
+    async fn stream_entries_action(&self, action: &StreamEntriesAction) -> Result<u64> {
+        let mut conn = self.conn().await?;
+        streams::run_action(&mut conn, action).await
+    }
+
     async fn test_connection(&self) -> Result<()> {
         let mut conn = self.conn().await?;
         redis::cmd("PING").query_async::<String>(&mut conn).await?;
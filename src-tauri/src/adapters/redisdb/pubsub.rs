@@ -0,0 +1,201 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use futures_util::StreamExt;
+use redis::aio::ConnectionManager;
+use tokio::sync::mpsc;
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::task::JoinHandle;
+
+use crate::error::{Error, Result};
+use crate::models::{now_ms, ChannelNotification, ChannelSubscription};
+
+/// Assinaturas abertas ao mesmo tempo por adapter; cada uma é uma conexão
+/// TCP a mais no servidor.
+const MAX_SUBSCRIPTIONS: usize = 8;
+
+/// Event classes of `notify-keyspace-events` (`A` is all but `m` and `n`).
+const KEYSPACE_EVENT_CLASSES: &str = "Ag$lshzxetdmn";
+
+/// The task reading a pub/sub connection. Aborting it drops the connection
+/// (the server forgets the subscription) and the sender, which ends the
+/// stream on the frontend side.
+pub struct Subscription {
+    task: JoinHandle<()>,
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+pub type Subscriptions = Mutex<HashMap<String, Subscription>>;
+
+/// `SUBSCRIBE`/`PSUBSCRIBE` on a connection of its own (a subscribed
+/// connection can't run anything else), sending what arrives to `events`
+/// until [`unsubscribe`] or the connection dropping.
+pub async fn subscribe(
+    client: &redis::Client,
+    conn: &mut ConnectionManager,
+    db_index: i64,
+    subscriptions: &Subscriptions,
+    subscription_id: &str,
+    request: &ChannelSubscription,
+    events: mpsc::Sender<ChannelNotification>,
+) -> Result<()> {
+    if request.channel.is_empty() && !request.keyspace {
+        return Err(Error::InvalidQuery("Channel name is empty".into()));
+    }
+    check_free(subscriptions, subscription_id)?;
+    if request.keyspace {
+        ensure_keyspace_events(conn, request.enable_keyspace_events).await?;
+    }
+
+    let mut pubsub = client.get_async_pubsub().await?;
+    if request.keyspace {
+        let keys = match request.channel.as_str() {
+            "" => "*",
+            keys => keys,
+        };
+        pubsub
+            .psubscribe(format!("__keyspace@{db_index}__:{keys}"))
+            .await?;
+    } else if request.pattern {
+        pubsub.psubscribe(&request.channel).await?;
+    } else {
+        pubsub.subscribe(&request.channel).await?;
+    }
+
+    let task = tokio::spawn(async move {
+        let mut messages = pubsub.into_on_message();
+        while let Some(message) = messages.next().await {
+            let notification = ChannelNotification {
+                pattern: message.get_pattern::<Option<String>>().ok().flatten(),
+                channel: message.get_channel_name().to_string(),
+                payload: String::from_utf8_lossy(message.get_payload_bytes()).into_owned(),
+                sender_pid: None,
+                timestamp_ms: now_ms(),
+            };
+            // Com o buffer cheio (o frontend não dá conta) a mensagem é
+            // descartada: esperar faria o servidor acumular o output buffer
+            // do cliente até derrubá-lo.
+            match events.try_send(notification) {
+                Ok(()) | Err(TrySendError::Full(_)) => {}
+                Err(TrySendError::Closed(_)) => break,
+            }
+        }
+    });
+
+    // Os awaits acima soltaram o lock: outra chamada pode ter ocupado o id.
+    let subscription = Subscription { task };
+    check_free(subscriptions, subscription_id)?;
+    subscriptions
+        .lock()
+        .unwrap()
+        .insert(subscription_id.to_string(), subscription);
+    Ok(())
+}
+
+pub fn unsubscribe(subscriptions: &Subscriptions, subscription_id: &str) -> Result<()> {
+    match subscriptions.lock().unwrap().remove(subscription_id) {
+        Some(_) => Ok(()),
+        None => Err(Error::NotFound(format!("Subscription {subscription_id}"))),
+    }
+}
+
+pub async fn publish(conn: &mut ConnectionManager, channel: &str, payload: &str) -> Result<()> {
+    redis::cmd("PUBLISH")
+        .arg(channel)
+        .arg(payload)
+        .query_async::<i64>(conn)
+        .await?;
+    Ok(())
+}
+
+/// Keyspace notifications are off by default. Turning them on is only done
+/// when asked; otherwise a server with them off fails here instead of
+/// subscribing to a channel that never gets anything. Servers that refuse
+/// `CONFIG` (managed ones) are trusted to have them configured.
+async fn ensure_keyspace_events(conn: &mut ConnectionManager, enable: bool) -> Result<()> {
+    let config: redis::RedisResult<HashMap<String, String>> = redis::cmd("CONFIG")
+        .arg("GET")
+        .arg("notify-keyspace-events")
+        .query_async(conn)
+        .await;
+    let Ok(config) = config else {
+        return Ok(());
+    };
+    let current = config
+        .get("notify-keyspace-events")
+        .map(String::as_str)
+        .unwrap_or_default();
+
+    let Some(flags) = keyspace_flags(current) else {
+        return Ok(());
+    };
+    if !enable {
+        return Err(Error::InvalidState(
+            "Keyspace notifications are off in this server (notify-keyspace-events); \
+             enable them to watch the keyspace"
+                .into(),
+        ));
+    }
+    redis::cmd("CONFIG")
+        .arg("SET")
+        .arg("notify-keyspace-events")
+        .arg(flags)
+        .query_async::<()>(conn)
+        .await?;
+    Ok(())
+}
+
+/// `current` plus what keyspace notifications need (`K` and every event
+/// class), or `None` when it already has both.
+fn keyspace_flags(current: &str) -> Option<String> {
+    let has_keyspace = current.contains('K');
+    let has_events = current.chars().any(|c| KEYSPACE_EVENT_CLASSES.contains(c));
+    if has_keyspace && has_events {
+        return None;
+    }
+
+    let mut flags = current.to_string();
+    if !has_keyspace {
+        flags.push('K');
+    }
+    if !has_events {
+        flags.push('A');
+    }
+    Some(flags)
+}
+
+fn check_free(subscriptions: &Subscriptions, subscription_id: &str) -> Result<()> {
+    let mut open = subscriptions.lock().unwrap();
+    // Conexões que caíram já encerraram a task: liberam o id e a vaga.
+    open.retain(|_, subscription| !subscription.task.is_finished());
+    if open.contains_key(subscription_id) {
+        return Err(Error::AlreadyExists(format!(
+            "Subscription {subscription_id}"
+        )));
+    }
+    if open.len() >= MAX_SUBSCRIPTIONS {
+        return Err(Error::InvalidState(format!(
+            "Too many channel subscriptions ({MAX_SUBSCRIPTIONS}): unsubscribe one first"
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keyspace_flags_adds_only_what_is_missing() {
+        assert_eq!(keyspace_flags("").as_deref(), Some("KA"));
+        assert_eq!(keyspace_flags("Ex").as_deref(), Some("ExK"));
+        assert_eq!(keyspace_flags("K").as_deref(), Some("KA"));
+        assert_eq!(keyspace_flags("K$g"), None);
+        assert_eq!(keyspace_flags("AKE"), None);
+    }
+}
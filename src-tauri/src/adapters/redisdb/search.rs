@@ -0,0 +1,172 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use redis::aio::ConnectionManager;
+use tokio::sync::mpsc;
+
+use crate::adapters::{scan_tables, ScanTarget, TableScan};
+use crate::error::Result;
+use crate::models::{ValueHit, ValueSearchEvent, ValueSearchRequest};
+
+use super::metadata::{key_group, scan_keys, SCAN_CAP};
+
+/// Elements read per list, set, hash or sorted set: a key bigger than this
+/// is only partly searched.
+const ELEMENT_SCAN_CAP: usize = 10_000;
+
+/// Keys whose TYPE goes in one pipeline.
+const TYPE_BATCH: usize = 500;
+
+/// Every key of the database (up to [`SCAN_CAP`]), one prefix group per
+/// "table". Strings match on their value, hashes on each field value (the
+/// field is the column), lists, sets and sorted sets on each element.
+/// Redis has no per-command timeout: a slow group is just dropped.
+pub async fn search_values(
+    conn: &ConnectionManager,
+    schema_name: &str,
+    request: &ValueSearchRequest,
+    events: mpsc::Sender<ValueSearchEvent>,
+) -> Result<()> {
+    let keys = scan_keys(&mut conn.clone(), "*", SCAN_CAP).await?;
+    let mut groups: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for key in keys {
+        groups
+            .entry(key_group(&key).to_string())
+            .or_default()
+            .push(key);
+    }
+
+    let targets = groups
+        .keys()
+        .map(|group| ScanTarget {
+            schema: Some(schema_name.to_string()),
+            table: group.clone(),
+        })
+        .collect();
+    let groups = Arc::new(groups);
+
+    scan_tables(request, targets, request.concurrency, events, |target| {
+        let mut conn = conn.clone();
+        let groups = Arc::clone(&groups);
+        let request = request.clone();
+        async move {
+            let keys = &groups[&target.table];
+            scan_group(&mut conn, &target, keys, &request).await
+        }
+    })
+    .await
+}
+
+async fn scan_group(
+    conn: &mut ConnectionManager,
+    target: &ScanTarget,
+    keys: &[String],
+    request: &ValueSearchRequest,
+) -> Result<TableScan> {
+    let mut hits = Vec::new();
+    for batch in keys.chunks(TYPE_BATCH) {
+        let mut pipe = redis::pipe();
+        for key in batch {
+            pipe.cmd("TYPE").arg(key);
+        }
+        let types: Vec<String> = pipe.query_async(conn).await?;
+
+        for (key, type_name) in batch.iter().zip(types) {
+            for (column, value) in elements(conn, key, &type_name).await? {
+                if !request.matches(&value) {
+                    continue;
+                }
+                hits.push(ValueHit {
+                    schema: target.schema.clone(),
+                    table: target.table.clone(),
+                    column,
+                    key_columns: vec!["key".to_string()],
+                    key_values: vec![Some(key.clone())],
+                    value: Some(value),
+                });
+                if hits.len() >= request.max_hits {
+                    return Ok(TableScan::Hits(hits));
+                }
+            }
+        }
+    }
+    Ok(TableScan::Hits(hits))
+}
+
+/// `(column, value)` pairs of a key to test: `value` for strings and list
+/// elements, `member` for sets and sorted sets, the field for hashes.
+async fn elements(
+    conn: &mut ConnectionManager,
+    key: &str,
+    type_name: &str,
+) -> Result<Vec<(String, String)>> {
+    let tagged = |column: &str, values: Vec<Vec<u8>>| {
+        values
+            .into_iter()
+            .map(|v| (column.to_string(), String::from_utf8_lossy(&v).into_owned()))
+            .collect()
+    };
+
+    Ok(match type_name {
+        "string" => {
+            let value: Option<Vec<u8>> = redis::cmd("GET").arg(key).query_async(conn).await?;
+            tagged("value", value.into_iter().collect())
+        }
+        "list" => {
+            let values = redis::cmd("LRANGE")
+                .arg(key)
+                .arg(0)
+                .arg(ELEMENT_SCAN_CAP - 1)
+                .query_async(conn)
+                .await?;
+            tagged("value", values)
+        }
+        "zset" => {
+            let values = redis::cmd("ZRANGE")
+                .arg(key)
+                .arg(0)
+                .arg(ELEMENT_SCAN_CAP - 1)
+                .query_async(conn)
+                .await?;
+            tagged("member", values)
+        }
+        "set" => tagged("member", scan_elements(conn, "SSCAN", key).await?),
+        "hash" => {
+            let flat = scan_elements(conn, "HSCAN", key).await?;
+            flat.chunks_exact(2)
+                .map(|pair| {
+                    (
+                        String::from_utf8_lossy(&pair[0]).into_owned(),
+                        String::from_utf8_lossy(&pair[1]).into_owned(),
+                    )
+                })
+                .collect()
+        }
+        // Streams (and module types) aren't searched.
+        _ => Vec::new(),
+    })
+}
+
+/// SSCAN/HSCAN sweep of one key, up to [`ELEMENT_SCAN_CAP`] replies.
+async fn scan_elements(
+    conn: &mut ConnectionManager,
+    command: &str,
+    key: &str,
+) -> Result<Vec<Vec<u8>>> {
+    let mut elements = Vec::new();
+    let mut cursor: u64 = 0;
+    loop {
+        let (next, batch): (u64, Vec<Vec<u8>>) = redis::cmd(command)
+            .arg(key)
+            .arg(cursor)
+            .arg("COUNT")
+            .arg(1000)
+            .query_async(conn)
+            .await?;
+        elements.extend(batch);
+        cursor = next;
+        if cursor == 0 || elements.len() >= ELEMENT_SCAN_CAP {
+            return Ok(elements);
+        }
+    }
+}
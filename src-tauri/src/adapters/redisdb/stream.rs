@@ -0,0 +1,156 @@
+use redis::aio::ConnectionManager;
+use redis::Value;
+
+use crate::adapters::{RowSink, STREAM_BATCH_ROWS};
+use crate::error::Result;
+use crate::models::QueryColumnInfo;
+
+use super::command::value_to_string;
+
+/// A key-space or collection sweep from the editor, rewritten to drive the
+/// SCAN cursor to the end instead of returning one page (or, for `KEYS`,
+/// blocking the server while it lists everything).
+#[derive(Debug, PartialEq)]
+pub struct ScanCommand {
+    name: &'static str,
+    /// The hash/set/sorted set swept by HSCAN/SSCAN/ZSCAN.
+    key: Option<String>,
+    cursor: String,
+    /// MATCH/COUNT/TYPE/NOVALUES, passed through on every call.
+    args: Vec<String>,
+    columns: &'static [&'static str],
+}
+
+impl ScanCommand {
+    /// `None` for any other command, which runs once as usual.
+    pub fn parse(tokens: &[String]) -> Option<Self> {
+        let upper = tokens[0].to_uppercase();
+        let (name, key, rest, columns): (_, _, &[String], &'static [&'static str]) =
+            match upper.as_str() {
+                // KEYS pattern = SCAN 0 MATCH pattern, without the blocking
+                "KEYS" if tokens.len() == 2 => {
+                    return Some(Self::with_count(Self {
+                        name: "SCAN",
+                        key: None,
+                        cursor: "0".into(),
+                        args: vec!["MATCH".into(), tokens[1].clone()],
+                        columns: &["key"],
+                    }));
+                }
+                "SCAN" if tokens.len() >= 2 => ("SCAN", None, &tokens[1..], &["key"]),
+                "SSCAN" if tokens.len() >= 3 => {
+                    ("SSCAN", Some(tokens[1].clone()), &tokens[2..], &["member"])
+                }
+                "HSCAN" if tokens.len() >= 3 => (
+                    "HSCAN",
+                    Some(tokens[1].clone()),
+                    &tokens[2..],
+                    &["field", "value"],
+                ),
+                "ZSCAN" if tokens.len() >= 3 => (
+                    "ZSCAN",
+                    Some(tokens[1].clone()),
+                    &tokens[2..],
+                    &["member", "score"],
+                ),
+                _ => return None,
+            };
+
+        let args = rest[1..].to_vec();
+        let novalues = args.iter().any(|a| a.eq_ignore_ascii_case("NOVALUES"));
+        Some(Self::with_count(Self {
+            name,
+            key,
+            cursor: rest[0].clone(),
+            args,
+            columns: if novalues { &["field"] } else { columns },
+        }))
+    }
+
+    /// Without a COUNT the server hands out ~10 elements per call; ask for a
+    /// full batch so each round-trip fills one.
+    fn with_count(mut self) -> Self {
+        if !self.args.iter().any(|a| a.eq_ignore_ascii_case("COUNT")) {
+            self.args.push("COUNT".into());
+            self.args.push(STREAM_BATCH_ROWS.to_string());
+        }
+        self
+    }
+
+    fn columns(&self) -> Vec<QueryColumnInfo> {
+        self.columns
+            .iter()
+            .map(|name| QueryColumnInfo {
+                name: name.to_string(),
+                type_name: "string".to_string(),
+                type_oid: None,
+            })
+            .collect()
+    }
+
+    /// Sweep from the given cursor until the server returns 0, one batch per
+    /// SCAN reply. Like any SCAN, an element may show up twice if the
+    /// collection was rehashed meanwhile.
+    pub async fn stream(
+        &self,
+        conn: &mut ConnectionManager,
+        sink: &mut dyn RowSink,
+    ) -> Result<u64> {
+        sink.columns(&self.columns()).await?;
+
+        let width = self.columns.len();
+        let mut cursor = self.cursor.clone();
+        let mut sent: u64 = 0;
+        loop {
+            let mut cmd = redis::cmd(self.name);
+            cmd.arg(&self.key).arg(&cursor).arg(&self.args);
+            let (next, items): (String, Vec<Value>) = cmd.query_async(conn).await?;
+
+            let rows: Vec<Vec<Option<String>>> = items
+                .chunks(width)
+                .map(|chunk| chunk.iter().map(value_to_string).collect())
+                .collect();
+            if !rows.is_empty() {
+                sent += rows.len() as u64;
+                sink.rows(rows).await?;
+            }
+
+            if next == "0" {
+                return Ok(sent);
+            }
+            cursor = next;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn keys_becomes_a_full_scan() {
+        let scan = ScanCommand::parse(&tokens("keys user:*")).unwrap();
+        assert_eq!(scan.name, "SCAN");
+        assert_eq!(scan.cursor, "0");
+        assert_eq!(scan.args, tokens("MATCH user:* COUNT 1000"));
+        assert_eq!(scan.columns, &["key"]);
+
+        assert!(ScanCommand::parse(&tokens("GET user:1")).is_none());
+        assert!(ScanCommand::parse(&tokens("KEYS")).is_none());
+    }
+
+    #[test]
+    fn collection_scans_keep_key_and_count() {
+        let scan = ScanCommand::parse(&tokens("HSCAN h 0 MATCH a* COUNT 50")).unwrap();
+        assert_eq!(scan.key.as_deref(), Some("h"));
+        assert_eq!(scan.args, tokens("MATCH a* COUNT 50"));
+        assert_eq!(scan.columns, &["field", "value"]);
+
+        let scan = ScanCommand::parse(&tokens("HSCAN h 0 NOVALUES")).unwrap();
+        assert_eq!(scan.columns, &["field"]);
+    }
+}
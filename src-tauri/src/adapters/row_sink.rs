@@ -0,0 +1,24 @@
+use async_trait::async_trait;
+
+use crate::error::Result;
+use crate::models::QueryColumnInfo;
+
+/// Linhas por lote entregue a um [`RowSink`]: grande o bastante para
+/// amortizar o round-trip de cada FETCH/batch, pequeno o bastante para que um
+/// resultado de milhões de linhas nunca fique inteiro na memória.
+pub const STREAM_BATCH_ROWS: usize = 1000;
+
+/// Destino das linhas de uma execução em streaming (`stream_query` /
+/// `stream_table`). O adapter chama `columns` uma vez e depois `rows` a cada
+/// lote, na ordem em que chegam do banco.
+///
+/// Como o `MessageSink`, vive na camada de adapters para que ela não conheça
+/// o Tauri nem o formato de saída: o export em arquivo é só uma implementação.
+/// Um `Err` devolvido pelo sink (disco cheio, export cancelado) interrompe o
+/// streaming e é propagado como resultado da execução.
+#[async_trait]
+pub trait RowSink: Send {
+    async fn columns(&mut self, columns: &[QueryColumnInfo]) -> Result<()>;
+
+    async fn rows(&mut self, rows: Vec<Vec<Option<String>>>) -> Result<()>;
+}
@@ -0,0 +1,40 @@
+use async_trait::async_trait;
+
+use crate::error::Result;
+
+/// Uma linha lida da origem, com os valores já na ordem de
+/// [`RowSource::columns`]. `line` é a linha no arquivo, para relatar erros.
+#[derive(Debug, Clone)]
+pub struct SourceRow {
+    pub line: u64,
+    pub values: Vec<Option<String>>,
+}
+
+/// Origem das linhas de um import (`import_rows`) — o caminho inverso do
+/// [`RowSink`](super::RowSink): o adapter puxa lotes de no máximo
+/// [`STREAM_BATCH_ROWS`](super::STREAM_BATCH_ROWS) linhas, grava cada um e
+/// avisa quantas entraram.
+///
+/// A política de erro fica na origem: o adapter chama `reject` para cada
+/// linha recusada pelo banco e só aborta se ela devolver `Err` (modo "stop").
+#[async_trait]
+pub trait RowSource: Send {
+    /// Nomes das colunas de destino.
+    fn columns(&self) -> &[String];
+
+    /// Validar sem gravar: bancos transacionais fazem rollback no fim.
+    fn dry_run(&self) -> bool;
+
+    /// Modo "stop": o primeiro `reject` vai abortar o import. Só serve para o
+    /// adapter não gravar à toa o que viria depois da linha ruim.
+    fn stop_on_error(&self) -> bool;
+
+    /// Próximo lote; `None` no fim do arquivo.
+    async fn next_batch(&mut self) -> Result<Option<Vec<SourceRow>>>;
+
+    /// Uma linha recusada pelo banco. `Err` interrompe o import.
+    fn reject(&mut self, line: u64, message: String) -> Result<()>;
+
+    /// `rows` linhas do último lote foram gravadas.
+    fn imported(&mut self, rows: u64);
+}
@@ -0,0 +1,492 @@
+use std::time::Instant;
+
+use rusqlite::Connection;
+use sqlparser::dialect::SQLiteDialect;
+
+use crate::adapters::normalize_where_expr;
+use crate::error::{Error, Result};
+use crate::models::{
+    EditableInfo, QueryColumnInfo, QueryResult, SortDirection, SortSpec, TableDataRequest,
+};
+
+use super::executor::{stream_statement, StreamSender, ROWID_COLUMN};
+use super::util::{
+    get_columns_ordered, has_rowid, object_type, pk_columns, query_err, quote_ident,
+    value_to_string,
+};
+
+pub fn fetch_table_data(
+    conn: &Connection,
+    database: &str,
+    request: TableDataRequest,
+) -> Result<QueryResult> {
+    let schema = request.schema.as_deref().unwrap_or(database);
+    let plan = plan_table_select(conn, schema, &request)?;
+    let base = &plan.base;
+
+    // +1 row to detect has_more without a second query
+    let select = format!(
+        "SELECT {} {base}{} LIMIT {} OFFSET {}",
+        plan.select_list.join(", "),
+        plan.order_clause,
+        request.limit + 1,
+        request.offset,
+    );
+
+    let exec_started = Instant::now();
+    let mut stmt = conn.prepare(&select).map_err(query_err)?;
+    let width = plan.select_list.len();
+    let mut rows: Vec<Vec<Option<String>>> = stmt
+        .query_map([], |row| {
+            (0..width)
+                .map(|i| row.get_ref(i).map(value_to_string))
+                .collect::<rusqlite::Result<Vec<_>>>()
+        })
+        .map_err(query_err)?
+        .collect::<rusqlite::Result<_>>()
+        .map_err(query_err)?;
+    let execution_time_ms = exec_started.elapsed().as_millis() as u64;
+
+    let total_count = if request.count_total {
+        Some(
+            conn.query_row(&format!("SELECT COUNT(*) {base}"), [], |r| r.get::<_, i64>(0))
+                .map_err(query_err)?,
+        )
+    } else {
+        None
+    };
+
+    let has_more = rows.len() as i64 > request.limit;
+    if has_more {
+        rows.truncate(request.limit as usize);
+    }
+
+    let editable_info =
+        detect_editable_info(schema, &request.table, &plan.key_columns, &plan.columns);
+
+    Ok(QueryResult {
+        row_count: rows.len(),
+        columns: plan.columns,
+        rows,
+        total_count,
+        has_more,
+        execution_time_ms,
+        editable_info,
+    })
+}
+
+/// Every row of the browse (same filter and order), sent in batches.
+pub fn stream_table_data(
+    conn: &Connection,
+    database: &str,
+    request: TableDataRequest,
+    out: &StreamSender,
+) -> Result<()> {
+    let schema = request.schema.as_deref().unwrap_or(database);
+    let plan = plan_table_select(conn, schema, &request)?;
+
+    let select = format!(
+        "SELECT {} {}{}",
+        plan.select_list.join(", "),
+        plan.base,
+        plan.order_clause
+    );
+    let mut stmt = conn.prepare(&select).map_err(query_err)?;
+    stream_statement(&mut stmt, plan.columns, out)
+}
+
+/// The parts of a browse query shared by the paged fetch and the stream.
+struct TableSelect {
+    columns: Vec<QueryColumnInfo>,
+    /// PK columns, or the rowid fallback
+    key_columns: Vec<String>,
+    /// `FROM schema.table[ WHERE (...)]`
+    base: String,
+    /// Leading-space `" ORDER BY ..."` or empty
+    order_clause: String,
+    select_list: Vec<String>,
+}
+
+fn plan_table_select(
+    conn: &Connection,
+    schema: &str,
+    request: &TableDataRequest,
+) -> Result<TableSelect> {
+    let columns_meta = get_columns_ordered(conn, schema, &request.table)?;
+    let column_names: Vec<String> = columns_meta.iter().map(|c| c.name.clone()).collect();
+
+    // Row identity: the declared PK, else the implicit rowid (plain tables
+    // only — views have neither, and a real column named `rowid` would shadow
+    // the alias).
+    let mut key_columns = pk_columns(&columns_meta);
+    let use_rowid = key_columns.is_empty()
+        && !column_names.iter().any(|c| c.eq_ignore_ascii_case(ROWID_COLUMN))
+        && object_type(conn, schema, &request.table)?.as_deref() == Some("table")
+        && has_rowid(conn, schema, &request.table)?;
+    if use_rowid {
+        key_columns.push(ROWID_COLUMN.to_string());
+    }
+
+    let clauses = build_clauses(request, &column_names, &key_columns)?;
+
+    let base = format!(
+        "FROM {}.{}{}",
+        quote_ident(schema),
+        quote_ident(&request.table),
+        clauses.where_clause,
+    );
+
+    let mut select_list: Vec<String> = columns_meta.iter().map(|c| quote_ident(&c.name)).collect();
+    if use_rowid {
+        select_list.insert(0, format!("_rowid_ AS {}", quote_ident(ROWID_COLUMN)));
+    }
+
+    let mut columns: Vec<QueryColumnInfo> = columns_meta
+        .iter()
+        .map(|c| QueryColumnInfo {
+            name: c.name.clone(),
+            type_name: c.declared_type.to_ascii_lowercase(),
+            type_oid: None,
+        })
+        .collect();
+    if use_rowid {
+        columns.insert(
+            0,
+            QueryColumnInfo {
+                name: ROWID_COLUMN.to_string(),
+                type_name: "integer".to_string(),
+                type_oid: None,
+            },
+        );
+    }
+
+    Ok(TableSelect {
+        columns,
+        key_columns,
+        base,
+        order_clause: clauses.order_clause,
+        select_list,
+    })
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// SQL building (pure, unit-testable)
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Debug)]
+struct BuiltClauses {
+    /// Leading-space `" WHERE (...)"` or empty
+    where_clause: String,
+    /// Leading-space `" ORDER BY ..."` or empty
+    order_clause: String,
+}
+
+fn build_clauses(
+    request: &TableDataRequest,
+    columns: &[String],
+    key_columns: &[String],
+) -> Result<BuiltClauses> {
+    let where_expr = normalize_where_expr(request.where_expr.as_deref(), &SQLiteDialect {})?;
+    let where_clause = match where_expr {
+        Some(expr) => format!(" WHERE ({expr})"),
+        None => String::new(),
+    };
+
+    let order_clause = build_order_clause(&request.sort, columns, key_columns)?;
+
+    Ok(BuiltClauses {
+        where_clause,
+        order_clause,
+    })
+}
+
+fn build_order_clause(
+    sort: &[SortSpec],
+    columns: &[String],
+    key_columns: &[String],
+) -> Result<String> {
+    // Default ordering: key desc (PK or rowid), keeping OFFSET pagination
+    // deterministic; views without a key get no ordering.
+    if sort.is_empty() {
+        if key_columns.is_empty() {
+            return Ok(String::new());
+        }
+        let parts: Vec<String> = key_columns
+            .iter()
+            .map(|col| format!("{} DESC", order_target(col, columns)))
+            .collect();
+        return Ok(format!(" ORDER BY {}", parts.join(", ")));
+    }
+
+    let parts: Vec<String> = sort
+        .iter()
+        .map(|s| {
+            let known = columns.contains(&s.column)
+                || (s.column == ROWID_COLUMN && key_columns.iter().any(|k| k == ROWID_COLUMN));
+            if !known {
+                return Err(Error::InvalidQuery(format!("Unknown column: {}", s.column)));
+            }
+            let dir = match s.direction {
+                SortDirection::Asc => "ASC",
+                SortDirection::Desc => "DESC",
+            };
+            Ok(format!("{} {}", order_target(&s.column, columns), dir))
+        })
+        .collect::<Result<_>>()?;
+
+    Ok(format!(" ORDER BY {}", parts.join(", ")))
+}
+
+/// The rowid fallback isn't a real column of the table, so it is ordered by
+/// the `_rowid_` alias instead of a quoted name.
+fn order_target(column: &str, columns: &[String]) -> String {
+    if column == ROWID_COLUMN && !columns.iter().any(|c| c == column) {
+        "_rowid_".to_string()
+    } else {
+        quote_ident(column)
+    }
+}
+
+fn detect_editable_info(
+    schema: &str,
+    table: &str,
+    key_columns: &[String],
+    result_columns: &[QueryColumnInfo],
+) -> Option<EditableInfo> {
+    if key_columns.is_empty() {
+        return None;
+    }
+
+    let pk_indices: Vec<usize> = key_columns
+        .iter()
+        .filter_map(|pk_name| result_columns.iter().position(|c| c.name == *pk_name))
+        .collect();
+
+    if pk_indices.len() != key_columns.len() {
+        return None;
+    }
+
+    Some(EditableInfo {
+        schema: schema.to_string(),
+        table: table.to_string(),
+        primary_key_columns: key_columns.to_vec(),
+        primary_key_column_indices: pk_indices,
+    })
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn req(where_expr: Option<&str>, sort: Vec<SortSpec>) -> TableDataRequest {
+        TableDataRequest {
+            schema: None,
+            table: "users".into(),
+            where_expr: where_expr.map(str::to_string),
+            sort,
+            limit: 100,
+            offset: 0,
+            count_total: false,
+        }
+    }
+
+    fn columns() -> Vec<String> {
+        vec!["id".to_string(), "name".to_string()]
+    }
+
+    #[test]
+    fn no_sort_defaults_to_pk_desc() {
+        let built = build_clauses(&req(None, vec![]), &columns(), &["id".to_string()]).unwrap();
+        assert_eq!(built.where_clause, "");
+        assert_eq!(built.order_clause, " ORDER BY \"id\" DESC");
+    }
+
+    #[test]
+    fn rowid_fallback_orders_by_alias() {
+        let built =
+            build_clauses(&req(None, vec![]), &columns(), &[ROWID_COLUMN.to_string()]).unwrap();
+        assert_eq!(built.order_clause, " ORDER BY _rowid_ DESC");
+    }
+
+    #[test]
+    fn where_expr_is_validated() {
+        let built =
+            build_clauses(&req(Some("WHERE name LIKE 'a%'"), vec![]), &columns(), &[]).unwrap();
+        assert_eq!(built.where_clause, " WHERE (name LIKE 'a%')");
+
+        let err = build_clauses(&req(Some("1 = 1; DROP TABLE users"), vec![]), &columns(), &[])
+            .unwrap_err();
+        assert!(err.to_string().contains("single WHERE expression"));
+    }
+
+    #[test]
+    fn unknown_sort_column_rejected() {
+        let err = build_clauses(
+            &req(
+                None,
+                vec![SortSpec {
+                    column: "nope".into(),
+                    direction: SortDirection::Asc,
+                }],
+            ),
+            &columns(),
+            &[],
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("Unknown column"));
+    }
+
+    // Runs against a throwaway file in the temp dir, so unlike the other
+    // adapters' end-to-end tests this one needs no server.
+    #[tokio::test]
+    async fn e2e_sqlite_adapter() {
+        use crate::adapters::sqlite::SqliteAdapter;
+        use crate::adapters::DatabaseAdapter;
+        use crate::models::{DatabaseType, QueryOptions, RowEdit, RowInsert, Server};
+
+        let dir = std::env::temp_dir().join(format!("octapus_e2e_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("e2e.db");
+        let _ = std::fs::remove_file(&path);
+        rusqlite::Connection::open(&path).unwrap();
+
+        let server = Server {
+            id: Some(1),
+            name: "e2e".into(),
+            db_type: DatabaseType::Sqlite,
+            host: path.to_string_lossy().into_owned(),
+            port: 0,
+            username: String::new(),
+            password: String::new(),
+            default_database: None,
+            tls: Default::default(),
+            connection_uri: None,
+            ssh_tunnel: None,
+            read_only: false,
+            confirm_writes: false,
+            created_at: 0,
+        };
+
+        let adapter = SqliteAdapter::new(&server, "main").unwrap();
+        adapter.test_connection().await.unwrap();
+
+        adapter
+            .execute_statement(
+                "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT, age INTEGER); \
+                 CREATE TABLE logs (message TEXT); \
+                 CREATE INDEX idx_users_age ON users(age);",
+            )
+            .await
+            .unwrap();
+        adapter
+            .execute_transaction(
+                (1..=30)
+                    .map(|i| format!("INSERT INTO users (name, age) VALUES ('user{i}', {})", i % 10))
+                    .chain(["INSERT INTO logs VALUES ('a'), ('b')".to_string()])
+                    .collect(),
+            )
+            .await
+            .unwrap();
+
+        // Structure
+        let tables = adapter.list_tables("main").await.unwrap();
+        assert_eq!(
+            tables.iter().map(|t| t.name.as_str()).collect::<Vec<_>>(),
+            vec!["logs", "users"]
+        );
+        let columns = adapter.list_columns("main", "users").await.unwrap();
+        assert!(columns[0].is_primary_key);
+        let indexes = adapter.list_indexes("main", "users").await.unwrap();
+        assert!(indexes.iter().any(|i| i.name == "idx_users_age"));
+
+        // Browse with filter, sort and pagination
+        let result = adapter
+            .fetch_table_data(TableDataRequest {
+                schema: None,
+                table: "users".into(),
+                where_expr: Some("age >= 5".into()),
+                sort: vec![SortSpec {
+                    column: "id".into(),
+                    direction: SortDirection::Desc,
+                }],
+                limit: 10,
+                offset: 0,
+                count_total: true,
+            })
+            .await
+            .unwrap();
+        assert_eq!(result.total_count, Some(15));
+        assert_eq!(result.row_count, 10);
+        assert!(result.has_more);
+        assert_eq!(result.rows[0][0].as_deref(), Some("29"));
+        let editable = result.editable_info.expect("PK table should be editable");
+
+        // Edit + insert + delete through the PK
+        let edited = adapter
+            .apply_row_edits(
+                &editable,
+                vec![RowEdit {
+                    pk_values: vec![Some("29".into())],
+                    changes: vec![("name".into(), Some("renamed".into()))],
+                }],
+            )
+            .await
+            .unwrap();
+        assert_eq!(edited.affected_rows, 1);
+        adapter
+            .insert_rows(
+                &editable,
+                vec![RowInsert {
+                    values: vec![("name".into(), Some("new".into()))],
+                }],
+            )
+            .await
+            .unwrap();
+        let deleted = adapter
+            .delete_rows(&editable, vec![vec![Some("1".into())]])
+            .await
+            .unwrap();
+        assert_eq!(deleted.affected_rows, 1);
+
+        // Table without a PK falls back to rowid
+        let logs = adapter
+            .fetch_table_data(TableDataRequest {
+                table: "logs".into(),
+                ..req(None, vec![])
+            })
+            .await
+            .unwrap();
+        let logs_editable = logs.editable_info.expect("rowid table should be editable");
+        assert_eq!(logs_editable.primary_key_columns, vec![ROWID_COLUMN.to_string()]);
+        assert_eq!(logs.rows[0][0].as_deref(), Some("2"));
+        let deleted = adapter
+            .delete_rows(&logs_editable, vec![vec![Some("2".into())]])
+            .await
+            .unwrap();
+        assert_eq!(deleted.affected_rows, 1);
+
+        // Free-form editor, paginated
+        let q = adapter
+            .execute_query(
+                "SELECT name FROM users WHERE name LIKE 'user%' ORDER BY id",
+                QueryOptions {
+                    limit: 5,
+                    count_total: true,
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(q.row_count, 5);
+        assert!(q.has_more);
+        assert_eq!(q.total_count, Some(28));
+        assert_eq!(q.rows[0][0].as_deref(), Some("user2"));
+
+        drop(adapter);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
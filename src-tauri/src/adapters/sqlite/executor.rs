@@ -0,0 +1,520 @@
+use std::sync::Arc;
+use std::time::Instant;
+
+use parking_lot::Mutex;
+use rusqlite::{Batch, Connection, Statement};
+
+use crate::adapters::STREAM_BATCH_ROWS;
+use crate::error::{Error, Result};
+use crate::models::{
+    EditableInfo, QueryColumnInfo, QueryOptions, QueryResult, RowEdit, RowInsert,
+    StatementResult,
+};
+
+use super::util::{get_columns_ordered, query_err, quote_ident, value_to_string, value_type_name};
+
+/// Pseudo-column used as the row identity for tables without a primary key.
+pub const ROWID_COLUMN: &str = "rowid";
+
+/// Publishes the query_id of the execution holding the connection, clearing
+/// it when the execution finishes (including early returns and errors).
+pub struct ActiveQueryGuard {
+    slot: Arc<Mutex<Option<String>>>,
+}
+
+impl ActiveQueryGuard {
+    pub fn register(slot: &Arc<Mutex<Option<String>>>, query_id: Option<String>) -> Option<Self> {
+        let query_id = query_id?;
+        *slot.lock() = Some(query_id);
+        Some(Self {
+            slot: Arc::clone(slot),
+        })
+    }
+}
+
+impl Drop for ActiveQueryGuard {
+    fn drop(&mut self) {
+        *self.slot.lock() = None;
+    }
+}
+
+/// Run a free-form script. Statements run one by one (each is prepared only
+/// after the previous one ran, so `CREATE TABLE t ...; INSERT INTO t ...`
+/// works); the last statement that returns rows provides the result set.
+pub fn execute_query(
+    conn: &Connection,
+    query: &str,
+    options: &QueryOptions,
+) -> Result<QueryResult> {
+    let trimmed = query.trim();
+    if trimmed.trim_end_matches(';').trim().is_empty() {
+        return Err(Error::InvalidQuery("Empty query".into()));
+    }
+
+    let start = Instant::now();
+    let mut batch = Batch::new(conn, trimmed);
+    let mut statement_count = 0;
+    let mut page: Option<Page> = None;
+
+    while let Some(mut stmt) = batch.next().map_err(query_err)? {
+        statement_count += 1;
+        if stmt.column_count() == 0 {
+            stmt.execute([]).map_err(query_err)?;
+        } else {
+            page = Some(read_page(&mut stmt, options)?);
+        }
+    }
+
+    let execution_time_ms = start.elapsed().as_millis() as u64;
+
+    let Some(page) = page else {
+        return Ok(QueryResult {
+            columns: vec![],
+            rows: vec![],
+            row_count: 0,
+            total_count: None,
+            has_more: false,
+            execution_time_ms,
+            editable_info: None,
+        });
+    };
+
+    // The total only makes sense for a single read statement; COUNT(*) over a
+    // wrapped PRAGMA or a script would fail or lie.
+    let body = trimmed.trim_end_matches(';').trim();
+    let total_count = if options.count_total && statement_count == 1 && is_select_query(body) {
+        conn.query_row(
+            &format!("SELECT COUNT(*) FROM ({body}) AS __c"),
+            [],
+            |r| r.get::<_, i64>(0),
+        )
+        .ok()
+    } else {
+        None
+    };
+
+    Ok(QueryResult {
+        row_count: page.rows.len(),
+        columns: page.columns,
+        rows: page.rows,
+        total_count,
+        has_more: page.has_more,
+        execution_time_ms,
+        // Free-form results aren't editable: the bundled SQLite is built
+        // without column-origin metadata, so a column can't be traced back to
+        // its table. The table browser covers editing.
+        editable_info: None,
+    })
+}
+
+struct Page {
+    columns: Vec<QueryColumnInfo>,
+    rows: Vec<Vec<Option<String>>>,
+    has_more: bool,
+}
+
+/// Step through a row-returning statement, keeping only the requested page.
+fn read_page(stmt: &mut Statement<'_>, options: &QueryOptions) -> Result<Page> {
+    let mut columns = statement_columns(stmt);
+    let column_count = columns.len();
+
+    let skip = if options.unlimited { 0 } else { options.offset.max(0) as usize };
+    let cap = (!options.unlimited).then_some(options.limit.max(0) as usize + 1); // +1 to detect has_more
+
+    let mut rows = Vec::new();
+    let mut seen = 0usize;
+    let mut raw = stmt.raw_query();
+
+    while let Some(row) = raw.next().map_err(query_err)? {
+        seen += 1;
+        if seen <= skip {
+            continue;
+        }
+
+        let mut values = Vec::with_capacity(column_count);
+        for (i, column) in columns.iter_mut().enumerate() {
+            let value = row.get_ref(i).map_err(query_err)?;
+            // Expressions have no declared type: use the storage class of
+            // the first non-null value instead.
+            if column.type_name.is_empty() && !matches!(value, rusqlite::types::ValueRef::Null) {
+                column.type_name = value_type_name(value).to_string();
+            }
+            values.push(value_to_string(value));
+        }
+        rows.push(values);
+
+        if cap.is_some_and(|cap| rows.len() >= cap) {
+            break;
+        }
+    }
+
+    let has_more = !options.unlimited && rows.len() as i64 > options.limit;
+    if has_more {
+        rows.truncate(options.limit as usize);
+    }
+
+    Ok(Page {
+        columns,
+        rows,
+        has_more,
+    })
+}
+
+fn statement_columns(stmt: &Statement<'_>) -> Vec<QueryColumnInfo> {
+    stmt.columns()
+        .iter()
+        .map(|c| QueryColumnInfo {
+            name: c.name().to_string(),
+            type_name: c.decl_type().unwrap_or_default().to_ascii_lowercase(),
+            type_oid: None,
+        })
+        .collect()
+}
+
+/// What the blocking side of a stream hands to the async side (see
+/// `SqliteAdapter::stream`).
+pub enum StreamItem {
+    Columns(Vec<QueryColumnInfo>),
+    Rows(Vec<Vec<Option<String>>>),
+}
+
+pub type StreamSender = tokio::sync::mpsc::Sender<StreamItem>;
+
+/// Stream a single row-returning statement. Scripts aren't accepted: only
+/// one statement can feed the stream.
+pub fn stream_query(conn: &Connection, query: &str, out: &StreamSender) -> Result<()> {
+    let trimmed = query.trim().trim_end_matches(';').trim();
+    if trimmed.is_empty() {
+        return Err(Error::InvalidQuery("Empty query".into()));
+    }
+
+    let mut stmt = conn.prepare(trimmed).map_err(query_err)?;
+    if stmt.column_count() == 0 {
+        return Err(Error::InvalidQuery(
+            "Only queries that return rows can be streamed".into(),
+        ));
+    }
+
+    let columns = statement_columns(&stmt);
+    stream_statement(&mut stmt, columns, out)
+}
+
+/// Step through `stmt`, sending rows in [`STREAM_BATCH_ROWS`] batches. The
+/// columns go out with the first batch, once expression columns (no
+/// declared type) got the storage class of their first value.
+pub fn stream_statement(
+    stmt: &mut Statement<'_>,
+    columns: Vec<QueryColumnInfo>,
+    out: &StreamSender,
+) -> Result<()> {
+    let width = columns.len();
+    let mut pending_columns = Some(columns);
+    let mut batch = Vec::with_capacity(STREAM_BATCH_ROWS);
+    let mut raw = stmt.raw_query();
+
+    while let Some(row) = raw.next().map_err(query_err)? {
+        let mut values = Vec::with_capacity(width);
+        for i in 0..width {
+            let value = row.get_ref(i).map_err(query_err)?;
+            if let Some(column) = pending_columns.as_mut().map(|c| &mut c[i]) {
+                if column.type_name.is_empty()
+                    && !matches!(value, rusqlite::types::ValueRef::Null)
+                {
+                    column.type_name = value_type_name(value).to_string();
+                }
+            }
+            values.push(value_to_string(value));
+        }
+        batch.push(values);
+
+        if batch.len() == STREAM_BATCH_ROWS {
+            if let Some(columns) = pending_columns.take() {
+                send(out, StreamItem::Columns(columns))?;
+            }
+            send(out, StreamItem::Rows(std::mem::take(&mut batch)))?;
+        }
+    }
+
+    if let Some(columns) = pending_columns.take() {
+        send(out, StreamItem::Columns(columns))?;
+    }
+    if !batch.is_empty() {
+        send(out, StreamItem::Rows(batch))?;
+    }
+    Ok(())
+}
+
+/// Blocks while the channel is full (backpressure). A closed channel means
+/// the consumer gave up, which ends the statement early.
+fn send(out: &StreamSender, item: StreamItem) -> Result<()> {
+    out.blocking_send(item)
+        .map_err(|_| Error::Query("Stream consumer stopped".into()))
+}
+
+pub fn apply_row_edits(
+    conn: &mut Connection,
+    editable: &EditableInfo,
+    edits: Vec<RowEdit>,
+) -> Result<StatementResult> {
+    if edits.is_empty() {
+        return Ok(StatementResult {
+            affected_rows: 0,
+            execution_time_ms: 0,
+        });
+    }
+
+    let known = known_columns(conn, editable)?;
+
+    let start = Instant::now();
+    let tx = conn.transaction().map_err(query_err)?;
+    let mut total_affected: u64 = 0;
+
+    for edit in &edits {
+        if edit.changes.is_empty() {
+            continue;
+        }
+
+        if edit.pk_values.len() != editable.primary_key_columns.len() {
+            return Err(Error::InvalidQuery(
+                "Primary key value count does not match primary key columns".into(),
+            ));
+        }
+
+        let mut set_clauses: Vec<String> = Vec::new();
+        let mut param_values: Vec<Option<&str>> = Vec::new();
+
+        for (col_name, value) in &edit.changes {
+            check_column(&known, col_name)?;
+            set_clauses.push(format!("{} = ?{}", quote_ident(col_name), param_values.len() + 1));
+            param_values.push(value.as_deref());
+        }
+
+        let where_clause = pk_where_clause(editable, &known, param_values.len())?;
+        param_values.extend(edit.pk_values.iter().map(|v| v.as_deref()));
+
+        let query = format!(
+            "UPDATE {}.{} SET {} WHERE {}",
+            quote_ident(&editable.schema),
+            quote_ident(&editable.table),
+            set_clauses.join(", "),
+            where_clause,
+        );
+
+        let affected = tx
+            .execute(&query, rusqlite::params_from_iter(&param_values))
+            .map_err(|e| Error::Query(format!("Failed to update row: {e}")))?;
+
+        total_affected += affected as u64;
+    }
+
+    tx.commit().map_err(query_err)?;
+
+    Ok(StatementResult {
+        affected_rows: total_affected,
+        execution_time_ms: start.elapsed().as_millis() as u64,
+    })
+}
+
+pub fn insert_rows(
+    conn: &mut Connection,
+    editable: &EditableInfo,
+    rows: Vec<RowInsert>,
+) -> Result<StatementResult> {
+    if rows.is_empty() {
+        return Ok(StatementResult {
+            affected_rows: 0,
+            execution_time_ms: 0,
+        });
+    }
+
+    let known = known_columns(conn, editable)?;
+
+    let start = Instant::now();
+    let tx = conn.transaction().map_err(query_err)?;
+    let mut total_affected: u64 = 0;
+
+    for row in &rows {
+        // Only the filled columns are sent; omitted ones fall back to the
+        // column DEFAULT (or a fresh rowid for INTEGER PRIMARY KEY).
+        if row.values.is_empty() {
+            continue;
+        }
+
+        let mut columns: Vec<String> = Vec::new();
+        let mut placeholders: Vec<String> = Vec::new();
+        let mut param_values: Vec<Option<&str>> = Vec::new();
+
+        for (i, (col_name, value)) in row.values.iter().enumerate() {
+            check_column(&known, col_name)?;
+            columns.push(quote_ident(col_name));
+            placeholders.push(format!("?{}", i + 1));
+            param_values.push(value.as_deref());
+        }
+
+        let query = format!(
+            "INSERT INTO {}.{} ({}) VALUES ({})",
+            quote_ident(&editable.schema),
+            quote_ident(&editable.table),
+            columns.join(", "),
+            placeholders.join(", "),
+        );
+
+        let affected = tx
+            .execute(&query, rusqlite::params_from_iter(&param_values))
+            .map_err(|e| Error::Query(format!("Failed to insert row: {e}")))?;
+
+        total_affected += affected as u64;
+    }
+
+    tx.commit().map_err(query_err)?;
+
+    Ok(StatementResult {
+        affected_rows: total_affected,
+        execution_time_ms: start.elapsed().as_millis() as u64,
+    })
+}
+
+pub fn delete_rows(
+    conn: &mut Connection,
+    editable: &EditableInfo,
+    pk_values: Vec<Vec<Option<String>>>,
+) -> Result<StatementResult> {
+    if pk_values.is_empty() {
+        return Ok(StatementResult {
+            affected_rows: 0,
+            execution_time_ms: 0,
+        });
+    }
+
+    let known = known_columns(conn, editable)?;
+    let where_clause = pk_where_clause(editable, &known, 0)?;
+    let query = format!(
+        "DELETE FROM {}.{} WHERE {}",
+        quote_ident(&editable.schema),
+        quote_ident(&editable.table),
+        where_clause,
+    );
+
+    let start = Instant::now();
+    let tx = conn.transaction().map_err(query_err)?;
+    let mut total_affected: u64 = 0;
+
+    for pk in &pk_values {
+        if pk.len() != editable.primary_key_columns.len() {
+            return Err(Error::InvalidQuery(
+                "Primary key value count does not match primary key columns".into(),
+            ));
+        }
+
+        let params: Vec<Option<&str>> = pk.iter().map(|v| v.as_deref()).collect();
+        let affected = tx
+            .execute(&query, rusqlite::params_from_iter(&params))
+            .map_err(|e| Error::Query(format!("Failed to delete row: {e}")))?;
+
+        total_affected += affected as u64;
+    }
+
+    tx.commit().map_err(query_err)?;
+
+    Ok(StatementResult {
+        affected_rows: total_affected,
+        execution_time_ms: start.elapsed().as_millis() as u64,
+    })
+}
+
+pub fn execute_statement(conn: &Connection, statement: &str) -> Result<StatementResult> {
+    let start = Instant::now();
+    let affected = run_script(conn, statement)?;
+
+    Ok(StatementResult {
+        affected_rows: affected,
+        execution_time_ms: start.elapsed().as_millis() as u64,
+    })
+}
+
+pub fn execute_transaction(
+    conn: &mut Connection,
+    statements: Vec<String>,
+) -> Result<Vec<StatementResult>> {
+    let tx = conn.transaction().map_err(query_err)?;
+
+    let mut results = Vec::with_capacity(statements.len());
+
+    for stmt in &statements {
+        let start = Instant::now();
+        let affected = run_script(&tx, stmt)?;
+        results.push(StatementResult {
+            affected_rows: affected,
+            execution_time_ms: start.elapsed().as_millis() as u64,
+        });
+    }
+
+    tx.commit().map_err(query_err)?;
+    Ok(results)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn is_select_query(query: &str) -> bool {
+    let first_word = query.split_whitespace().next().unwrap_or("");
+    matches!(first_word.to_uppercase().as_str(), "SELECT" | "WITH" | "VALUES")
+}
+
+/// Run every statement of `sql`, returning the total number of changed rows.
+/// Row-returning statements are stepped to completion and their rows dropped.
+fn run_script(conn: &Connection, sql: &str) -> Result<u64> {
+    let mut batch = Batch::new(conn, sql);
+    let mut affected: u64 = 0;
+
+    while let Some(mut stmt) = batch.next().map_err(query_err)? {
+        if stmt.column_count() == 0 {
+            affected += stmt.execute([]).map_err(query_err)? as u64;
+        } else {
+            let mut rows = stmt.raw_query();
+            while rows.next().map_err(query_err)?.is_some() {}
+        }
+    }
+
+    Ok(affected)
+}
+
+/// Column names of the edited table, used to reject unknown columns before
+/// they are spliced (quoted) into the statement.
+fn known_columns(conn: &Connection, editable: &EditableInfo) -> Result<Vec<String>> {
+    Ok(get_columns_ordered(conn, &editable.schema, &editable.table)?
+        .into_iter()
+        .map(|c| c.name)
+        .collect())
+}
+
+fn check_column(known: &[String], column: &str) -> Result<()> {
+    if known.iter().any(|c| c == column) {
+        Ok(())
+    } else {
+        Err(Error::InvalidQuery(format!("Unknown column: {column}")))
+    }
+}
+
+/// `"pk1" = ?N AND "pk2" = ?N+1 ...`, numbering placeholders after the
+/// `offset` parameters already used by the statement. The rowid fallback is
+/// compared as an integer: a text parameter would never match it.
+fn pk_where_clause(editable: &EditableInfo, known: &[String], offset: usize) -> Result<String> {
+    let clauses = editable
+        .primary_key_columns
+        .iter()
+        .enumerate()
+        .map(|(i, pk_col)| {
+            let placeholder = offset + i + 1;
+            if pk_col == ROWID_COLUMN && !known.iter().any(|c| c == ROWID_COLUMN) {
+                return Ok(format!("_rowid_ = CAST(?{placeholder} AS INTEGER)"));
+            }
+            check_column(known, pk_col)
+                .map_err(|_| Error::InvalidQuery(format!("Unknown primary key column: {pk_col}")))?;
+            Ok(format!("{} = ?{placeholder}", quote_ident(pk_col)))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(clauses.join(" AND "))
+}
@@ -0,0 +1,93 @@
+use rusqlite::{params_from_iter, Connection};
+use tokio::sync::mpsc::{Receiver, Sender};
+
+use crate::adapters::SourceRow;
+use crate::error::{Error, Result};
+
+use super::util::{get_columns_ordered, query_err, quote_ident};
+
+/// What the async side (which owns the `RowSource`) sends to the blocking
+/// writer.
+pub enum ImportItem {
+    Batch(Vec<SourceRow>),
+    /// End of the file: commit, or roll back on a dry run.
+    Finish {
+        commit: bool,
+    },
+}
+
+/// The writer's answer to each batch.
+pub struct BatchOutcome {
+    pub imported: u64,
+    /// `(line, message)` of every row SQLite refused.
+    pub rejected: Vec<(u64, String)>,
+}
+
+/// Insert the batches received on `input` with one prepared `INSERT`, in a
+/// single transaction. A failed `INSERT` only undoes itself, so bad rows are
+/// reported and the writer moves on; the async side decides whether that
+/// stops the import (by closing `input`, which rolls everything back).
+pub fn import_rows(
+    conn: &mut Connection,
+    schema: &str,
+    table: &str,
+    columns: &[String],
+    input: &mut Receiver<ImportItem>,
+    output: &Sender<BatchOutcome>,
+) -> Result<()> {
+    let known = get_columns_ordered(conn, schema, table)?;
+    if let Some(unknown) = columns
+        .iter()
+        .find(|c| !known.iter().any(|k| k.name == **c))
+    {
+        return Err(Error::InvalidQuery(format!("Unknown column: {unknown}")));
+    }
+
+    let sql = format!(
+        "INSERT INTO {}.{} ({}) VALUES ({})",
+        quote_ident(schema),
+        quote_ident(table),
+        columns
+            .iter()
+            .map(|c| quote_ident(c))
+            .collect::<Vec<_>>()
+            .join(", "),
+        (1..=columns.len())
+            .map(|i| format!("?{i}"))
+            .collect::<Vec<_>>()
+            .join(", "),
+    );
+
+    // Dropping an uncommitted transaction rolls it back.
+    let tx = conn.transaction().map_err(query_err)?;
+    let mut stmt = tx.prepare(&sql).map_err(query_err)?;
+
+    while let Some(item) = input.blocking_recv() {
+        match item {
+            ImportItem::Batch(rows) => {
+                let mut outcome = BatchOutcome {
+                    imported: 0,
+                    rejected: Vec::new(),
+                };
+                for row in rows {
+                    match stmt.execute(params_from_iter(row.values.iter())) {
+                        Ok(_) => outcome.imported += 1,
+                        Err(e) => outcome.rejected.push((row.line, e.to_string())),
+                    }
+                }
+                output
+                    .blocking_send(outcome)
+                    .map_err(|_| Error::Query("Import aborted".into()))?;
+            }
+            ImportItem::Finish { commit } => {
+                drop(stmt);
+                if commit {
+                    tx.commit().map_err(query_err)?;
+                }
+                return Ok(());
+            }
+        }
+    }
+
+    Err(Error::Query("Import aborted".into()))
+}
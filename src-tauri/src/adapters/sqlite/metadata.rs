@@ -0,0 +1,164 @@
+use chrono::Utc;
+use rusqlite::Connection;
+
+use crate::error::Result;
+use crate::models::{
+    ColumnInfo, DatabaseInfo, DatabaseStructure, IndexInfo, SchemaStructure, TableInfo,
+    TableStructure, TableType,
+};
+
+use super::util::{get_columns_ordered, query_err, quote_ident};
+
+/// Databases of the connection: `main`, `temp` and any attached file.
+pub fn list_databases(conn: &Connection) -> Result<Vec<DatabaseInfo>> {
+    let mut stmt = conn
+        .prepare("SELECT name FROM pragma_database_list ORDER BY seq")
+        .map_err(query_err)?;
+    let names = stmt
+        .query_map([], |r| r.get::<_, String>(0))
+        .map_err(query_err)?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(query_err)?;
+
+    Ok(names
+        .into_iter()
+        .map(|name| {
+            let pragma = |pragma: &str| {
+                conn.query_row(&format!("PRAGMA {}.{pragma}", quote_ident(&name)), [], |r| {
+                    r.get::<_, i64>(0)
+                })
+                .ok()
+            };
+            let size_bytes = pragma("page_count")
+                .zip(pragma("page_size"))
+                .map(|(pages, page_size)| pages * page_size);
+            DatabaseInfo { name, size_bytes }
+        })
+        .collect())
+}
+
+pub fn list_tables(conn: &Connection, schema: &str) -> Result<Vec<TableInfo>> {
+    let sql = format!(
+        "SELECT name, type FROM {}.sqlite_master \
+         WHERE type IN ('table', 'view') AND name NOT LIKE 'sqlite\\_%' ESCAPE '\\' \
+         ORDER BY name",
+        quote_ident(schema)
+    );
+    let mut stmt = conn.prepare(&sql).map_err(query_err)?;
+
+    let tables = stmt
+        .query_map([], |r| {
+            let kind: String = r.get(1)?;
+            Ok(TableInfo {
+                name: r.get(0)?,
+                schema: schema.to_string(),
+                table_type: if kind == "view" {
+                    TableType::View
+                } else {
+                    TableType::Table
+                },
+                // SQLite keeps no cheap row estimate (sqlite_stat1 only
+                // exists after ANALYZE).
+                row_estimate: None,
+            })
+        })
+        .map_err(query_err)?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(query_err)?;
+
+    Ok(tables)
+}
+
+/// Row count recorded by the last `ANALYZE` (first number of the
+/// `sqlite_stat1` entry), for the ALTER warning. `None` if never analyzed.
+pub fn row_estimate(conn: &Connection, schema: &str, table: &str) -> Option<i64> {
+    let sql = format!(
+        "SELECT stat FROM {}.sqlite_stat1 WHERE tbl = ?1 LIMIT 1",
+        quote_ident(schema)
+    );
+    let stat: String = conn.query_row(&sql, [table], |r| r.get(0)).ok()?;
+    stat.split_whitespace().next()?.parse().ok()
+}
+
+pub fn list_columns(conn: &Connection, schema: &str, table: &str) -> Result<Vec<ColumnInfo>> {
+    let columns = get_columns_ordered(conn, schema, table)?;
+
+    let fk_columns: Vec<String> = {
+        let mut stmt = conn
+            .prepare("SELECT \"from\" FROM pragma_foreign_key_list(?1, ?2)")
+            .map_err(query_err)?;
+        let rows = stmt
+            .query_map([table, schema], |r| r.get::<_, String>(0))
+            .map_err(query_err)?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .map_err(query_err)?;
+        rows
+    };
+
+    Ok(columns
+        .into_iter()
+        .enumerate()
+        .map(|(i, c)| ColumnInfo {
+            is_foreign_key: fk_columns.contains(&c.name),
+            name: c.name,
+            ordinal: i as i32 + 1,
+            data_type: c.declared_type,
+            // A PK column of a rowid table accepts NULL in DDL terms, but the
+            // grid should still treat it as required.
+            is_nullable: !c.not_null && c.pk_position == 0,
+            default_value: c.default_value,
+            is_primary_key: c.pk_position > 0,
+        })
+        .collect())
+}
+
+pub fn list_indexes(conn: &Connection, schema: &str, table: &str) -> Result<Vec<IndexInfo>> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT il.name, il.\"unique\", il.origin, \
+                    (SELECT group_concat(name, char(31)) FROM \
+                        (SELECT ii.name FROM pragma_index_info(il.name, ?2) ii ORDER BY ii.seqno)) \
+             FROM pragma_index_list(?1, ?2) il \
+             ORDER BY il.name",
+        )
+        .map_err(query_err)?;
+
+    let indexes = stmt
+        .query_map([table, schema], |r| {
+            let origin: String = r.get(2)?;
+            let columns: Option<String> = r.get(3)?;
+            Ok(IndexInfo {
+                name: r.get(0)?,
+                // Expression indexes report NULL column names.
+                columns: columns
+                    .map(|c| c.split('\u{1f}').map(str::to_string).collect())
+                    .unwrap_or_default(),
+                is_unique: r.get::<_, i64>(1)? != 0,
+                is_primary: origin == "pk",
+                index_type: "btree".to_string(),
+            })
+        })
+        .map_err(query_err)?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(query_err)?;
+
+    Ok(indexes)
+}
+
+pub fn list_schemas_with_tables(conn: &Connection, schema: &str) -> Result<DatabaseStructure> {
+    let tables = list_tables(conn, schema)?
+        .into_iter()
+        .map(|t| TableStructure {
+            name: t.name,
+            table_type: t.table_type,
+        })
+        .collect();
+
+    Ok(DatabaseStructure {
+        schemas: vec![SchemaStructure {
+            name: schema.to_string(),
+            tables,
+        }],
+        fetched_at: Utc::now().timestamp_millis(),
+    })
+}
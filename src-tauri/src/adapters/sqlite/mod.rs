@@ -0,0 +1,338 @@
+mod browse;
+mod executor;
+mod import;
+mod metadata;
+mod util;
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use rusqlite::{Connection, InterruptHandle, OpenFlags};
+use sqlparser::dialect::SQLiteDialect;
+
+use crate::adapters::{
+    analyze_sql, is_read_only_sql, sql_structure_changes, DatabaseAdapter, RowSink, RowSource,
+};
+use crate::error::{Error, Result};
+use crate::models::*;
+
+/// SQLite is an embedded file, so there is no pool: one connection per
+/// adapter, serialised behind a mutex. rusqlite is blocking, so every call
+/// runs on tokio's blocking thread pool (see [`SqliteAdapter::run`]).
+pub struct SqliteAdapter {
+    conn: Arc<Mutex<Connection>>,
+    /// Schema name used to qualify objects: `main`, or an attached/temp
+    /// database picked in the tree.
+    database: String,
+    /// `sqlite3_interrupt` works from any thread, so cancel_query doesn't
+    /// need the connection mutex that the running query is holding.
+    interrupt: InterruptHandle,
+    /// query_id of the execution currently holding the connection.
+    active_query: Arc<Mutex<Option<String>>>,
+}
+
+impl SqliteAdapter {
+    /// The file path comes from `connection_uri` when present (a plain path or
+    /// a `file:` URI), otherwise from `host`. The file must already exist: a
+    /// typo in the path should fail, not silently create an empty database.
+    pub fn new(server: &Server, database: &str) -> Result<Self> {
+        let path = server
+            .connection_uri
+            .as_deref()
+            .filter(|uri| !uri.trim().is_empty())
+            .unwrap_or(&server.host)
+            .trim();
+
+        if path.is_empty() {
+            return Err(Error::Connection("SQLite database file path is empty".into()));
+        }
+
+        let flags = OpenFlags::SQLITE_OPEN_READ_WRITE
+            | OpenFlags::SQLITE_OPEN_URI
+            | OpenFlags::SQLITE_OPEN_NO_MUTEX;
+
+        let conn = Connection::open_with_flags(path, flags)
+            .map_err(|e| Error::Connection(format!("Failed to open {path}: {e}")))?;
+
+        // Another process (the app under development) may be writing to the
+        // same file; wait for its lock instead of failing with SQLITE_BUSY.
+        conn.busy_timeout(std::time::Duration::from_secs(5))
+            .map_err(|e| Error::Connection(e.to_string()))?;
+
+        Ok(Self {
+            interrupt: conn.get_interrupt_handle(),
+            conn: Arc::new(Mutex::new(conn)),
+            database: database.to_string(),
+            active_query: Arc::new(Mutex::new(None)),
+        })
+    }
+
+    /// Run a blocking closure against the connection on the blocking pool.
+    async fn run<T, F>(&self, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut Connection) -> Result<T> + Send + 'static,
+    {
+        let conn = Arc::clone(&self.conn);
+        tokio::task::spawn_blocking(move || f(&mut conn.lock()))
+            .await
+            .map_err(|e| Error::Query(format!("SQLite task failed: {e}")))?
+    }
+
+    /// Run a blocking producer on the blocking pool while this task feeds its
+    /// batches to `sink`. The small bounded channel is the backpressure: a
+    /// slow sink (disk) pauses the SQLite step loop instead of piling rows up.
+    async fn stream<F>(&self, sink: &mut dyn RowSink, f: F) -> Result<u64>
+    where
+        F: FnOnce(&mut Connection, &executor::StreamSender) -> Result<()> + Send + 'static,
+    {
+        let (tx, mut rx) = tokio::sync::mpsc::channel(2);
+        let producer = self.run(move |conn| f(conn, &tx));
+        let consumer = async move {
+            let mut sent: u64 = 0;
+            while let Some(item) = rx.recv().await {
+                match item {
+                    executor::StreamItem::Columns(columns) => sink.columns(&columns).await?,
+                    executor::StreamItem::Rows(rows) => {
+                        sent += rows.len() as u64;
+                        sink.rows(rows).await?;
+                    }
+                }
+            }
+            Ok::<_, Error>(sent)
+        };
+
+        let (produced, consumed) = tokio::join!(producer, consumer);
+        // A sink error drops the receiver, which makes the producer fail with
+        // a generic "stopped" error: the sink's own error is the one to report.
+        let sent = consumed?;
+        produced?;
+        Ok(sent)
+    }
+
+    /// The import mirror of [`SqliteAdapter::stream`]: this task reads the
+    /// source and applies the error policy, the blocking writer holds the
+    /// connection (and its transaction) for the whole import.
+    async fn import(
+        &self,
+        schema: String,
+        table: String,
+        source: &mut dyn RowSource,
+    ) -> Result<u64> {
+        let columns = source.columns().to_vec();
+        let (batch_tx, mut batch_rx) = tokio::sync::mpsc::channel(1);
+        let (outcome_tx, mut outcome_rx) = tokio::sync::mpsc::channel(1);
+
+        let writer = self.run(move |conn| {
+            import::import_rows(conn, &schema, &table, &columns, &mut batch_rx, &outcome_tx)
+        });
+        let feeder = async move {
+            let mut total: u64 = 0;
+            while let Some(batch) = source.next_batch().await? {
+                // A closed channel means the writer failed; its error wins.
+                if batch_tx.send(import::ImportItem::Batch(batch)).await.is_err() {
+                    return Ok(total);
+                }
+                let Some(outcome) = outcome_rx.recv().await else {
+                    return Ok(total);
+                };
+                for (line, message) in outcome.rejected {
+                    source.reject(line, message)?;
+                }
+                total += outcome.imported;
+                source.imported(outcome.imported);
+            }
+            let commit = !source.dry_run();
+            let _ = batch_tx.send(import::ImportItem::Finish { commit }).await;
+            Ok::<_, Error>(total)
+        };
+
+        let (written, fed) = tokio::join!(writer, feeder);
+        // Stopping on a bad row drops the batch sender, which makes the
+        // writer roll back with a generic "aborted": report the row instead.
+        let total = fed?;
+        written?;
+        Ok(total)
+    }
+}
+
+#[async_trait]
+impl DatabaseAdapter for SqliteAdapter {
+    fn capabilities(&self) -> AdapterCapabilities {
+        AdapterCapabilities::sqlite()
+    }
+
+    fn is_read_only_query(&self, query: &str) -> bool {
+        is_read_only_sql(&SQLiteDialect {}, query)
+    }
+
+    fn structure_changes(&self, query: &str) -> Vec<StructureChange> {
+        sql_structure_changes(&SQLiteDialect {}, query)
+    }
+
+    async fn analyze_query(&self, query: &str) -> Result<Vec<StatementWarning>> {
+        let mut analysis = analyze_sql(&SQLiteDialect {}, query);
+        let altered = std::mem::take(&mut analysis.altered_tables);
+        if altered.is_empty() {
+            return Ok(analysis.into_warnings());
+        }
+
+        let database = self.database.clone();
+        self.run(move |conn| {
+            for table in &altered {
+                let schema = table.schema.as_deref().unwrap_or(&database);
+                let rows = metadata::row_estimate(conn, schema, &table.table);
+                analysis.flag_if_large(table, rows);
+            }
+            Ok(analysis.into_warnings())
+        })
+        .await
+    }
+
+    async fn execute_query(
+        &self,
+        query: &str,
+        options: QueryOptions,
+    ) -> Result<QueryResult> {
+        let query = query.to_string();
+        let active = Arc::clone(&self.active_query);
+        self.run(move |conn| {
+            // Registered only while the connection is held, so cancel_query
+            // never interrupts someone else's statement.
+            let _guard = executor::ActiveQueryGuard::register(&active, options.query_id.clone());
+            executor::execute_query(conn, &query, &options)
+        })
+        .await
+    }
+
+    async fn fetch_table_data(&self, request: TableDataRequest) -> Result<QueryResult> {
+        let database = self.database.clone();
+        self.run(move |conn| browse::fetch_table_data(conn, &database, request))
+            .await
+    }
+
+    async fn stream_query(
+        &self,
+        query: &str,
+        query_id: Option<&str>,
+        sink: &mut dyn RowSink,
+    ) -> Result<u64> {
+        let query = query.to_string();
+        let query_id = query_id.map(str::to_string);
+        let active = Arc::clone(&self.active_query);
+        self.stream(sink, move |conn, out| {
+            let _guard = executor::ActiveQueryGuard::register(&active, query_id);
+            executor::stream_query(conn, &query, out)
+        })
+        .await
+    }
+
+    async fn stream_table(&self, request: TableDataRequest, sink: &mut dyn RowSink) -> Result<u64> {
+        let database = self.database.clone();
+        self.stream(sink, move |conn, out| {
+            browse::stream_table_data(conn, &database, request, out)
+        })
+        .await
+    }
+
+    async fn apply_row_edits(
+        &self,
+        editable: &EditableInfo,
+        edits: Vec<RowEdit>,
+    ) -> Result<StatementResult> {
+        let editable = editable.clone();
+        self.run(move |conn| executor::apply_row_edits(conn, &editable, edits))
+            .await
+    }
+
+    async fn insert_rows(
+        &self,
+        editable: &EditableInfo,
+        rows: Vec<RowInsert>,
+    ) -> Result<StatementResult> {
+        let editable = editable.clone();
+        self.run(move |conn| executor::insert_rows(conn, &editable, rows))
+            .await
+    }
+
+    async fn import_rows(
+        &self,
+        target: &ImportTarget,
+        source: &mut dyn RowSource,
+    ) -> Result<u64> {
+        let schema = target.schema.clone().unwrap_or_else(|| self.database.clone());
+        self.import(schema, target.table.clone(), source).await
+    }
+
+    async fn delete_rows(
+        &self,
+        editable: &EditableInfo,
+        pk_values: Vec<Vec<Option<String>>>,
+    ) -> Result<StatementResult> {
+        let editable = editable.clone();
+        self.run(move |conn| executor::delete_rows(conn, &editable, pk_values))
+            .await
+    }
+
+    async fn execute_statement(&self, statement: &str) -> Result<StatementResult> {
+        let statement = statement.to_string();
+        self.run(move |conn| executor::execute_statement(conn, &statement))
+            .await
+    }
+
+    async fn execute_transaction(
+        &self,
+        statements: Vec<String>,
+    ) -> Result<Vec<StatementResult>> {
+        self.run(move |conn| executor::execute_transaction(conn, statements))
+            .await
+    }
+
+    async fn list_databases(&self) -> Result<Vec<DatabaseInfo>> {
+        self.run(|conn| metadata::list_databases(conn)).await
+    }
+
+    async fn list_tables(&self, _schema: &str) -> Result<Vec<TableInfo>> {
+        let database = self.database.clone();
+        self.run(move |conn| metadata::list_tables(conn, &database))
+            .await
+    }
+
+    async fn list_columns(&self, _schema: &str, table: &str) -> Result<Vec<ColumnInfo>> {
+        let database = self.database.clone();
+        let table = table.to_string();
+        self.run(move |conn| metadata::list_columns(conn, &database, &table))
+            .await
+    }
+
+    async fn list_indexes(&self, _schema: &str, table: &str) -> Result<Vec<IndexInfo>> {
+        let database = self.database.clone();
+        let table = table.to_string();
+        self.run(move |conn| metadata::list_indexes(conn, &database, &table))
+            .await
+    }
+
+    async fn list_schemas_with_tables(&self) -> Result<DatabaseStructure> {
+        let database = self.database.clone();
+        self.run(move |conn| metadata::list_schemas_with_tables(conn, &database))
+            .await
+    }
+
+    async fn test_connection(&self) -> Result<()> {
+        self.run(|conn| {
+            conn.query_row("SELECT 1", [], |_| Ok(()))
+                .map_err(util::query_err)
+        })
+        .await
+    }
+
+    async fn cancel_query(&self, query_id: &str) -> Result<()> {
+        // Unknown id means the query already finished (or is still waiting
+        // for the connection); cancelling is then a benign no-op.
+        if self.active_query.lock().as_deref() == Some(query_id) {
+            self.interrupt.interrupt();
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,126 @@
+use rusqlite::types::ValueRef;
+use rusqlite::Connection;
+
+use crate::error::{Error, Result};
+
+/// Safely quotes a SQLite identifier (prevents SQL injection on names).
+pub fn quote_ident(ident: &str) -> String {
+    format!("\"{}\"", ident.replace('"', "\"\""))
+}
+
+/// rusqlite errors convert into `Error::Storage` (meant for the app's own
+/// store); errors from a user database are query errors.
+pub fn query_err(e: rusqlite::Error) -> Error {
+    Error::Query(e.to_string())
+}
+
+/// Render a SQLite value as text. Blobs are shown as hex, like Postgres
+/// `bytea` output, so the grid always receives printable strings.
+pub fn value_to_string(value: ValueRef<'_>) -> Option<String> {
+    match value {
+        ValueRef::Null => None,
+        ValueRef::Integer(v) => Some(v.to_string()),
+        ValueRef::Real(v) => Some(v.to_string()),
+        ValueRef::Text(bytes) => Some(String::from_utf8_lossy(bytes).into_owned()),
+        ValueRef::Blob(bytes) => Some(format!("\\x{}", hex::encode(bytes))),
+    }
+}
+
+/// Storage class name of a value, used as the column type when the result
+/// column has no declared type (expressions, aggregates).
+pub fn value_type_name(value: ValueRef<'_>) -> &'static str {
+    match value {
+        ValueRef::Null => "null",
+        ValueRef::Integer(_) => "integer",
+        ValueRef::Real(_) => "real",
+        ValueRef::Text(_) => "text",
+        ValueRef::Blob(_) => "blob",
+    }
+}
+
+/// One row of `pragma_table_info`, in declaration order.
+pub struct ColumnMeta {
+    pub name: String,
+    /// Declared type as written in the DDL (may be empty: SQLite allows
+    /// untyped columns).
+    pub declared_type: String,
+    pub not_null: bool,
+    pub default_value: Option<String>,
+    /// Position inside the primary key (1-based); 0 when not part of it.
+    pub pk_position: i32,
+}
+
+/// Fetches the ordered column metadata for a given table or view.
+pub fn get_columns_ordered(
+    conn: &Connection,
+    schema: &str,
+    table: &str,
+) -> Result<Vec<ColumnMeta>> {
+    let mut stmt = conn
+        .prepare("SELECT name, type, \"notnull\", dflt_value, pk FROM pragma_table_info(?1, ?2)")
+        .map_err(query_err)?;
+
+    let columns = stmt
+        .query_map([table, schema], |r| {
+            Ok(ColumnMeta {
+                name: r.get(0)?,
+                declared_type: r.get(1)?,
+                not_null: r.get::<_, i64>(2)? != 0,
+                default_value: r.get(3)?,
+                pk_position: r.get(4)?,
+            })
+        })
+        .map_err(query_err)?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(query_err)?;
+
+    if columns.is_empty() {
+        return Err(Error::InvalidQuery(format!(
+            "Table {schema}.{table} not found or has no columns"
+        )));
+    }
+
+    Ok(columns)
+}
+
+/// Primary-key column names in key order, or empty if the table has none.
+pub fn pk_columns(columns: &[ColumnMeta]) -> Vec<String> {
+    let mut pk: Vec<&ColumnMeta> = columns.iter().filter(|c| c.pk_position > 0).collect();
+    pk.sort_by_key(|c| c.pk_position);
+    pk.into_iter().map(|c| c.name.clone()).collect()
+}
+
+/// Object type from `sqlite_master` (`table`, `view`), or `None` if missing.
+pub fn object_type(conn: &Connection, schema: &str, name: &str) -> Result<Option<String>> {
+    let sql = format!(
+        "SELECT type FROM {}.sqlite_master WHERE name = ?1 AND type IN ('table', 'view')",
+        quote_ident(schema)
+    );
+    match conn.query_row(&sql, [name], |r| r.get(0)) {
+        Ok(kind) => Ok(Some(kind)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(query_err(e)),
+    }
+}
+
+/// Whether a table was declared `WITHOUT ROWID` (those always have a PK, so
+/// the rowid fallback never applies to them).
+pub fn has_rowid(conn: &Connection, schema: &str, table: &str) -> Result<bool> {
+    let sql = format!(
+        "SELECT sql FROM {}.sqlite_master WHERE type = 'table' AND name = ?1",
+        quote_ident(schema)
+    );
+    let ddl: Option<String> = match conn.query_row(&sql, [table], |r| r.get(0)) {
+        Ok(ddl) => ddl,
+        Err(rusqlite::Error::QueryReturnedNoRows) => return Ok(false),
+        Err(e) => return Err(query_err(e)),
+    };
+
+    // Table options come after the closing parenthesis of the column list
+    // (`) WITHOUT ROWID, STRICT`).
+    let ddl = ddl.unwrap_or_default().to_ascii_uppercase();
+    let options = ddl.rsplit_once(')').map(|(_, tail)| tail).unwrap_or("");
+    let options = options.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    Ok(!options.contains("WITHOUT ROWID"))
+}
@@ -0,0 +1,238 @@
+use sqlparser::ast::{FromTable, ObjectName, Statement, TableFactor, TableWithJoins};
+use sqlparser::dialect::Dialect;
+use sqlparser::parser::Parser;
+
+use crate::models::{StatementWarning, WarningKind, WarningSeverity};
+
+/// Estimated rows above which an `ALTER TABLE` is flagged.
+pub const LARGE_TABLE_ROWS: i64 = 1_000_000;
+
+/// Warnings found by parsing alone, plus the tables under `ALTER TABLE`:
+/// whether those are large needs the database, so each SQL adapter looks up
+/// their row estimate and calls [`SqlAnalysis::flag_if_large`].
+#[derive(Debug, Default)]
+pub struct SqlAnalysis {
+    pub warnings: Vec<StatementWarning>,
+    pub altered_tables: Vec<AlteredTable>,
+}
+
+#[derive(Debug)]
+pub struct AlteredTable {
+    pub statement_index: usize,
+    pub schema: Option<String>,
+    pub table: String,
+}
+
+impl SqlAnalysis {
+    pub fn flag_if_large(&mut self, altered: &AlteredTable, row_estimate: Option<i64>) {
+        let Some(rows) = row_estimate.filter(|rows| *rows >= LARGE_TABLE_ROWS) else {
+            return;
+        };
+        self.warnings.push(StatementWarning {
+            kind: WarningKind::AlterLargeTable,
+            severity: WarningSeverity::Warning,
+            statement_index: altered.statement_index,
+            object: Some(altered.table.clone()),
+            message: format!(
+                "ALTER TABLE on {} (~{rows} rows) may lock or rewrite the table",
+                altered.table
+            ),
+        });
+    }
+
+    /// Warnings in statement order (the ALTER ones are added last).
+    pub fn into_warnings(mut self) -> Vec<StatementWarning> {
+        self.warnings.sort_by_key(|w| w.statement_index);
+        self.warnings
+    }
+}
+
+/// Flag the statements of `sql` that deserve a confirmation. SQL that
+/// doesn't parse yields nothing: the database will reject it anyway.
+pub fn analyze_sql(dialect: &dyn Dialect, sql: &str) -> SqlAnalysis {
+    let mut analysis = SqlAnalysis::default();
+    let Ok(statements) = Parser::parse_sql(dialect, sql) else {
+        return analysis;
+    };
+
+    if statements.len() > 1 {
+        analysis.warnings.push(StatementWarning {
+            kind: WarningKind::MultiStatement,
+            severity: WarningSeverity::Info,
+            statement_index: 0,
+            object: None,
+            message: format!("Script with {} statements", statements.len()),
+        });
+    }
+
+    for (index, statement) in statements.iter().enumerate() {
+        analyze_statement(&mut analysis, index, statement);
+    }
+    analysis
+}
+
+fn analyze_statement(analysis: &mut SqlAnalysis, index: usize, statement: &Statement) {
+    let danger = |kind, object: Option<String>, message: String| StatementWarning {
+        kind,
+        severity: WarningSeverity::Danger,
+        statement_index: index,
+        object,
+        message,
+    };
+
+    match statement {
+        Statement::Update {
+            table, selection, ..
+        } if selection.is_none() => {
+            let object = table_name(table);
+            let message = format!(
+                "UPDATE without WHERE changes every row of {}",
+                or_table(&object)
+            );
+            analysis
+                .warnings
+                .push(danger(WarningKind::UpdateWithoutWhere, object, message));
+        }
+        Statement::Delete(delete) if delete.selection.is_none() => {
+            let (FromTable::WithFromKeyword(tables) | FromTable::WithoutKeyword(tables)) =
+                &delete.from;
+            let object = tables.first().and_then(table_name);
+            let message = format!(
+                "DELETE without WHERE removes every row of {}",
+                or_table(&object)
+            );
+            analysis
+                .warnings
+                .push(danger(WarningKind::DeleteWithoutWhere, object, message));
+        }
+        Statement::Drop {
+            object_type, names, ..
+        } => {
+            for name in names {
+                let (_, object) = split_name(name);
+                let message = format!("DROP {object_type} {name}");
+                analysis
+                    .warnings
+                    .push(danger(WarningKind::Drop, Some(object), message));
+            }
+        }
+        Statement::Truncate { table_names, .. } => {
+            for target in table_names {
+                let (_, object) = split_name(&target.name);
+                let message = format!("TRUNCATE removes every row of {}", target.name);
+                analysis
+                    .warnings
+                    .push(danger(WarningKind::Truncate, Some(object), message));
+            }
+        }
+        Statement::AlterTable { name, .. } => {
+            let (schema, table) = split_name(name);
+            analysis.altered_tables.push(AlteredTable {
+                statement_index: index,
+                schema,
+                table,
+            });
+        }
+        _ => {}
+    }
+}
+
+fn table_name(table: &TableWithJoins) -> Option<String> {
+    match &table.relation {
+        TableFactor::Table { name, .. } => Some(split_name(name).1),
+        _ => None,
+    }
+}
+
+fn or_table(object: &Option<String>) -> &str {
+    object.as_deref().unwrap_or("the table")
+}
+
+/// `schema.table` → (schema, table) without identifier quotes. Anything
+/// before the schema (a Postgres database, a SQL Server server) is dropped.
+pub(super) fn split_name(name: &ObjectName) -> (Option<String>, String) {
+    let mut parts = split_identifiers(&name.to_string());
+    let table = parts.pop().unwrap_or_default();
+    (parts.pop(), table)
+}
+
+fn split_identifiers(text: &str) -> Vec<String> {
+    let mut parts = vec![String::new()];
+    let mut quote: Option<char> = None;
+
+    for c in text.chars() {
+        match (quote, c) {
+            (Some(close), c) if c == close => quote = None,
+            (Some(_), c) => parts.last_mut().unwrap().push(c),
+            (None, '"') | (None, '`') => quote = Some(c),
+            (None, '[') => quote = Some(']'),
+            (None, '.') => parts.push(String::new()),
+            (None, c) => parts.last_mut().unwrap().push(c),
+        }
+    }
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlparser::dialect::{MySqlDialect, PostgreSqlDialect};
+
+    use super::*;
+
+    fn kinds(analysis: &SqlAnalysis) -> Vec<WarningKind> {
+        analysis.warnings.iter().map(|w| w.kind).collect()
+    }
+
+    #[test]
+    fn flags_unfiltered_writes_and_drops() {
+        let analysis = analyze_sql(
+            &PostgreSqlDialect {},
+            "UPDATE users SET active = false; DELETE FROM \"audit\".\"log\"; \
+             DELETE FROM users WHERE id = 1; DROP TABLE a, b; TRUNCATE orders",
+        );
+
+        assert_eq!(
+            kinds(&analysis),
+            vec![
+                WarningKind::MultiStatement,
+                WarningKind::UpdateWithoutWhere,
+                WarningKind::DeleteWithoutWhere,
+                WarningKind::Drop,
+                WarningKind::Drop,
+                WarningKind::Truncate,
+            ]
+        );
+        assert_eq!(analysis.warnings[0].severity, WarningSeverity::Info);
+        assert_eq!(analysis.warnings[2].object.as_deref(), Some("log"));
+        assert_eq!(analysis.warnings[2].statement_index, 1);
+        assert!(analysis.warnings[1..]
+            .iter()
+            .all(StatementWarning::needs_confirmation));
+    }
+
+    #[test]
+    fn alter_is_flagged_only_on_large_tables() {
+        let mut analysis = analyze_sql(
+            &MySqlDialect {},
+            "ALTER TABLE shop.`orders` ADD COLUMN note TEXT",
+        );
+        assert!(analysis.warnings.is_empty());
+
+        let altered = analysis.altered_tables.pop().unwrap();
+        assert_eq!(altered.schema.as_deref(), Some("shop"));
+        assert_eq!(altered.table, "orders");
+
+        analysis.flag_if_large(&altered, Some(10));
+        analysis.flag_if_large(&altered, None);
+        assert!(analysis.warnings.is_empty());
+        analysis.flag_if_large(&altered, Some(5_000_000));
+        assert_eq!(kinds(&analysis), vec![WarningKind::AlterLargeTable]);
+    }
+
+    #[test]
+    fn reads_and_unparsable_sql_have_no_warnings() {
+        let pg = PostgreSqlDialect {};
+        assert!(analyze_sql(&pg, "SELECT * FROM users").warnings.is_empty());
+        assert!(analyze_sql(&pg, "DELETE FROM").warnings.is_empty());
+    }
+}
@@ -0,0 +1,131 @@
+use sqlparser::ast::Statement;
+use sqlparser::dialect::Dialect;
+use sqlparser::parser::Parser;
+
+use crate::models::StructureChange;
+
+use super::statement_analysis::split_name;
+
+/// What the statements of `sql` change in the structure, for the metadata
+/// cache. DML and reads change nothing; DDL the parser can't pin to a table
+/// (and SQL that doesn't parse) counts as [`StructureChange::Unknown`].
+pub fn sql_structure_changes(dialect: &dyn Dialect, sql: &str) -> Vec<StructureChange> {
+    let Ok(statements) = Parser::parse_sql(dialect, sql) else {
+        return vec![StructureChange::Unknown];
+    };
+
+    let mut changes: Vec<StructureChange> = Vec::new();
+    for statement in &statements {
+        for change in statement_changes(statement) {
+            if !changes.contains(&change) {
+                changes.push(change);
+            }
+        }
+    }
+    changes
+}
+
+fn statement_changes(statement: &Statement) -> Vec<StructureChange> {
+    match statement {
+        Statement::CreateTable(create) => vec![table(&create.name)],
+        Statement::CreateView { name, .. } | Statement::AlterTable { name, .. } => {
+            vec![table(name)]
+        }
+        Statement::CreateIndex(create) => vec![table(&create.table_name)],
+        // No MySQL, schema e database são a mesma coisa.
+        Statement::CreateSchema { schema_name, .. } => {
+            let name = schema_name.to_string();
+            let name = name.split_whitespace().next().unwrap_or_default();
+            vec![StructureChange::Databases, schema(name)]
+        }
+        Statement::CreateDatabase { db_name, .. } => {
+            vec![StructureChange::Databases, schema(&db_name.to_string())]
+        }
+        Statement::Drop {
+            object_type, names, ..
+        } => match object_type.to_string().as_str() {
+            "SCHEMA" | "DATABASE" => std::iter::once(StructureChange::Databases)
+                .chain(names.iter().map(|name| schema(&name.to_string())))
+                .collect(),
+            // O nome do índice não diz de que tabela ele era.
+            "INDEX" => vec![StructureChange::Unknown],
+            _ => names.iter().map(table).collect(),
+        },
+        other => {
+            let text = other.to_string();
+            let keyword = text.split_whitespace().next().unwrap_or_default();
+            if ["CREATE", "ALTER", "DROP", "RENAME"].contains(&keyword.to_uppercase().as_str()) {
+                vec![StructureChange::Unknown]
+            } else {
+                Vec::new()
+            }
+        }
+    }
+}
+
+fn table(name: &sqlparser::ast::ObjectName) -> StructureChange {
+    let (schema, table) = split_name(name);
+    StructureChange::Table { schema, table }
+}
+
+fn schema(name: &str) -> StructureChange {
+    StructureChange::Schema(name.trim_matches(|c| c == '"' || c == '`').to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlparser::dialect::{MySqlDialect, PostgreSqlDialect};
+
+    use super::*;
+
+    fn table(schema: Option<&str>, table: &str) -> StructureChange {
+        StructureChange::Table {
+            schema: schema.map(str::to_string),
+            table: table.into(),
+        }
+    }
+
+    #[test]
+    fn ddl_is_pinned_to_its_tables() {
+        let pg = PostgreSqlDialect {};
+        assert_eq!(
+            sql_structure_changes(
+                &pg,
+                "CREATE TABLE app.users (id int); ALTER TABLE \"Orders\" ADD note text; \
+                 CREATE INDEX ix ON app.users (id); DROP VIEW v1, app.v2",
+            ),
+            vec![
+                table(Some("app"), "users"),
+                table(None, "Orders"),
+                table(None, "v1"),
+                table(Some("app"), "v2"),
+            ]
+        );
+        assert_eq!(
+            sql_structure_changes(&MySqlDialect {}, "CREATE DATABASE shop"),
+            vec![
+                StructureChange::Databases,
+                StructureChange::Schema("shop".into())
+            ]
+        );
+    }
+
+    #[test]
+    fn dml_changes_nothing_and_the_unclear_changes_everything() {
+        let pg = PostgreSqlDialect {};
+        assert!(sql_structure_changes(&pg, "INSERT INTO t VALUES (1); SELECT 1").is_empty());
+        assert!(sql_structure_changes(&pg, "TRUNCATE t; UPDATE t SET a = 1").is_empty());
+        assert_eq!(
+            sql_structure_changes(&pg, "DROP INDEX ix"),
+            vec![StructureChange::Unknown]
+        );
+        assert_eq!(
+            sql_structure_changes(&pg, "CREATE SEQUENCE s"),
+            vec![StructureChange::Unknown]
+        );
+        assert_eq!(
+            sql_structure_changes(&pg, "ALTR TABLE"),
+            vec![StructureChange::Unknown]
+        );
+    }
+}
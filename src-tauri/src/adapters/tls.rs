@@ -0,0 +1,295 @@
+use std::path::Path;
+
+use pkcs8::der::{Decode, Document};
+use pkcs8::{EncryptedPrivateKeyInfoOwned, LineEnding};
+
+use crate::error::{Error, Result};
+use crate::models::{TlsConfig, TlsMode};
+
+/// Client certificate + key, as loaded from the server's TLS settings.
+pub enum ClientIdentity {
+    /// PEM certificate chain and a PKCS#8 PEM key, already decrypted.
+    Pem { cert: Vec<u8>, key: Vec<u8> },
+    /// PKCS#12 bundle (.p12/.pfx) and its password.
+    Pkcs12 { der: Vec<u8>, password: String },
+}
+
+/// The configured CA bundle (PEM), if any.
+pub fn ca_bundle(tls: &TlsConfig) -> Result<Option<Vec<u8>>> {
+    match non_empty(&tls.ca_cert_path) {
+        Some(path) => read_file(path, "CA bundle").map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Loads the client certificate and key. Without a key path the key is read
+/// from the certificate file itself (combined PEM). Encrypted PKCS#8 keys are
+/// decrypted here, so drivers only ever see a plain key in memory.
+pub fn client_identity(tls: &TlsConfig) -> Result<Option<ClientIdentity>> {
+    let Some(cert_path) = non_empty(&tls.client_cert_path) else {
+        return Ok(None);
+    };
+
+    if is_pkcs12(cert_path) {
+        return Ok(Some(ClientIdentity::Pkcs12 {
+            der: read_file(cert_path, "client certificate")?,
+            password: tls.client_key_passphrase.clone().unwrap_or_default(),
+        }));
+    }
+
+    let cert = read_file(cert_path, "client certificate")?;
+    let key = match non_empty(&tls.client_key_path) {
+        Some(key_path) => read_file(key_path, "client key")?,
+        None => cert.clone(),
+    };
+    let key = decrypt_key(&key, tls.client_key_passphrase.as_deref())?;
+
+    Ok(Some(ClientIdentity::Pem { cert, key }))
+}
+
+pub fn is_pkcs12(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("p12") || e.eq_ignore_ascii_case("pfx"))
+}
+
+/// native-tls connector for the mode (Postgres). `require` encrypts without
+/// verifying; `verify-ca` skips only the hostname check.
+pub fn native_connector(tls: &TlsConfig) -> Result<native_tls::TlsConnector> {
+    let mut builder = native_tls::TlsConnector::builder();
+
+    match tls.mode {
+        TlsMode::Disable | TlsMode::Require => {
+            builder
+                .danger_accept_invalid_certs(true)
+                .danger_accept_invalid_hostnames(true);
+        }
+        TlsMode::VerifyCa => {
+            builder.danger_accept_invalid_hostnames(true);
+        }
+        TlsMode::VerifyFull => {}
+    }
+
+    if let Some(bundle) = ca_bundle(tls)? {
+        for pem in split_certificates(&bundle) {
+            let cert = native_tls::Certificate::from_pem(pem)
+                .map_err(|e| Error::Connection(format!("Invalid CA bundle: {e}")))?;
+            builder.add_root_certificate(cert);
+        }
+    }
+
+    if let Some(identity) = client_identity(tls)? {
+        let identity = match identity {
+            ClientIdentity::Pem { cert, key } => native_tls::Identity::from_pkcs8(&cert, &key),
+            ClientIdentity::Pkcs12 { der, password } => {
+                native_tls::Identity::from_pkcs12(&der, &password)
+            }
+        }
+        .map_err(|e| Error::Connection(format!("Invalid client certificate/key: {e}")))?;
+        builder.identity(identity);
+    }
+
+    builder
+        .build()
+        .map_err(|e| Error::Connection(format!("TLS setup failed: {e}")))
+}
+
+/// Rewrites a connection failure caused by TLS into a message that says what
+/// went wrong and what to change; other errors pass through untouched.
+pub fn explain_error(err: Error, tls: &TlsConfig) -> Error {
+    let message = err.to_string();
+    let lower = message.to_lowercase();
+    let has = |needles: &[&str]| needles.iter().any(|n| lower.contains(n));
+
+    if !tls.mode.is_enabled() {
+        return err;
+    }
+
+    let hint = if has(&[
+        "server does not support tls",
+        "server does not support ssl",
+        "server doesn't support tls",
+        "ssl is not enabled",
+        "tls not supported",
+    ]) {
+        "the server doesn't accept TLS; set the TLS mode to disable".to_string()
+    } else if has(&[
+        "certificate required",
+        "bad certificate",
+        "certificate unknown",
+    ]) {
+        "the server rejected the client certificate; check the client certificate and key"
+            .to_string()
+    } else if has(&[
+        "notvalidforname",
+        "not valid for name",
+        "hostname mismatch",
+        "hostname verification",
+        "does not match",
+        "doesn't match",
+        "ip address mismatch",
+    ]) {
+        format!(
+            "the server certificate doesn't match the hostname ({}); connect using a name \
+             listed in the certificate or use verify-ca",
+            tls.mode.as_str()
+        )
+    } else if has(&[
+        "unknownissuer",
+        "unknown issuer",
+        "self signed",
+        "self-signed",
+        "unable to get local issuer",
+        "certificate verify failed",
+        "not trusted",
+        "untrusted",
+        "invalid peer certificate",
+        "certificate has expired",
+    ]) {
+        format!(
+            "the server certificate isn't trusted ({}); set the CA bundle to the CA that \
+             signed it, or use require to skip verification",
+            tls.mode.as_str()
+        )
+    } else {
+        return err;
+    };
+
+    Error::Connection(format!("TLS certificate error: {hint}. Details: {message}"))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn non_empty(path: &Option<String>) -> Option<&str> {
+    path.as_deref().filter(|p| !p.trim().is_empty())
+}
+
+fn read_file(path: &str, what: &str) -> Result<Vec<u8>> {
+    std::fs::read(path).map_err(|e| Error::Connection(format!("Cannot read {what} '{path}': {e}")))
+}
+
+/// Each `BEGIN CERTIFICATE` block of a PEM bundle.
+fn split_certificates(bundle: &[u8]) -> Vec<&[u8]> {
+    const END: &[u8] = b"-----END CERTIFICATE-----";
+
+    let mut certs = Vec::new();
+    let mut rest = bundle;
+    while let Some(pos) = rest.windows(END.len()).position(|w| w == END) {
+        let (cert, tail) = rest.split_at(pos + END.len());
+        certs.push(cert);
+        rest = tail;
+    }
+    certs
+}
+
+/// Returns the key as plain PEM, decrypting `ENCRYPTED PRIVATE KEY` blocks.
+fn decrypt_key(pem: &[u8], passphrase: Option<&str>) -> Result<Vec<u8>> {
+    let text = String::from_utf8_lossy(pem);
+
+    let Some(block) = pem_block(&text, "ENCRYPTED PRIVATE KEY") else {
+        if text.contains("Proc-Type: 4,ENCRYPTED") {
+            return Err(Error::Connection(
+                "Client key uses legacy OpenSSL encryption; convert it with \
+                 `openssl pkcs8 -topk8 -in key.pem -out key.p8`"
+                    .into(),
+            ));
+        }
+        return Ok(pem.to_vec());
+    };
+
+    let Some(passphrase) = passphrase.filter(|p| !p.is_empty()) else {
+        return Err(Error::Connection(
+            "Client key is encrypted; set its passphrase".into(),
+        ));
+    };
+
+    let invalid = |e: &dyn std::fmt::Display| Error::Connection(format!("Invalid client key: {e}"));
+    let (_, document) = Document::from_pem(block).map_err(|e| invalid(&e))?;
+    let encrypted =
+        EncryptedPrivateKeyInfoOwned::from_der(document.as_bytes()).map_err(|e| invalid(&e))?;
+    let decrypted = encrypted
+        .decrypt(passphrase)
+        .map_err(|_| Error::Connection("Wrong passphrase for the client key".into()))?;
+    let pem = decrypted
+        .to_pem("PRIVATE KEY", LineEnding::LF)
+        .map_err(|e| invalid(&e))?;
+
+    Ok(pem.as_bytes().to_vec())
+}
+
+fn pem_block<'a>(text: &'a str, label: &str) -> Option<&'a str> {
+    let begin = format!("-----BEGIN {label}-----");
+    let end = format!("-----END {label}-----");
+    let start = text.find(&begin)?;
+    let stop = text[start..].find(&end)? + start + end.len();
+    Some(&text[start..stop])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tls(mode: TlsMode) -> TlsConfig {
+        TlsConfig {
+            mode,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn explain_error_classifies_certificate_failures() {
+        let untrusted = explain_error(
+            Error::Connection("invalid peer certificate: UnknownIssuer".into()),
+            &tls(TlsMode::VerifyCa),
+        )
+        .to_string();
+        assert!(
+            untrusted.contains("isn't trusted (verify-ca)"),
+            "{untrusted}"
+        );
+        assert!(
+            untrusted.contains("UnknownIssuer"),
+            "keeps the driver message"
+        );
+
+        let hostname = explain_error(
+            Error::Query("error performing TLS handshake: certificate NotValidForName".into()),
+            &tls(TlsMode::VerifyFull),
+        )
+        .to_string();
+        assert!(
+            hostname.contains("doesn't match the hostname"),
+            "{hostname}"
+        );
+    }
+
+    #[test]
+    fn explain_error_leaves_other_errors_alone() {
+        let auth = explain_error(
+            Error::Query("password authentication failed for user \"app\"".into()),
+            &tls(TlsMode::VerifyFull),
+        );
+        assert!(matches!(auth, Error::Query(_)));
+
+        // With TLS off a certificate word in the message isn't a TLS problem
+        let plain = explain_error(
+            Error::Query("relation \"certificate\" does not exist".into()),
+            &tls(TlsMode::Disable),
+        );
+        assert!(matches!(plain, Error::Query(_)));
+    }
+
+    #[test]
+    fn split_certificates_handles_bundles() {
+        let bundle = b"-----BEGIN CERTIFICATE-----\nAAA\n-----END CERTIFICATE-----\n\
+                       -----BEGIN CERTIFICATE-----\nBBB\n-----END CERTIFICATE-----\n";
+        let certs = split_certificates(bundle);
+        assert_eq!(certs.len(), 2);
+        assert!(certs[1].ends_with(b"-----END CERTIFICATE-----"));
+        assert!(is_pkcs12("/certs/client.PFX"));
+        assert!(!is_pkcs12("/certs/client.pem"));
+    }
+}
@@ -1,13 +1,19 @@
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use tokio::sync::mpsc;
 
-use crate::adapters::MessageSink;
+use crate::adapters::{rank_matches, MessageSink, RowSink, RowSource};
 use crate::error::{Error, Result};
 use crate::models::{
-    AdapterCapabilities, ColumnInfo, DatabaseInfo, DatabaseStructure, EditableInfo, IndexInfo,
-    QueryOptions, QueryResult, RowEdit, RowInsert, SchemaInfo, StatementResult, TableDataRequest,
-    TableInfo,
+    AdapterCapabilities, ChannelNotification, ChannelSubscription, ColumnInfo, ConstraintInfo,
+    DatabaseInfo, DatabaseStructure, DdlRequest, EditableInfo, ExplainOptions, ExplainPlan,
+    ForeignKeyInfo, ImportTarget, IndexInfo, KeyEntriesPage, KeyEntriesRequest, ObjectKind,
+    ObjectMatch, ObjectSearchRequest, QueryOptions, QueryResult, RowEdit, RowInsert, SchemaInfo,
+    SchemaSnapshot, StatementResult, StatementWarning, StreamConsumerInfo, StreamEntriesAction,
+    StreamGroupInfo, StreamInfo, StreamPage, StreamPendingEntry, StreamPendingRequest,
+    StreamRangeRequest, StructureChange, TableDataRequest, TableInfo, TableType,
+    TransactionState, ValueSearchEvent, ValueSearchRequest,
 };
 
 /// Core trait that all database adapters must implement.
@@ -56,10 +62,91 @@ pub trait DatabaseAdapter: Send + Sync {
         self.execute_query(query, options).await
     }
 
+    /// The execution plan of `query` as a tree, for the plan viewer. With
+    /// `options.analyze` the query really runs, to measure it.
+    async fn explain_query(&self, _query: &str, _options: ExplainOptions) -> Result<ExplainPlan> {
+        Err(Error::UnsupportedType(
+            "Query plans are not supported for this database".into(),
+        ))
+    }
+
+    /// Whether `query` (free-form, in the adapter's syntax) only reads, so it
+    /// may run against a read-only server. Anything the adapter can't tell
+    /// apart counts as a write.
+    fn is_read_only_query(&self, _query: &str) -> bool {
+        false
+    }
+
+    /// Warnings to confirm before `query` runs (a DELETE without WHERE, a
+    /// DROP...), in statement order. Empty when nothing stands out.
+    async fn analyze_query(&self, _query: &str) -> Result<Vec<StatementWarning>> {
+        Ok(Vec::new())
+    }
+
+    /// What running `query` changes in the structure (tables, columns,
+    /// indexes), so the metadata cache can drop it. By default every write
+    /// may have changed anything.
+    fn structure_changes(&self, query: &str) -> Vec<StructureChange> {
+        if self.is_read_only_query(query) {
+            Vec::new()
+        } else {
+            vec![StructureChange::Unknown]
+        }
+    }
+
+    /// Whether writing rows outside the editor (grid edits, imports) changes
+    /// the cached structure: only where it's derived from the data.
+    fn row_writes_change_structure(&self) -> bool {
+        false
+    }
+
     /// Browse a table's data with server-side pagination, sorting and
     /// filtering. "Table" is adapter-defined (table/collection/key-prefix).
     async fn fetch_table_data(&self, request: TableDataRequest) -> Result<QueryResult>;
 
+    /// Browse the elements of one key (Redis hash, list, set or sorted set)
+    /// as a table, a page at a time.
+    async fn fetch_key_entries(&self, _request: &KeyEntriesRequest) -> Result<KeyEntriesPage> {
+        Err(Error::UnsupportedType(
+            "Key drill-down is not supported for this database".into(),
+        ))
+    }
+
+    /// Run `query` without a row cap, pushing rows into `sink` in batches as
+    /// the database produces them, so a result of millions of rows is never
+    /// held in memory. Returns how many rows were sent.
+    ///
+    /// `query_id` registers the execution for [`DatabaseAdapter::cancel_query`]
+    /// while the database is busy producing a batch; between batches the sink
+    /// itself can stop the stream by returning `Err`.
+    ///
+    /// The default runs an unlimited [`DatabaseAdapter::execute_query`] and
+    /// sends it as a single batch — fine only where the reply is already
+    /// bounded (a Redis command); adapters with cursors override it.
+    async fn stream_query(
+        &self,
+        query: &str,
+        query_id: Option<&str>,
+        sink: &mut dyn RowSink,
+    ) -> Result<u64> {
+        let options = QueryOptions {
+            unlimited: true,
+            query_id: query_id.map(str::to_string),
+            ..Default::default()
+        };
+        let result = self.execute_query(query, options).await?;
+        let sent = result.rows.len() as u64;
+
+        sink.columns(&result.columns).await?;
+        sink.rows(result.rows).await?;
+        Ok(sent)
+    }
+
+    /// Stream every row of a table browse (same filter and sort as
+    /// [`DatabaseAdapter::fetch_table_data`]; `limit`, `offset` and
+    /// `count_total` are ignored).
+    async fn stream_table(&self, request: TableDataRequest, sink: &mut dyn RowSink) -> Result<u64>;
+
     async fn apply_row_edits(
         &self,
         _editable: &EditableInfo,
@@ -80,6 +167,18 @@ pub trait DatabaseAdapter: Send + Sync {
         ))
     }
 
+    /// Bulk-load the rows of `source` into `target`, batch by batch. Returns
+    /// how many rows were written (or, on a dry run, would have been).
+    async fn import_rows(
+        &self,
+        _target: &ImportTarget,
+        _source: &mut dyn RowSource,
+    ) -> Result<u64> {
+        Err(Error::UnsupportedType(
+            "Import is not supported for this database".into(),
+        ))
+    }
+
     async fn delete_rows(
         &self,
         _editable: &EditableInfo,
@@ -119,8 +218,79 @@ pub trait DatabaseAdapter: Send + Sync {
         Ok(vec![])
     }
 
+    /// Foreign keys declared on the table.
+    async fn list_foreign_keys(&self, _schema: &str, _table: &str) -> Result<Vec<ForeignKeyInfo>> {
+        Ok(vec![])
+    }
+
+    /// Primary key, unique, check and exclusion constraints of the table.
+    async fn list_constraints(&self, _schema: &str, _table: &str) -> Result<Vec<ConstraintInfo>> {
+        Ok(vec![])
+    }
+
+    /// Foreign keys of other tables (or the table itself) that reference it.
+    async fn list_referencing_tables(
+        &self,
+        _schema: &str,
+        _table: &str,
+    ) -> Result<Vec<ForeignKeyInfo>> {
+        Ok(vec![])
+    }
+
     async fn list_schemas_with_tables(&self) -> Result<DatabaseStructure>;
 
+    /// Objects whose name fuzzy-matches `request.query`, best first (see
+    /// [`rank_matches`]). By default only tables and views, from
+    /// [`DatabaseAdapter::list_schemas_with_tables`].
+    async fn search_objects(&self, request: &ObjectSearchRequest) -> Result<Vec<ObjectMatch>> {
+        let structure = self.list_schemas_with_tables().await?;
+        let candidates = structure.schemas.into_iter().flat_map(|schema| {
+            let schema_name = schema.name;
+            schema.tables.into_iter().map(move |table| ObjectMatch {
+                kind: match table.table_type {
+                    TableType::View | TableType::MaterializedView => ObjectKind::View,
+                    TableType::Table | TableType::Foreign => ObjectKind::Table,
+                },
+                schema: Some(schema_name.clone()),
+                parent: None,
+                name: table.name,
+                detail: None,
+                score: 0,
+            })
+        });
+        Ok(rank_matches(request, candidates))
+    }
+
+    /// `CREATE` script that rebuilds the object (with its indexes, triggers
+    /// and comments, for a table), ready to copy into another database.
+    async fn get_object_ddl(&self, _request: &DdlRequest) -> Result<String> {
+        Err(Error::UnsupportedType(
+            "DDL generation is not supported for this database".into(),
+        ))
+    }
+
+    /// Tables, views and functions of `schema` with their definitions, for
+    /// `compare_schemas`.
+    async fn schema_snapshot(&self, _schema: &str) -> Result<SchemaSnapshot> {
+        Err(Error::UnsupportedType(
+            "Schema comparison is not supported for this database".into(),
+        ))
+    }
+
+    /// Look for `request.value` in every table of a schema (collection, key
+    /// group), sending hits to `events` as each table finishes (see
+    /// [`scan_tables`](crate::adapters::scan_tables)). Dropping the future
+    /// cancels the search.
+    async fn search_values(
+        &self,
+        _request: &ValueSearchRequest,
+        _events: mpsc::Sender<ValueSearchEvent>,
+    ) -> Result<()> {
+        Err(Error::UnsupportedType(
+            "Value search is not supported for this database".into(),
+        ))
+    }
+
     // ─────────────────────────────────────────────────────────────────────
     // Connection
     // ─────────────────────────────────────────────────────────────────────
@@ -133,6 +303,114 @@ pub trait DatabaseAdapter: Send + Sync {
         ))
     }
 
+    // ─────────────────────────────────────────────────────────────────────
+    // Sessions (interactive transactions)
+    // ─────────────────────────────────────────────────────────────────────
+
+    /// Check a connection out of the pool and pin it to `session_id`:
+    /// `execute_query` calls whose options carry the id run on it, so a
+    /// `BEGIN` survives between calls until [`DatabaseAdapter::end_session`].
+    async fn open_session(&self, _session_id: &str) -> Result<TransactionState> {
+        Err(Error::UnsupportedType(
+            "Sessions are not supported for this database".into(),
+        ))
+    }
+
+    async fn session_state(&self, _session_id: &str) -> Result<TransactionState> {
+        Err(Error::UnsupportedType(
+            "Sessions are not supported for this database".into(),
+        ))
+    }
+
+    /// COMMIT (or ROLLBACK) the session's open transaction, if any, and
+    /// release its connection.
+    async fn end_session(&self, _session_id: &str, _commit: bool) -> Result<()> {
+        Err(Error::UnsupportedType(
+            "Sessions are not supported for this database".into(),
+        ))
+    }
+
+    // ─────────────────────────────────────────────────────────────────────
+    // Channels (LISTEN/NOTIFY, pub/sub)
+    // ─────────────────────────────────────────────────────────────────────
+
+    /// Start listening on `request.channel` with a connection of its own,
+    /// sending every message that arrives to `events` until
+    /// [`DatabaseAdapter::unsubscribe_channel`] with the same id or the
+    /// connection closing, either of which drops the sender.
+    async fn subscribe_channel(
+        &self,
+        _subscription_id: &str,
+        _request: &ChannelSubscription,
+        _events: mpsc::Sender<ChannelNotification>,
+    ) -> Result<()> {
+        Err(Error::UnsupportedType(
+            "Channel subscriptions are not supported for this database".into(),
+        ))
+    }
+
+    async fn unsubscribe_channel(&self, _subscription_id: &str) -> Result<()> {
+        Err(Error::UnsupportedType(
+            "Channel subscriptions are not supported for this database".into(),
+        ))
+    }
+
+    /// Send `payload` on `channel`, e.g. to test a subscriber.
+    async fn notify_channel(&self, _channel: &str, _payload: &str) -> Result<()> {
+        Err(Error::UnsupportedType(
+            "Channel notifications are not supported for this database".into(),
+        ))
+    }
+
+    // ─────────────────────────────────────────────────────────────────────
+    // Streams (Redis)
+    // ─────────────────────────────────────────────────────────────────────
+
+    /// A page of the entries of stream `request.key`, one column per field.
+    async fn fetch_stream_entries(&self, _request: &StreamRangeRequest) -> Result<StreamPage> {
+        Err(Error::UnsupportedType(
+            "Streams are not supported for this database".into(),
+        ))
+    }
+
+    async fn stream_info(&self, _key: &str) -> Result<StreamInfo> {
+        Err(Error::UnsupportedType(
+            "Streams are not supported for this database".into(),
+        ))
+    }
+
+    async fn list_stream_groups(&self, _key: &str) -> Result<Vec<StreamGroupInfo>> {
+        Err(Error::UnsupportedType(
+            "Streams are not supported for this database".into(),
+        ))
+    }
+
+    async fn list_stream_consumers(
+        &self,
+        _key: &str,
+        _group: &str,
+    ) -> Result<Vec<StreamConsumerInfo>> {
+        Err(Error::UnsupportedType(
+            "Streams are not supported for this database".into(),
+        ))
+    }
+
+    async fn list_stream_pending(
+        &self,
+        _request: &StreamPendingRequest,
+    ) -> Result<Vec<StreamPendingEntry>> {
+        Err(Error::UnsupportedType(
+            "Streams are not supported for this database".into(),
+        ))
+    }
+
+    /// Ack, claim or delete entries; returns how many it applied to.
+    async fn stream_entries_action(&self, _action: &StreamEntriesAction) -> Result<u64> {
+        Err(Error::UnsupportedType(
+            "Streams are not supported for this database".into(),
+        ))
+    }
+
     fn pool_stats(&self) -> Option<PoolStats> {
         None
     }
@@ -0,0 +1,195 @@
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::{mpsc, Semaphore};
+use tokio::task::JoinSet;
+
+use crate::error::Result;
+use crate::models::{ValueHit, ValueSearchEvent, ValueSearchRequest};
+
+/// Extra time a table gets past `tableTimeoutMs` before its task is dropped:
+/// the database-side timeout (Postgres, Mongo) should fire first and end the
+/// query there too.
+const TIMEOUT_GRACE: Duration = Duration::from_secs(1);
+
+/// A table (collection, key group) for `search_values` to scan.
+#[derive(Debug, Clone)]
+pub struct ScanTarget {
+    pub schema: Option<String>,
+    pub table: String,
+}
+
+/// Outcome of scanning one table.
+pub enum TableScan {
+    Hits(Vec<ValueHit>),
+    /// The database gave up (statement_timeout, maxTimeMS).
+    TimedOut,
+}
+
+/// Scan `targets` with `scan`, at most `concurrency` at a time and each for
+/// at most the request's table timeout, sending the hits and the outcome of
+/// every table to `events`. Stops starting tables once the receiver is gone
+/// (cancelled, or it has enough hits); dropping the returned future aborts
+/// the scans still running.
+pub async fn scan_tables<F, Fut>(
+    request: &ValueSearchRequest,
+    targets: Vec<ScanTarget>,
+    concurrency: usize,
+    events: mpsc::Sender<ValueSearchEvent>,
+    scan: F,
+) -> Result<()>
+where
+    F: Fn(ScanTarget) -> Fut,
+    Fut: Future<Output = Result<TableScan>> + Send + 'static,
+{
+    let started = ValueSearchEvent::Started {
+        tables: targets.len(),
+    };
+    if events.send(started).await.is_err() {
+        return Ok(());
+    }
+
+    let slots = Arc::new(Semaphore::new(concurrency.max(1)));
+    let timeout = Duration::from_millis(request.table_timeout_ms) + TIMEOUT_GRACE;
+    let mut running = JoinSet::new();
+
+    for target in targets {
+        let Ok(slot) = Arc::clone(&slots).acquire_owned().await else {
+            break;
+        };
+        if events.is_closed() {
+            break;
+        }
+        // Terminadas ficam no JoinSet até serem colhidas.
+        while running.try_join_next().is_some() {}
+
+        let scanning = scan(target.clone());
+        let events = events.clone();
+        running.spawn(async move {
+            let (hits, timed_out, error) = match tokio::time::timeout(timeout, scanning).await {
+                Ok(Ok(TableScan::Hits(hits))) => (hits, false, None),
+                Ok(Ok(TableScan::TimedOut)) | Err(_) => (Vec::new(), true, None),
+                Ok(Err(e)) => (Vec::new(), false, Some(e.to_string())),
+            };
+            drop(slot);
+
+            let count = hits.len();
+            for hit in hits {
+                if events.send(ValueSearchEvent::Hit(hit)).await.is_err() {
+                    return;
+                }
+            }
+            let _ = events
+                .send(ValueSearchEvent::TableScanned {
+                    schema: target.schema,
+                    table: target.table,
+                    hits: count,
+                    timed_out,
+                    error,
+                })
+                .await;
+        });
+    }
+
+    while running.join_next().await.is_some() {}
+    Ok(())
+}
+
+/// `%value%` for `ILIKE`, with the wildcards of the value escaped.
+pub fn like_contains(value: &str) -> String {
+    let mut pattern = String::from("%");
+    for c in value.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+    }
+    pattern.push('%');
+    pattern
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ValueMatchMode;
+
+    fn request(timeout_ms: u64) -> ValueSearchRequest {
+        ValueSearchRequest {
+            value: "x".into(),
+            schema: None,
+            mode: ValueMatchMode::Exact,
+            concurrency: 2,
+            table_timeout_ms: timeout_ms,
+            max_hits: 10,
+        }
+    }
+
+    fn target(table: &str) -> ScanTarget {
+        ScanTarget {
+            schema: None,
+            table: table.into(),
+        }
+    }
+
+    fn hit(table: &str) -> ValueHit {
+        ValueHit {
+            schema: None,
+            table: table.into(),
+            column: "c".into(),
+            key_columns: vec!["id".into()],
+            key_values: vec![Some("1".into())],
+            value: Some("x".into()),
+        }
+    }
+
+    #[tokio::test]
+    async fn reports_every_table_and_bounds_slow_ones() {
+        let (tx, mut rx) = mpsc::channel(16);
+        let targets = vec![target("fast"), target("slow"), target("broken")];
+
+        scan_tables(&request(50), targets, 2, tx, |target| async move {
+            match target.table.as_str() {
+                "fast" => Ok(TableScan::Hits(vec![hit("fast")])),
+                "slow" => {
+                    tokio::time::sleep(Duration::from_secs(60)).await;
+                    Ok(TableScan::Hits(Vec::new()))
+                }
+                _ => Err(crate::error::Error::Query("boom".into())),
+            }
+        })
+        .await
+        .unwrap();
+
+        let mut hits = 0;
+        let mut outcomes = Vec::new();
+        while let Some(event) = rx.recv().await {
+            match event {
+                ValueSearchEvent::Hit(_) => hits += 1,
+                ValueSearchEvent::TableScanned {
+                    table,
+                    timed_out,
+                    error,
+                    ..
+                } => outcomes.push((table, timed_out, error.is_some())),
+                ValueSearchEvent::Started { tables } => assert_eq!(tables, 3),
+            }
+        }
+        outcomes.sort();
+
+        assert_eq!(hits, 1);
+        assert_eq!(
+            outcomes,
+            [
+                ("broken".to_string(), false, true),
+                ("fast".to_string(), false, false),
+                ("slow".to_string(), true, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn contains_pattern_escapes_wildcards() {
+        assert_eq!(like_contains("50%_a\\b"), "%50\\%\\_a\\\\b%");
+    }
+}
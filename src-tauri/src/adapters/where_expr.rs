@@ -0,0 +1,54 @@
+use sqlparser::dialect::Dialect;
+use sqlparser::parser::Parser;
+use sqlparser::tokenizer::Token;
+
+use crate::error::{Error, Result};
+
+/// Parse `raw` as a single SQL expression in the given dialect.
+/// Empty/whitespace → `None`. A leading `WHERE` keyword is stripped so the
+/// input can match the UI label.
+///
+/// Shared by the SQL adapters' table browsers: the expression is spliced into
+/// a generated SELECT, so anything after it (`UNION`, `;`) must be rejected.
+pub fn normalize_where_expr(raw: Option<&str>, dialect: &dyn Dialect) -> Result<Option<String>> {
+    let Some(raw) = raw else {
+        return Ok(None);
+    };
+    let trimmed = raw.trim();
+    if trimmed.is_empty() {
+        return Ok(None);
+    }
+
+    let expr = strip_leading_where(trimmed);
+    if expr.is_empty() {
+        return Ok(None);
+    }
+
+    let mut parser = Parser::new(dialect)
+        .try_with_sql(expr)
+        .map_err(|e| Error::InvalidQuery(format!("Invalid WHERE expression: {e}")))?;
+    parser
+        .parse_expr()
+        .map_err(|e| Error::InvalidQuery(format!("Invalid WHERE expression: {e}")))?;
+
+    if !matches!(parser.peek_token().token, Token::EOF) {
+        return Err(Error::InvalidQuery(
+            "Filter must be a single WHERE expression".into(),
+        ));
+    }
+
+    Ok(Some(expr.to_string()))
+}
+
+fn strip_leading_where(s: &str) -> &str {
+    let trimmed = s.trim_start();
+    let Some(rest) = trimmed.get(5..) else {
+        return trimmed;
+    };
+    if trimmed[..5].eq_ignore_ascii_case("where")
+        && (rest.is_empty() || rest.starts_with(char::is_whitespace))
+    {
+        return rest.trim_start();
+    }
+    trimmed
+}
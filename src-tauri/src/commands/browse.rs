@@ -1,6 +1,10 @@
-use tauri::State;
+use tauri::ipc::{Channel, JavaScriptChannelId};
+use tauri::{State, Webview};
 
-use crate::models::{AdapterCapabilities, QueryResult, TableDataRequest};
+use crate::models::{
+    AdapterCapabilities, KeyEntriesPage, KeyEntriesRequest, QueryResult, TableDataRequest,
+    ValueSearchEvent, ValueSearchRequest, ValueSearchSummary,
+};
 use crate::state::AppState;
 use crate::storage::repositories::servers;
 
@@ -21,6 +25,60 @@ pub async fn fetch_table_data(
         .map_err(|e| e.to_string())
 }
 
+/// One Redis key as a table (hash fields, list elements, set or sorted set
+/// members), paged by `next_cursor`.
+#[tauri::command]
+pub async fn fetch_key_entries(
+    state: State<'_, AppState>,
+    server_id: i64,
+    database: String,
+    request: KeyEntriesRequest,
+) -> Result<KeyEntriesPage, String> {
+    let adapter = connect_adapter(&state, server_id, &database).await?;
+
+    adapter
+        .fetch_key_entries(&request)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Look for a value in every table of a schema (collection, Redis key),
+/// sending `ValueSearchEvent`s over `events` as each table finishes.
+/// `cancel_value_search` with the same `search_id` stops it.
+#[tauri::command]
+pub async fn search_values(
+    webview: Webview,
+    state: State<'_, AppState>,
+    server_id: i64,
+    database: String,
+    search_id: String,
+    request: ValueSearchRequest,
+    events: JavaScriptChannelId,
+) -> Result<ValueSearchSummary, String> {
+    let adapter = connect_adapter(&state, server_id, &database).await?;
+    let channel: Channel<ValueSearchEvent> = events.channel_on(webview);
+
+    state
+        .searches
+        .search_values(
+            adapter,
+            &request,
+            &search_id,
+            Box::new(move |event| {
+                let _ = channel.send(event);
+            }),
+        )
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Stop value search `search_id`. A search that already ended is ignored.
+#[tauri::command]
+pub fn cancel_value_search(state: State<'_, AppState>, search_id: String) -> Result<(), String> {
+    state.searches.cancel(&search_id);
+    Ok(())
+}
+
 #[tauri::command]
 pub fn get_capabilities(
     state: State<'_, AppState>,
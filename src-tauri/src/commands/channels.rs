@@ -0,0 +1,104 @@
+use tauri::ipc::{Channel, JavaScriptChannelId};
+use tauri::{State, Webview};
+use tokio::sync::mpsc;
+
+use crate::models::{ChannelEvent, ChannelSubscription};
+use crate::state::AppState;
+
+use super::{connect_adapter, ensure_writable};
+
+/// Notifications buffered between the connection and the frontend; past
+/// that, new ones are dropped until the frontend catches up.
+const NOTIFICATION_BUFFER: usize = 1024;
+
+/// Mensagens entregues por assinatura. Um monitor de keyspace esquecido
+/// aberto num servidor movimentado não enche o front para sempre: no teto a
+/// assinatura é encerrada e o `Closed` sai com `truncated`.
+const MAX_STREAMED_NOTIFICATIONS: usize = 10_000;
+
+/// Listen on `request.channel` with a dedicated connection, sending each
+/// message as a `ChannelEvent` over `events` until `unsubscribe_channel`,
+/// the connection closing or the message cap, and then a last `Closed`.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn subscribe_channel(
+    webview: Webview,
+    state: State<'_, AppState>,
+    server_id: i64,
+    database: String,
+    subscription_id: String,
+    request: ChannelSubscription,
+    events: JavaScriptChannelId,
+    confirmed: Option<bool>,
+) -> Result<(), String> {
+    // Ligar as notificações de keyspace muda a config do servidor.
+    if request.enable_keyspace_events {
+        ensure_writable(&state, server_id, confirmed)?;
+    }
+    let adapter = connect_adapter(&state, server_id, &database).await?;
+    let ipc: Channel<ChannelEvent> = events.channel_on(webview);
+
+    let (sender, mut received) = mpsc::channel(NOTIFICATION_BUFFER);
+    adapter
+        .subscribe_channel(&subscription_id, &request, sender)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    // Termina sozinho quando a assinatura cai e o sender é solto.
+    tokio::spawn(async move {
+        let mut sent = 0;
+        let mut truncated = false;
+        while let Some(notification) = received.recv().await {
+            if sent == MAX_STREAMED_NOTIFICATIONS {
+                truncated = true;
+                let _ = adapter.unsubscribe_channel(&subscription_id).await;
+                break;
+            }
+            let _ = ipc.send(ChannelEvent::Message(notification));
+            sent += 1;
+        }
+        let _ = ipc.send(ChannelEvent::Closed { truncated });
+    });
+    Ok(())
+}
+
+/// A subscription lives on an open connection: if that was closed, so was
+/// the subscription.
+#[tauri::command]
+pub async fn unsubscribe_channel(
+    state: State<'_, AppState>,
+    server_id: i64,
+    database: String,
+    subscription_id: String,
+) -> Result<(), String> {
+    let adapter = state
+        .connections
+        .get_cached(server_id, &database)
+        .ok_or_else(|| format!("Not found: Subscription {subscription_id}"))?;
+
+    adapter
+        .unsubscribe_channel(&subscription_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Send a test payload on `channel` (`NOTIFY` in Postgres, `PUBLISH` in
+/// Redis). It reaches other services, so it follows the server's write
+/// policy.
+#[tauri::command]
+pub async fn notify_channel(
+    state: State<'_, AppState>,
+    server_id: i64,
+    database: String,
+    channel: String,
+    payload: String,
+    confirmed: Option<bool>,
+) -> Result<(), String> {
+    ensure_writable(&state, server_id, confirmed)?;
+    let adapter = connect_adapter(&state, server_id, &database).await?;
+
+    adapter
+        .notify_channel(&channel, &payload)
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -1,6 +1,6 @@
 use tauri::State;
 
-use crate::adapters::PoolStats;
+use crate::adapters::{tls, PoolStats};
 use crate::state::AppState;
 use crate::storage::repositories::servers;
 
@@ -26,7 +26,7 @@ pub async fn connect(
     adapter
         .test_connection()
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(|e| tls::explain_error(e, &server.tls).to_string())?;
 
     Ok(true)
 }
@@ -48,7 +48,7 @@ pub async fn test_connection(
     adapter
         .test_connection()
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(|e| tls::explain_error(e, &server.tls).to_string())?;
 
     Ok(true)
 }
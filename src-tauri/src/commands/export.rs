@@ -0,0 +1,93 @@
+use tauri::ipc::JavaScriptChannelId;
+use tauri::{State, Webview};
+
+use crate::error::Result as AppResult;
+use crate::models::{ExportOptions, ExportSummary, TableDataRequest};
+use crate::services::{ExportFile, QueryTarget};
+use crate::state::AppState;
+
+use super::{connect_adapter, ensure_query_allowed, progress_fn};
+
+/// Finish the file once the stream is done, or remove the partial file when
+/// it failed.
+async fn finish_export(
+    file: ExportFile,
+    streamed: AppResult<u64>,
+) -> Result<ExportSummary, String> {
+    match streamed {
+        Ok(_) => file.finish().await.map_err(|e| e.to_string()),
+        Err(e) => {
+            file.discard().await;
+            Err(e.to_string())
+        }
+    }
+}
+
+/// Run `query` without a row cap and write every row to `path`;
+/// `cancel_query` with the same `query_id` stops it and removes the file.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn export_query(
+    webview: Webview,
+    state: State<'_, AppState>,
+    server_id: i64,
+    database: String,
+    query: String,
+    query_id: String,
+    path: String,
+    options: ExportOptions,
+    progress: Option<JavaScriptChannelId>,
+) -> Result<ExportSummary, String> {
+    let adapter = connect_adapter(&state, server_id, &database).await?;
+    // Export é leitura: uma escrita aqui nunca é confirmada.
+    ensure_query_allowed(&state, server_id, adapter.as_ref(), &query, None).await?;
+    let mut file = ExportFile::create(&path, options, "export", progress_fn(webview, progress))
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let streamed = state
+        .queries
+        .export_query(
+            QueryTarget {
+                server_id,
+                database: &database,
+            },
+            adapter,
+            &query,
+            &query_id,
+            &mut file,
+        )
+        .await;
+    finish_export(file, streamed).await
+}
+
+/// Write every row of a table browse (same filter and sort) to `path`,
+/// cancellable like [`export_query`].
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn export_table(
+    webview: Webview,
+    state: State<'_, AppState>,
+    server_id: i64,
+    database: String,
+    request: TableDataRequest,
+    query_id: String,
+    path: String,
+    options: ExportOptions,
+    progress: Option<JavaScriptChannelId>,
+) -> Result<ExportSummary, String> {
+    let adapter = connect_adapter(&state, server_id, &database).await?;
+    let table = match &request.schema {
+        Some(schema) => format!("{schema}.{}", request.table),
+        None => request.table.clone(),
+    };
+    let mut file = ExportFile::create(&path, options, &table, progress_fn(webview, progress))
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let streamed = state
+        .queries
+        .export_table(adapter, request, &query_id, &mut file)
+        .await;
+    finish_export(file, streamed).await
+}
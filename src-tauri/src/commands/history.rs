@@ -0,0 +1,41 @@
+use tauri::State;
+
+use crate::models::{HistoryFilter, HistoryPurge, QueryHistoryEntry};
+use crate::state::AppState;
+use crate::storage::repositories::query_history;
+
+#[tauri::command]
+pub fn list_query_history(
+    state: State<'_, AppState>,
+    filter: Option<HistoryFilter>,
+) -> Result<Vec<QueryHistoryEntry>, String> {
+    query_history::list(&state.storage, &filter.unwrap_or_default()).map_err(|e| e.to_string())
+}
+
+/// Full-text search over the query text; `filter` narrows it further.
+#[tauri::command]
+pub fn search_query_history(
+    state: State<'_, AppState>,
+    search: String,
+    filter: Option<HistoryFilter>,
+) -> Result<Vec<QueryHistoryEntry>, String> {
+    let filter = HistoryFilter {
+        search: Some(search),
+        ..filter.unwrap_or_default()
+    };
+    query_history::list(&state.storage, &filter).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn pin_query_history(state: State<'_, AppState>, id: i64, pinned: bool) -> Result<(), String> {
+    query_history::set_pinned(&state.storage, id, pinned).map_err(|e| e.to_string())
+}
+
+/// Returns how many entries were removed.
+#[tauri::command]
+pub fn purge_query_history(
+    state: State<'_, AppState>,
+    options: Option<HistoryPurge>,
+) -> Result<usize, String> {
+    query_history::purge(&state.storage, &options.unwrap_or_default()).map_err(|e| e.to_string())
+}
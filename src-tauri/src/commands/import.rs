@@ -0,0 +1,39 @@
+use tauri::ipc::JavaScriptChannelId;
+use tauri::{State, Webview};
+
+use crate::models::{ConnectionId, ImportOptions, ImportSummary, ImportTarget};
+use crate::services::ImportFile;
+use crate::state::AppState;
+
+use super::{connect_adapter, ensure_writable, progress_fn};
+
+/// Load a CSV/NDJSON file into a table, collection or Redis key group.
+///
+/// Rows skipped in skip mode come back in the summary; in stop mode the
+/// first bad row fails the command with its line number.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn import_file(
+    webview: Webview,
+    state: State<'_, AppState>,
+    server_id: i64,
+    database: String,
+    target: ImportTarget,
+    path: String,
+    options: ImportOptions,
+    progress: Option<JavaScriptChannelId>,
+    confirmed: Option<bool>,
+) -> Result<ImportSummary, String> {
+    ensure_writable(&state, server_id, confirmed)?;
+    let adapter = connect_adapter(&state, server_id, &database).await?;
+    let mut file = ImportFile::open(&path, options, progress_fn(webview, progress))
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let result = adapter.import_rows(&target, &mut file).await;
+    let connection = ConnectionId::new(server_id, &database);
+    state.structure.rows_written(&connection, adapter.as_ref());
+
+    result.map_err(|e| e.to_string())?;
+    Ok(file.finish())
+}
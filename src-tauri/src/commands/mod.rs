@@ -1,20 +1,36 @@
 mod browse;
+mod channels;
 mod servers;
 mod connections;
+mod export;
+mod history;
+mod import;
 mod queries;
+mod saved_queries;
+mod sessions;
+mod streams;
 mod structure;
 
 pub use browse::*;
+pub use channels::*;
 pub use servers::*;
 pub use connections::*;
+pub use export::*;
+pub use history::*;
+pub use import::*;
 pub use queries::*;
+pub use saved_queries::*;
+pub use sessions::*;
+pub use streams::*;
 pub use structure::*;
 
 use std::sync::Arc;
 
-use tauri::State;
+use tauri::ipc::{Channel, JavaScriptChannelId};
+use tauri::{State, Webview};
 
 use crate::adapters::DatabaseAdapter;
+use crate::services::{self, ProgressFn};
 use crate::state::AppState;
 use crate::storage::repositories::servers as server_store;
 
@@ -47,3 +63,47 @@ pub async fn connect_adapter(
         .await
         .map_err(|e| e.to_string())
 }
+
+/// Modo read-only / "confirmar escritas" do server, checado antes de qualquer
+/// escrita. O server é relido do storage a cada chamada (só metadados, sem
+/// decifrar a senha): mudar o modo vale já para a próxima escrita.
+pub fn ensure_writable(
+    state: &State<'_, AppState>,
+    server_id: i64,
+    confirmed: Option<bool>,
+) -> Result<(), String> {
+    let server =
+        server_store::get_by_id_meta(&state.storage, server_id).map_err(|e| e.to_string())?;
+    services::check_write(&server, confirmed.unwrap_or(false)).map_err(|e| e.to_string())
+}
+
+/// Como [`ensure_writable`], para texto livre: só passa pela política o que
+/// o adapter não reconhecer como leitura. Depois, statements perigosos
+/// (DELETE sem WHERE, DROP...) também exigem `confirmed`.
+pub async fn ensure_query_allowed(
+    state: &State<'_, AppState>,
+    server_id: i64,
+    adapter: &dyn DatabaseAdapter,
+    query: &str,
+    confirmed: Option<bool>,
+) -> Result<(), String> {
+    if adapter.is_read_only_query(query) {
+        return Ok(());
+    }
+    ensure_writable(state, server_id, confirmed)?;
+
+    let warnings = adapter.analyze_query(query).await.map_err(|e| e.to_string())?;
+    services::check_warnings(&warnings, confirmed.unwrap_or(false)).map_err(|e| e.to_string())
+}
+
+/// Liga o progresso de um export/import a um `Channel`, como o `ChannelSink`
+/// faz com os notices. Canal fechado não é erro: o arquivo continua andando.
+pub fn progress_fn<T>(webview: Webview, id: Option<JavaScriptChannelId>) -> Option<ProgressFn<T>>
+where
+    T: serde::Serialize + Send + 'static,
+{
+    let channel: Channel<T> = id?.channel_on(webview);
+    Some(Box::new(move |progress| {
+        let _ = channel.send(progress);
+    }))
+}
@@ -4,14 +4,17 @@ use std::sync::Arc;
 use tauri::ipc::{Channel, JavaScriptChannelId};
 use tauri::{State, Webview};
 
-use crate::adapters::MessageSink;
+use crate::adapters::{detect_params, MessageSink, ParamSyntax};
 use crate::models::{
-    EditableInfo, QueryMessage, QueryMessageKind, QueryOptions, QueryResult, RowEdit, RowInsert,
-    StatementResult,
+    AdapterCapabilities, ConnectionId, DatabaseType, EditableInfo, ExplainOptions, ExplainPlan,
+    QueryMessage, QueryMessageKind, QueryOptions, QueryParamInfo, QueryResult, QueryStreamEvent,
+    QueryStreamSummary, RowEdit, RowInsert, StatementResult, StatementWarning,
 };
+use crate::services::QueryTarget;
 use crate::state::AppState;
+use crate::storage::repositories::servers as server_store;
 
-use super::connect_adapter;
+use super::{connect_adapter, ensure_query_allowed, ensure_writable};
 
 /// Teto de notices encaminhados por execução.
 ///
@@ -73,7 +76,10 @@ impl MessageSink for ChannelSink {
     }
 }
 
+/// Writes are subject to the server's safety mode: refused when it is
+/// read-only, and with "confirm writes" only run with `confirmed: true`.
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn execute_query(
     webview: Webview,
     state: State<'_, AppState>,
@@ -84,33 +90,156 @@ pub async fn execute_query(
     // `Channel<T>` não implementa Deserialize, então o argumento opcional
     // trafega como o id do canal e vira Channel aqui.
     messages: Option<JavaScriptChannelId>,
+    confirmed: Option<bool>,
 ) -> Result<QueryResult, String> {
     let adapter = connect_adapter(&state, server_id, &database).await?;
+    ensure_query_allowed(&state, server_id, adapter.as_ref(), &query, confirmed).await?;
     let sink = messages.map(|id| {
         Arc::new(ChannelSink::new(id.channel_on(webview))) as Arc<dyn MessageSink>
     });
 
+    let result = state
+        .queries
+        .execute_query(
+            QueryTarget { server_id, database: &database },
+            Arc::clone(&adapter),
+            &query,
+            options.unwrap_or_default(),
+            sink,
+        )
+        .await;
+    // Mesmo com erro: um script pode ter rodado um CREATE antes de falhar.
+    let connection = ConnectionId::new(server_id, &database);
+    state.structure.query_ran(&connection, adapter.as_ref(), &query);
+
+    result.map_err(|e| e.to_string())
+}
+
+/// What in `query` would need `confirmed: true` to run (and what is only
+/// informative), without running it — for the confirmation dialog.
+#[tauri::command]
+pub async fn analyze_query(
+    state: State<'_, AppState>,
+    server_id: i64,
+    database: String,
+    query: String,
+) -> Result<Vec<StatementWarning>, String> {
+    let adapter = connect_adapter(&state, server_id, &database).await?;
+
+    adapter.analyze_query(&query).await.map_err(|e| e.to_string())
+}
+
+/// The placeholders in `query` (`:name`, `$1`) the UI should ask values for
+/// before running it with `options.params`. Empty for databases that don't
+/// bind parameters. Needs no connection: only the server's type is read.
+#[tauri::command]
+pub async fn detect_query_params(
+    state: State<'_, AppState>,
+    server_id: i64,
+    query: String,
+) -> Result<Vec<QueryParamInfo>, String> {
+    let server =
+        server_store::get_by_id_meta(&state.storage, server_id).map_err(|e| e.to_string())?;
+    let supported = AdapterCapabilities::for_db_type(server.db_type)
+        .is_some_and(|capabilities| capabilities.supports_params);
+    if !supported {
+        return Ok(Vec::new());
+    }
+
+    let syntax = match server.db_type {
+        DatabaseType::Mongodb => ParamSyntax::Mongo,
+        _ => ParamSyntax::Sql,
+    };
+    Ok(detect_params(syntax, &query))
+}
+
+/// The plan of `query` for the plan viewer (`options` defaults to a plain
+/// EXPLAIN, without running the query).
+#[tauri::command]
+pub async fn explain_query(
+    state: State<'_, AppState>,
+    server_id: i64,
+    database: String,
+    query: String,
+    options: Option<ExplainOptions>,
+) -> Result<ExplainPlan, String> {
+    let adapter = connect_adapter(&state, server_id, &database).await?;
+
+    adapter
+        .explain_query(&query, options.unwrap_or_default())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Run `query` without a row cap, sending the rows over `rows` in batches as
+/// the database produces them. The frontend acknowledges each batch with
+/// `ack_query_stream`; `cancel_query` with the same `query_id` stops it.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn stream_query(
+    webview: Webview,
+    state: State<'_, AppState>,
+    server_id: i64,
+    database: String,
+    query: String,
+    query_id: String,
+    rows: JavaScriptChannelId,
+    confirmed: Option<bool>,
+) -> Result<QueryStreamSummary, String> {
+    let adapter = connect_adapter(&state, server_id, &database).await?;
+    ensure_query_allowed(&state, server_id, adapter.as_ref(), &query, confirmed).await?;
+    let channel: Channel<QueryStreamEvent> = rows.channel_on(webview);
+
     state
         .queries
-        .execute_query(adapter, &query, options.unwrap_or_default(), sink)
+        .stream_to_frontend(
+            QueryTarget { server_id, database: &database },
+            adapter,
+            &query,
+            &query_id,
+            Box::new(move |event| {
+                // Canal fechado: sem ack, o stream expira sozinho.
+                let _ = channel.send(event);
+            }),
+        )
         .await
         .map_err(|e| e.to_string())
 }
 
+/// Free `batches` slots (default 1) in the window of stream `query_id`.
+#[tauri::command]
+pub fn ack_query_stream(
+    state: State<'_, AppState>,
+    query_id: String,
+    batches: Option<usize>,
+) -> Result<(), String> {
+    state.queries.ack_stream(&query_id, batches.unwrap_or(1));
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn execute_statement(
     state: State<'_, AppState>,
     server_id: i64,
     database: String,
     statement: String,
+    confirmed: Option<bool>,
 ) -> Result<StatementResult, String> {
     let adapter = connect_adapter(&state, server_id, &database).await?;
+    ensure_query_allowed(&state, server_id, adapter.as_ref(), &statement, confirmed).await?;
 
-    state
+    let result = state
         .queries
-        .execute_statement(adapter, &statement)
-        .await
-        .map_err(|e| e.to_string())
+        .execute_statement(
+            QueryTarget { server_id, database: &database },
+            Arc::clone(&adapter),
+            &statement,
+        )
+        .await;
+    let connection = ConnectionId::new(server_id, &database);
+    state.structure.query_ran(&connection, adapter.as_ref(), &statement);
+
+    result.map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -120,13 +249,16 @@ pub async fn apply_row_edits(
     database: String,
     editable: EditableInfo,
     edits: Vec<RowEdit>,
+    confirmed: Option<bool>,
 ) -> Result<StatementResult, String> {
+    ensure_writable(&state, server_id, confirmed)?;
     let adapter = connect_adapter(&state, server_id, &database).await?;
 
-    adapter
-        .apply_row_edits(&editable, edits)
-        .await
-        .map_err(|e| e.to_string())
+    let result = adapter.apply_row_edits(&editable, edits).await;
+    let connection = ConnectionId::new(server_id, &database);
+    state.structure.rows_written(&connection, adapter.as_ref());
+
+    result.map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -136,13 +268,16 @@ pub async fn insert_rows(
     database: String,
     editable: EditableInfo,
     rows: Vec<RowInsert>,
+    confirmed: Option<bool>,
 ) -> Result<StatementResult, String> {
+    ensure_writable(&state, server_id, confirmed)?;
     let adapter = connect_adapter(&state, server_id, &database).await?;
 
-    adapter
-        .insert_rows(&editable, rows)
-        .await
-        .map_err(|e| e.to_string())
+    let result = adapter.insert_rows(&editable, rows).await;
+    let connection = ConnectionId::new(server_id, &database);
+    state.structure.rows_written(&connection, adapter.as_ref());
+
+    result.map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -152,13 +287,16 @@ pub async fn delete_rows(
     database: String,
     editable: EditableInfo,
     pk_values: Vec<Vec<Option<String>>>,
+    confirmed: Option<bool>,
 ) -> Result<StatementResult, String> {
+    ensure_writable(&state, server_id, confirmed)?;
     let adapter = connect_adapter(&state, server_id, &database).await?;
 
-    adapter
-        .delete_rows(&editable, pk_values)
-        .await
-        .map_err(|e| e.to_string())
+    let result = adapter.delete_rows(&editable, pk_values).await;
+    let connection = ConnectionId::new(server_id, &database);
+    state.structure.rows_written(&connection, adapter.as_ref());
+
+    result.map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -167,14 +305,30 @@ pub async fn execute_transaction(
     server_id: i64,
     database: String,
     statements: Vec<String>,
+    confirmed: Option<bool>,
 ) -> Result<Vec<StatementResult>, String> {
     let adapter = connect_adapter(&state, server_id, &database).await?;
+    for statement in &statements {
+        ensure_query_allowed(&state, server_id, adapter.as_ref(), statement, confirmed).await?;
+    }
 
-    state
+    let result = state
         .queries
-        .execute_transaction(adapter, statements)
-        .await
-        .map_err(|e| e.to_string())
+        .execute_transaction(
+            QueryTarget { server_id, database: &database },
+            Arc::clone(&adapter),
+            statements.clone(),
+        )
+        .await;
+    // Uma transação que falhou fez rollback: não mudou nada.
+    if result.is_ok() {
+        let connection = ConnectionId::new(server_id, &database);
+        for statement in &statements {
+            state.structure.query_ran(&connection, adapter.as_ref(), statement);
+        }
+    }
+
+    result.map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -184,14 +338,18 @@ pub async fn cancel_query(
     database: String,
     query_id: String,
 ) -> Result<(), String> {
+    // A stream stops at its next batch even while waiting for an ack, and on
+    // every database (Mongo/Redis have no server-side cancel).
+    let streaming = state.queries.cancel_stream(&query_id);
+
     // Cancellation only makes sense against the connection running the query.
     // If it isn't open, there is nothing to cancel — don't create a pool.
     let Some(adapter) = state.connections.get_cached(server_id, &database) else {
         return Ok(());
     };
 
-    adapter
-        .cancel_query(&query_id)
-        .await
-        .map_err(|e| e.to_string())
+    match adapter.cancel_query(&query_id).await {
+        Err(_) if streaming => Ok(()),
+        result => result.map_err(|e| e.to_string()),
+    }
 }
@@ -0,0 +1,66 @@
+use tauri::State;
+
+use crate::models::{SavedQuery, SavedQueryFilter, SavedQueryImportSummary, SavedQueryInput};
+use crate::state::AppState;
+use crate::storage::repositories::saved_queries;
+
+#[tauri::command]
+pub fn list_saved_queries(
+    state: State<'_, AppState>,
+    filter: Option<SavedQueryFilter>,
+) -> Result<Vec<SavedQuery>, String> {
+    saved_queries::list(&state.storage, &filter.unwrap_or_default()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_saved_query(state: State<'_, AppState>, id: i64) -> Result<SavedQuery, String> {
+    saved_queries::get_by_id(&state.storage, id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn create_saved_query(
+    state: State<'_, AppState>,
+    input: SavedQueryInput,
+) -> Result<SavedQuery, String> {
+    saved_queries::create(&state.storage, input).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn update_saved_query(
+    state: State<'_, AppState>,
+    id: i64,
+    input: SavedQueryInput,
+) -> Result<SavedQuery, String> {
+    saved_queries::update(&state.storage, id, input).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn delete_saved_query(state: State<'_, AppState>, id: i64) -> Result<(), String> {
+    saved_queries::delete(&state.storage, id).map_err(|e| e.to_string())
+}
+
+/// Writes the library (or only `ids`) as pretty JSON to `path`, so it diffs
+/// well in a repo. Returns how many snippets were written.
+#[tauri::command]
+pub fn export_saved_queries(
+    state: State<'_, AppState>,
+    path: String,
+    ids: Option<Vec<i64>>,
+) -> Result<usize, String> {
+    let library =
+        saved_queries::export(&state.storage, ids.as_deref()).map_err(|e| e.to_string())?;
+    let json = serde_json::to_string_pretty(&library).map_err(|e| e.to_string())?;
+    std::fs::write(&path, json + "\n").map_err(|e| format!("Failed to write {path}: {e}"))?;
+    Ok(library.queries.len())
+}
+
+#[tauri::command]
+pub fn import_saved_queries(
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<SavedQueryImportSummary, String> {
+    let json = std::fs::read_to_string(&path).map_err(|e| format!("Failed to read {path}: {e}"))?;
+    let library =
+        serde_json::from_str(&json).map_err(|e| format!("Invalid saved query library: {e}"))?;
+    saved_queries::import(&state.storage, library).map_err(|e| e.to_string())
+}
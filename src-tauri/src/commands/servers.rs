@@ -25,8 +25,10 @@ pub fn update_server(
     id: i64,
     input: ServerInput,
 ) -> Result<Server, String> {
-    // Disconnect existing connections when server config changes
+    // Disconnect existing connections when server config changes; the
+    // cached structure may be of another host now.
     state.connections.disconnect_server(id);
+    state.structure.forget_server(id);
 
     servers::update(&state.storage, id, input).map_err(|e| e.to_string())
 }
@@ -35,6 +37,7 @@ pub fn update_server(
 pub fn delete_server(state: State<'_, AppState>, id: i64) -> Result<(), String> {
     // Disconnect all connections for this server first
     state.connections.disconnect_server(id);
+    state.structure.forget_server(id);
 
     servers::delete(&state.storage, id).map_err(|e| e.to_string())
 }
\ No newline at end of file
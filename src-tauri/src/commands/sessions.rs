@@ -0,0 +1,94 @@
+use std::sync::Arc;
+
+use tauri::State;
+
+use crate::adapters::DatabaseAdapter;
+use crate::models::{ConnectionId, TransactionState};
+use crate::state::AppState;
+
+use super::connect_adapter;
+
+/// A session lives on an open connection: without it there is nothing to
+/// look up (and reconnecting would not bring the session back).
+fn session_adapter(
+    state: &State<'_, AppState>,
+    server_id: i64,
+    database: &str,
+    session_id: &str,
+) -> Result<Arc<dyn DatabaseAdapter>, String> {
+    state
+        .connections
+        .get_cached(server_id, database)
+        .ok_or_else(|| format!("Not found: Session {session_id} (the connection was closed)"))
+}
+
+/// Pin a pooled connection to `session_id`; pass the id as
+/// `options.sessionId` to `execute_query` to run on it.
+#[tauri::command]
+pub async fn open_session(
+    state: State<'_, AppState>,
+    server_id: i64,
+    database: String,
+    session_id: String,
+) -> Result<TransactionState, String> {
+    let adapter = connect_adapter(&state, server_id, &database).await?;
+
+    adapter
+        .open_session(&session_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Whether the session is idle, inside a transaction or in a failed one.
+#[tauri::command]
+pub async fn get_session_state(
+    state: State<'_, AppState>,
+    server_id: i64,
+    database: String,
+    session_id: String,
+) -> Result<TransactionState, String> {
+    let adapter = session_adapter(&state, server_id, &database, &session_id)?;
+
+    adapter
+        .session_state(&session_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// COMMIT the open transaction (if any) and release the connection.
+#[tauri::command]
+pub async fn commit_session(
+    state: State<'_, AppState>,
+    server_id: i64,
+    database: String,
+    session_id: String,
+) -> Result<(), String> {
+    let adapter = session_adapter(&state, server_id, &database, &session_id)?;
+
+    let result = adapter.end_session(&session_id, true).await;
+    // O DDL que a transação rodou passou a valer.
+    state
+        .structure
+        .refresh(&ConnectionId::new(server_id, &database), None, None);
+
+    result.map_err(|e| e.to_string())
+}
+
+/// ROLLBACK the open transaction (if any) and release the connection.
+#[tauri::command]
+pub async fn rollback_session(
+    state: State<'_, AppState>,
+    server_id: i64,
+    database: String,
+    session_id: String,
+) -> Result<(), String> {
+    let adapter = session_adapter(&state, server_id, &database, &session_id)?;
+
+    let result = adapter.end_session(&session_id, false).await;
+    // O rollback desfaz o DDL que a transação tinha rodado.
+    state
+        .structure
+        .refresh(&ConnectionId::new(server_id, &database), None, None);
+
+    result.map_err(|e| e.to_string())
+}
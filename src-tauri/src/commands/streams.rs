@@ -0,0 +1,101 @@
+use tauri::State;
+
+use crate::models::{
+    StreamConsumerInfo, StreamEntriesAction, StreamGroupInfo, StreamInfo, StreamPage,
+    StreamPendingEntry, StreamPendingRequest, StreamRangeRequest,
+};
+use crate::state::AppState;
+
+use super::{connect_adapter, ensure_writable};
+
+#[tauri::command]
+pub async fn fetch_stream_entries(
+    state: State<'_, AppState>,
+    server_id: i64,
+    database: String,
+    request: StreamRangeRequest,
+) -> Result<StreamPage, String> {
+    let adapter = connect_adapter(&state, server_id, &database).await?;
+
+    adapter
+        .fetch_stream_entries(&request)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_stream_info(
+    state: State<'_, AppState>,
+    server_id: i64,
+    database: String,
+    key: String,
+) -> Result<StreamInfo, String> {
+    let adapter = connect_adapter(&state, server_id, &database).await?;
+
+    adapter.stream_info(&key).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn list_stream_groups(
+    state: State<'_, AppState>,
+    server_id: i64,
+    database: String,
+    key: String,
+) -> Result<Vec<StreamGroupInfo>, String> {
+    let adapter = connect_adapter(&state, server_id, &database).await?;
+
+    adapter
+        .list_stream_groups(&key)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn list_stream_consumers(
+    state: State<'_, AppState>,
+    server_id: i64,
+    database: String,
+    key: String,
+    group: String,
+) -> Result<Vec<StreamConsumerInfo>, String> {
+    let adapter = connect_adapter(&state, server_id, &database).await?;
+
+    adapter
+        .list_stream_consumers(&key, &group)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn list_stream_pending(
+    state: State<'_, AppState>,
+    server_id: i64,
+    database: String,
+    request: StreamPendingRequest,
+) -> Result<Vec<StreamPendingEntry>, String> {
+    let adapter = connect_adapter(&state, server_id, &database).await?;
+
+    adapter
+        .list_stream_pending(&request)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// `XACK`, `XCLAIM` or `XDEL` the selected entries. All of them change the
+/// stream or its groups, so they follow the server's write policy.
+#[tauri::command]
+pub async fn stream_action(
+    state: State<'_, AppState>,
+    server_id: i64,
+    database: String,
+    action: StreamEntriesAction,
+    confirmed: Option<bool>,
+) -> Result<u64, String> {
+    ensure_writable(&state, server_id, confirmed)?;
+    let adapter = connect_adapter(&state, server_id, &database).await?;
+
+    adapter
+        .stream_entries_action(&action)
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -1,6 +1,11 @@
 use tauri::State;
 
-use crate::models::{ColumnInfo, DatabaseInfo, DatabaseStructure, IndexInfo, SchemaInfo, TableInfo};
+use crate::models::{
+    ColumnInfo, ConnectionId, ConstraintInfo, DatabaseInfo, DatabaseStructure, DdlRequest,
+    ForeignKeyInfo, IndexInfo, ObjectMatch, ObjectSearchRequest, SchemaDiff, SchemaInfo,
+    SchemaTarget, TableInfo,
+};
+use crate::services::diff_schemas;
 use crate::state::AppState;
 use crate::storage::repositories::servers;
 
@@ -22,7 +27,7 @@ pub async fn list_databases(
 
     state
         .structure
-        .list_databases(adapter)
+        .list_databases(ConnectionId::new(server_id, db), adapter)
         .await
         .map_err(|e| e.to_string())
 }
@@ -37,7 +42,7 @@ pub async fn list_schemas(
 
     state
         .structure
-        .list_schemas(adapter)
+        .list_schemas(ConnectionId::new(server_id, &database), adapter)
         .await
         .map_err(|e| e.to_string())
 }
@@ -53,7 +58,7 @@ pub async fn list_tables(
 
     state
         .structure
-        .list_tables(adapter, &schema)
+        .list_tables(ConnectionId::new(server_id, &database), adapter, &schema)
         .await
         .map_err(|e| e.to_string())
 }
@@ -70,7 +75,7 @@ pub async fn list_columns(
 
     state
         .structure
-        .list_columns(adapter, &schema, &table)
+        .list_columns(ConnectionId::new(server_id, &database), adapter, &schema, &table)
         .await
         .map_err(|e| e.to_string())
 }
@@ -87,7 +92,59 @@ pub async fn list_indexes(
 
     state
         .structure
-        .list_indexes(adapter, &schema, &table)
+        .list_indexes(ConnectionId::new(server_id, &database), adapter, &schema, &table)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn list_foreign_keys(
+    state: State<'_, AppState>,
+    server_id: i64,
+    database: String,
+    schema: String,
+    table: String,
+) -> Result<Vec<ForeignKeyInfo>, String> {
+    let adapter = connect_adapter(&state, server_id, &database).await?;
+
+    state
+        .structure
+        .list_foreign_keys(ConnectionId::new(server_id, &database), adapter, &schema, &table)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn list_constraints(
+    state: State<'_, AppState>,
+    server_id: i64,
+    database: String,
+    schema: String,
+    table: String,
+) -> Result<Vec<ConstraintInfo>, String> {
+    let adapter = connect_adapter(&state, server_id, &database).await?;
+
+    state
+        .structure
+        .list_constraints(ConnectionId::new(server_id, &database), adapter, &schema, &table)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Foreign keys elsewhere that point at `schema.table` ("referenced by").
+#[tauri::command]
+pub async fn list_referencing_tables(
+    state: State<'_, AppState>,
+    server_id: i64,
+    database: String,
+    schema: String,
+    table: String,
+) -> Result<Vec<ForeignKeyInfo>, String> {
+    let adapter = connect_adapter(&state, server_id, &database).await?;
+
+    state
+        .structure
+        .list_referencing_tables(ConnectionId::new(server_id, &database), adapter, &schema, &table)
         .await
         .map_err(|e| e.to_string())
 }
@@ -100,8 +157,90 @@ pub async fn list_schemas_with_tables(
 ) -> Result<DatabaseStructure, String> {
     let adapter = connect_adapter(&state, server_id, &database).await?;
 
+    state
+        .structure
+        .list_schemas_with_tables(ConnectionId::new(server_id, &database), adapter)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Fuzzy search over the object names of a database (tables, views,
+/// columns, functions, indexes, collections, key groups), best match first.
+#[tauri::command]
+pub async fn search_objects(
+    state: State<'_, AppState>,
+    server_id: i64,
+    database: String,
+    request: ObjectSearchRequest,
+) -> Result<Vec<ObjectMatch>, String> {
+    if request.query.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    let adapter = connect_adapter(&state, server_id, &database).await?;
+
+    state
+        .structure
+        .search_objects(ConnectionId::new(server_id, &database), adapter, &request)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// `CREATE` script of one object, rebuilt from the catalog on every call
+/// (never cached: it's what the user is about to copy elsewhere).
+#[tauri::command]
+pub async fn get_object_ddl(
+    state: State<'_, AppState>,
+    server_id: i64,
+    database: String,
+    request: DdlRequest,
+) -> Result<String, String> {
+    let adapter = connect_adapter(&state, server_id, &database).await?;
     adapter
-        .list_schemas_with_tables()
+        .get_object_ddl(&request)
         .await
         .map_err(|e| e.to_string())
+}
+
+/// What differs between two schemas (each on its own server/database) and
+/// the script that brings `target` in line with `source`. Only reads: the
+/// migration is for the user to review and run.
+#[tauri::command]
+pub async fn compare_schemas(
+    state: State<'_, AppState>,
+    source: SchemaTarget,
+    target: SchemaTarget,
+) -> Result<SchemaDiff, String> {
+    let source_adapter = connect_adapter(&state, source.server_id, &source.database).await?;
+    let target_adapter = connect_adapter(&state, target.server_id, &target.database).await?;
+    let source_schema = source.schema.as_deref().unwrap_or("public");
+    let target_schema = target.schema.as_deref().unwrap_or("public");
+
+    let (from, to) = tokio::try_join!(
+        source_adapter.schema_snapshot(source_schema),
+        target_adapter.schema_snapshot(target_schema),
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(diff_schemas(&from, &to, target_schema))
+}
+
+/// Drop cached structure so the next listing goes to the database: one
+/// table, one schema, the whole database (neither) or, without `database`,
+/// everything of the server. Needs no connection.
+#[tauri::command]
+pub async fn refresh_structure(
+    state: State<'_, AppState>,
+    server_id: i64,
+    database: Option<String>,
+    schema: Option<String>,
+    table: Option<String>,
+) -> Result<(), String> {
+    match database {
+        Some(database) => state.structure.refresh(
+            &ConnectionId::new(server_id, database),
+            schema.as_deref(),
+            table.as_deref(),
+        ),
+        None => state.structure.forget_server(server_id),
+    }
+    Ok(())
 }
\ No newline at end of file
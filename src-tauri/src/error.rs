@@ -22,6 +22,10 @@ pub enum Error {
     // State errors
     InvalidState(String),
 
+    // Safety errors (per-server write policy)
+    ReadOnly(String),
+    ConfirmationRequired(String),
+
     // Adapter errors
     UnsupportedDatabase(String),
     UnsupportedType(String),
@@ -39,6 +43,8 @@ impl fmt::Display for Error {
             Self::NotFound(msg) => write!(f, "Not found: {msg}"),
             Self::AlreadyExists(msg) => write!(f, "Already exists: {msg}"),
             Self::InvalidState(msg) => write!(f, "Invalid state: {msg}"),
+            Self::ReadOnly(msg) => write!(f, "Read-only: {msg}"),
+            Self::ConfirmationRequired(msg) => write!(f, "Confirmation required: {msg}"),
             Self::UnsupportedDatabase(msg) => write!(f, "Unsupported database: {msg}"),
             Self::UnsupportedType(msg) => write!(f, "Unsupported type: {msg}"),
         }
@@ -88,6 +94,20 @@ impl From<redis::RedisError> for Error {
     }
 }
 
+impl From<mysql_async::Error> for Error {
+    fn from(e: mysql_async::Error) -> Self {
+        match e {
+            // Same as Postgres: the toast shows the server message, not the
+            // driver's "Server error: `ERROR 1146 (42S02): ...'" wrapper.
+            mysql_async::Error::Server(e) => Error::Query(e.message),
+            mysql_async::Error::Io(_) | mysql_async::Error::Url(_) => {
+                Error::Connection(e.to_string())
+            }
+            _ => Error::Query(e.to_string()),
+        }
+    }
+}
+
 impl From<deadpool_postgres::PoolError> for Error {
     fn from(e: deadpool_postgres::PoolError) -> Self {
         match e {
@@ -124,6 +144,8 @@ impl Error {
             Self::NotFound(_) => "NOT_FOUND",
             Self::AlreadyExists(_) => "ALREADY_EXISTS",
             Self::InvalidState(_) => "INVALID_STATE",
+            Self::ReadOnly(_) => "READ_ONLY",
+            Self::ConfirmationRequired(_) => "CONFIRMATION_REQUIRED",
             Self::UnsupportedDatabase(_) => "UNSUPPORTED_DATABASE",
             Self::UnsupportedType(_) => "UNSUPPORTED_TYPE",
         }
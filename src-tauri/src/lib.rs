@@ -51,8 +51,49 @@ pub fn run() {
             commands::delete_rows,
             commands::execute_transaction,
             commands::cancel_query,
+            commands::stream_query,
+            commands::ack_query_stream,
+            commands::analyze_query,
+            commands::detect_query_params,
+            commands::explain_query,
+            // Sessions (interactive transactions)
+            commands::open_session,
+            commands::get_session_state,
+            commands::commit_session,
+            commands::rollback_session,
+            // Channels (LISTEN/NOTIFY, pub/sub)
+            commands::subscribe_channel,
+            commands::unsubscribe_channel,
+            commands::notify_channel,
+            // Redis Streams
+            commands::fetch_stream_entries,
+            commands::get_stream_info,
+            commands::list_stream_groups,
+            commands::list_stream_consumers,
+            commands::list_stream_pending,
+            commands::stream_action,
+            // Export/import (streamed to/from a file)
+            commands::export_query,
+            commands::export_table,
+            commands::import_file,
+            // Query history
+            commands::list_query_history,
+            commands::search_query_history,
+            commands::pin_query_history,
+            commands::purge_query_history,
+            // Saved queries (snippet library)
+            commands::list_saved_queries,
+            commands::get_saved_query,
+            commands::create_saved_query,
+            commands::update_saved_query,
+            commands::delete_saved_query,
+            commands::export_saved_queries,
+            commands::import_saved_queries,
             // Browse (server-side pagination/sort/filter)
             commands::fetch_table_data,
+            commands::fetch_key_entries,
+            commands::search_values,
+            commands::cancel_value_search,
             commands::get_capabilities,
             // Structure (lazy loading)
             commands::list_databases,
@@ -60,7 +101,14 @@ pub fn run() {
             commands::list_tables,
             commands::list_columns,
             commands::list_indexes,
+            commands::list_foreign_keys,
+            commands::list_constraints,
+            commands::list_referencing_tables,
             commands::list_schemas_with_tables,
+            commands::refresh_structure,
+            commands::search_objects,
+            commands::get_object_ddl,
+            commands::compare_schemas,
         ])
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_process::init());
@@ -0,0 +1,47 @@
+use serde::Serialize;
+
+/// How much a warning should stop the user. `warning` and `danger` need
+/// `confirmed: true` to run; `info` is only shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WarningSeverity {
+    Info,
+    Warning,
+    Danger,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WarningKind {
+    /// SQL `UPDATE` without `WHERE`; Mongo `updateMany({})`.
+    UpdateWithoutWhere,
+    /// SQL `DELETE` without `WHERE`; Mongo `deleteMany({})`.
+    DeleteWithoutWhere,
+    /// `DROP` of any object; Mongo `drop()`.
+    Drop,
+    Truncate,
+    /// `ALTER TABLE` on a table estimated above a million rows (may rewrite
+    /// or lock it for a long time).
+    AlterLargeTable,
+    /// More than one statement in the query.
+    MultiStatement,
+}
+
+/// Something in a query worth a second look before it runs.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatementWarning {
+    pub kind: WarningKind,
+    pub severity: WarningSeverity,
+    /// Position of the statement in the script, from 0.
+    pub statement_index: usize,
+    /// Table/collection the statement acts on, when there is one.
+    pub object: Option<String>,
+    pub message: String,
+}
+
+impl StatementWarning {
+    pub fn needs_confirmation(&self) -> bool {
+        self.severity >= WarningSeverity::Warning
+    }
+}
@@ -1,4 +1,6 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+
+use super::QueryResult;
 
 /// Request to browse a table's data server-side (pagination, sorting and
 /// filtering happen in the adapter, not in a frontend-built query).
@@ -44,3 +46,34 @@ pub enum SortDirection {
     Asc,
     Desc,
 }
+
+/// Request to browse the elements of a single Redis key (hash, list, set or
+/// sorted set) as a table, one page at a time.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyEntriesRequest {
+    pub key: String,
+    /// Glob (`*`, `?`, `[...]`) on the hash field, list element or member.
+    #[serde(default)]
+    pub pattern: Option<String>,
+    /// Sorted sets are ordered by score; lists by index, hashes and sets in
+    /// scan order, whatever this says.
+    #[serde(default)]
+    pub direction: SortDirection,
+    /// `next_cursor` of the previous page.
+    #[serde(default)]
+    pub cursor: Option<String>,
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyEntriesPage {
+    /// Redis type of the key (`hash`, `list`, `set`, `zset`).
+    pub type_name: String,
+    /// `total_count` is the size of the whole key, not of what matched.
+    pub result: QueryResult,
+    /// `None` on the last page.
+    pub next_cursor: Option<String>,
+}
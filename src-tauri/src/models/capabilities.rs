@@ -7,11 +7,11 @@ use serde::Serialize;
 #[serde(rename_all = "camelCase")]
 pub struct AdapterCapabilities {
     /// Whether the database exposes a schema level between database and table
-    /// (Postgres: yes; MongoDB/Redis: no).
+    /// (Postgres: yes; MongoDB/Redis/SQLite/MySQL: no).
     pub has_schemas: bool,
 
     /// Whether rows/documents have a stable primary key usable for editing
-    /// (Postgres: per-table PK; MongoDB: `_id`; Redis: no).
+    /// (Postgres: per-table PK; MongoDB: `_id`; Redis: the key).
     pub has_primary_keys: bool,
 
     /// Whether the free-form editor accepts SQL (Postgres) or native commands
@@ -26,6 +26,17 @@ pub struct AdapterCapabilities {
 
     /// Whether `fetch_table_data` (paginated/sorted/filtered browse) is available.
     pub browsable: bool,
+
+    /// Whether `explain_query` returns a plan tree (Postgres, MongoDB).
+    pub supports_explain: bool,
+
+    /// Whether `open_session` can pin a connection for interactive
+    /// transactions (Postgres).
+    pub supports_sessions: bool,
+
+    /// Whether `QueryOptions::params` binds `:name` / `$1` placeholders
+    /// (Postgres, MongoDB).
+    pub supports_params: bool,
 }
 
 impl AdapterCapabilities {
@@ -37,6 +48,9 @@ impl AdapterCapabilities {
             supports_transactions: true,
             supports_indexes: true,
             browsable: true,
+            supports_explain: true,
+            supports_sessions: true,
+            supports_params: true,
         }
     }
 
@@ -48,17 +62,51 @@ impl AdapterCapabilities {
             supports_transactions: false,
             supports_indexes: true,
             browsable: true,
+            supports_explain: true,
+            supports_sessions: false,
+            supports_params: true,
         }
     }
 
     pub const fn redis() -> Self {
         Self {
             has_schemas: false,
-            has_primary_keys: false,
+            has_primary_keys: true, // the key
             supports_sql: false,
             supports_transactions: false,
             supports_indexes: false,
             browsable: true,
+            supports_explain: false,
+            supports_sessions: false,
+            supports_params: false,
+        }
+    }
+
+    pub const fn sqlite() -> Self {
+        Self {
+            has_schemas: false,
+            has_primary_keys: true, // declared PK, else rowid
+            supports_sql: true,
+            supports_transactions: true,
+            supports_indexes: true,
+            browsable: true,
+            supports_explain: false,
+            supports_sessions: false,
+            supports_params: false,
+        }
+    }
+
+    pub const fn mysql() -> Self {
+        Self {
+            has_schemas: false, // the database is the schema
+            has_primary_keys: true,
+            supports_sql: true,
+            supports_transactions: true,
+            supports_indexes: true,
+            browsable: true,
+            supports_explain: false,
+            supports_sessions: false,
+            supports_params: false,
         }
     }
 
@@ -69,7 +117,8 @@ impl AdapterCapabilities {
             super::DatabaseType::Postgres => Some(Self::postgres()),
             super::DatabaseType::Mongodb => Some(Self::mongodb()),
             super::DatabaseType::Redis => Some(Self::redis()),
-            super::DatabaseType::Mysql | super::DatabaseType::Sqlite => None,
+            super::DatabaseType::Sqlite => Some(Self::sqlite()),
+            super::DatabaseType::Mysql => Some(Self::mysql()),
         }
     }
 }
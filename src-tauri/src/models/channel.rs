@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+
+/// What `subscribe_channel` listens on.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelSubscription {
+    /// Channel name; a glob pattern with `pattern`, or the key pattern
+    /// (default `*`) with `keyspace`.
+    #[serde(default)]
+    pub channel: String,
+    /// Redis: `PSUBSCRIBE` instead of `SUBSCRIBE`.
+    #[serde(default)]
+    pub pattern: bool,
+    /// Redis: watch the keyspace notifications (`__keyspace@<db>__:<key>`)
+    /// of the connection's database.
+    #[serde(default)]
+    pub keyspace: bool,
+    /// Redis, with `keyspace`: turn the notifications on in the server
+    /// (`CONFIG SET notify-keyspace-events`) if they are off. A server
+    /// change, so it follows the write policy.
+    #[serde(default)]
+    pub enable_keyspace_events: bool,
+}
+
+/// A message received on a subscribed channel (Postgres `NOTIFY`, Redis
+/// pub/sub).
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelNotification {
+    /// Redis: the pattern that matched, for `PSUBSCRIBE`/keyspace.
+    pub pattern: Option<String>,
+    pub channel: String,
+    pub payload: String,
+    /// Backend PID of the session that sent it (Postgres).
+    pub sender_pid: Option<i32>,
+    /// When the app received it (epoch ms).
+    pub timestamp_ms: u64,
+}
+
+/// Events of a subscription, as streamed to the frontend.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum ChannelEvent {
+    Message(ChannelNotification),
+    /// Last event: unsubscribed, the connection dropped or, with
+    /// `truncated`, the message cap was reached (and the subscription
+    /// ended with it).
+    Closed {
+        truncated: bool,
+    },
+}
@@ -0,0 +1,30 @@
+use serde::Deserialize;
+
+/// Objects `get_object_ddl` can script.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DdlObjectKind {
+    Table,
+    View,
+    MaterializedView,
+    Index,
+    Function,
+    Sequence,
+    Trigger,
+    Type,
+    Collection,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DdlRequest {
+    pub kind: DdlObjectKind,
+    /// Default `public` (Postgres); ignored by Mongo.
+    pub schema: Option<String>,
+    pub name: String,
+    /// Table of a trigger: trigger names are only unique per table.
+    pub table: Option<String>,
+    /// Identity arguments of a function (`integer, text`, the `detail` of
+    /// `search_objects`), to pick one overload; without it, all of them.
+    pub arguments: Option<String>,
+}
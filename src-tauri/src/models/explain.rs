@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+
+/// What `explain_query` asks the database for. Without `analyze` the query
+/// is only planned, never run.
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExplainOptions {
+    /// Run the query to get actual rows and timings (Postgres `ANALYZE`,
+    /// Mongo `executionStats`). Postgres runs it in a transaction that is
+    /// always rolled back, so a write statement changes nothing.
+    #[serde(default)]
+    pub analyze: bool,
+    /// Postgres only: shared/local/temp block counts per node.
+    #[serde(default)]
+    pub buffers: bool,
+    /// Postgres only: output columns and schema-qualified names.
+    #[serde(default)]
+    pub verbose: bool,
+}
+
+/// An execution plan as a tree, the same shape for every database.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExplainPlan {
+    pub root: PlanNode,
+    pub planning_time_ms: Option<f64>,
+    /// Only with `analyze`.
+    pub execution_time_ms: Option<f64>,
+    /// The plan exactly as the database returned it (JSON), for a raw view or
+    /// to paste somewhere else.
+    pub raw: String,
+}
+
+/// One step of the plan. Fields a database doesn't report are `None`/empty.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanNode {
+    /// `Seq Scan`, `Hash Join`... (Postgres) or the stage, `IXSCAN`,
+    /// `$group`... (Mongo).
+    pub node_type: String,
+    /// Table / collection read by this node.
+    pub relation: Option<String>,
+    pub schema: Option<String>,
+    pub alias: Option<String>,
+    pub index_name: Option<String>,
+    pub join_type: Option<String>,
+    pub startup_cost: Option<f64>,
+    pub total_cost: Option<f64>,
+    /// Planner estimate, per loop.
+    pub estimated_rows: Option<f64>,
+    /// Measured rows, per loop (Postgres) or in total (Mongo `nReturned`).
+    pub actual_rows: Option<f64>,
+    pub loops: Option<f64>,
+    pub actual_startup_time_ms: Option<f64>,
+    /// Per loop on Postgres; Mongo's `executionTimeMillisEstimate`.
+    pub actual_total_time_ms: Option<f64>,
+    pub buffers: Option<PlanBuffers>,
+    /// Filters and conditions, labelled as the database names them
+    /// (`Filter`, `Index Cond`, `Hash Cond`, `filter`, `indexBounds`...).
+    pub conditions: Vec<PlanCondition>,
+    pub rows_removed_by_filter: Option<f64>,
+    /// Mongo only: index keys and documents the stage looked at.
+    pub keys_examined: Option<f64>,
+    pub docs_examined: Option<f64>,
+    /// Output columns (Postgres `VERBOSE`).
+    pub output: Vec<String>,
+    pub children: Vec<PlanNode>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanCondition {
+    pub kind: String,
+    pub expression: String,
+}
+
+/// Block counts of a Postgres node (`BUFFERS`), in 8 kB pages.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanBuffers {
+    pub shared_hit: u64,
+    pub shared_read: u64,
+    pub shared_dirtied: u64,
+    pub shared_written: u64,
+    pub local_hit: u64,
+    pub local_read: u64,
+    pub temp_read: u64,
+    pub temp_written: u64,
+}
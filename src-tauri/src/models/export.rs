@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+
+/// File format written by `export_query` / `export_table`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Csv,
+    /// A single JSON array of objects keyed by column name.
+    Json,
+    /// One JSON object per line.
+    Ndjson,
+    /// PostgreSQL `INSERT` statements, one per row.
+    Sql,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportOptions {
+    pub format: ExportFormat,
+    #[serde(default)]
+    pub csv: CsvOptions,
+    /// Target table of the `INSERT` statements. Defaults to the exported
+    /// table (`export_table`) or `export` (`export_query`).
+    pub table_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CsvOptions {
+    pub delimiter: char,
+    pub quote: char,
+    /// Write the column names as the first line.
+    pub header: bool,
+    /// Quote every field; otherwise only fields that need it.
+    pub quote_all: bool,
+    /// Text written for NULL (empty by default, like `COPY ... CSV`).
+    pub null_value: String,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: ',',
+            quote: '"',
+            header: true,
+            quote_all: false,
+            null_value: String::new(),
+        }
+    }
+}
+
+/// Sent over the progress channel after every batch written, and once more
+/// with `done: true` when the file is complete.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportProgress {
+    pub rows_written: u64,
+    pub bytes_written: u64,
+    pub done: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportSummary {
+    pub path: String,
+    pub rows_written: u64,
+    pub bytes_written: u64,
+    pub execution_time_ms: u64,
+}
@@ -0,0 +1,87 @@
+use serde::{Deserialize, Serialize};
+
+/// Which command produced a history entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HistoryKind {
+    Query,
+    Statement,
+    Transaction,
+}
+
+/// One execution recorded in `query_history`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryHistoryEntry {
+    pub id: i64,
+    pub server_id: i64,
+    pub database: String,
+    pub kind: HistoryKind,
+    /// Statements of a transaction are joined with `;\n`.
+    pub query: String,
+    /// Epoch in milliseconds.
+    pub started_at: i64,
+    pub duration_ms: i64,
+    /// Rows returned (queries only).
+    pub row_count: Option<i64>,
+    /// Rows affected (statements and transactions only).
+    pub affected_rows: Option<i64>,
+    /// `Error::code()` of a failed execution, e.g. `QUERY_ERROR`.
+    pub error_code: Option<String>,
+    pub error_message: Option<String>,
+    /// Pinned entries are never removed by retention.
+    pub pinned: bool,
+}
+
+/// What gets written for an execution; the store fills `id` and `pinned`.
+#[derive(Debug, Clone)]
+pub struct NewHistoryEntry {
+    pub server_id: i64,
+    pub database: String,
+    pub kind: HistoryKind,
+    pub query: String,
+    pub started_at: i64,
+    pub duration_ms: i64,
+    pub row_count: Option<i64>,
+    pub affected_rows: Option<i64>,
+    pub error_code: Option<String>,
+    pub error_message: Option<String>,
+}
+
+/// Filters for listing/searching history. Everything is optional; results
+/// come newest first.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryFilter {
+    pub server_id: Option<i64>,
+    pub database: Option<String>,
+    /// Full-text search over the query text: every word must appear (prefix
+    /// match), e.g. `orders stat` finds `SELECT status FROM orders`.
+    pub search: Option<String>,
+    #[serde(default)]
+    pub pinned_only: bool,
+    #[serde(default)]
+    pub errors_only: bool,
+    /// Epoch ms range on `started_at`, both inclusive.
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+    /// Page size; 0 = 100, capped at 1000.
+    #[serde(default)]
+    pub limit: u32,
+    #[serde(default)]
+    pub offset: u32,
+}
+
+/// Which entries `purge_query_history` removes. Without options it clears
+/// every unpinned entry.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryPurge {
+    pub server_id: Option<i64>,
+    /// Only entries started before this epoch ms.
+    pub before: Option<i64>,
+    /// Keep the newest N entries in scope and remove the rest.
+    pub keep_last: Option<u32>,
+    #[serde(default)]
+    pub include_pinned: bool,
+}
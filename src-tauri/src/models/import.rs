@@ -0,0 +1,95 @@
+use serde::{Deserialize, Serialize};
+
+use super::CsvOptions;
+
+/// File format read by `import_file`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportFormat {
+    Csv,
+    /// One JSON object per line.
+    Ndjson,
+}
+
+/// What happens when a row can't be read or is rejected by the database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportErrorMode {
+    /// Abort on the first bad row; nothing is kept on transactional
+    /// databases (Postgres, MySQL, SQLite).
+    #[default]
+    Stop,
+    /// Skip bad rows and report them in `ImportSummary.errors`.
+    Skip,
+}
+
+/// Where the rows go: a table (SQL), a collection (Mongo) or a key-prefix
+/// group (Redis), named like in `TableDataRequest`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportTarget {
+    pub schema: Option<String>,
+    pub table: String,
+}
+
+/// One file column loaded into one target column.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ColumnMapping {
+    /// CSV header name (or 1-based position, `"1"`, without a header) or
+    /// NDJSON key.
+    pub source: String,
+    pub target: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportOptions {
+    pub format: ImportFormat,
+    /// `delimiter`, `quote`, `header` and `nullValue` apply; `quoteAll` is
+    /// export-only.
+    #[serde(default)]
+    pub csv: CsvOptions,
+    /// Empty = every file column into the target column of the same name.
+    #[serde(default)]
+    pub mapping: Vec<ColumnMapping>,
+    /// Validate without keeping anything: SQL databases run the import and
+    /// roll it back; Mongo and Redis only check that every row converts.
+    #[serde(default)]
+    pub dry_run: bool,
+    #[serde(default)]
+    pub on_error: ImportErrorMode,
+}
+
+/// A row that was skipped, by its line in the file (1-based; a CSV record
+/// spanning several lines is reported by its first line).
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportRowError {
+    pub line: u64,
+    pub message: String,
+}
+
+/// Sent over the progress channel after every batch, and once more with
+/// `done: true` at the end.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportProgress {
+    pub rows_read: u64,
+    pub rows_imported: u64,
+    pub rows_skipped: u64,
+    pub done: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportSummary {
+    pub rows_read: u64,
+    /// Rows written (or, on a dry run, that would have been).
+    pub rows_imported: u64,
+    pub rows_skipped: u64,
+    /// The first skipped rows; `rows_skipped` has the full count.
+    pub errors: Vec<ImportRowError>,
+    pub dry_run: bool,
+    pub execution_time_ms: u64,
+}
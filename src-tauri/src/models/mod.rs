@@ -1,11 +1,33 @@
+pub mod analysis;
 pub mod browse;
 pub mod capabilities;
+pub mod channel;
+pub mod ddl;
+pub mod explain;
+pub mod export;
+pub mod history;
+pub mod import;
 pub mod query;
+pub mod saved_query;
+pub mod schema_diff;
+pub mod search;
 pub mod server;
+pub mod streams;
 pub mod structure;
 
+pub use analysis::*;
 pub use browse::*;
 pub use capabilities::*;
+pub use channel::*;
+pub use ddl::*;
+pub use explain::*;
+pub use export::*;
+pub use history::*;
+pub use import::*;
 pub use query::*;
+pub use saved_query::*;
+pub use schema_diff::*;
+pub use search::*;
 pub use server::*;
+pub use streams::*;
 pub use structure::*;
@@ -63,6 +63,14 @@ pub struct QueryOptions {
     /// `cancel_query` command; without it the query cannot be cancelled.
     #[serde(default)]
     pub query_id: Option<String>,
+    /// Run on the connection pinned by `open_session` instead of any pooled
+    /// one, so a `BEGIN` from an earlier call is still open.
+    #[serde(default)]
+    pub session_id: Option<String>,
+    /// Values for the `:name` / `$1` placeholders of the query, bound by the
+    /// adapter instead of pasted into the text (Postgres, MongoDB).
+    #[serde(default)]
+    pub params: Vec<QueryParam>,
 }
 
 fn default_limit() -> i64 {
@@ -77,16 +85,79 @@ impl Default for QueryOptions {
             count_total: false,
             unlimited: false,
             query_id: None,
+            session_id: None,
+            params: Vec::new(),
         }
     }
 }
 
+/// Valor de um placeholder. Sem `name`, vale para `$1`, `$2`... na ordem
+/// em que os parâmetros sem nome aparecem.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryParam {
+    #[serde(default)]
+    pub name: Option<String>,
+    pub value: serde_json::Value,
+    /// Tipo para o valor: um tipo Postgres (`int4`, `timestamptz`, vira um
+    /// cast) ou, no MongoDB, `objectId`/`date`/`long`/`int`/`double`/`decimal`.
+    #[serde(default)]
+    pub type_hint: Option<String>,
+}
+
+/// Um placeholder encontrado por `detect_query_params`, uma vez por nome
+/// (ou posição), na ordem em que aparece primeiro.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryParamInfo {
+    pub name: Option<String>,
+    pub position: Option<usize>,
+    pub occurrences: usize,
+}
+
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct StatementResult {
     pub affected_rows: u64,
     pub execution_time_ms: u64,
 }
+
+/// Estado da transação na conexão de uma sessão.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TransactionState {
+    Idle,
+    InTransaction,
+    /// Um erro abortou a transação: até o ROLLBACK o banco recusa tudo.
+    Failed,
+}
+
+/// Uma mensagem do canal de `stream_query`: as colunas uma vez, antes de
+/// tudo, e depois um lote de linhas por vez. Cada lote `rows` precisa ser
+/// confirmado com `ack_query_stream` para o backend mandar mais.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum QueryStreamEvent {
+    Columns {
+        columns: Vec<QueryColumnInfo>,
+    },
+    Rows {
+        /// 0-based, na ordem em que os lotes saíram do banco.
+        batch: u64,
+        rows: Vec<Vec<Option<String>>>,
+    },
+}
+
+/// Retorno de `stream_query`, quando o último lote já foi enviado.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryStreamSummary {
+    pub row_count: u64,
+    pub execution_time_ms: u64,
+    /// Interrompido por `cancel_query`: `rowCount` é o que chegou a sair.
+    pub cancelled: bool,
+}
+
 /// Categoria da mensagem, usada pelo front para escolher o estilo da linha.
 #[derive(Debug, Clone, Copy, Serialize)]
 #[serde(rename_all = "camelCase")]
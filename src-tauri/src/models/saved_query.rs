@@ -0,0 +1,83 @@
+use serde::{Deserialize, Serialize};
+
+use super::DatabaseType;
+
+/// A query kept in the snippet library. Without `server_id`/`db_type` it is
+/// global; `db_type` scopes it to one kind of database, `server_id` to one
+/// server.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedQuery {
+    pub id: i64,
+    pub name: String,
+    /// Slash-separated path, e.g. `diagnostics/locks`; `None` = library root.
+    pub folder: Option<String>,
+    pub tags: Vec<String>,
+    pub body: String,
+    pub server_id: Option<i64>,
+    pub db_type: Option<DatabaseType>,
+    /// Unix seconds, like `Server.created_at`.
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedQueryInput {
+    pub name: String,
+    pub folder: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub body: String,
+    pub server_id: Option<i64>,
+    pub db_type: Option<DatabaseType>,
+}
+
+/// Filters for `list_saved_queries`. Everything is optional.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedQueryFilter {
+    /// Case-insensitive match on name, body, folder and tags.
+    pub search: Option<String>,
+    /// Exact folder; subfolders are included.
+    pub folder: Option<String>,
+    pub tag: Option<String>,
+    /// Snippets usable on this server: bound to it or not bound to any.
+    pub server_id: Option<i64>,
+    /// Snippets usable on this kind of database: same `db_type` or none.
+    pub db_type: Option<DatabaseType>,
+}
+
+/// The JSON file written by `export_saved_queries` and read back by
+/// `import_saved_queries`. Server ids are local to each machine, so the
+/// binding travels as the server name.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedQueryLibrary {
+    pub version: u32,
+    pub queries: Vec<SavedQueryExport>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedQueryExport {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub folder: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    pub body: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub db_type: Option<DatabaseType>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub server_name: Option<String>,
+}
+
+/// What an import did. Entries with the same folder + name as an existing
+/// snippet replace it.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedQueryImportSummary {
+    pub created: usize,
+    pub updated: usize,
+}
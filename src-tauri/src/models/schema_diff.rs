@@ -0,0 +1,132 @@
+use serde::{Deserialize, Serialize};
+
+/// One side of `compare_schemas`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaTarget {
+    pub server_id: i64,
+    pub database: String,
+    /// Default `public`.
+    pub schema: Option<String>,
+}
+
+/// What `compare_schemas` reads of a schema. Definitions come from the
+/// `pg_get_*` functions with the schema name left out, so the same object
+/// compares equal between two schemas of different names.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SchemaSnapshot {
+    pub tables: Vec<TableSnapshot>,
+    pub views: Vec<ViewSnapshot>,
+    pub functions: Vec<FunctionSnapshot>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableSnapshot {
+    pub name: String,
+    pub columns: Vec<ColumnSnapshot>,
+    /// Indexes no constraint creates.
+    pub indexes: Vec<IndexSnapshot>,
+    pub constraints: Vec<ConstraintSnapshot>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ColumnSnapshot {
+    pub name: String,
+    pub data_type: String,
+    pub nullable: bool,
+    pub default_value: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexSnapshot {
+    pub name: String,
+    pub definition: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConstraintSnapshot {
+    pub name: String,
+    /// `pg_constraint.contype`: `p`, `u`, `f`, `c` or `x`.
+    pub kind: String,
+    pub definition: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ViewSnapshot {
+    pub name: String,
+    pub materialized: bool,
+    /// The `SELECT`, from `pg_get_viewdef`.
+    pub definition: String,
+    /// Tables and views of the same schema it reads from.
+    pub dependencies: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FunctionSnapshot {
+    pub name: String,
+    /// Identity arguments, names included: what `DROP FUNCTION` takes.
+    pub arguments: String,
+    /// Just the argument types (`oidvectortypes`): with the name, what tells
+    /// overloads apart, so renaming an argument still changes the function.
+    pub argument_types: String,
+    /// `pg_get_function_result`; `None` for procedures.
+    pub result: Option<String>,
+    pub is_procedure: bool,
+    /// `CREATE OR REPLACE FUNCTION ...`, from `pg_get_functiondef`.
+    pub definition: String,
+}
+
+/// Which way an object differs, going from the target to the source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DiffChange {
+    /// Only in the source: the migration creates it.
+    Added,
+    /// Only in the target: the migration drops it.
+    Removed,
+    Changed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaDiff {
+    pub tables: Vec<TableDiff>,
+    pub views: Vec<DefinitionDiff>,
+    pub functions: Vec<DefinitionDiff>,
+    /// Statements that bring the target in line with the source, in the
+    /// order they have to run. Empty when the schemas match.
+    pub migration: Vec<String>,
+}
+
+/// A table that differs. Added and removed tables leave the column, index
+/// and constraint lists empty.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TableDiff {
+    pub name: String,
+    pub change: DiffChange,
+    pub columns: Vec<ColumnDiff>,
+    pub indexes: Vec<DefinitionDiff>,
+    pub constraints: Vec<DefinitionDiff>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ColumnDiff {
+    pub name: String,
+    pub change: DiffChange,
+    pub source: Option<ColumnSnapshot>,
+    pub target: Option<ColumnSnapshot>,
+}
+
+/// An index, constraint, view or function (`name(arguments)`) that differs,
+/// with its definition on each side.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DefinitionDiff {
+    pub name: String,
+    pub change: DiffChange,
+    pub source: Option<String>,
+    pub target: Option<String>,
+}
@@ -0,0 +1,150 @@
+use serde::{Deserialize, Serialize};
+
+/// What `search_objects` can find. Each adapter reports the kinds it has.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ObjectKind {
+    Table,
+    View,
+    Column,
+    Function,
+    Index,
+    Collection,
+    /// Redis keys sharing a prefix (the "tables" of the Redis tree).
+    KeyGroup,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectSearchRequest {
+    pub query: String,
+    /// Kinds to return; empty = all.
+    #[serde(default)]
+    pub kinds: Vec<ObjectKind>,
+    #[serde(default = "default_search_limit")]
+    pub limit: usize,
+}
+
+fn default_search_limit() -> usize {
+    100
+}
+
+impl ObjectSearchRequest {
+    pub fn wants(&self, kind: ObjectKind) -> bool {
+        self.kinds.is_empty() || self.kinds.contains(&kind)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectMatch {
+    pub kind: ObjectKind,
+    pub schema: Option<String>,
+    /// Table (or collection) of a column or index.
+    pub parent: Option<String>,
+    pub name: String,
+    /// Column type, function arguments, key count...
+    pub detail: Option<String>,
+    /// Higher is better: exact > prefix > substring > scattered letters.
+    pub score: u32,
+}
+
+/// How `search_values` compares a cell with the value searched for.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ValueMatchMode {
+    /// The whole value, as text (`42` finds the number 42 and the string "42").
+    #[default]
+    Exact,
+    /// Anywhere in the value, ignoring case.
+    Contains,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValueSearchRequest {
+    pub value: String,
+    /// Postgres schema to scan (default `public`); Mongo and Redis scan the
+    /// whole database.
+    pub schema: Option<String>,
+    #[serde(default)]
+    pub mode: ValueMatchMode,
+    /// Tables scanned at once, capped by the pool size.
+    #[serde(default = "default_search_concurrency")]
+    pub concurrency: usize,
+    /// A table still scanning after this is abandoned (reported as timed out).
+    #[serde(default = "default_table_timeout_ms")]
+    pub table_timeout_ms: u64,
+    /// Rows reported per table, and hits in total, before the search stops.
+    #[serde(default = "default_max_hits")]
+    pub max_hits: usize,
+}
+
+fn default_search_concurrency() -> usize {
+    4
+}
+
+fn default_table_timeout_ms() -> u64 {
+    10_000
+}
+
+fn default_max_hits() -> usize {
+    1000
+}
+
+impl ValueSearchRequest {
+    /// Whether `cell` matches, with the same rule the database filtered by.
+    pub fn matches(&self, cell: &str) -> bool {
+        match self.mode {
+            ValueMatchMode::Exact => cell == self.value,
+            ValueMatchMode::Contains => cell.to_lowercase().contains(&self.value.to_lowercase()),
+        }
+    }
+}
+
+/// A row (document, key) where a column (field, hash field) holds the value.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValueHit {
+    pub schema: Option<String>,
+    pub table: String,
+    pub column: String,
+    /// Identify the row: the primary key (`ctid` without one), `_id`, `key`.
+    pub key_columns: Vec<String>,
+    pub key_values: Vec<Option<String>>,
+    pub value: Option<String>,
+}
+
+/// Events of a running `search_values`, in the order they happen.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum ValueSearchEvent {
+    /// How many tables will be scanned; sent once, first.
+    Started {
+        tables: usize,
+    },
+    Hit(ValueHit),
+    /// A table is done: after its hits, or when it failed or timed out.
+    TableScanned {
+        schema: Option<String>,
+        table: String,
+        hits: usize,
+        timed_out: bool,
+        error: Option<String>,
+    },
+}
+
+/// Return of `search_values`, when the last event was sent.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValueSearchSummary {
+    pub tables_scanned: usize,
+    pub tables_timed_out: usize,
+    pub tables_failed: usize,
+    pub hit_count: usize,
+    pub execution_time_ms: u64,
+    /// Stopped at `maxHits`.
+    pub truncated: bool,
+    /// Interrupted by `cancel_value_search`.
+    pub cancelled: bool,
+}
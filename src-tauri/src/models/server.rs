@@ -11,8 +11,8 @@ pub enum DatabaseType {
 }
 
 impl DatabaseType {
-    /// Default port per database type, for prefilling the server form.
-    #[allow(dead_code)]
+    /// Default port per database type, for prefilling the server form (and
+    /// for URIs that omit the port).
     pub fn default_port(&self) -> u16 {
         match self {
             Self::Postgres => 5432,
@@ -48,10 +48,23 @@ pub struct Server {
     #[serde(skip_serializing)] // Never send password to frontend
     pub password: String,
     pub default_database: Option<String>,
-    pub ssl_enabled: bool,
+    #[serde(default)]
+    pub tls: TlsConfig,
     /// Full connection URI (e.g. mongodb+srv://... or rediss://...); when
     /// present it takes precedence over host/port/username for connecting.
     pub connection_uri: Option<String>,
+    /// Optional SSH jump host; when set, the connection goes through a local
+    /// port forwarded over SSH (like `ssh -L`).
+    #[serde(default)]
+    pub ssh_tunnel: Option<SshTunnel>,
+    /// Production safety: every write is refused (editor, grid edits,
+    /// imports). Postgres and MySQL connections also open read-only at the
+    /// server.
+    #[serde(default)]
+    pub read_only: bool,
+    /// Writes only run when the command comes back with `confirmed: true`.
+    #[serde(default)]
+    pub confirm_writes: bool,
     pub created_at: i64,
 }
 
@@ -66,8 +79,89 @@ pub struct ServerInput {
     pub username: String,
     pub password: String,
     pub default_database: Option<String>,
-    pub ssl_enabled: Option<bool>,
+    /// Absent = TLS disabled.
+    pub tls: Option<TlsConfig>,
     pub connection_uri: Option<String>,
+    pub ssh_tunnel: Option<SshTunnel>,
+    /// Absent = off on create, unchanged on update.
+    #[serde(default)]
+    pub read_only: Option<bool>,
+    #[serde(default)]
+    pub confirm_writes: Option<bool>,
+}
+
+/// How the connection is encrypted and what gets verified (same meaning as
+/// libpq's sslmode of the same names).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TlsMode {
+    /// Plain TCP.
+    #[default]
+    Disable,
+    /// Encrypted, but any certificate is accepted.
+    Require,
+    /// Certificate must chain to a trusted CA; the hostname isn't checked.
+    VerifyCa,
+    /// Trusted CA and the certificate must match the hostname.
+    VerifyFull,
+}
+
+impl TlsMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Disable => "disable",
+            Self::Require => "require",
+            Self::VerifyCa => "verify-ca",
+            Self::VerifyFull => "verify-full",
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        *self != Self::Disable
+    }
+
+    pub fn verifies_certificate(&self) -> bool {
+        matches!(self, Self::VerifyCa | Self::VerifyFull)
+    }
+}
+
+/// TLS settings. Paths point to PEM files, except that `client_cert_path` may
+/// also be a PKCS#12 bundle (.p12/.pfx) holding both certificate and key.
+/// The key passphrase is received on create/update but never sent back.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TlsConfig {
+    #[serde(default)]
+    pub mode: TlsMode,
+    /// CA bundle trusted for verify-ca/verify-full (instead of, for Postgres
+    /// and MySQL, in addition to the system roots).
+    pub ca_cert_path: Option<String>,
+    pub client_cert_path: Option<String>,
+    /// Empty when the key lives in the certificate file (combined PEM or
+    /// PKCS#12).
+    pub client_key_path: Option<String>,
+    #[serde(default, skip_serializing)]
+    pub client_key_passphrase: Option<String>,
+    /// Hostname the certificate must match under verify-full when it differs
+    /// from `host` — set internally when connecting through an SSH tunnel.
+    #[serde(skip)]
+    pub server_name: Option<String>,
+}
+
+/// SSH jump host settings. Authenticates with the private key when
+/// `private_key_path` is set, otherwise with the password. Secrets are
+/// received on create/update but never sent back to the frontend.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SshTunnel {
+    pub host: String,
+    pub port: u16,
+    pub username: String,
+    #[serde(default, skip_serializing)]
+    pub password: Option<String>,
+    pub private_key_path: Option<String>,
+    #[serde(default, skip_serializing)]
+    pub private_key_passphrase: Option<String>,
 }
 
 /// Connection identifier
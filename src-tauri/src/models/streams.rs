@@ -0,0 +1,118 @@
+use serde::{Deserialize, Serialize};
+
+use super::QueryResult;
+
+/// A page of a Redis stream key (`XRANGE`/`XREVRANGE`).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamRangeRequest {
+    pub key: String,
+    /// Inclusive bounds, entry ids or just the millisecond part; `-`/`+`
+    /// when absent.
+    pub start: Option<String>,
+    pub end: Option<String>,
+    /// `next_cursor` of the previous page: the page starts right after it.
+    pub cursor: Option<String>,
+    /// Newest first (`XREVRANGE`).
+    #[serde(default)]
+    pub reverse: bool,
+    pub limit: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamPage {
+    /// `id` plus one column per field seen in the page, in the order they
+    /// first show up. `total_count` is the stream length.
+    pub result: QueryResult,
+    /// Id of the last entry, when there are more.
+    pub next_cursor: Option<String>,
+}
+
+/// `XINFO STREAM`. The `Option`s come from Redis 7.0+.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamInfo {
+    pub length: i64,
+    pub groups: i64,
+    pub last_generated_id: String,
+    pub max_deleted_entry_id: Option<String>,
+    pub entries_added: Option<i64>,
+    pub first_entry_id: Option<String>,
+    pub last_entry_id: Option<String>,
+}
+
+/// A consumer group, from `XINFO GROUPS`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamGroupInfo {
+    pub name: String,
+    pub consumers: i64,
+    /// Entries delivered and not acknowledged yet.
+    pub pending: i64,
+    pub last_delivered_id: String,
+    /// Redis 7.0+; `None` too when Redis can't tell.
+    pub entries_read: Option<i64>,
+    pub lag: Option<i64>,
+}
+
+/// A consumer of a group, from `XINFO CONSUMERS`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamConsumerInfo {
+    pub name: String,
+    pub pending: i64,
+    /// Since its last read attempt.
+    pub idle_ms: i64,
+    /// Since its last successful read (Redis 7.2+).
+    pub inactive_ms: Option<i64>,
+}
+
+/// Pending entries of a group (`XPENDING`, extended form), oldest first.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamPendingRequest {
+    pub key: String,
+    pub group: String,
+    /// Only this consumer's.
+    pub consumer: Option<String>,
+    /// Only entries delivered at least this long ago.
+    pub min_idle_ms: Option<u64>,
+    /// Id of the last entry of the previous page.
+    pub cursor: Option<String>,
+    pub limit: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamPendingEntry {
+    pub id: String,
+    pub consumer: String,
+    pub idle_ms: i64,
+    pub delivery_count: i64,
+}
+
+/// What `stream_action` does to the selected entries of a stream.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "action", rename_all = "camelCase")]
+pub enum StreamEntriesAction {
+    /// `XACK`: take them off the group's pending list.
+    Ack {
+        key: String,
+        group: String,
+        ids: Vec<String>,
+    },
+    /// `XCLAIM`: hand pending entries idle for at least `min_idle_ms` to
+    /// `consumer`.
+    #[serde(rename_all = "camelCase")]
+    Claim {
+        key: String,
+        group: String,
+        consumer: String,
+        #[serde(default)]
+        min_idle_ms: u64,
+        ids: Vec<String>,
+    },
+    /// `XDEL`: remove them from the stream.
+    Delete { key: String, ids: Vec<String> },
+}
@@ -1,20 +1,20 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DatabaseInfo {
     pub name: String,
     pub size_bytes: Option<i64>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SchemaInfo {
     pub name: String,
     pub table_count: Option<i64>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TableInfo {
     pub name: String,
@@ -23,7 +23,7 @@ pub struct TableInfo {
     pub row_estimate: Option<i64>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum TableType {
     Table,
@@ -32,7 +32,7 @@ pub enum TableType {
     Foreign,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ColumnInfo {
     pub name: String,
@@ -44,7 +44,7 @@ pub struct ColumnInfo {
     pub is_foreign_key: bool,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct IndexInfo {
     pub name: String,
@@ -54,24 +54,94 @@ pub struct IndexInfo {
     pub index_type: String,
 }
 
+/// What a foreign key does to the referencing rows when the referenced row
+/// is deleted or its key updated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ForeignKeyAction {
+    NoAction,
+    Restrict,
+    Cascade,
+    SetNull,
+    SetDefault,
+}
+
+/// A foreign key from `schema.table` to `referenced_schema.referenced_table`.
+/// `columns[i]` references `referenced_columns[i]`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForeignKeyInfo {
+    pub name: String,
+    pub schema: String,
+    pub table: String,
+    pub columns: Vec<String>,
+    pub referenced_schema: String,
+    pub referenced_table: String,
+    pub referenced_columns: Vec<String>,
+    pub on_update: ForeignKeyAction,
+    pub on_delete: ForeignKeyAction,
+    pub is_deferrable: bool,
+    pub is_initially_deferred: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConstraintType {
+    PrimaryKey,
+    Unique,
+    Check,
+    Exclusion,
+}
+
+/// A table constraint other than a foreign key (see [`ForeignKeyInfo`]).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConstraintInfo {
+    pub name: String,
+    pub constraint_type: ConstraintType,
+    /// Columns it covers; empty for a check that names none.
+    pub columns: Vec<String>,
+    /// As the database prints it, e.g. `CHECK (price > 0)`.
+    pub definition: String,
+    pub is_deferrable: bool,
+    pub is_initially_deferred: bool,
+}
+
 /// Full structure for caching (used sparingly)
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DatabaseStructure {
     pub schemas: Vec<SchemaStructure>,
     pub fetched_at: i64,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SchemaStructure {
     pub name: String,
     pub tables: Vec<TableStructure>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TableStructure {
     pub name: String,
     pub table_type: TableType,
-}
\ No newline at end of file
+}
+/// What a statement changed in the structure, so the cached metadata it
+/// touched can be dropped (see `DatabaseAdapter::structure_changes`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StructureChange {
+    /// Databases were created or dropped.
+    Databases,
+    /// A schema was created, dropped or altered: everything under it.
+    Schema(String),
+    /// A table, view or index of `table`; `schema: None` for an unqualified
+    /// name, which may be in any schema.
+    Table {
+        schema: Option<String>,
+        table: String,
+    },
+    /// Some other DDL: anything of the connection may have changed.
+    Unknown,
+}
@@ -7,8 +7,17 @@ use crate::adapters::{create_adapter, DatabaseAdapter, PoolStats};
 use crate::error::{Error, Result};
 use crate::models::{ConnectionId, Server};
 
+use super::tunnel::{self, Tunnel};
+
+/// An open adapter plus the SSH tunnel it connects through, if any. Removing
+/// the entry from the map closes the tunnel.
+struct Connection {
+    adapter: Arc<dyn DatabaseAdapter>,
+    _tunnel: Option<Tunnel>,
+}
+
 pub struct ConnectionService {
-    adapters: RwLock<HashMap<ConnectionId, Arc<dyn DatabaseAdapter>>>,
+    adapters: RwLock<HashMap<ConnectionId, Connection>>,
 }
 
 impl ConnectionService {
@@ -28,7 +37,7 @@ impl ConnectionService {
     ) -> Option<Arc<dyn DatabaseAdapter>> {
         let conn_id = ConnectionId::new(server_id, database);
         let adapters = self.adapters.read();
-        adapters.get(&conn_id).map(Arc::clone)
+        adapters.get(&conn_id).map(|c| Arc::clone(&c.adapter))
     }
 
     /// Get or create an adapter for the given connection
@@ -43,22 +52,37 @@ impl ConnectionService {
         // Fast path: adapter exists (lock is not held across the await below)
         {
             let adapters = self.adapters.read();
-            if let Some(adapter) = adapters.get(&conn_id) {
-                return Ok(Arc::clone(adapter));
+            if let Some(conn) = adapters.get(&conn_id) {
+                return Ok(Arc::clone(&conn.adapter));
             }
         }
 
         // Slow path: create adapter. Concurrent creations may race; the
-        // first inserted adapter wins and duplicates are dropped.
-        let adapter = create_adapter(server, database).await?;
+        // first inserted adapter wins and duplicates (with their tunnels)
+        // are dropped.
+        let conn = match server.ssh_tunnel.as_ref() {
+            Some(ssh) => {
+                // The forwarded port must be listening before the adapter
+                // opens its first connection.
+                let (remote_host, remote_port) = tunnel::remote_target(server)?;
+                let tunnel = Tunnel::open(ssh, &remote_host, remote_port).await?;
+                let routed = tunnel::route_through(server, tunnel.local_port())?;
+                Connection {
+                    adapter: create_adapter(&routed, database).await?,
+                    _tunnel: Some(tunnel),
+                }
+            }
+            None => Connection {
+                adapter: create_adapter(server, database).await?,
+                _tunnel: None,
+            },
+        };
 
         let mut adapters = self.adapters.write();
-        Ok(Arc::clone(
-            adapters.entry(conn_id).or_insert(adapter),
-        ))
+        Ok(Arc::clone(&adapters.entry(conn_id).or_insert(conn).adapter))
     }
 
-    /// Disconnect from a specific database
+    /// Disconnect from a specific database (closing its SSH tunnel, if any)
     pub fn disconnect(&self, server_id: i64, database: &str) {
         let conn_id = ConnectionId::new(server_id, database);
         let mut adapters = self.adapters.write();
@@ -75,6 +99,6 @@ impl ConnectionService {
     pub fn pool_stats(&self, server_id: i64, database: &str) -> Option<PoolStats> {
         let conn_id = ConnectionId::new(server_id, database);
         let adapters = self.adapters.read();
-        adapters.get(&conn_id).and_then(|adapter| adapter.pool_stats())
+        adapters.get(&conn_id).and_then(|conn| conn.adapter.pool_stats())
     }
 }
\ No newline at end of file
@@ -0,0 +1,375 @@
+use std::time::Instant;
+
+use async_trait::async_trait;
+use serde::de::IgnoredAny;
+use tokio::fs::File;
+use tokio::io::{AsyncWriteExt, BufWriter};
+
+use crate::adapters::RowSink;
+use crate::error::{Error, Result};
+use crate::models::{
+    CsvOptions, ExportFormat, ExportOptions, ExportProgress, ExportSummary, QueryColumnInfo,
+};
+
+use super::ProgressFn;
+
+/// `RowSink` que grava cada lote direto no arquivo de destino.
+///
+/// Só o lote corrente fica em memória: o texto de cada lote é montado num
+/// buffer e despejado no `BufWriter` antes de o adapter buscar o próximo.
+pub struct ExportFile {
+    path: String,
+    writer: BufWriter<File>,
+    options: ExportOptions,
+    /// Tabela dos `INSERT` já com aspas (`"schema"."tabela"`).
+    insert_target: String,
+    columns: Vec<QueryColumnInfo>,
+    kinds: Vec<ValueKind>,
+    rows_written: u64,
+    bytes_written: u64,
+    started: Instant,
+    progress: Option<ProgressFn<ExportProgress>>,
+}
+
+impl ExportFile {
+    /// Create (or truncate) `path`. `default_table` names the `INSERT` target
+    /// when the options don't.
+    pub async fn create(
+        path: &str,
+        options: ExportOptions,
+        default_table: &str,
+        progress: Option<ProgressFn<ExportProgress>>,
+    ) -> Result<Self> {
+        validate_csv(&options.csv)?;
+
+        let file = File::create(path)
+            .await
+            .map_err(|e| Error::InvalidState(format!("Cannot create {path}: {e}")))?;
+        let insert_target = insert_target(options.table_name.as_deref().unwrap_or(default_table));
+
+        Ok(Self {
+            path: path.to_string(),
+            writer: BufWriter::new(file),
+            options,
+            insert_target,
+            columns: Vec::new(),
+            kinds: Vec::new(),
+            rows_written: 0,
+            bytes_written: 0,
+            started: Instant::now(),
+            progress,
+        })
+    }
+
+    /// Close the JSON array, flush and report the final progress.
+    pub async fn finish(mut self) -> Result<ExportSummary> {
+        if self.options.format == ExportFormat::Json {
+            let closing = if self.rows_written == 0 {
+                "]\n"
+            } else {
+                "\n]\n"
+            };
+            self.write(closing).await?;
+        }
+        self.writer.flush().await.map_err(|e| self.io_error(e))?;
+        self.report(true);
+
+        Ok(ExportSummary {
+            path: self.path,
+            rows_written: self.rows_written,
+            bytes_written: self.bytes_written,
+            execution_time_ms: self.started.elapsed().as_millis() as u64,
+        })
+    }
+
+    /// Drop a half-written file after a failed export.
+    pub async fn discard(self) {
+        let path = self.path;
+        drop(self.writer);
+        let _ = tokio::fs::remove_file(&path).await;
+    }
+
+    async fn write(&mut self, text: &str) -> Result<()> {
+        self.writer
+            .write_all(text.as_bytes())
+            .await
+            .map_err(|e| self.io_error(e))?;
+        self.bytes_written += text.len() as u64;
+        Ok(())
+    }
+
+    fn io_error(&self, e: std::io::Error) -> Error {
+        Error::InvalidState(format!("Cannot write {}: {e}", self.path))
+    }
+
+    fn report(&self, done: bool) {
+        if let Some(progress) = &self.progress {
+            progress(ExportProgress {
+                rows_written: self.rows_written,
+                bytes_written: self.bytes_written,
+                done,
+            });
+        }
+    }
+
+    fn encode_row(&self, row: &[Option<String>], first: bool, out: &mut String) {
+        match self.options.format {
+            ExportFormat::Csv => {
+                let fields: Vec<String> = row
+                    .iter()
+                    .map(|v| match v {
+                        Some(v) => csv_field(v, &self.options.csv),
+                        None => self.options.csv.null_value.clone(),
+                    })
+                    .collect();
+                out.push_str(&fields.join(&self.options.csv.delimiter.to_string()));
+                out.push('\n');
+            }
+            ExportFormat::Json => {
+                out.push_str(if first { "  " } else { ",\n  " });
+                out.push_str(&self.json_object(row));
+            }
+            ExportFormat::Ndjson => {
+                out.push_str(&self.json_object(row));
+                out.push('\n');
+            }
+            ExportFormat::Sql => {
+                let values: Vec<String> = row
+                    .iter()
+                    .zip(&self.kinds)
+                    .map(|(v, kind)| sql_literal(v.as_deref(), *kind))
+                    .collect();
+                out.push_str(&format!(
+                    "INSERT INTO {} ({}) VALUES ({});\n",
+                    self.insert_target,
+                    self.columns
+                        .iter()
+                        .map(|c| quote_ident(&c.name))
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    values.join(", "),
+                ));
+            }
+        }
+    }
+
+    /// Written by hand: `serde_json::Map` would sort the keys, and the export
+    /// must keep the column order.
+    fn json_object(&self, row: &[Option<String>]) -> String {
+        let fields: Vec<String> = self
+            .columns
+            .iter()
+            .zip(&self.kinds)
+            .zip(row)
+            .map(|((column, kind), value)| {
+                format!(
+                    "{}:{}",
+                    serde_json::Value::String(column.name.clone()),
+                    json_value(value.as_deref(), *kind)
+                )
+            })
+            .collect();
+        format!("{{{}}}", fields.join(","))
+    }
+}
+
+#[async_trait]
+impl RowSink for ExportFile {
+    async fn columns(&mut self, columns: &[QueryColumnInfo]) -> Result<()> {
+        self.columns = columns.to_vec();
+        self.kinds = columns.iter().map(|c| value_kind(&c.type_name)).collect();
+
+        match self.options.format {
+            ExportFormat::Csv if self.options.csv.header => {
+                let names: Vec<String> = columns
+                    .iter()
+                    .map(|c| csv_field(&c.name, &self.options.csv))
+                    .collect();
+                let line = names.join(&self.options.csv.delimiter.to_string()) + "\n";
+                self.write(&line).await
+            }
+            ExportFormat::Json => self.write("[\n").await,
+            _ => Ok(()),
+        }
+    }
+
+    async fn rows(&mut self, rows: Vec<Vec<Option<String>>>) -> Result<()> {
+        let mut text = String::new();
+        for (i, row) in rows.iter().enumerate() {
+            let first = self.rows_written == 0 && i == 0;
+            self.encode_row(row, first, &mut text);
+        }
+        self.write(&text).await?;
+        self.rows_written += rows.len() as u64;
+        self.report(false);
+        Ok(())
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Value encoding (pure, unit-testable)
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// How a column's display strings are written to JSON and SQL. Adapters
+/// hand every value over as text, so the column type decides whether `42`
+/// is a number or a string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ValueKind {
+    Number,
+    Bool,
+    Json,
+    Text,
+}
+
+fn value_kind(type_name: &str) -> ValueKind {
+    let t = type_name.to_ascii_lowercase();
+    match t.as_str() {
+        "bool" | "boolean" => ValueKind::Bool,
+        "json" | "jsonb" | "object" | "array" => ValueKind::Json,
+        "int2" | "int4" | "int8" | "float4" | "float8" | "numeric" | "decimal" | "integer"
+        | "int" | "smallint" | "bigint" | "tinyint" | "mediumint" | "real" | "double" | "float"
+        | "int32" | "int64" | "long" => ValueKind::Number,
+        _ => ValueKind::Text,
+    }
+}
+
+/// The JSON text of a cell. Numbers and JSON values are written as they
+/// came, not through `serde_json::Value`, which would round `numeric`s and
+/// bigints past 2^53 to an `f64`.
+fn json_value(value: Option<&str>, kind: ValueKind) -> String {
+    let Some(value) = value else {
+        return "null".to_string();
+    };
+    match kind {
+        ValueKind::Number if is_number(value) => return value.trim().to_string(),
+        ValueKind::Bool => match value {
+            "true" | "t" | "1" => return "true".to_string(),
+            "false" | "f" | "0" => return "false".to_string(),
+            _ => {}
+        },
+        // A raw line break can only be whitespace in valid JSON, and NDJSON
+        // needs the value on one line.
+        ValueKind::Json if serde_json::from_str::<IgnoredAny>(value).is_ok() => {
+            return value.trim().replace(['\n', '\r'], " ");
+        }
+        _ => {}
+    }
+    serde_json::Value::String(value.to_string()).to_string()
+}
+
+fn sql_literal(value: Option<&str>, kind: ValueKind) -> String {
+    match value {
+        None => "NULL".to_string(),
+        Some(v) if kind == ValueKind::Number && is_number(v) => v.to_string(),
+        Some(v) => format!("'{}'", v.replace('\'', "''")),
+    }
+}
+
+/// A plain decimal literal. `NaN`, `Infinity` and money-formatted text are
+/// neither JSON numbers nor SQL numeric literals, so they stay quoted.
+fn is_number(value: &str) -> bool {
+    serde_json::from_str::<serde_json::Number>(value).is_ok()
+}
+
+fn csv_field(value: &str, options: &CsvOptions) -> String {
+    let needs_quotes = options.quote_all
+        || value.contains(options.delimiter)
+        || value.contains(options.quote)
+        || value.contains(['\n', '\r'])
+        || (value.is_empty() && options.null_value.is_empty());
+    if !needs_quotes {
+        return value.to_string();
+    }
+
+    let quote = options.quote.to_string();
+    let escaped = value.replace(options.quote, &quote.repeat(2));
+    format!("{quote}{escaped}{quote}")
+}
+
+fn validate_csv(options: &CsvOptions) -> Result<()> {
+    if options.delimiter == options.quote || matches!(options.delimiter, '\n' | '\r') {
+        return Err(Error::InvalidQuery(
+            "CSV delimiter must differ from the quote character and newlines".into(),
+        ));
+    }
+    Ok(())
+}
+
+fn quote_ident(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// `schema.table` vira `"schema"."table"`; um nome já entre aspas é mantido.
+fn insert_target(name: &str) -> String {
+    if name.starts_with('"') {
+        return name.to_string();
+    }
+    name.split('.')
+        .map(quote_ident)
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_quotes_only_when_needed() {
+        let options = CsvOptions::default();
+        assert_eq!(csv_field("plain", &options), "plain");
+        assert_eq!(csv_field("a,b", &options), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\"", &options), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("two\nlines", &options), "\"two\nlines\"");
+        // empty string vs NULL (written as nothing) stay distinguishable
+        assert_eq!(csv_field("", &options), "\"\"");
+
+        let semicolon = CsvOptions {
+            delimiter: ';',
+            quote_all: true,
+            ..Default::default()
+        };
+        assert_eq!(csv_field("a,b", &semicolon), "\"a,b\"");
+        assert_eq!(csv_field("x", &semicolon), "\"x\"");
+    }
+
+    #[test]
+    fn sql_literals_follow_column_type() {
+        assert_eq!(sql_literal(None, ValueKind::Text), "NULL");
+        assert_eq!(sql_literal(Some("42"), ValueKind::Number), "42");
+        assert_eq!(sql_literal(Some("NaN"), ValueKind::Number), "'NaN'");
+        assert_eq!(sql_literal(Some("42"), ValueKind::Text), "'42'");
+        assert_eq!(sql_literal(Some("O'Brien"), ValueKind::Text), "'O''Brien'");
+        assert_eq!(insert_target("public.users"), "\"public\".\"users\"");
+    }
+
+    #[test]
+    fn json_values_are_typed_by_column() {
+        assert_eq!(json_value(Some("1.5"), ValueKind::Number), "1.5");
+        assert_eq!(json_value(Some("t"), ValueKind::Bool), "true");
+        assert_eq!(
+            json_value(Some("{\"a\": 1,\n \"b\": [2]}"), ValueKind::Json),
+            "{\"a\": 1,  \"b\": [2]}"
+        );
+        assert_eq!(json_value(Some("{oops"), ValueKind::Json), "\"{oops\"");
+        assert_eq!(json_value(Some("007"), ValueKind::Text), "\"007\"");
+        assert_eq!(json_value(Some("NaN"), ValueKind::Number), "\"NaN\"");
+        assert_eq!(json_value(None, ValueKind::Number), "null");
+    }
+
+    #[test]
+    fn json_numbers_keep_their_precision() {
+        assert_eq!(
+            json_value(Some("12345678901234567890.123"), ValueKind::Number),
+            "12345678901234567890.123"
+        );
+        assert_eq!(
+            json_value(Some("9007199254740993"), ValueKind::Number),
+            "9007199254740993"
+        );
+        assert_eq!(
+            json_value(Some(r#"{"id": 9007199254740993}"#), ValueKind::Json),
+            r#"{"id": 9007199254740993}"#
+        );
+    }
+}
@@ -0,0 +1,496 @@
+use std::time::Instant;
+
+use async_trait::async_trait;
+use tokio::fs::File;
+use tokio::io::{AsyncBufReadExt, BufReader};
+
+use crate::adapters::{RowSource, SourceRow, STREAM_BATCH_ROWS};
+use crate::error::{Error, Result};
+use crate::models::{
+    CsvOptions, ImportErrorMode, ImportFormat, ImportOptions, ImportProgress, ImportRowError,
+    ImportSummary,
+};
+
+use super::ProgressFn;
+
+/// Quantas linhas puladas são devolvidas com a mensagem; além disso só o
+/// contador sobe, para um arquivo todo errado não virar um resumo gigante.
+const MAX_REPORTED_ERRORS: usize = 1000;
+
+/// `RowSource` que lê um CSV ou NDJSON linha a linha: só o lote corrente fica
+/// em memória, seja qual for o tamanho do arquivo.
+pub struct ImportFile {
+    path: String,
+    reader: BufReader<File>,
+    options: ImportOptions,
+    /// Colunas de destino, na ordem dos valores de cada `SourceRow`.
+    targets: Vec<String>,
+    /// CSV: posição no registro de cada coluna de destino, e quantos campos
+    /// o cabeçalho tem (0 = sem cabeçalho, sem checagem).
+    positions: Vec<usize>,
+    width: usize,
+    /// NDJSON: chave do objeto de cada coluna de destino.
+    keys: Vec<String>,
+    /// Linhas físicas já consumidas do arquivo.
+    line: u64,
+    /// Primeiro objeto NDJSON, lido na abertura para descobrir as colunas.
+    pending: Option<(u64, serde_json::Map<String, serde_json::Value>)>,
+    rows_read: u64,
+    rows_imported: u64,
+    rows_skipped: u64,
+    errors: Vec<ImportRowError>,
+    started: Instant,
+    progress: Option<ProgressFn<ImportProgress>>,
+}
+
+impl ImportFile {
+    /// Open `path` and resolve the column mapping against the CSV header or
+    /// the keys of the first NDJSON object.
+    pub async fn open(
+        path: &str,
+        options: ImportOptions,
+        progress: Option<ProgressFn<ImportProgress>>,
+    ) -> Result<Self> {
+        let file = File::open(path)
+            .await
+            .map_err(|e| Error::InvalidState(format!("Cannot open {path}: {e}")))?;
+
+        let mut import = Self {
+            path: path.to_string(),
+            reader: BufReader::new(file),
+            options,
+            targets: Vec::new(),
+            positions: Vec::new(),
+            width: 0,
+            keys: Vec::new(),
+            line: 0,
+            pending: None,
+            rows_read: 0,
+            rows_imported: 0,
+            rows_skipped: 0,
+            errors: Vec::new(),
+            started: Instant::now(),
+            progress,
+        };
+
+        match import.options.format {
+            ImportFormat::Csv => import.resolve_csv_columns().await?,
+            ImportFormat::Ndjson => import.resolve_ndjson_columns().await?,
+        }
+
+        let mut seen = std::collections::HashSet::new();
+        if let Some(dup) = import.targets.iter().find(|t| !seen.insert(t.as_str())) {
+            return Err(Error::InvalidQuery(format!("Column {dup} is mapped twice")));
+        }
+        Ok(import)
+    }
+
+    /// Final counts; also sends the last progress message.
+    pub fn finish(self) -> ImportSummary {
+        self.report(true);
+        ImportSummary {
+            rows_read: self.rows_read,
+            rows_imported: self.rows_imported,
+            rows_skipped: self.rows_skipped,
+            errors: self.errors,
+            dry_run: self.options.dry_run,
+            execution_time_ms: self.started.elapsed().as_millis() as u64,
+        }
+    }
+
+    async fn resolve_csv_columns(&mut self) -> Result<()> {
+        let header = if self.options.csv.header {
+            match self.read_csv_record().await? {
+                Some((_, Ok(fields))) => Some(fields.into_iter().map(|(name, _)| name).collect()),
+                Some((line, Err(message))) => {
+                    return Err(Error::InvalidQuery(format!("Line {line}: {message}")))
+                }
+                None => return Err(Error::InvalidQuery("The file is empty".into())),
+            }
+        } else {
+            None
+        };
+
+        let (targets, positions) = csv_positions(header.as_deref(), &self.options.mapping)?;
+        self.width = header.map_or(0, |h: Vec<String>| h.len());
+        self.targets = targets;
+        self.positions = positions;
+        Ok(())
+    }
+
+    async fn resolve_ndjson_columns(&mut self) -> Result<()> {
+        if !self.options.mapping.is_empty() {
+            self.targets = self
+                .options
+                .mapping
+                .iter()
+                .map(|m| m.target.clone())
+                .collect();
+            self.keys = self
+                .options
+                .mapping
+                .iter()
+                .map(|m| m.source.clone())
+                .collect();
+            return Ok(());
+        }
+
+        // Sem mapeamento, as chaves do primeiro objeto definem as colunas.
+        let (line, text) = self
+            .read_line()
+            .await?
+            .ok_or_else(|| Error::InvalidQuery("The file is empty".into()))?;
+        let object = parse_ndjson(&text)
+            .map_err(|message| Error::InvalidQuery(format!("Line {line}: {message}")))?;
+
+        self.targets = object.keys().cloned().collect();
+        self.keys = self.targets.clone();
+        self.pending = Some((line, object));
+        Ok(())
+    }
+
+    /// Next non-blank line (without its line break) and its number.
+    async fn read_line(&mut self) -> Result<Option<(u64, String)>> {
+        loop {
+            let mut text = String::new();
+            let read = self
+                .reader
+                .read_line(&mut text)
+                .await
+                .map_err(|e| Error::InvalidState(format!("Cannot read {}: {e}", self.path)))?;
+            if read == 0 {
+                return Ok(None);
+            }
+            self.line += 1;
+
+            if self.line == 1 {
+                text = text.trim_start_matches('\u{feff}').to_string();
+            }
+            let text = text.trim_end_matches(['\n', '\r']);
+            if !text.trim().is_empty() {
+                return Ok(Some((self.line, text.to_string())));
+            }
+        }
+    }
+
+    /// Next CSV record, joining physical lines while a quoted field is open.
+    /// The inner `Err` is a malformed record, handled by the error policy.
+    async fn read_csv_record(&mut self) -> Result<Option<(u64, RecordResult)>> {
+        let Some((first_line, mut text)) = self.read_line().await? else {
+            return Ok(None);
+        };
+
+        let quote = self.options.csv.quote;
+        // Aspas escapadas vêm dobradas, então a paridade diz se ainda há um
+        // campo entre aspas aberto.
+        while text.matches(quote).count() % 2 == 1 {
+            let mut next = String::new();
+            let read = self
+                .reader
+                .read_line(&mut next)
+                .await
+                .map_err(|e| Error::InvalidState(format!("Cannot read {}: {e}", self.path)))?;
+            if read == 0 {
+                break;
+            }
+            self.line += 1;
+            text.push('\n');
+            text.push_str(next.trim_end_matches(['\n', '\r']));
+        }
+
+        Ok(Some((
+            first_line,
+            parse_csv_record(&text, &self.options.csv),
+        )))
+    }
+
+    /// The next record mapped to the target columns.
+    async fn next_row(&mut self) -> Result<Option<(u64, RowResult)>> {
+        let row = match self.options.format {
+            ImportFormat::Csv => self
+                .read_csv_record()
+                .await?
+                .map(|(line, record)| (line, record.and_then(|f| self.map_csv(f)))),
+            ImportFormat::Ndjson => {
+                let object = match self.pending.take() {
+                    Some((line, object)) => Some((line, Ok(object))),
+                    None => self
+                        .read_line()
+                        .await?
+                        .map(|(line, text)| (line, parse_ndjson(&text))),
+                };
+                object.map(|(line, object)| (line, object.map(|o| self.map_ndjson(&o))))
+            }
+        };
+        Ok(row)
+    }
+
+    fn map_csv(&self, fields: Vec<(String, bool)>) -> RowResult {
+        if self.width > 0 && fields.len() != self.width {
+            return Err(format!(
+                "Expected {} fields, found {}",
+                self.width,
+                fields.len()
+            ));
+        }
+        self.positions
+            .iter()
+            .map(|&i| match fields.get(i) {
+                Some((value, quoted)) => Ok(csv_value(value, *quoted, &self.options.csv)),
+                None => Err(format!("Missing field {}", i + 1)),
+            })
+            .collect()
+    }
+
+    fn map_ndjson(
+        &self,
+        object: &serde_json::Map<String, serde_json::Value>,
+    ) -> Vec<Option<String>> {
+        self.keys
+            .iter()
+            .map(|key| object.get(key).and_then(json_text))
+            .collect()
+    }
+
+    fn report(&self, done: bool) {
+        if let Some(progress) = &self.progress {
+            progress(ImportProgress {
+                rows_read: self.rows_read,
+                rows_imported: self.rows_imported,
+                rows_skipped: self.rows_skipped,
+                done,
+            });
+        }
+    }
+}
+
+type RecordResult = std::result::Result<Vec<(String, bool)>, String>;
+type RowResult = std::result::Result<Vec<Option<String>>, String>;
+
+#[async_trait]
+impl RowSource for ImportFile {
+    fn columns(&self) -> &[String] {
+        &self.targets
+    }
+
+    fn dry_run(&self) -> bool {
+        self.options.dry_run
+    }
+
+    fn stop_on_error(&self) -> bool {
+        self.options.on_error == ImportErrorMode::Stop
+    }
+
+    async fn next_batch(&mut self) -> Result<Option<Vec<SourceRow>>> {
+        let mut batch = Vec::with_capacity(STREAM_BATCH_ROWS);
+        while batch.len() < STREAM_BATCH_ROWS {
+            let Some((line, row)) = self.next_row().await? else {
+                break;
+            };
+            self.rows_read += 1;
+            match row {
+                Ok(values) => batch.push(SourceRow { line, values }),
+                Err(message) => self.reject(line, message)?,
+            }
+        }
+
+        // Linhas ruins não contam para encher o lote: vazio só no fim do arquivo.
+        Ok((!batch.is_empty()).then_some(batch))
+    }
+
+    fn reject(&mut self, line: u64, message: String) -> Result<()> {
+        self.rows_skipped += 1;
+        if self.stop_on_error() {
+            return Err(Error::InvalidQuery(format!("Line {line}: {message}")));
+        }
+        if self.errors.len() < MAX_REPORTED_ERRORS {
+            self.errors.push(ImportRowError { line, message });
+        }
+        Ok(())
+    }
+
+    fn imported(&mut self, rows: u64) {
+        self.rows_imported += rows;
+        self.report(false);
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Parsing (pure, unit-testable)
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Target columns and, for each, its position in the CSV record. Without a
+/// header, mapping sources are 1-based positions.
+fn csv_positions(
+    header: Option<&[String]>,
+    mapping: &[crate::models::ColumnMapping],
+) -> Result<(Vec<String>, Vec<usize>)> {
+    if mapping.is_empty() {
+        let header = header.ok_or_else(|| {
+            Error::InvalidQuery("A CSV without a header needs a column mapping".into())
+        })?;
+        return Ok((header.to_vec(), (0..header.len()).collect()));
+    }
+
+    let positions = mapping
+        .iter()
+        .map(|m| {
+            let found = match header {
+                Some(header) => header.iter().position(|h| *h == m.source),
+                None => m
+                    .source
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|&n| n > 0)
+                    .map(|n| n - 1),
+            };
+            found.ok_or_else(|| {
+                Error::InvalidQuery(format!("Column {} not found in the file", m.source))
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok((
+        mapping.iter().map(|m| m.target.clone()).collect(),
+        positions,
+    ))
+}
+
+/// Split one CSV record into `(value, was_quoted)` fields.
+fn parse_csv_record(text: &str, options: &CsvOptions) -> RecordResult {
+    let (delimiter, quote) = (options.delimiter, options.quote);
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            if c != quote {
+                field.push(c);
+            } else if chars.peek() == Some(&quote) {
+                field.push(quote);
+                chars.next();
+            } else {
+                in_quotes = false;
+            }
+        } else if c == delimiter {
+            fields.push((std::mem::take(&mut field), quoted));
+            quoted = false;
+        } else if c == quote && field.is_empty() && !quoted {
+            in_quotes = true;
+            quoted = true;
+        } else if quoted {
+            return Err(format!("Unexpected {c:?} after a closing quote"));
+        } else {
+            field.push(c);
+        }
+    }
+
+    if in_quotes {
+        return Err("Unterminated quoted field".into());
+    }
+    fields.push((field, quoted));
+    Ok(fields)
+}
+
+/// An unquoted field equal to `nullValue` is NULL; quoted, it's text. With
+/// the default empty `nullValue`, `,,` is NULL and `,"",` an empty string.
+fn csv_value(value: &str, quoted: bool, options: &CsvOptions) -> Option<String> {
+    (quoted || value != options.null_value).then(|| value.to_string())
+}
+
+fn parse_ndjson(
+    text: &str,
+) -> std::result::Result<serde_json::Map<String, serde_json::Value>, String> {
+    match serde_json::from_str(text) {
+        Ok(serde_json::Value::Object(object)) => Ok(object),
+        Ok(_) => Err("Expected a JSON object".into()),
+        Err(e) => Err(format!("Invalid JSON: {e}")),
+    }
+}
+
+/// Strings go as-is, NULL as NULL, and numbers, booleans, objects and arrays
+/// as their JSON text (which the adapters cast or, on Mongo, parse back).
+fn json_text(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::Null => None,
+        serde_json::Value::String(s) => Some(s.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ColumnMapping;
+
+    #[test]
+    fn csv_records_keep_quoting_and_nulls_apart() {
+        let options = CsvOptions::default();
+        let fields = parse_csv_record("1,,\"\",\"a,\"\"b\"\"\nc\"", &options).unwrap();
+        assert_eq!(
+            fields,
+            [
+                ("1".to_string(), false),
+                (String::new(), false),
+                (String::new(), true),
+                ("a,\"b\"\nc".to_string(), true),
+            ]
+        );
+
+        let values: Vec<Option<String>> = fields
+            .iter()
+            .map(|(v, q)| csv_value(v, *q, &options))
+            .collect();
+        assert_eq!(values[1], None);
+        assert_eq!(values[2].as_deref(), Some(""));
+
+        assert!(parse_csv_record("\"open", &options).is_err());
+        assert!(parse_csv_record("\"a\"b", &options).is_err());
+    }
+
+    #[test]
+    fn mapping_resolves_header_names_or_positions() {
+        let header = ["id", "name", "email"].map(String::from);
+        let mapping = vec![
+            ColumnMapping {
+                source: "email".into(),
+                target: "mail".into(),
+            },
+            ColumnMapping {
+                source: "id".into(),
+                target: "id".into(),
+            },
+        ];
+
+        let (targets, positions) = csv_positions(Some(&header), &mapping).unwrap();
+        assert_eq!(targets, ["mail", "id"]);
+        assert_eq!(positions, [2, 0]);
+
+        let by_position = vec![ColumnMapping {
+            source: "2".into(),
+            target: "name".into(),
+        }];
+        assert_eq!(csv_positions(None, &by_position).unwrap().1, [1]);
+
+        assert!(csv_positions(None, &[]).is_err());
+        let unknown = vec![ColumnMapping {
+            source: "phone".into(),
+            target: "phone".into(),
+        }];
+        assert!(csv_positions(Some(&header), &unknown).is_err());
+    }
+
+    #[test]
+    fn ndjson_values_become_text() {
+        let object =
+            parse_ndjson(r#"{"n": 1.5, "ok": true, "tags": ["a"], "none": null}"#).unwrap();
+        assert_eq!(json_text(&object["n"]).as_deref(), Some("1.5"));
+        assert_eq!(json_text(&object["ok"]).as_deref(), Some("true"));
+        assert_eq!(json_text(&object["tags"]).as_deref(), Some("[\"a\"]"));
+        assert_eq!(json_text(&object["none"]), None);
+        assert!(parse_ndjson("[1]").is_err());
+    }
+}
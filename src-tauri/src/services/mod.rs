@@ -1,7 +1,25 @@
 mod connection;
+mod export;
+mod import;
 mod query;
+mod safety;
+mod schema_diff;
+mod stream;
 mod structure;
+mod structure_cache;
+mod tunnel;
+mod value_search;
 
 pub use connection::ConnectionService;
-pub use query::QueryService;
-pub use structure::StructureService;
\ No newline at end of file
+pub use export::ExportFile;
+pub use import::ImportFile;
+pub use query::{QueryService, QueryTarget};
+pub use safety::{check_warnings, check_write};
+pub use schema_diff::diff_schemas;
+pub use structure::StructureService;
+pub use value_search::SearchService;
+
+/// Callback que recebe o progresso de um export/import (ou os lotes de um
+/// stream) a cada lote; o command o liga a um `Channel` do Tauri, assim os
+/// serviços não conhecem o IPC.
+pub type ProgressFn<T> = Box<dyn Fn(T) + Send>;
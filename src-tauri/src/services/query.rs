@@ -1,23 +1,45 @@
+use std::future::Future;
 use std::sync::Arc;
+use std::time::Instant;
 
-use crate::adapters::{DatabaseAdapter, MessageSink};
-use crate::error::Result;
-use crate::models::{QueryOptions, QueryResult, StatementResult};
+use parking_lot::Mutex;
+use rusqlite::Connection;
+
+use crate::adapters::{DatabaseAdapter, MessageSink, RowSink};
+use crate::error::{Error, Result};
+use crate::models::{
+    HistoryKind, NewHistoryEntry, QueryOptions, QueryResult, QueryStreamEvent, QueryStreamSummary,
+    StatementResult, TableDataRequest,
+};
+use crate::storage::repositories::query_history;
+
+use super::stream::StreamRegistry;
+use super::ProgressFn;
 
 /// Query service - handles query execution logic
 ///
-/// Currently a thin wrapper, but can be extended for:
-/// - Query history tracking
+/// Every execution is recorded in `query_history` (app.db), success or
+/// failure. Also tracks the streams running to the frontend, so their window
+/// can be acknowledged and cancelled. Can be extended for:
 /// - Query analysis/explain
-/// - Query cancellation management
 /// - Result caching
 pub struct QueryService {
-    // Future: query history storage, active queries tracking, etc.
+    storage: Arc<Mutex<Connection>>,
+    streams: StreamRegistry,
+}
+
+/// Where an execution ran, for the history record.
+pub struct QueryTarget<'a> {
+    pub server_id: i64,
+    pub database: &'a str,
 }
 
 impl QueryService {
-    pub fn new() -> Self {
-        Self {}
+    pub fn new(storage: Arc<Mutex<Connection>>) -> Self {
+        Self {
+            storage,
+            streams: StreamRegistry::default(),
+        }
     }
 
     /// Execute a SELECT query with pagination
@@ -26,36 +48,196 @@ impl QueryService {
     /// result set (RAISE NOTICE, erro detalhado, conclusão). `None` = sem log.
     pub async fn execute_query(
         &self,
+        target: QueryTarget<'_>,
         adapter: Arc<dyn DatabaseAdapter>,
         query: &str,
         options: QueryOptions,
         sink: Option<Arc<dyn MessageSink>>,
     ) -> Result<QueryResult> {
-        // Future: log to query history, track active queries, etc.
-        adapter.execute_query_with_messages(query, options, sink).await
+        // Sem suporte, os valores seriam ignorados e a query rodaria com os
+        // placeholders crus: melhor recusar.
+        if !options.params.is_empty() && !adapter.capabilities().supports_params {
+            return Err(Error::UnsupportedType(
+                "Query parameters are not supported for this database".into(),
+            ));
+        }
+
+        self.recorded(
+            target,
+            HistoryKind::Query,
+            query.to_string(),
+            adapter.execute_query_with_messages(query, options, sink),
+            |result, entry| entry.row_count = Some(result.row_count as i64),
+        )
+        .await
     }
 
     /// Execute a statement (INSERT, UPDATE, DELETE, etc.)
     pub async fn execute_statement(
         &self,
+        target: QueryTarget<'_>,
         adapter: Arc<dyn DatabaseAdapter>,
         statement: &str,
     ) -> Result<StatementResult> {
-        adapter.execute_statement(statement).await
+        self.recorded(
+            target,
+            HistoryKind::Statement,
+            statement.to_string(),
+            adapter.execute_statement(statement),
+            |result, entry| entry.affected_rows = Some(result.affected_rows as i64),
+        )
+        .await
     }
 
     /// Execute multiple statements in a transaction
     pub async fn execute_transaction(
         &self,
+        target: QueryTarget<'_>,
         adapter: Arc<dyn DatabaseAdapter>,
         statements: Vec<String>,
     ) -> Result<Vec<StatementResult>> {
-        adapter.execute_transaction(statements).await
+        let text = statements.join(";\n");
+        self.recorded(
+            target,
+            HistoryKind::Transaction,
+            text,
+            adapter.execute_transaction(statements),
+            |results, entry| {
+                let total: u64 = results.iter().map(|r| r.affected_rows).sum();
+                entry.affected_rows = Some(total as i64);
+            },
+        )
+        .await
+    }
+
+    /// Run a query without a row cap, streaming its rows into `sink` (an
+    /// export). Recorded like a query, with the number of rows streamed.
+    pub async fn stream_query(
+        &self,
+        target: QueryTarget<'_>,
+        adapter: Arc<dyn DatabaseAdapter>,
+        query: &str,
+        query_id: Option<&str>,
+        sink: &mut dyn RowSink,
+    ) -> Result<u64> {
+        self.recorded(
+            target,
+            HistoryKind::Query,
+            query.to_string(),
+            adapter.stream_query(query, query_id, sink),
+            |sent, entry| entry.row_count = Some(*sent as i64),
+        )
+        .await
+    }
+
+    /// [`stream_query`](Self::stream_query) registered as stream `query_id`,
+    /// so `cancel_stream` stops the export at its next batch (and the
+    /// adapter can cancel it server-side).
+    pub async fn export_query(
+        &self,
+        target: QueryTarget<'_>,
+        adapter: Arc<dyn DatabaseAdapter>,
+        query: &str,
+        query_id: &str,
+        sink: &mut dyn RowSink,
+    ) -> Result<u64> {
+        let mut export = self.streams.open_export(query_id, sink)?;
+        self.stream_query(target, adapter, query, Some(query_id), &mut export)
+            .await
+    }
+
+    /// Every row of a table browse into `sink`, cancellable like
+    /// [`export_query`](Self::export_query). Not recorded in the history.
+    pub async fn export_table(
+        &self,
+        adapter: Arc<dyn DatabaseAdapter>,
+        request: TableDataRequest,
+        query_id: &str,
+        sink: &mut dyn RowSink,
+    ) -> Result<u64> {
+        let mut export = self.streams.open_export(query_id, sink)?;
+        adapter.stream_table(request, &mut export).await
+    }
+
+    /// Stream a query's rows to the frontend in batches through `send`,
+    /// pausing whenever [`STREAM_WINDOW_BATCHES`](super::stream::STREAM_WINDOW_BATCHES)
+    /// batches are still unacknowledged. Cancelling ends it with the rows
+    /// sent so far instead of an error.
+    pub async fn stream_to_frontend(
+        &self,
+        target: QueryTarget<'_>,
+        adapter: Arc<dyn DatabaseAdapter>,
+        query: &str,
+        query_id: &str,
+        send: ProgressFn<QueryStreamEvent>,
+    ) -> Result<QueryStreamSummary> {
+        let mut stream = self.streams.open(query_id, send)?;
+        let clock = Instant::now();
+
+        let cancelled = match self
+            .stream_query(target, adapter, query, Some(query_id), &mut stream)
+            .await
+        {
+            Ok(_) => false,
+            // Closed window or pg_cancel_backend/KILL QUERY: either way the
+            // user asked for it.
+            Err(_) if stream.cancelled() => true,
+            Err(e) => return Err(e),
+        };
+
+        Ok(QueryStreamSummary {
+            row_count: stream.rows_sent(),
+            execution_time_ms: clock.elapsed().as_millis() as u64,
+            cancelled,
+        })
     }
-}
 
-impl Default for QueryService {
-    fn default() -> Self {
-        Self::new()
+    /// The frontend rendered `batches` more batches of stream `query_id`.
+    pub fn ack_stream(&self, query_id: &str, batches: usize) {
+        self.streams.ack(query_id, batches);
     }
-}
\ No newline at end of file
+
+    /// Stop stream `query_id` at its next batch; `false` if it isn't running.
+    pub fn cancel_stream(&self, query_id: &str) -> bool {
+        self.streams.cancel(query_id)
+    }
+
+    /// Runs `execution`, timing it, and writes the outcome to the history.
+    async fn recorded<T>(
+        &self,
+        target: QueryTarget<'_>,
+        kind: HistoryKind,
+        query: String,
+        execution: impl Future<Output = Result<T>>,
+        fill: impl FnOnce(&T, &mut NewHistoryEntry),
+    ) -> Result<T> {
+        let started_at = chrono::Utc::now().timestamp_millis();
+        let clock = Instant::now();
+        let result = execution.await;
+
+        let mut entry = NewHistoryEntry {
+            server_id: target.server_id,
+            database: target.database.to_string(),
+            kind,
+            query,
+            started_at,
+            duration_ms: clock.elapsed().as_millis() as i64,
+            row_count: None,
+            affected_rows: None,
+            error_code: None,
+            error_message: None,
+        };
+        match &result {
+            Ok(value) => fill(value, &mut entry),
+            Err(e) => {
+                entry.error_code = Some(e.code().to_string());
+                entry.error_message = Some(e.to_string());
+            }
+        }
+
+        // Falha ao gravar o histórico não pode derrubar a query do usuário.
+        let _ = query_history::record(&self.storage, &entry);
+
+        result
+    }
+}
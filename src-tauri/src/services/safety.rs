@@ -0,0 +1,97 @@
+use crate::error::{Error, Result};
+use crate::models::{Server, StatementWarning};
+
+/// Apply the write policy of `server` to a write that is about to run: a
+/// read-only server refuses it, and one in "confirm writes" mode wants the
+/// command repeated with `confirmed: true` (after the user said yes).
+pub fn check_write(server: &Server, confirmed: bool) -> Result<()> {
+    if server.read_only {
+        return Err(Error::ReadOnly(format!(
+            "{} is in read-only mode; writes are disabled",
+            server.name
+        )));
+    }
+    if server.confirm_writes && !confirmed {
+        return Err(Error::ConfirmationRequired(format!(
+            "{} asks for confirmation before writes",
+            server.name
+        )));
+    }
+    Ok(())
+}
+
+/// A query whose analysis found something at `warning` level or above only
+/// runs confirmed; the message lists what the user is confirming.
+pub fn check_warnings(warnings: &[StatementWarning], confirmed: bool) -> Result<()> {
+    if confirmed {
+        return Ok(());
+    }
+    let pending: Vec<&str> = warnings
+        .iter()
+        .filter(|w| w.needs_confirmation())
+        .map(|w| w.message.as_str())
+        .collect();
+    if pending.is_empty() {
+        return Ok(());
+    }
+    Err(Error::ConfirmationRequired(pending.join("; ")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{DatabaseType, WarningKind, WarningSeverity};
+
+    fn server(read_only: bool, confirm_writes: bool) -> Server {
+        Server {
+            id: Some(1),
+            name: "prod".into(),
+            db_type: DatabaseType::Postgres,
+            host: "localhost".into(),
+            port: 5432,
+            username: "app".into(),
+            password: String::new(),
+            default_database: None,
+            tls: Default::default(),
+            connection_uri: None,
+            ssh_tunnel: None,
+            read_only,
+            confirm_writes,
+            created_at: 0,
+        }
+    }
+
+    #[test]
+    fn policy_per_mode() {
+        assert!(check_write(&server(false, false), false).is_ok());
+
+        let confirm = server(false, true);
+        assert!(matches!(
+            check_write(&confirm, false),
+            Err(Error::ConfirmationRequired(_))
+        ));
+        assert!(check_write(&confirm, true).is_ok());
+
+        // Confirming doesn't get past read-only.
+        assert!(matches!(check_write(&server(true, true), true), Err(Error::ReadOnly(_))));
+    }
+
+    #[test]
+    fn only_warning_and_danger_need_confirmation() {
+        let warning = |severity| StatementWarning {
+            kind: WarningKind::Drop,
+            severity,
+            statement_index: 0,
+            object: None,
+            message: "DROP TABLE users".into(),
+        };
+
+        assert!(check_warnings(&[warning(WarningSeverity::Info)], false).is_ok());
+        let danger = [warning(WarningSeverity::Info), warning(WarningSeverity::Danger)];
+        match check_warnings(&danger, false) {
+            Err(Error::ConfirmationRequired(message)) => assert_eq!(message, "DROP TABLE users"),
+            other => panic!("expected a confirmation, got {other:?}"),
+        }
+        assert!(check_warnings(&danger, true).is_ok());
+    }
+}
@@ -0,0 +1,749 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::adapters::postgres::util::quote_ident;
+use crate::models::{
+    ColumnDiff, ColumnSnapshot, ConstraintSnapshot, DefinitionDiff, DiffChange, FunctionSnapshot,
+    SchemaDiff, SchemaSnapshot, TableDiff, TableSnapshot, ViewSnapshot,
+};
+
+/// What differs between `source` and `target`, and the Postgres script that
+/// turns `target` (schema `target_schema`) into `source`. Objects are matched
+/// by name (functions by name and arguments): a rename shows up as a drop
+/// plus a create.
+pub fn diff_schemas(
+    source: &SchemaSnapshot,
+    target: &SchemaSnapshot,
+    target_schema: &str,
+) -> SchemaDiff {
+    let source_tables = by_name(&source.tables, |t| t.name.clone());
+    let target_tables = by_name(&target.tables, |t| t.name.clone());
+
+    let mut tables = Vec::new();
+    for name in union(&source_tables, &target_tables) {
+        let diff = match (source_tables.get(&name), target_tables.get(&name)) {
+            (Some(_), None) => whole_table(name, DiffChange::Added),
+            (None, Some(_)) => whole_table(name, DiffChange::Removed),
+            (Some(from), Some(to)) => {
+                let diff = TableDiff {
+                    name,
+                    change: DiffChange::Changed,
+                    columns: column_diffs(&from.columns, &to.columns),
+                    indexes: definition_diffs(
+                        by_name(&from.indexes, |i| i.name.clone()),
+                        by_name(&to.indexes, |i| i.name.clone()),
+                        |i| i.definition.clone(),
+                    ),
+                    constraints: definition_diffs(
+                        by_name(&from.constraints, |c| c.name.clone()),
+                        by_name(&to.constraints, |c| c.name.clone()),
+                        |c| c.definition.clone(),
+                    ),
+                };
+                if diff.columns.is_empty() && diff.indexes.is_empty() && diff.constraints.is_empty()
+                {
+                    continue;
+                }
+                diff
+            }
+            (None, None) => continue,
+        };
+        tables.push(diff);
+    }
+
+    let views = definition_diffs(
+        by_name(&source.views, |v| v.name.clone()),
+        by_name(&target.views, |v| v.name.clone()),
+        create_view,
+    );
+    let functions = definition_diffs(
+        by_name(&source.functions, signature),
+        by_name(&target.functions, signature),
+        |f| f.definition.clone(),
+    );
+
+    let mut diff = SchemaDiff {
+        tables,
+        views,
+        functions,
+        migration: Vec::new(),
+    };
+    if !(diff.tables.is_empty() && diff.views.is_empty() && diff.functions.is_empty()) {
+        diff.migration = migration(source, target, &diff, target_schema);
+    }
+    diff
+}
+
+/// An added or removed table: its contents are in the script, not the diff.
+fn whole_table(name: String, change: DiffChange) -> TableDiff {
+    TableDiff {
+        name,
+        change,
+        columns: Vec::new(),
+        indexes: Vec::new(),
+        constraints: Vec::new(),
+    }
+}
+
+/// Columns in source order, then the ones only the target has.
+fn column_diffs(source: &[ColumnSnapshot], target: &[ColumnSnapshot]) -> Vec<ColumnDiff> {
+    let mut diffs = Vec::new();
+    for column in source {
+        let other = target.iter().find(|c| c.name == column.name);
+        let change = match other {
+            None => DiffChange::Added,
+            Some(other) if other != column => DiffChange::Changed,
+            Some(_) => continue,
+        };
+        diffs.push(ColumnDiff {
+            name: column.name.clone(),
+            change,
+            source: Some(column.clone()),
+            target: other.cloned(),
+        });
+    }
+    for column in target {
+        if !source.iter().any(|c| c.name == column.name) {
+            diffs.push(ColumnDiff {
+                name: column.name.clone(),
+                change: DiffChange::Removed,
+                source: None,
+                target: Some(column.clone()),
+            });
+        }
+    }
+    diffs
+}
+
+fn definition_diffs<T>(
+    source: BTreeMap<String, &T>,
+    target: BTreeMap<String, &T>,
+    definition: impl Fn(&T) -> String,
+) -> Vec<DefinitionDiff> {
+    union(&source, &target)
+        .into_iter()
+        .filter_map(|name| {
+            let from = source.get(&name).map(|o| definition(o));
+            let to = target.get(&name).map(|o| definition(o));
+            let change = match (&from, &to) {
+                (Some(_), None) => DiffChange::Added,
+                (None, Some(_)) => DiffChange::Removed,
+                (Some(a), Some(b)) if a != b => DiffChange::Changed,
+                _ => return None,
+            };
+            Some(DefinitionDiff {
+                name,
+                change,
+                source: from,
+                target: to,
+            })
+        })
+        .collect()
+}
+
+/// Drops before creates, so nothing collides or depends on what goes away:
+/// views, then foreign keys and other constraints, then indexes; functions
+/// before the tables whose defaults may call them; new foreign keys only
+/// once every table and unique constraint they point at exists; views last.
+/// Unchanged views that read from a column changing type or going away (or
+/// from a view that is dropped) are dropped and recreated along with them.
+fn migration(
+    source: &SchemaSnapshot,
+    target: &SchemaSnapshot,
+    diff: &SchemaDiff,
+    target_schema: &str,
+) -> Vec<String> {
+    let source_tables = by_name(&source.tables, |t| t.name.clone());
+    let target_tables = by_name(&target.tables, |t| t.name.clone());
+    let target_views = by_name(&target.views, |v| v.name.clone());
+    let source_views = by_name(&source.views, |v| v.name.clone());
+    let source_functions = by_name(&source.functions, signature);
+    let target_functions = by_name(&target.functions, signature);
+    let changed = |d: &&DefinitionDiff| d.change != DiffChange::Removed;
+    let dropped = |d: &&DefinitionDiff| d.change != DiffChange::Added;
+
+    let mut script = vec![format!(
+        "SET search_path TO {};",
+        quote_ident(target_schema)
+    )];
+
+    let rebuilt = dependent_views(&target_views, diff);
+    let view_drops: BTreeSet<String> = diff
+        .views
+        .iter()
+        .filter(dropped)
+        .map(|v| v.name.clone())
+        .chain(rebuilt.iter().cloned())
+        .collect();
+    // Quem depende sai antes.
+    for name in dependency_order(&view_drops, &target_views).iter().rev() {
+        let materialized = target_views[name].materialized;
+        script.push(format!(
+            "DROP {}VIEW {};",
+            if materialized { "MATERIALIZED " } else { "" },
+            quote_ident(name)
+        ));
+    }
+
+    let altered: Vec<&TableDiff> = diff
+        .tables
+        .iter()
+        .filter(|t| t.change == DiffChange::Changed)
+        .collect();
+    let mut constraint_drops: Vec<(bool, String)> = Vec::new();
+    for table in &altered {
+        let kinds = by_name(&target_tables[&table.name].constraints, |c| c.name.clone());
+        for constraint in table.constraints.iter().filter(dropped) {
+            constraint_drops.push((
+                kinds[&constraint.name].kind != "f",
+                format!(
+                    "ALTER TABLE {} DROP CONSTRAINT {};",
+                    quote_ident(&table.name),
+                    quote_ident(&constraint.name)
+                ),
+            ));
+        }
+    }
+    // FKs primeiro: podem depender de uma PK/unique que também sai.
+    constraint_drops.sort_by_key(|(not_fk, _)| *not_fk);
+    script.extend(constraint_drops.into_iter().map(|(_, sql)| sql));
+    for table in &altered {
+        for index in table.indexes.iter().filter(dropped) {
+            script.push(format!("DROP INDEX {};", quote_ident(&index.name)));
+        }
+    }
+
+    for function in diff.functions.iter().filter(changed) {
+        let from = source_functions[&function.name];
+        // CREATE OR REPLACE can't change the result type, the argument
+        // names or a function into a procedure.
+        if let Some(to) = target_functions.get(&function.name).filter(|to| {
+            to.result != from.result
+                || to.arguments != from.arguments
+                || to.is_procedure != from.is_procedure
+        }) {
+            script.push(drop_function(to));
+        }
+        script.push(format!("{};", create_function(from, target_schema)));
+    }
+
+    let added: Vec<&TableSnapshot> = diff
+        .tables
+        .iter()
+        .filter(|t| t.change == DiffChange::Added)
+        .map(|t| source_tables[&t.name])
+        .collect();
+    let mut foreign_keys = Vec::new();
+    for table in &added {
+        let mut lines: Vec<String> = table.columns.iter().map(column_definition).collect();
+        for constraint in &table.constraints {
+            if constraint.kind == "f" {
+                foreign_keys.push(add_constraint(&table.name, constraint));
+            } else {
+                lines.push(format!(
+                    "CONSTRAINT {} {}",
+                    quote_ident(&constraint.name),
+                    constraint.definition
+                ));
+            }
+        }
+        script.push(format!(
+            "CREATE TABLE {} (\n    {}\n);",
+            quote_ident(&table.name),
+            lines.join(",\n    ")
+        ));
+    }
+
+    for table in &altered {
+        for column in &table.columns {
+            script.extend(alter_column(&table.name, column));
+        }
+    }
+    for table in &altered {
+        let constraints = by_name(&source_tables[&table.name].constraints, |c| c.name.clone());
+        for diff in table.constraints.iter().filter(changed) {
+            let constraint = constraints[&diff.name];
+            let sql = add_constraint(&table.name, constraint);
+            if constraint.kind == "f" {
+                foreign_keys.push(sql);
+            } else {
+                script.push(sql);
+            }
+        }
+    }
+
+    for table in &added {
+        script.extend(table.indexes.iter().map(|i| format!("{};", i.definition)));
+    }
+    for table in &altered {
+        for index in table.indexes.iter().filter(changed) {
+            script.extend(index.source.as_ref().map(|sql| format!("{sql};")));
+        }
+    }
+    script.extend(foreign_keys);
+
+    let view_creates: BTreeSet<String> = diff
+        .views
+        .iter()
+        .filter(changed)
+        .map(|v| v.name.clone())
+        .chain(rebuilt)
+        .collect();
+    for name in dependency_order(&view_creates, &source_views) {
+        script.push(format!("{};", create_view(source_views[&name])));
+    }
+
+    let removed: Vec<String> = diff
+        .tables
+        .iter()
+        .filter(|t| t.change == DiffChange::Removed)
+        .map(|t| quote_ident(&t.name))
+        .collect();
+    if !removed.is_empty() {
+        // Um DROP só: FKs entre as tabelas removidas não atrapalham.
+        script.push(format!("DROP TABLE {};", removed.join(", ")));
+    }
+    for function in diff
+        .functions
+        .iter()
+        .filter(|f| f.change == DiffChange::Removed)
+    {
+        script.push(drop_function(target_functions[&function.name]));
+    }
+    script
+}
+
+/// Views the diff leaves alone but that can't stay while the script runs:
+/// they read, directly or through other views, from a table whose column
+/// changes type or is dropped, or from a view that is dropped.
+fn dependent_views(
+    target_views: &BTreeMap<String, &ViewSnapshot>,
+    diff: &SchemaDiff,
+) -> BTreeSet<String> {
+    let mut blocked: BTreeSet<String> = diff
+        .tables
+        .iter()
+        .filter(|t| {
+            t.change == DiffChange::Changed
+                && t.columns.iter().any(|c| match (&c.source, &c.target) {
+                    (Some(from), Some(to)) => from.data_type != to.data_type,
+                    (None, Some(_)) => true,
+                    _ => false,
+                })
+        })
+        .map(|t| t.name.clone())
+        .chain(
+            diff.views
+                .iter()
+                .filter(|v| v.change != DiffChange::Added)
+                .map(|v| v.name.clone()),
+        )
+        .collect();
+
+    let mut grew = true;
+    while grew {
+        grew = false;
+        for view in target_views.values() {
+            if !blocked.contains(&view.name)
+                && view.dependencies.iter().any(|d| blocked.contains(d))
+            {
+                blocked.insert(view.name.clone());
+                grew = true;
+            }
+        }
+    }
+
+    let in_diff: BTreeSet<&String> = diff.views.iter().map(|v| &v.name).collect();
+    blocked
+        .into_iter()
+        .filter(|name| target_views.contains_key(name) && !in_diff.contains(name))
+        .collect()
+}
+
+/// `names` with every view after the ones of the set it reads from.
+fn dependency_order(
+    names: &BTreeSet<String>,
+    views: &BTreeMap<String, &ViewSnapshot>,
+) -> Vec<String> {
+    fn visit(
+        name: &str,
+        names: &BTreeSet<String>,
+        views: &BTreeMap<String, &ViewSnapshot>,
+        order: &mut Vec<String>,
+    ) {
+        if order.iter().any(|n| n == name) {
+            return;
+        }
+        if let Some(view) = views.get(name) {
+            for dependency in view.dependencies.iter().filter(|d| names.contains(*d)) {
+                visit(dependency, names, views, order);
+            }
+        }
+        order.push(name.to_string());
+    }
+
+    let mut order = Vec::new();
+    for name in names {
+        visit(name, names, views, &mut order);
+    }
+    order
+}
+
+fn drop_function(function: &FunctionSnapshot) -> String {
+    format!(
+        "DROP {} {}({});",
+        function_keyword(function),
+        quote_ident(&function.name),
+        function.arguments
+    )
+}
+
+/// The definition with the target schema in the header: `pg_get_functiondef`
+/// names the schema it read from, which the snapshot leaves out, and
+/// relying on `search_path` alone would be one `SET` away from creating it
+/// elsewhere.
+fn create_function(function: &FunctionSnapshot, target_schema: &str) -> String {
+    let keyword = format!("{} ", function_keyword(function));
+    function.definition.replacen(
+        &keyword,
+        &format!("{keyword}{}.", quote_ident(target_schema)),
+        1,
+    )
+}
+
+fn function_keyword(function: &FunctionSnapshot) -> &'static str {
+    if function.is_procedure {
+        "PROCEDURE"
+    } else {
+        "FUNCTION"
+    }
+}
+
+/// `ALTER TABLE` statements that take the target column to the source one.
+fn alter_column(table: &str, diff: &ColumnDiff) -> Vec<String> {
+    let alter = format!("ALTER TABLE {}", quote_ident(table));
+    let name = quote_ident(&diff.name);
+    let (from, to) = match (&diff.source, &diff.target) {
+        (Some(from), None) => {
+            return vec![format!("{alter} ADD COLUMN {};", column_definition(from))]
+        }
+        (None, Some(_)) => return vec![format!("{alter} DROP COLUMN {name};")],
+        (Some(from), Some(to)) => (from, to),
+        (None, None) => return Vec::new(),
+    };
+
+    let mut statements = Vec::new();
+    let type_changed = from.data_type != to.data_type;
+    let default_changed = type_changed || from.default_value != to.default_value;
+    // O default antigo pode não converter para o tipo novo.
+    if default_changed && to.default_value.is_some() {
+        statements.push(format!("{alter} ALTER COLUMN {name} DROP DEFAULT;"));
+    }
+    if type_changed {
+        statements.push(format!(
+            "{alter} ALTER COLUMN {name} TYPE {ty} USING {name}::{ty};",
+            ty = from.data_type
+        ));
+    }
+    if let Some(default) = from.default_value.as_ref().filter(|_| default_changed) {
+        statements.push(format!(
+            "{alter} ALTER COLUMN {name} SET DEFAULT {default};"
+        ));
+    }
+    if from.nullable != to.nullable {
+        let action = if from.nullable { "DROP" } else { "SET" };
+        statements.push(format!("{alter} ALTER COLUMN {name} {action} NOT NULL;"));
+    }
+    statements
+}
+
+fn column_definition(column: &ColumnSnapshot) -> String {
+    let mut definition = format!("{} {}", quote_ident(&column.name), column.data_type);
+    if let Some(default) = &column.default_value {
+        definition.push_str(&format!(" DEFAULT {default}"));
+    }
+    if !column.nullable {
+        definition.push_str(" NOT NULL");
+    }
+    definition
+}
+
+fn add_constraint(table: &str, constraint: &ConstraintSnapshot) -> String {
+    format!(
+        "ALTER TABLE {} ADD CONSTRAINT {} {};",
+        quote_ident(table),
+        quote_ident(&constraint.name),
+        constraint.definition
+    )
+}
+
+fn create_view(view: &ViewSnapshot) -> String {
+    if view.materialized {
+        format!(
+            "CREATE MATERIALIZED VIEW {} AS\n{}",
+            quote_ident(&view.name),
+            view.definition
+        )
+    } else {
+        format!(
+            "CREATE VIEW {} AS\n{}",
+            quote_ident(&view.name),
+            view.definition
+        )
+    }
+}
+
+fn signature(function: &FunctionSnapshot) -> String {
+    format!("{}({})", function.name, function.argument_types)
+}
+
+fn by_name<T>(items: &[T], key: impl Fn(&T) -> String) -> BTreeMap<String, &T> {
+    items.iter().map(|item| (key(item), item)).collect()
+}
+
+fn union<T>(a: &BTreeMap<String, T>, b: &BTreeMap<String, T>) -> BTreeSet<String> {
+    a.keys().chain(b.keys()).cloned().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::IndexSnapshot;
+
+    fn column(name: &str, data_type: &str, nullable: bool) -> ColumnSnapshot {
+        ColumnSnapshot {
+            name: name.into(),
+            data_type: data_type.into(),
+            nullable,
+            default_value: None,
+        }
+    }
+
+    fn table(name: &str, columns: Vec<ColumnSnapshot>) -> TableSnapshot {
+        TableSnapshot {
+            name: name.into(),
+            columns,
+            indexes: Vec::new(),
+            constraints: vec![ConstraintSnapshot {
+                name: format!("{name}_pkey"),
+                kind: "p".into(),
+                definition: "PRIMARY KEY (id)".into(),
+            }],
+        }
+    }
+
+    fn function(name: &str, arguments: &str, result: &str, body: &str) -> FunctionSnapshot {
+        FunctionSnapshot {
+            name: name.into(),
+            arguments: arguments.into(),
+            argument_types: arguments
+                .split(", ")
+                .filter_map(|argument| argument.split_once(' ').map(|(_, ty)| ty))
+                .collect::<Vec<_>>()
+                .join(", "),
+            result: Some(result.into()),
+            is_procedure: false,
+            definition: format!(
+                "CREATE OR REPLACE FUNCTION {name}({arguments})\n RETURNS {result}\n{body}"
+            ),
+        }
+    }
+
+    #[test]
+    fn identical_schemas_have_no_migration() {
+        let schema = SchemaSnapshot {
+            tables: vec![table("users", vec![column("id", "integer", false)])],
+            ..Default::default()
+        };
+        let diff = diff_schemas(&schema, &schema.clone(), "public");
+        assert!(diff.tables.is_empty() && diff.views.is_empty() && diff.functions.is_empty());
+        assert!(diff.migration.is_empty());
+    }
+
+    #[test]
+    fn migration_orders_drops_creates_and_foreign_keys() {
+        let mut orders = table(
+            "orders",
+            vec![
+                column("id", "integer", false),
+                column("user_id", "integer", true),
+            ],
+        );
+        orders.constraints.push(ConstraintSnapshot {
+            name: "orders_user_fk".into(),
+            kind: "f".into(),
+            definition: "FOREIGN KEY (user_id) REFERENCES users(id)".into(),
+        });
+        orders.indexes.push(IndexSnapshot {
+            name: "orders_user_idx".into(),
+            definition: "CREATE INDEX orders_user_idx ON orders USING btree (user_id)".into(),
+        });
+        let source = SchemaSnapshot {
+            tables: vec![
+                table(
+                    "users",
+                    vec![
+                        column("id", "integer", false),
+                        column("email", "text", false),
+                    ],
+                ),
+                orders,
+            ],
+            views: vec![ViewSnapshot {
+                name: "emails".into(),
+                materialized: false,
+                definition: " SELECT email\n   FROM users".into(),
+                dependencies: vec!["users".into()],
+            }],
+            functions: Vec::new(),
+        };
+        let target = SchemaSnapshot {
+            tables: vec![
+                table(
+                    "users",
+                    vec![
+                        column("id", "integer", false),
+                        column("email", "character varying(100)", true),
+                        column("legacy", "text", true),
+                    ],
+                ),
+                table("old_audit", vec![column("id", "integer", false)]),
+            ],
+            views: vec![ViewSnapshot {
+                name: "emails".into(),
+                materialized: false,
+                definition: " SELECT email, legacy\n   FROM users".into(),
+                dependencies: vec!["users".into()],
+            }],
+            functions: vec![function("touch", "", "trigger", "...")],
+        };
+
+        let diff = diff_schemas(&source, &target, "staging");
+        let changes: Vec<(&str, DiffChange)> = diff
+            .tables
+            .iter()
+            .map(|t| (t.name.as_str(), t.change))
+            .collect();
+        assert_eq!(
+            changes,
+            [
+                ("old_audit", DiffChange::Removed),
+                ("orders", DiffChange::Added),
+                ("users", DiffChange::Changed),
+            ]
+        );
+        let users = &diff.tables[2];
+        assert_eq!(users.columns.len(), 2);
+        assert_eq!(users.columns[0].change, DiffChange::Changed);
+        assert_eq!(users.columns[1].change, DiffChange::Removed);
+        assert_eq!(diff.views[0].change, DiffChange::Changed);
+        assert_eq!(diff.functions[0].change, DiffChange::Removed);
+
+        let position = |needle: &str| {
+            diff.migration
+                .iter()
+                .position(|s| s.contains(needle))
+                .unwrap_or_else(|| panic!("{needle} not in {:#?}", diff.migration))
+        };
+        assert_eq!(diff.migration[0], "SET search_path TO \"staging\";");
+        assert!(position("DROP VIEW \"emails\"") < position("CREATE TABLE \"orders\""));
+        assert!(
+            position("CREATE TABLE \"orders\"") < position("ADD CONSTRAINT \"orders_user_fk\"")
+        );
+        assert!(position("CREATE INDEX orders_user_idx") < position("CREATE VIEW \"emails\""));
+        assert!(position("TYPE text USING \"email\"::text") < position("CREATE VIEW \"emails\""));
+        assert!(position("\"email\" SET NOT NULL") > 0);
+        assert!(position("DROP COLUMN \"legacy\"") > 0);
+        assert!(position("DROP TABLE \"old_audit\"") > position("CREATE VIEW \"emails\""));
+        assert_eq!(diff.migration.last().unwrap(), "DROP FUNCTION \"touch\"();");
+        assert!(!diff.migration[position("CREATE TABLE \"orders\"")].contains("FOREIGN KEY"));
+    }
+
+    #[test]
+    fn changed_functions_go_to_the_target_schema() {
+        let source = SchemaSnapshot {
+            functions: vec![
+                function("total", "a integer", "bigint", "body"),
+                function("label", "id integer", "text", "new body"),
+            ],
+            ..Default::default()
+        };
+        let target = SchemaSnapshot {
+            functions: vec![
+                function("total", "a integer", "integer", "body"),
+                function("label", "id integer", "text", "old body"),
+            ],
+            ..Default::default()
+        };
+
+        let diff = diff_schemas(&source, &target, "staging");
+        assert_eq!(
+            diff.migration[1..],
+            [
+                "CREATE OR REPLACE FUNCTION \"staging\".label(id integer)\n RETURNS text\nnew body;",
+                "DROP FUNCTION \"total\"(a integer);",
+                "CREATE OR REPLACE FUNCTION \"staging\".total(a integer)\n RETURNS bigint\nbody;",
+            ]
+        );
+
+        // Renamed argument: same function, dropped and created again
+        let renamed = SchemaSnapshot {
+            functions: vec![function("label", "key integer", "text", "old body")],
+            ..Default::default()
+        };
+        let label_only = SchemaSnapshot {
+            functions: vec![target.functions[1].clone()],
+            ..Default::default()
+        };
+        let diff = diff_schemas(&renamed, &label_only, "staging");
+        assert_eq!(diff.functions.len(), 1);
+        assert_eq!(diff.functions[0].change, DiffChange::Changed);
+        assert_eq!(diff.migration[1], "DROP FUNCTION \"label\"(id integer);");
+    }
+
+    #[test]
+    fn views_on_a_retyped_column_are_rebuilt() {
+        let view = |name: &str, definition: &str, dependencies: &[&str]| ViewSnapshot {
+            name: name.into(),
+            materialized: false,
+            definition: definition.into(),
+            dependencies: dependencies.iter().map(|d| d.to_string()).collect(),
+        };
+        let views = vec![
+            view("domains", " SELECT email FROM emails", &["emails"]),
+            view("emails", " SELECT email FROM users", &["users"]),
+            view("order_ids", " SELECT id FROM orders", &["orders"]),
+        ];
+        let source = SchemaSnapshot {
+            tables: vec![
+                table("users", vec![column("email", "text", false)]),
+                table("orders", vec![column("id", "integer", false)]),
+            ],
+            views: views.clone(),
+            functions: Vec::new(),
+        };
+        let target = SchemaSnapshot {
+            tables: vec![
+                table(
+                    "users",
+                    vec![column("email", "character varying(100)", false)],
+                ),
+                table("orders", vec![column("id", "integer", false)]),
+            ],
+            views,
+            functions: Vec::new(),
+        };
+
+        let diff = diff_schemas(&source, &target, "staging");
+        assert!(diff.views.is_empty());
+        assert_eq!(
+            diff.migration[1..],
+            [
+                "DROP VIEW \"domains\";",
+                "DROP VIEW \"emails\";",
+                "ALTER TABLE \"users\" ALTER COLUMN \"email\" TYPE text USING \"email\"::text;",
+                "CREATE VIEW \"emails\" AS\n SELECT email FROM users;",
+                "CREATE VIEW \"domains\" AS\n SELECT email FROM emails;",
+            ]
+        );
+    }
+}
@@ -0,0 +1,265 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use tokio::sync::Semaphore;
+
+use crate::adapters::RowSink;
+use crate::error::{Error, Result};
+use crate::models::{QueryColumnInfo, QueryStreamEvent};
+
+use super::ProgressFn;
+
+/// Lotes enviados ao front sem confirmação antes de o stream pausar. Com
+/// lotes de 1000 linhas, no máximo ~4000 linhas esperam na fila do IPC.
+pub const STREAM_WINDOW_BATCHES: usize = 4;
+
+/// Sem nenhum ack por este tempo o front sumiu (aba fechada sem
+/// `cancel_query`): o stream desiste e devolve a conexão ao pool.
+const ACK_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Streams em andamento, por query_id. Cada um tem uma janela de lotes (um
+/// semáforo): enviar um lote consome uma vaga, o ack do front devolve, e
+/// fechar o semáforo é o cancelamento — acorda o stream mesmo se estiver
+/// parado esperando ack.
+#[derive(Default)]
+pub struct StreamRegistry {
+    windows: Mutex<HashMap<String, Arc<Semaphore>>>,
+}
+
+impl StreamRegistry {
+    /// Register `query_id` and return the sink that feeds `send`. The entry
+    /// is removed when the sink is dropped.
+    pub fn open(
+        &self,
+        query_id: &str,
+        send: ProgressFn<QueryStreamEvent>,
+    ) -> Result<RowStream<'_>> {
+        Ok(RowStream {
+            registry: self,
+            query_id: query_id.to_string(),
+            window: self.register(query_id)?,
+            send,
+            batches: 0,
+            rows: 0,
+        })
+    }
+
+    /// Register `query_id` for rows that go to `sink` instead of the frontend
+    /// (an export): nothing to acknowledge, but [`cancel`](Self::cancel)
+    /// still stops it at its next batch.
+    pub fn open_export<'a>(
+        &'a self,
+        query_id: &str,
+        sink: &'a mut dyn RowSink,
+    ) -> Result<ExportStream<'a>> {
+        Ok(ExportStream {
+            registry: self,
+            query_id: query_id.to_string(),
+            window: self.register(query_id)?,
+            sink,
+        })
+    }
+
+    fn register(&self, query_id: &str) -> Result<Arc<Semaphore>> {
+        let window = Arc::new(Semaphore::new(STREAM_WINDOW_BATCHES));
+        let mut windows = self.windows.lock();
+        if windows.contains_key(query_id) {
+            return Err(Error::AlreadyExists(format!(
+                "A stream with query id {query_id} is already running"
+            )));
+        }
+        windows.insert(query_id.to_string(), Arc::clone(&window));
+        Ok(window)
+    }
+
+    /// The frontend consumed `batches` batches. A late ack (stream already
+    /// over) is ignored.
+    pub fn ack(&self, query_id: &str, batches: usize) {
+        if let Some(window) = self.windows.lock().get(query_id) {
+            // Nunca mais vagas que a janela, mesmo com acks repetidos.
+            let free = STREAM_WINDOW_BATCHES.saturating_sub(window.available_permits());
+            window.add_permits(batches.min(free));
+        }
+    }
+
+    /// Stop the stream at its next batch. `false` if no such stream is running.
+    pub fn cancel(&self, query_id: &str) -> bool {
+        match self.windows.lock().get(query_id) {
+            Some(window) => {
+                window.close();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// [`RowSink`] that hands each batch to the frontend, waiting for a free slot
+/// in the window first. That wait is the backpressure: the adapter only
+/// fetches the next batch (next FETCH, cursor batch or SCAN) after `rows`
+/// returns.
+pub struct RowStream<'a> {
+    registry: &'a StreamRegistry,
+    query_id: String,
+    window: Arc<Semaphore>,
+    send: ProgressFn<QueryStreamEvent>,
+    batches: u64,
+    rows: u64,
+}
+
+impl RowStream<'_> {
+    pub fn cancelled(&self) -> bool {
+        self.window.is_closed()
+    }
+
+    pub fn rows_sent(&self) -> u64 {
+        self.rows
+    }
+}
+
+#[async_trait]
+impl RowSink for RowStream<'_> {
+    async fn columns(&mut self, columns: &[QueryColumnInfo]) -> Result<()> {
+        (self.send)(QueryStreamEvent::Columns {
+            columns: columns.to_vec(),
+        });
+        Ok(())
+    }
+
+    async fn rows(&mut self, rows: Vec<Vec<Option<String>>>) -> Result<()> {
+        match tokio::time::timeout(ACK_TIMEOUT, self.window.acquire()).await {
+            Ok(Ok(slot)) => slot.forget(),
+            Ok(Err(_closed)) => return Err(Error::Query("Query cancelled".into())),
+            Err(_elapsed) => {
+                return Err(Error::Query(format!(
+                    "Stream abandoned: no batch acknowledged in {} s",
+                    ACK_TIMEOUT.as_secs()
+                )))
+            }
+        }
+
+        self.rows += rows.len() as u64;
+        (self.send)(QueryStreamEvent::Rows {
+            batch: self.batches,
+            rows,
+        });
+        self.batches += 1;
+        Ok(())
+    }
+}
+
+impl Drop for RowStream<'_> {
+    fn drop(&mut self) {
+        self.registry.windows.lock().remove(&self.query_id);
+    }
+}
+
+/// [`RowSink`] that passes every batch on to an export file until the
+/// stream is cancelled. Only the window's closing is used: an export is
+/// paced by the disk, not by acks.
+pub struct ExportStream<'a> {
+    registry: &'a StreamRegistry,
+    query_id: String,
+    window: Arc<Semaphore>,
+    sink: &'a mut dyn RowSink,
+}
+
+#[async_trait]
+impl RowSink for ExportStream<'_> {
+    async fn columns(&mut self, columns: &[QueryColumnInfo]) -> Result<()> {
+        self.sink.columns(columns).await
+    }
+
+    async fn rows(&mut self, rows: Vec<Vec<Option<String>>>) -> Result<()> {
+        if self.window.is_closed() {
+            return Err(Error::Query("Export cancelled".into()));
+        }
+        self.sink.rows(rows).await
+    }
+}
+
+impl Drop for ExportStream<'_> {
+    fn drop(&mut self) {
+        self.registry.windows.lock().remove(&self.query_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    fn counting() -> (Arc<AtomicU64>, ProgressFn<QueryStreamEvent>) {
+        let sent = Arc::new(AtomicU64::new(0));
+        let counter = Arc::clone(&sent);
+        let send: ProgressFn<QueryStreamEvent> = Box::new(move |event| {
+            if let QueryStreamEvent::Rows { .. } = event {
+                counter.fetch_add(1, Ordering::SeqCst);
+            }
+        });
+        (sent, send)
+    }
+
+    #[tokio::test]
+    async fn window_pauses_until_ack_and_cancel_wakes_it() {
+        let registry = StreamRegistry::default();
+        let (sent, send) = counting();
+        let mut stream = registry.open("q1", send).unwrap();
+        assert!(registry.open("q1", Box::new(|_| {})).is_err());
+
+        for _ in 0..STREAM_WINDOW_BATCHES {
+            stream.rows(vec![vec![None]]).await.unwrap();
+        }
+        let blocked = tokio::time::timeout(Duration::from_millis(20), stream.rows(vec![])).await;
+        assert!(blocked.is_err(), "window full: the next batch must wait");
+
+        registry.ack("q1", 1);
+        stream.rows(vec![vec![None]]).await.unwrap();
+        assert_eq!(
+            sent.load(Ordering::SeqCst),
+            STREAM_WINDOW_BATCHES as u64 + 1
+        );
+
+        assert!(registry.cancel("q1"));
+        assert!(stream.rows(vec![]).await.is_err());
+        assert!(stream.cancelled());
+        assert_eq!(stream.rows_sent(), STREAM_WINDOW_BATCHES as u64 + 1);
+
+        drop(stream);
+        assert!(!registry.cancel("q1"));
+    }
+
+    struct Counter(u64);
+
+    #[async_trait]
+    impl RowSink for Counter {
+        async fn columns(&mut self, _columns: &[QueryColumnInfo]) -> Result<()> {
+            Ok(())
+        }
+
+        async fn rows(&mut self, rows: Vec<Vec<Option<String>>>) -> Result<()> {
+            self.0 += rows.len() as u64;
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn export_runs_without_acks_until_cancelled() {
+        let registry = StreamRegistry::default();
+        let mut counter = Counter(0);
+        let mut export = registry.open_export("e1", &mut counter).unwrap();
+
+        for _ in 0..STREAM_WINDOW_BATCHES * 2 {
+            export.rows(vec![vec![None]]).await.unwrap();
+        }
+        assert!(registry.cancel("e1"));
+        assert!(export.rows(vec![vec![None]]).await.is_err());
+
+        drop(export);
+        assert!(!registry.cancel("e1"));
+        assert_eq!(counter.0, STREAM_WINDOW_BATCHES as u64 * 2);
+    }
+}
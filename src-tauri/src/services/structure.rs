@@ -1,67 +1,251 @@
+use std::future::Future;
 use std::sync::Arc;
+use std::time::Duration;
+
+use parking_lot::Mutex;
+use rusqlite::Connection;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 
 use crate::adapters::DatabaseAdapter;
 use crate::error::Result;
-use crate::models::{ColumnInfo, DatabaseInfo, IndexInfo, SchemaInfo, TableInfo};
+use crate::models::{
+    ColumnInfo, ConnectionId, ConstraintInfo, DatabaseInfo, DatabaseStructure, ForeignKeyInfo,
+    IndexInfo, ObjectMatch, ObjectSearchRequest, SchemaInfo, StructureChange, TableInfo,
+};
+
+use super::structure_cache::{CacheKey, CachePath, StructureCache};
+
+/// How long a listing is served from the cache before going back to the
+/// database. DDL run from the app invalidates sooner; this bounds how long a
+/// change made by someone else goes unnoticed (short of a refresh).
+pub const STRUCTURE_CACHE_TTL: Duration = Duration::from_secs(10 * 60);
 
 /// Structure service - handles database metadata/structure
 ///
-/// Currently a thin wrapper, but can be extended for:
-/// - Structure caching
-/// - Change detection
-/// - Background refresh
+/// Listings are cached per connection and object path (see
+/// [`StructureCache`]), so the tree and autocompletion don't hit the
+/// database (or, in Mongo, sample documents) on every expand. DDL run
+/// through the app drops the entries it touched; `refresh` drops the rest.
 pub struct StructureService {
-    // Future: structure cache, refresh scheduling, etc.
+    cache: StructureCache,
 }
 
 impl StructureService {
-    pub fn new() -> Self {
-        Self {}
+    /// `storage` (app.db) keeps the cache across restarts; `None` = memory only.
+    pub fn new(storage: Option<Arc<Mutex<Connection>>>) -> Self {
+        Self {
+            cache: StructureCache::new(storage, STRUCTURE_CACHE_TTL),
+        }
     }
 
     pub async fn list_databases(
         &self,
+        connection: ConnectionId,
         adapter: Arc<dyn DatabaseAdapter>,
     ) -> Result<Vec<DatabaseInfo>> {
-        adapter.list_databases().await
+        self.cached(connection, CachePath::Databases, adapter.list_databases())
+            .await
     }
 
     pub async fn list_schemas(
         &self,
+        connection: ConnectionId,
         adapter: Arc<dyn DatabaseAdapter>,
     ) -> Result<Vec<SchemaInfo>> {
-        adapter.list_schemas().await
+        self.cached(connection, CachePath::Schemas, adapter.list_schemas())
+            .await
     }
 
     pub async fn list_tables(
         &self,
+        connection: ConnectionId,
         adapter: Arc<dyn DatabaseAdapter>,
         schema: &str,
     ) -> Result<Vec<TableInfo>> {
-        adapter.list_tables(schema).await
+        let path = CachePath::Tables {
+            schema: schema.to_string(),
+        };
+        self.cached(connection, path, adapter.list_tables(schema))
+            .await
     }
 
     pub async fn list_columns(
         &self,
+        connection: ConnectionId,
         adapter: Arc<dyn DatabaseAdapter>,
         schema: &str,
         table: &str,
     ) -> Result<Vec<ColumnInfo>> {
-        adapter.list_columns(schema, table).await
+        let path = CachePath::Columns {
+            schema: schema.to_string(),
+            table: table.to_string(),
+        };
+        self.cached(connection, path, adapter.list_columns(schema, table))
+            .await
     }
 
     pub async fn list_indexes(
         &self,
+        connection: ConnectionId,
         adapter: Arc<dyn DatabaseAdapter>,
         schema: &str,
         table: &str,
     ) -> Result<Vec<IndexInfo>> {
-        adapter.list_indexes(schema, table).await
+        let path = CachePath::Indexes {
+            schema: schema.to_string(),
+            table: table.to_string(),
+        };
+        self.cached(connection, path, adapter.list_indexes(schema, table))
+            .await
+    }
+
+    pub async fn list_foreign_keys(
+        &self,
+        connection: ConnectionId,
+        adapter: Arc<dyn DatabaseAdapter>,
+        schema: &str,
+        table: &str,
+    ) -> Result<Vec<ForeignKeyInfo>> {
+        let path = CachePath::ForeignKeys {
+            schema: schema.to_string(),
+            table: table.to_string(),
+        };
+        self.cached(connection, path, adapter.list_foreign_keys(schema, table))
+            .await
+    }
+
+    pub async fn list_constraints(
+        &self,
+        connection: ConnectionId,
+        adapter: Arc<dyn DatabaseAdapter>,
+        schema: &str,
+        table: &str,
+    ) -> Result<Vec<ConstraintInfo>> {
+        let path = CachePath::Constraints {
+            schema: schema.to_string(),
+            table: table.to_string(),
+        };
+        self.cached(connection, path, adapter.list_constraints(schema, table))
+            .await
+    }
+
+    pub async fn list_referencing_tables(
+        &self,
+        connection: ConnectionId,
+        adapter: Arc<dyn DatabaseAdapter>,
+        schema: &str,
+        table: &str,
+    ) -> Result<Vec<ForeignKeyInfo>> {
+        let path = CachePath::ReferencingTables {
+            schema: schema.to_string(),
+            table: table.to_string(),
+        };
+        self.cached(
+            connection,
+            path,
+            adapter.list_referencing_tables(schema, table),
+        )
+        .await
+    }
+
+    pub async fn list_schemas_with_tables(
+        &self,
+        connection: ConnectionId,
+        adapter: Arc<dyn DatabaseAdapter>,
+    ) -> Result<DatabaseStructure> {
+        self.cached(
+            connection,
+            CachePath::Structure,
+            adapter.list_schemas_with_tables(),
+        )
+        .await
+    }
+
+    /// Cached like the listings, keyed by the trimmed, lowercased query (the
+    /// ranking ignores case) plus the kinds and limit.
+    pub async fn search_objects(
+        &self,
+        connection: ConnectionId,
+        adapter: Arc<dyn DatabaseAdapter>,
+        request: &ObjectSearchRequest,
+    ) -> Result<Vec<ObjectMatch>> {
+        let mut kinds: Vec<String> = request.kinds.iter().map(|k| format!("{k:?}")).collect();
+        kinds.sort();
+        kinds.dedup();
+        let path = CachePath::Search {
+            query: request.query.trim().to_lowercase(),
+            filter: format!("{}/{}", kinds.join(","), request.limit),
+        };
+        self.cached(connection, path, adapter.search_objects(request))
+            .await
+    }
+
+    /// After `query` ran (or failed halfway) on `connection`: drop what its
+    /// DDL may have changed.
+    pub fn query_ran(&self, connection: &ConnectionId, adapter: &dyn DatabaseAdapter, query: &str) {
+        self.cache
+            .invalidate(connection, &adapter.structure_changes(query));
+    }
+
+    /// After grid edits or an import on `connection` (even a failed one,
+    /// which may have written part of the rows).
+    pub fn rows_written(&self, connection: &ConnectionId, adapter: &dyn DatabaseAdapter) {
+        if adapter.row_writes_change_structure() {
+            self.cache
+                .invalidate(connection, &[StructureChange::Unknown]);
+        }
+    }
+
+    /// Explicit refresh: a table, a schema, or with neither the whole
+    /// connection (the database list included).
+    pub fn refresh(&self, connection: &ConnectionId, schema: Option<&str>, table: Option<&str>) {
+        let change = match (schema, table) {
+            (schema, Some(table)) => StructureChange::Table {
+                schema: schema.map(str::to_string),
+                table: table.to_string(),
+            },
+            (Some(schema), None) => StructureChange::Schema(schema.to_string()),
+            (None, None) => {
+                self.cache.invalidate(
+                    connection,
+                    &[StructureChange::Databases, StructureChange::Unknown],
+                );
+                return;
+            }
+        };
+        self.cache.invalidate(connection, &[change]);
+    }
+
+    /// Everything cached for a server: after editing or deleting it, or a
+    /// refresh of the whole server.
+    pub fn forget_server(&self, server_id: i64) {
+        self.cache.invalidate_server(server_id);
+    }
+
+    async fn cached<T>(
+        &self,
+        connection: ConnectionId,
+        path: CachePath,
+        load: impl Future<Output = Result<T>>,
+    ) -> Result<T>
+    where
+        T: Clone + Serialize + DeserializeOwned + Send + Sync + 'static,
+    {
+        let key = CacheKey { connection, path };
+        if let Some(value) = self.cache.get(&key) {
+            return Ok(value);
+        }
+
+        let generation = self.cache.generation();
+        let value = load.await?;
+        self.cache.put(key, &value, generation);
+        Ok(value)
     }
 }
 
 impl Default for StructureService {
     fn default() -> Self {
-        Self::new()
+        Self::new(None)
     }
-}
\ No newline at end of file
+}
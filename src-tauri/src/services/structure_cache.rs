@@ -0,0 +1,403 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use parking_lot::Mutex;
+use rusqlite::Connection;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::models::{now_ms, ConnectionId, StructureChange};
+use crate::storage::repositories::structure_cache::{self as cache_store, CacheSlot};
+
+/// Which listing of a connection an entry holds.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum CachePath {
+    Databases,
+    Schemas,
+    /// `list_schemas_with_tables` (autocompletion).
+    Structure,
+    Tables {
+        schema: String,
+    },
+    Columns {
+        schema: String,
+        table: String,
+    },
+    Indexes {
+        schema: String,
+        table: String,
+    },
+    ForeignKeys {
+        schema: String,
+        table: String,
+    },
+    Constraints {
+        schema: String,
+        table: String,
+    },
+    /// Foreign keys of other tables pointing at `table`.
+    ReferencingTables {
+        schema: String,
+        table: String,
+    },
+    /// `search_objects` results: the normalized query, and the kinds and
+    /// limit it was filtered with.
+    Search {
+        query: String,
+        filter: String,
+    },
+}
+
+impl CachePath {
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::Databases => "databases",
+            Self::Schemas => "schemas",
+            Self::Structure => "structure",
+            Self::Tables { .. } => "tables",
+            Self::Columns { .. } => "columns",
+            Self::Indexes { .. } => "indexes",
+            Self::ForeignKeys { .. } => "foreign_keys",
+            Self::Constraints { .. } => "constraints",
+            Self::ReferencingTables { .. } => "referencing_tables",
+            Self::Search { .. } => "search",
+        }
+    }
+
+    fn from_slot(slot: &CacheSlot) -> Option<Self> {
+        let schema = slot.schema.clone();
+        let table = slot.table.clone();
+        Some(match slot.kind.as_str() {
+            "databases" => Self::Databases,
+            "schemas" => Self::Schemas,
+            "structure" => Self::Structure,
+            "tables" => Self::Tables { schema },
+            "columns" => Self::Columns { schema, table },
+            "indexes" => Self::Indexes { schema, table },
+            "foreign_keys" => Self::ForeignKeys { schema, table },
+            "constraints" => Self::Constraints { schema, table },
+            "referencing_tables" => Self::ReferencingTables { schema, table },
+            "search" => Self::Search {
+                query: schema,
+                filter: table,
+            },
+            _ => return None,
+        })
+    }
+
+    fn schema(&self) -> Option<&str> {
+        match self {
+            Self::Tables { schema }
+            | Self::Columns { schema, .. }
+            | Self::Indexes { schema, .. }
+            | Self::ForeignKeys { schema, .. }
+            | Self::Constraints { schema, .. }
+            | Self::ReferencingTables { schema, .. } => Some(schema),
+            // Stored in the slot's schema/table columns, never matched as names.
+            Self::Search { query, .. } => Some(query),
+            _ => None,
+        }
+    }
+
+    fn table(&self) -> Option<&str> {
+        match self {
+            Self::Columns { table, .. }
+            | Self::Indexes { table, .. }
+            | Self::ForeignKeys { table, .. }
+            | Self::Constraints { table, .. }
+            | Self::ReferencingTables { table, .. } => Some(table),
+            Self::Search { filter, .. } => Some(filter),
+            _ => None,
+        }
+    }
+
+    /// Names are compared ignoring case: the DDL may spell `Users` where the
+    /// tree has `users`, and dropping one entry too many only costs a refetch.
+    /// The database list belongs to the server, see [`StructureCache::invalidate`].
+    /// A search may have matched any object, and the keys pointing at a table
+    /// are declared on other tables, so any DDL drops both.
+    fn affected_by(&self, change: &StructureChange) -> bool {
+        let same = |a: &str, b: &str| a.eq_ignore_ascii_case(b);
+        match change {
+            StructureChange::Databases => false,
+            _ if matches!(self, Self::Search { .. } | Self::ReferencingTables { .. }) => true,
+            StructureChange::Unknown => !matches!(self, Self::Databases),
+            StructureChange::Schema(name) => match self {
+                Self::Schemas | Self::Structure => true,
+                path => path.schema().is_some_and(|schema| same(schema, name)),
+            },
+            StructureChange::Table { schema, table } => {
+                let in_schema = self
+                    .schema()
+                    .is_some_and(|own| schema.as_deref().is_none_or(|s| same(own, s)));
+                match self {
+                    Self::Databases => false,
+                    Self::Schemas | Self::Structure => true,
+                    Self::Tables { .. } => in_schema,
+                    path => in_schema && path.table().is_some_and(|own| same(own, table)),
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CacheKey {
+    pub connection: ConnectionId,
+    pub path: CachePath,
+}
+
+impl CacheKey {
+    fn slot(&self) -> CacheSlot {
+        CacheSlot {
+            server_id: self.connection.server_id,
+            database: self.connection.database.clone(),
+            kind: self.path.kind().to_string(),
+            schema: self.path.schema().unwrap_or_default().to_string(),
+            table: self.path.table().unwrap_or_default().to_string(),
+        }
+    }
+
+    fn from_slot(slot: &CacheSlot) -> Option<Self> {
+        Some(Self {
+            connection: ConnectionId::new(slot.server_id, &slot.database),
+            path: CachePath::from_slot(slot)?,
+        })
+    }
+}
+
+struct CacheEntry {
+    value: Arc<dyn Any + Send + Sync>,
+    cached_at: u64,
+}
+
+/// Metadata listings by connection and path, expiring after `ttl`. With
+/// `storage` the entries are also written to app.db, so a restart starts
+/// with a warm tree; the disk is best-effort, a failure there only costs a
+/// trip to the database.
+pub struct StructureCache {
+    entries: Mutex<HashMap<CacheKey, CacheEntry>>,
+    storage: Option<Arc<Mutex<Connection>>>,
+    ttl: Duration,
+    /// Bumped by every invalidation: a listing that started loading before
+    /// one isn't stored, it may predate the DDL.
+    generation: AtomicU64,
+}
+
+impl StructureCache {
+    pub fn new(storage: Option<Arc<Mutex<Connection>>>, ttl: Duration) -> Self {
+        if let Some(storage) = &storage {
+            let _ = cache_store::prune(storage, now_ms().saturating_sub(ttl_ms(ttl)) as i64);
+        }
+        Self {
+            entries: Mutex::new(HashMap::new()),
+            storage,
+            ttl,
+            generation: AtomicU64::new(0),
+        }
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::SeqCst)
+    }
+
+    pub fn get<T>(&self, key: &CacheKey) -> Option<T>
+    where
+        T: Clone + DeserializeOwned + Send + Sync + 'static,
+    {
+        let fresh_since = now_ms().saturating_sub(ttl_ms(self.ttl));
+        if let Some(entry) = self.entries.lock().get(key) {
+            if entry.cached_at >= fresh_since {
+                return entry.value.downcast_ref::<T>().cloned();
+            }
+        }
+
+        let (payload, cached_at) = cache_store::get(self.storage.as_ref()?, &key.slot()).ok()??;
+        let cached_at = cached_at as u64;
+        if cached_at < fresh_since {
+            return None;
+        }
+        let value: T = serde_json::from_str(&payload).ok()?;
+        self.entries.lock().insert(
+            key.clone(),
+            CacheEntry {
+                value: Arc::new(value.clone()),
+                cached_at,
+            },
+        );
+        Some(value)
+    }
+
+    /// Store `value` unless an invalidation happened since `generation` was
+    /// read (before the listing was loaded).
+    pub fn put<T>(&self, key: CacheKey, value: &T, generation: u64)
+    where
+        T: Clone + Serialize + Send + Sync + 'static,
+    {
+        let mut entries = self.entries.lock();
+        if self.generation() != generation {
+            return;
+        }
+        let cached_at = now_ms();
+        if let (Some(storage), Ok(payload)) = (&self.storage, serde_json::to_string(value)) {
+            let _ = cache_store::put(storage, &key.slot(), &payload, cached_at as i64);
+        }
+        entries.insert(
+            key,
+            CacheEntry {
+                value: Arc::new(value.clone()),
+                cached_at,
+            },
+        );
+    }
+
+    /// Drop what `changes`, made through `connection`, may have made stale.
+    /// The database list is shared by every connection of the server.
+    pub fn invalidate(&self, connection: &ConnectionId, changes: &[StructureChange]) {
+        if changes.is_empty() {
+            return;
+        }
+        let databases_changed = changes.contains(&StructureChange::Databases);
+        self.remove_where(connection.server_id, |key| {
+            if key.path == CachePath::Databases {
+                return databases_changed;
+            }
+            key.connection == *connection
+                && changes.iter().any(|change| key.path.affected_by(change))
+        });
+    }
+
+    /// Drop everything cached for a server (edited, deleted, refreshed).
+    pub fn invalidate_server(&self, server_id: i64) {
+        let mut entries = self.entries.lock();
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        entries.retain(|key, _| key.connection.server_id != server_id);
+        if let Some(storage) = &self.storage {
+            let _ = cache_store::delete_server(storage, server_id);
+        }
+    }
+
+    fn remove_where(&self, server_id: i64, stale: impl Fn(&CacheKey) -> bool) {
+        let mut entries = self.entries.lock();
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        entries.retain(|key, _| !stale(key));
+
+        let Some(storage) = &self.storage else {
+            return;
+        };
+        for slot in cache_store::slots(storage, server_id).unwrap_or_default() {
+            if CacheKey::from_slot(&slot).is_none_or(|key| stale(&key)) {
+                let _ = cache_store::delete(storage, &slot);
+            }
+        }
+    }
+}
+
+fn ttl_ms(ttl: Duration) -> u64 {
+    ttl.as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(database: &str, path: CachePath) -> CacheKey {
+        CacheKey {
+            connection: ConnectionId::new(1, database),
+            path,
+        }
+    }
+
+    fn columns(schema: &str, table: &str) -> CachePath {
+        CachePath::Columns {
+            schema: schema.into(),
+            table: table.into(),
+        }
+    }
+
+    #[test]
+    fn ddl_drops_only_what_it_touched() {
+        let cache = StructureCache::new(None, Duration::from_secs(60));
+        let paths = [
+            key("app", columns("public", "users")),
+            key("app", columns("public", "orders")),
+            key("app", columns("audit", "users")),
+            key(
+                "app",
+                CachePath::Tables {
+                    schema: "audit".into(),
+                },
+            ),
+            key("other", columns("public", "users")),
+            key("postgres", CachePath::Databases),
+        ];
+        for path in &paths {
+            cache.put(path.clone(), &vec![1], cache.generation());
+        }
+
+        let change = StructureChange::Table {
+            schema: Some("PUBLIC".into()),
+            table: "Users".into(),
+        };
+        cache.invalidate(&ConnectionId::new(1, "app"), &[change]);
+
+        let cached: Vec<bool> = paths
+            .iter()
+            .map(|path| cache.get::<Vec<i32>>(path).is_some())
+            .collect();
+        assert_eq!(cached, [false, true, true, true, true, true]);
+
+        cache.invalidate(&ConnectionId::new(1, "app"), &[StructureChange::Databases]);
+        assert!(cache.get::<Vec<i32>>(&paths[5]).is_none());
+        assert!(cache.get::<Vec<i32>>(&paths[3]).is_some());
+    }
+
+    #[test]
+    fn ddl_on_any_table_drops_referencing_tables() {
+        let cache = StructureCache::new(None, Duration::from_secs(60));
+        let referencing = key(
+            "app",
+            CachePath::ReferencingTables {
+                schema: "public".into(),
+                table: "users".into(),
+            },
+        );
+        let foreign_keys = key(
+            "app",
+            CachePath::ForeignKeys {
+                schema: "public".into(),
+                table: "users".into(),
+            },
+        );
+        cache.put(referencing.clone(), &vec![1], cache.generation());
+        cache.put(foreign_keys.clone(), &vec![1], cache.generation());
+
+        // O FK novo é declarado em orders, mas aponta para users.
+        let change = StructureChange::Table {
+            schema: Some("sales".into()),
+            table: "orders".into(),
+        };
+        cache.invalidate(&ConnectionId::new(1, "app"), &[change]);
+        assert!(cache.get::<Vec<i32>>(&referencing).is_none());
+        assert!(cache.get::<Vec<i32>>(&foreign_keys).is_some());
+    }
+
+    #[test]
+    fn loads_that_raced_an_invalidation_are_not_stored() {
+        let cache = StructureCache::new(None, Duration::from_secs(60));
+        let path = key("app", CachePath::Schemas);
+
+        let generation = cache.generation();
+        cache.invalidate(&ConnectionId::new(1, "app"), &[StructureChange::Unknown]);
+        cache.put(path.clone(), &vec![1], generation);
+        assert!(cache.get::<Vec<i32>>(&path).is_none());
+
+        let expired = StructureCache::new(None, Duration::ZERO);
+        expired.put(path.clone(), &vec![1], expired.generation());
+        std::thread::sleep(Duration::from_millis(2));
+        assert!(expired.get::<Vec<i32>>(&path).is_none());
+    }
+}
@@ -0,0 +1,441 @@
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+
+use russh::client::{self, Handle};
+use russh::keys::ssh_key::certificate::CertType;
+use russh::keys::{self, known_hosts, PrivateKeyWithHashAlg, PublicKey, PublicKeyOrCertificate};
+use russh::Disconnect;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::{JoinHandle, JoinSet};
+
+use crate::error::{Error, Result};
+use crate::models::{DatabaseType, Server, SshTunnel};
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+/// Keepalive SSH: evita que firewalls/NAT derrubem o túnel ocioso enquanto o
+/// app fica aberto sem uso (mesma ideia do keepalive TCP dos pools).
+const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(30);
+const KEEPALIVE_MAX: usize = 3;
+const ACCEPT_RETRY_DELAY: Duration = Duration::from_millis(200);
+
+/// A local port forwarded to the database through an SSH jump host
+/// (`ssh -L 127.0.0.1:<local_port>:<db_host>:<db_port> jump`).
+///
+/// Every connection accepted on the local port gets its own `direct-tcpip`
+/// channel over the single SSH session, so pools work unchanged. Dropping the
+/// tunnel stops the listener, closes the forwarded connections and ends the
+/// SSH session.
+pub struct Tunnel {
+    local_port: u16,
+    session: Arc<Handle<HostKeyCheck>>,
+    accept_task: JoinHandle<()>,
+}
+
+impl Tunnel {
+    /// Connects and authenticates to the jump host, then starts listening on
+    /// an ephemeral port of 127.0.0.1.
+    pub async fn open(ssh: &SshTunnel, remote_host: &str, remote_port: u16) -> Result<Self> {
+        let config = Arc::new(client::Config {
+            keepalive_interval: Some(KEEPALIVE_INTERVAL),
+            keepalive_max: KEEPALIVE_MAX,
+            ..Default::default()
+        });
+        let handler = HostKeyCheck {
+            host: ssh.host.clone(),
+            port: ssh.port,
+        };
+
+        let mut session = tokio::time::timeout(
+            CONNECT_TIMEOUT,
+            client::connect(config, (ssh.host.as_str(), ssh.port), handler),
+        )
+        .await
+        .map_err(|_| Error::ConnectionTimeout)?
+        .map_err(ssh_error)?;
+
+        authenticate(&mut session, ssh).await?;
+
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+            .await
+            .map_err(|e| Error::Connection(format!("SSH tunnel: cannot bind local port: {e}")))?;
+        let local_port = listener
+            .local_addr()
+            .map_err(|e| Error::Connection(format!("SSH tunnel: {e}")))?
+            .port();
+
+        let session = Arc::new(session);
+        let accept_task = tokio::spawn(forward(
+            listener,
+            Arc::clone(&session),
+            remote_host.to_string(),
+            remote_port,
+        ));
+
+        Ok(Self {
+            local_port,
+            session,
+            accept_task,
+        })
+    }
+
+    pub fn local_port(&self) -> u16 {
+        self.local_port
+    }
+}
+
+impl Drop for Tunnel {
+    fn drop(&mut self) {
+        // Aborting the accept loop drops its JoinSet, which aborts every
+        // forwarded connection as well.
+        self.accept_task.abort();
+
+        // Polite SSH disconnect; without a runtime (app shutdown) the session
+        // simply ends when its last handle goes away.
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            let session = Arc::clone(&self.session);
+            runtime.spawn(async move {
+                let _ = session
+                    .disconnect(Disconnect::ByApplication, "", "en")
+                    .await;
+            });
+        }
+    }
+}
+
+/// Where the tunnel must forward to: the database host/port as seen from the
+/// jump host, taken from the connection URI when there is one.
+pub fn remote_target(server: &Server) -> Result<(String, u16)> {
+    if server.db_type == DatabaseType::Sqlite {
+        return Err(Error::InvalidState(
+            "SSH tunnels are not supported for SQLite (local file)".into(),
+        ));
+    }
+
+    match server.connection_uri.as_deref() {
+        Some(uri) => {
+            let (_, authority, _) = split_uri(uri)?;
+            parse_authority(authority, server.db_type.default_port())
+        }
+        None => Ok((server.host.clone(), server.port)),
+    }
+}
+
+/// Copy of `server` pointing at the tunnel's local port instead of the
+/// database host; everything else (credentials, TLS, URI options) is kept.
+/// Certificates are still verified against the real database hostname.
+pub fn route_through(server: &Server, local_port: u16) -> Result<Server> {
+    let (remote_host, _) = remote_target(server)?;
+
+    let mut routed = server.clone();
+    routed.host = Ipv4Addr::LOCALHOST.to_string();
+    routed.port = local_port;
+    routed.tls.server_name = Some(remote_host);
+
+    if let Some(uri) = server.connection_uri.as_deref() {
+        let (prefix, _, rest) = split_uri(uri)?;
+        routed.connection_uri = Some(format!("{prefix}{}:{local_port}{rest}", routed.host));
+    }
+
+    Ok(routed)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// SSH session
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Host key policy, trust on first use: a host not yet in
+/// `~/.ssh/known_hosts` gets its key recorded there, and from then on any
+/// other key is rejected (possible man-in-the-middle). A host certificate is
+/// checked by the key it certifies, as the app has no CAs to trust.
+struct HostKeyCheck {
+    host: String,
+    port: u16,
+}
+
+impl client::Handler for HostKeyCheck {
+    type Error = russh::Error;
+
+    async fn check_server_key(
+        &mut self,
+        server_public_key: &PublicKeyOrCertificate,
+    ) -> std::result::Result<bool, Self::Error> {
+        let key = match server_public_key {
+            PublicKeyOrCertificate::PublicKey { key, .. } => key.clone(),
+            PublicKeyOrCertificate::Certificate(cert) if cert.cert_type() == CertType::Host => {
+                PublicKey::from(cert.public_key().clone())
+            }
+            PublicKeyOrCertificate::Certificate(_) => return Ok(false),
+        };
+
+        let known = known_hosts::known_host_keys(&self.host, self.port)?;
+        match known.iter().find(|(_, recorded)| *recorded == key) {
+            Some(_) => Ok(true),
+            None if known.is_empty() => {
+                known_hosts::learn_known_hosts(&self.host, self.port, &key)?;
+                Ok(true)
+            }
+            // Listed with another key (or another key type)
+            None => Err(keys::Error::KeyChanged { line: known[0].0 }.into()),
+        }
+    }
+}
+
+/// Private key when a path is configured, password otherwise.
+async fn authenticate(session: &mut Handle<HostKeyCheck>, ssh: &SshTunnel) -> Result<()> {
+    let key_path = ssh.private_key_path.as_deref().filter(|p| !p.is_empty());
+
+    let auth = if let Some(path) = key_path {
+        let key = keys::load_secret_key(expand_home(path), ssh.private_key_passphrase.as_deref())
+            .map_err(|e| Error::Connection(format!("SSH private key '{path}': {e}")))?;
+        // RSA keys must sign with an algorithm the server accepts (rsa-sha2-*)
+        let hash_alg = session
+            .best_supported_rsa_hash()
+            .await
+            .map_err(ssh_error)?
+            .flatten();
+
+        session
+            .authenticate_publickey(
+                &ssh.username,
+                PrivateKeyWithHashAlg::new(Arc::new(key), hash_alg),
+            )
+            .await
+    } else {
+        session
+            .authenticate_password(&ssh.username, ssh.password.clone().unwrap_or_default())
+            .await
+    }
+    .map_err(ssh_error)?;
+
+    if !auth.success() {
+        return Err(Error::Connection(format!(
+            "SSH authentication failed for {}@{}",
+            ssh.username, ssh.host
+        )));
+    }
+
+    Ok(())
+}
+
+/// Accept loop: one `direct-tcpip` channel per local connection.
+async fn forward(
+    listener: TcpListener,
+    session: Arc<Handle<HostKeyCheck>>,
+    remote_host: String,
+    remote_port: u16,
+) {
+    let mut connections = JoinSet::new();
+
+    loop {
+        let (socket, peer) = match listener.accept().await {
+            Ok(accepted) => accepted,
+            // Out of file descriptors and the like: retrying right away
+            // would spin, and the error usually clears once connections close
+            Err(_) => {
+                tokio::time::sleep(ACCEPT_RETRY_DELAY).await;
+                continue;
+            }
+        };
+
+        // Reap finished connections so the set doesn't grow unbounded
+        while connections.try_join_next().is_some() {}
+
+        connections.spawn(forward_connection(
+            socket,
+            peer,
+            Arc::clone(&session),
+            remote_host.clone(),
+            remote_port,
+        ));
+    }
+}
+
+async fn forward_connection(
+    mut socket: TcpStream,
+    peer: SocketAddr,
+    session: Arc<Handle<HostKeyCheck>>,
+    remote_host: String,
+    remote_port: u16,
+) {
+    // If the channel can't be opened the socket is dropped and the driver
+    // sees the connection close, reporting its own connection error.
+    let Ok(channel) = session
+        .channel_open_direct_tcpip(
+            remote_host,
+            remote_port as u32,
+            peer.ip().to_string(),
+            peer.port() as u32,
+        )
+        .await
+    else {
+        return;
+    };
+
+    let mut stream = channel.into_stream();
+    let _ = tokio::io::copy_bidirectional(&mut socket, &mut stream).await;
+}
+
+fn ssh_error(e: russh::Error) -> Error {
+    match e {
+        russh::Error::Keys(keys::Error::KeyChanged { line }) => Error::Connection(format!(
+            "SSH host key does not match ~/.ssh/known_hosts (line {line}); \
+             refusing to connect"
+        )),
+        russh::Error::UnknownKey => Error::Connection(
+            "SSH server presented a certificate that is not a host certificate".into(),
+        ),
+        russh::Error::Keys(e) => Error::Connection(format!(
+            "SSH tunnel: cannot check or record the host key in ~/.ssh/known_hosts: {e}"
+        )),
+        e => Error::Connection(format!("SSH tunnel: {e}")),
+    }
+}
+
+fn expand_home(path: &str) -> String {
+    match path.strip_prefix("~/") {
+        Some(rest) => match std::env::var("HOME").or_else(|_| std::env::var("USERPROFILE")) {
+            Ok(home) => format!("{home}/{rest}"),
+            Err(_) => path.to_string(),
+        },
+        None => path.to_string(),
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// URI helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Splits `scheme://[userinfo@]host[:port][/path?query]` into the part before
+/// the host (scheme + userinfo), the `host[:port]` authority and the rest.
+fn split_uri(uri: &str) -> Result<(&str, &str, &str)> {
+    if uri.starts_with("mongodb+srv://") {
+        return Err(Error::Connection(
+            "SSH tunnels can't use mongodb+srv:// URIs (DNS seed list); \
+             use a mongodb:// URI with a single host"
+                .into(),
+        ));
+    }
+
+    let start = uri
+        .find("://")
+        .map(|i| i + 3)
+        .ok_or_else(|| Error::Connection(format!("Invalid URI: {uri}")))?;
+    let end = uri[start..]
+        .find(['/', '?', '#'])
+        .map_or(uri.len(), |i| start + i);
+    // Userinfo ends at the last '@' (passwords may contain '@' if unescaped)
+    let host_start = uri[start..end].rfind('@').map_or(start, |i| start + i + 1);
+
+    Ok((&uri[..host_start], &uri[host_start..end], &uri[end..]))
+}
+
+fn parse_authority(authority: &str, default_port: u16) -> Result<(String, u16)> {
+    if authority.contains(',') {
+        return Err(Error::Connection(
+            "SSH tunnels forward a single host; the URI lists several".into(),
+        ));
+    }
+
+    let (host, port) = if let Some(bracketed) = authority.strip_prefix('[') {
+        // IPv6 literal: [::1]:5432
+        let (host, rest) = bracketed
+            .split_once(']')
+            .ok_or_else(|| Error::Connection(format!("Invalid host in URI: {authority}")))?;
+        (host, rest.strip_prefix(':'))
+    } else {
+        match authority.rsplit_once(':') {
+            Some((host, port)) => (host, Some(port)),
+            None => (authority, None),
+        }
+    };
+
+    let port = match port.filter(|p| !p.is_empty()) {
+        Some(p) => p
+            .parse()
+            .map_err(|_| Error::Connection(format!("Invalid port in URI: {p}")))?,
+        None => default_port,
+    };
+    let host = if host.is_empty() { "localhost" } else { host };
+
+    Ok((host.to_string(), port))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server(db_type: DatabaseType, uri: Option<&str>) -> Server {
+        Server {
+            id: Some(1),
+            name: "tunnel".into(),
+            db_type,
+            host: "db.internal".into(),
+            port: 5433,
+            username: "user".into(),
+            password: "pw".into(),
+            default_database: None,
+            tls: Default::default(),
+            connection_uri: uri.map(str::to_string),
+            ssh_tunnel: None,
+            read_only: false,
+            confirm_writes: false,
+            created_at: 0,
+        }
+    }
+
+    #[test]
+    fn remote_target_uses_host_fields_or_uri() {
+        let plain = server(DatabaseType::Postgres, None);
+        assert_eq!(remote_target(&plain).unwrap(), ("db.internal".into(), 5433));
+
+        let uri = server(
+            DatabaseType::Mongodb,
+            Some("mongodb://u:p@w@mongo.internal/admin?authSource=admin"),
+        );
+        assert_eq!(
+            remote_target(&uri).unwrap(),
+            ("mongo.internal".into(), 27017)
+        );
+
+        let ipv6 = server(DatabaseType::Redis, Some("redis://[fd00::7]:6380/2"));
+        assert_eq!(remote_target(&ipv6).unwrap(), ("fd00::7".into(), 6380));
+    }
+
+    #[test]
+    fn remote_target_rejects_unforwardable_uris() {
+        let srv = server(
+            DatabaseType::Mongodb,
+            Some("mongodb+srv://u:p@cluster.example.net"),
+        );
+        assert!(remote_target(&srv).is_err());
+
+        let replica = server(
+            DatabaseType::Mongodb,
+            Some("mongodb://h1:27017,h2:27017/db"),
+        );
+        assert!(remote_target(&replica).is_err());
+
+        assert!(remote_target(&server(DatabaseType::Sqlite, None)).is_err());
+    }
+
+    #[test]
+    fn route_through_rewrites_host_and_uri() {
+        let routed = route_through(
+            &server(
+                DatabaseType::Postgres,
+                Some("postgresql://u:p@db.internal:5433/app?sslmode=require"),
+            ),
+            40123,
+        )
+        .unwrap();
+
+        assert_eq!(routed.host, "127.0.0.1");
+        assert_eq!(routed.port, 40123);
+        assert_eq!(routed.tls.server_name.as_deref(), Some("db.internal"));
+        assert_eq!(
+            routed.connection_uri.as_deref(),
+            Some("postgresql://u:p@127.0.0.1:40123/app?sslmode=require")
+        );
+    }
+}
@@ -0,0 +1,114 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Instant;
+
+use parking_lot::Mutex;
+use tokio::sync::{mpsc, oneshot};
+
+use crate::adapters::DatabaseAdapter;
+use crate::error::{Error, Result};
+use crate::models::{ValueSearchEvent, ValueSearchRequest, ValueSearchSummary};
+
+use super::ProgressFn;
+
+/// Events buffered between the adapter and the frontend: a full buffer
+/// holds the table scans back until the frontend catches up.
+const EVENT_BUFFER: usize = 256;
+
+/// Value searches in progress, by search id, so they can be cancelled.
+#[derive(Default)]
+pub struct SearchService {
+    running: Mutex<HashMap<String, oneshot::Sender<()>>>,
+}
+
+impl SearchService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run `adapter.search_values`, forwarding its events to `send` and
+    /// counting them. Ends when every table is scanned, at `maxHits` or on
+    /// [`SearchService::cancel`]; the last two drop the scans still running.
+    pub async fn search_values(
+        &self,
+        adapter: Arc<dyn DatabaseAdapter>,
+        request: &ValueSearchRequest,
+        search_id: &str,
+        send: ProgressFn<ValueSearchEvent>,
+    ) -> Result<ValueSearchSummary> {
+        if request.value.is_empty() {
+            return Err(Error::InvalidQuery(
+                "The value to search for is empty".into(),
+            ));
+        }
+        let mut cancelled = self.register(search_id)?;
+        let clock = Instant::now();
+
+        let (events, mut received) = mpsc::channel(EVENT_BUFFER);
+        let search = adapter.search_values(request, events);
+        tokio::pin!(search);
+
+        let mut summary = ValueSearchSummary::default();
+        let mut outcome: Option<Result<()>> = None;
+        loop {
+            tokio::select! {
+                event = received.recv() => {
+                    // Canal fechado: a busca terminou e tudo já foi repassado.
+                    let Some(event) = event else { break };
+                    count(&mut summary, &event);
+                    send(event);
+                    if summary.hit_count >= request.max_hits {
+                        summary.truncated = true;
+                        break;
+                    }
+                }
+                result = &mut search, if outcome.is_none() => outcome = Some(result),
+                _ = &mut cancelled => {
+                    summary.cancelled = true;
+                    break;
+                }
+            }
+        }
+        self.running.lock().remove(search_id);
+
+        if let Some(Err(e)) = outcome {
+            return Err(e);
+        }
+        summary.execution_time_ms = clock.elapsed().as_millis() as u64;
+        Ok(summary)
+    }
+
+    /// Stop search `search_id`; `false` if it isn't running.
+    pub fn cancel(&self, search_id: &str) -> bool {
+        match self.running.lock().remove(search_id) {
+            Some(stop) => stop.send(()).is_ok(),
+            None => false,
+        }
+    }
+
+    fn register(&self, search_id: &str) -> Result<oneshot::Receiver<()>> {
+        let mut running = self.running.lock();
+        if running.contains_key(search_id) {
+            return Err(Error::AlreadyExists(format!(
+                "A search with id {search_id} is already running"
+            )));
+        }
+        let (stop, stopped) = oneshot::channel();
+        running.insert(search_id.to_string(), stop);
+        Ok(stopped)
+    }
+}
+
+fn count(summary: &mut ValueSearchSummary, event: &ValueSearchEvent) {
+    match event {
+        ValueSearchEvent::Started { .. } => {}
+        ValueSearchEvent::Hit(_) => summary.hit_count += 1,
+        ValueSearchEvent::TableScanned {
+            timed_out, error, ..
+        } => {
+            summary.tables_scanned += 1;
+            summary.tables_timed_out += usize::from(*timed_out);
+            summary.tables_failed += usize::from(error.is_some());
+        }
+    }
+}
@@ -1,12 +1,14 @@
+use std::sync::Arc;
+
 use parking_lot::Mutex;
 use rusqlite::Connection;
 
-use crate::services::{ConnectionService, QueryService, StructureService};
+use crate::services::{ConnectionService, QueryService, SearchService, StructureService};
 
 /// Application state managed by Tauri
 pub struct AppState {
     /// Local SQLite connection for app storage
-    pub storage: Mutex<Connection>,
+    pub storage: Arc<Mutex<Connection>>,
 
     /// Connection management service
     pub connections: ConnectionService,
@@ -16,15 +18,20 @@ pub struct AppState {
 
     /// Database structure service
     pub structure: StructureService,
+
+    /// Value searches across tables (cancellable)
+    pub searches: SearchService,
 }
 
 impl AppState {
     pub fn new(storage_conn: Connection) -> Self {
+        let storage = Arc::new(Mutex::new(storage_conn));
         Self {
-            storage: Mutex::new(storage_conn),
             connections: ConnectionService::new(),
-            queries: QueryService::new(),
-            structure: StructureService::new(),
+            queries: QueryService::new(storage.clone()),
+            structure: StructureService::new(Some(storage.clone())),
+            searches: SearchService::new(),
+            storage,
         }
     }
 }
\ No newline at end of file
@@ -21,10 +21,87 @@ pub fn init_storage<P: AsRef<Path>>(db_path: P) -> Result<Connection> {
             default_database TEXT,
             ssl_enabled     INTEGER NOT NULL DEFAULT 0,
             connection_uri  TEXT,
+            ssh_host        TEXT,
+            ssh_port        INTEGER,
+            ssh_username    TEXT,
+            ssh_password    TEXT,
+            ssh_private_key_path TEXT,
+            ssh_passphrase  TEXT,
+            tls_mode        TEXT,
+            tls_ca_path     TEXT,
+            tls_client_cert_path TEXT,
+            tls_client_key_path TEXT,
+            tls_key_passphrase TEXT,
+            read_only       INTEGER NOT NULL DEFAULT 0,
+            confirm_writes  INTEGER NOT NULL DEFAULT 0,
             created_at      INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
         );
 
         CREATE INDEX IF NOT EXISTS idx_servers_name ON servers(name);
+
+        CREATE TABLE IF NOT EXISTS query_history (
+            id              INTEGER PRIMARY KEY AUTOINCREMENT,
+            server_id       INTEGER NOT NULL REFERENCES servers(id) ON DELETE CASCADE,
+            database        TEXT NOT NULL,
+            kind            TEXT NOT NULL,
+            query           TEXT NOT NULL,
+            started_at      INTEGER NOT NULL,
+            duration_ms     INTEGER NOT NULL,
+            row_count       INTEGER,
+            affected_rows   INTEGER,
+            error_code      TEXT,
+            error_message   TEXT,
+            pinned          INTEGER NOT NULL DEFAULT 0
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_query_history_started
+            ON query_history(started_at);
+        CREATE INDEX IF NOT EXISTS idx_query_history_server
+            ON query_history(server_id, database, started_at);
+
+        -- Índice full-text sobre o texto da query (external content: o texto
+        -- não é duplicado, os triggers mantêm o índice em dia).
+        CREATE VIRTUAL TABLE IF NOT EXISTS query_history_fts USING fts5(
+            query, content='query_history', content_rowid='id'
+        );
+
+        CREATE TRIGGER IF NOT EXISTS query_history_ai AFTER INSERT ON query_history BEGIN
+            INSERT INTO query_history_fts(rowid, query) VALUES (new.id, new.query);
+        END;
+        CREATE TRIGGER IF NOT EXISTS query_history_ad AFTER DELETE ON query_history BEGIN
+            INSERT INTO query_history_fts(query_history_fts, rowid, query)
+            VALUES ('delete', old.id, old.query);
+        END;
+
+        -- Biblioteca de snippets. Sem server_id/db_type = global; apagar o
+        -- server não apaga o snippet, ele só deixa de estar vinculado.
+        CREATE TABLE IF NOT EXISTS saved_queries (
+            id              INTEGER PRIMARY KEY AUTOINCREMENT,
+            name            TEXT NOT NULL,
+            folder          TEXT,
+            tags            TEXT NOT NULL DEFAULT '[]',
+            body            TEXT NOT NULL,
+            server_id       INTEGER REFERENCES servers(id) ON DELETE SET NULL,
+            db_type         TEXT,
+            created_at      INTEGER NOT NULL,
+            updated_at      INTEGER NOT NULL
+        );
+
+        CREATE UNIQUE INDEX IF NOT EXISTS idx_saved_queries_path
+            ON saved_queries(COALESCE(folder, ''), name);
+
+        -- Cache dos metadados da árvore (tabelas, colunas, índices) por
+        -- conexão; schema_name/table_name vazios quando não se aplicam.
+        CREATE TABLE IF NOT EXISTS structure_cache (
+            server_id       INTEGER NOT NULL REFERENCES servers(id) ON DELETE CASCADE,
+            database        TEXT NOT NULL,
+            kind            TEXT NOT NULL,
+            schema_name     TEXT NOT NULL DEFAULT '',
+            table_name      TEXT NOT NULL DEFAULT '',
+            payload         TEXT NOT NULL,
+            cached_at       INTEGER NOT NULL,
+            PRIMARY KEY (server_id, database, kind, schema_name, table_name)
+        );
         "#,
     )?;
 
@@ -44,6 +121,23 @@ fn run_migrations(conn: &Connection) -> Result<()> {
         ("db_type", "ALTER TABLE servers ADD COLUMN db_type TEXT NOT NULL DEFAULT 'postgres'"),
         ("ssl_enabled", "ALTER TABLE servers ADD COLUMN ssl_enabled INTEGER NOT NULL DEFAULT 0"),
         ("connection_uri", "ALTER TABLE servers ADD COLUMN connection_uri TEXT"),
+        ("ssh_host", "ALTER TABLE servers ADD COLUMN ssh_host TEXT"),
+        ("ssh_port", "ALTER TABLE servers ADD COLUMN ssh_port INTEGER"),
+        ("ssh_username", "ALTER TABLE servers ADD COLUMN ssh_username TEXT"),
+        ("ssh_password", "ALTER TABLE servers ADD COLUMN ssh_password TEXT"),
+        ("ssh_private_key_path", "ALTER TABLE servers ADD COLUMN ssh_private_key_path TEXT"),
+        ("ssh_passphrase", "ALTER TABLE servers ADD COLUMN ssh_passphrase TEXT"),
+        // NULL for rows saved before TLS modes: read back from ssl_enabled
+        ("tls_mode", "ALTER TABLE servers ADD COLUMN tls_mode TEXT"),
+        ("tls_ca_path", "ALTER TABLE servers ADD COLUMN tls_ca_path TEXT"),
+        ("tls_client_cert_path", "ALTER TABLE servers ADD COLUMN tls_client_cert_path TEXT"),
+        ("tls_client_key_path", "ALTER TABLE servers ADD COLUMN tls_client_key_path TEXT"),
+        ("tls_key_passphrase", "ALTER TABLE servers ADD COLUMN tls_key_passphrase TEXT"),
+        ("read_only", "ALTER TABLE servers ADD COLUMN read_only INTEGER NOT NULL DEFAULT 0"),
+        (
+            "confirm_writes",
+            "ALTER TABLE servers ADD COLUMN confirm_writes INTEGER NOT NULL DEFAULT 0",
+        ),
     ];
 
     for (col_name, alter_sql) in migrations {
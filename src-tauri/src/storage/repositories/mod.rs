@@ -1 +1,4 @@
-pub mod servers;
\ No newline at end of file
+pub mod query_history;
+pub mod saved_queries;
+pub mod servers;
+pub mod structure_cache;
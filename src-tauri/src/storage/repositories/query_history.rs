@@ -0,0 +1,334 @@
+use parking_lot::Mutex;
+use rusqlite::types::Value;
+use rusqlite::{params, params_from_iter, Connection, Row};
+
+use crate::error::{Error, Result};
+use crate::models::{HistoryFilter, HistoryKind, HistoryPurge, NewHistoryEntry, QueryHistoryEntry};
+
+const SELECT_COLUMNS: &str = "h.id, h.server_id, h.database, h.kind, h.query, h.started_at, \
+                              h.duration_ms, h.row_count, h.affected_rows, h.error_code, \
+                              h.error_message, h.pinned";
+
+/// Retention applied on every insert: unpinned entries beyond the newest
+/// `MAX_ENTRIES`, or older than `MAX_AGE_MS`, are dropped.
+const MAX_ENTRIES: i64 = 10_000;
+const MAX_AGE_MS: i64 = 90 * 24 * 60 * 60 * 1000;
+
+const DEFAULT_PAGE_SIZE: u32 = 100;
+const MAX_PAGE_SIZE: u32 = 1000;
+
+/// Record an execution and enforce the retention limits.
+pub fn record(storage: &Mutex<Connection>, entry: &NewHistoryEntry) -> Result<i64> {
+    let conn = storage.lock();
+
+    conn.execute(
+        "INSERT INTO query_history (server_id, database, kind, query, started_at, duration_ms, \
+                                    row_count, affected_rows, error_code, error_message) \
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        params![
+            entry.server_id,
+            entry.database,
+            kind_to_string(entry.kind),
+            entry.query,
+            entry.started_at,
+            entry.duration_ms,
+            entry.row_count,
+            entry.affected_rows,
+            entry.error_code,
+            entry.error_message,
+        ],
+    )?;
+    let id = conn.last_insert_rowid();
+
+    // `id < NULL` (fewer than MAX_ENTRIES rows) is never true.
+    conn.execute(
+        "DELETE FROM query_history \
+         WHERE pinned = 0 \
+           AND (started_at < ?1 \
+                OR id < (SELECT id FROM query_history WHERE pinned = 0 \
+                         ORDER BY id DESC LIMIT 1 OFFSET ?2))",
+        params![entry.started_at - MAX_AGE_MS, MAX_ENTRIES - 1],
+    )?;
+
+    Ok(id)
+}
+
+/// List history, newest first, optionally filtered and full-text searched.
+pub fn list(storage: &Mutex<Connection>, filter: &HistoryFilter) -> Result<Vec<QueryHistoryEntry>> {
+    let conn = storage.lock();
+
+    let mut sql = format!("SELECT {SELECT_COLUMNS} FROM query_history h");
+    let mut conditions: Vec<&str> = Vec::new();
+    let mut values: Vec<Value> = Vec::new();
+
+    if let Some(search) = filter.search.as_deref().and_then(fts_query) {
+        sql.push_str(" JOIN query_history_fts f ON f.rowid = h.id");
+        conditions.push("query_history_fts MATCH ?");
+        values.push(Value::Text(search));
+    }
+    if let Some(server_id) = filter.server_id {
+        conditions.push("h.server_id = ?");
+        values.push(Value::Integer(server_id));
+    }
+    if let Some(database) = &filter.database {
+        conditions.push("h.database = ?");
+        values.push(Value::Text(database.clone()));
+    }
+    if filter.pinned_only {
+        conditions.push("h.pinned = 1");
+    }
+    if filter.errors_only {
+        conditions.push("h.error_code IS NOT NULL");
+    }
+    if let Some(from) = filter.from {
+        conditions.push("h.started_at >= ?");
+        values.push(Value::Integer(from));
+    }
+    if let Some(to) = filter.to {
+        conditions.push("h.started_at <= ?");
+        values.push(Value::Integer(to));
+    }
+
+    if !conditions.is_empty() {
+        sql.push_str(" WHERE ");
+        sql.push_str(&conditions.join(" AND "));
+    }
+
+    let limit = match filter.limit {
+        0 => DEFAULT_PAGE_SIZE,
+        n => n.min(MAX_PAGE_SIZE),
+    };
+    sql.push_str(" ORDER BY h.started_at DESC, h.id DESC LIMIT ? OFFSET ?");
+    values.push(Value::Integer(limit as i64));
+    values.push(Value::Integer(filter.offset as i64));
+
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(params_from_iter(values), map_row)?;
+    rows.collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|e| Error::Storage(e.to_string()))
+}
+
+/// Pin (keep forever) or unpin an entry.
+pub fn set_pinned(storage: &Mutex<Connection>, id: i64, pinned: bool) -> Result<()> {
+    let conn = storage.lock();
+
+    let affected = conn.execute(
+        "UPDATE query_history SET pinned = ?1 WHERE id = ?2",
+        params![pinned as i32, id],
+    )?;
+
+    if affected == 0 {
+        return Err(Error::NotFound(format!("History entry {id} not found")));
+    }
+
+    Ok(())
+}
+
+/// Delete entries matching `purge`; returns how many were removed.
+pub fn purge(storage: &Mutex<Connection>, purge: &HistoryPurge) -> Result<usize> {
+    let conn = storage.lock();
+
+    let mut conditions: Vec<&str> = Vec::new();
+    let mut values: Vec<Value> = Vec::new();
+
+    if let Some(server_id) = purge.server_id {
+        conditions.push("server_id = ?");
+        values.push(Value::Integer(server_id));
+    }
+    if !purge.include_pinned {
+        conditions.push("pinned = 0");
+    }
+
+    // keepLast keeps the newest N entries of the same scope (server/pinned),
+    // regardless of `before`.
+    let scope = if conditions.is_empty() {
+        "1 = 1".to_string()
+    } else {
+        conditions.join(" AND ")
+    };
+    let mut scope_values = values.clone();
+
+    let mut sql = format!("DELETE FROM query_history WHERE {scope}");
+    if let Some(before) = purge.before {
+        sql.push_str(" AND started_at < ?");
+        values.push(Value::Integer(before));
+    }
+    if let Some(keep_last) = purge.keep_last {
+        sql.push_str(&format!(
+            " AND id NOT IN (SELECT id FROM query_history WHERE {scope} \
+                             ORDER BY started_at DESC, id DESC LIMIT ?)"
+        ));
+        scope_values.push(Value::Integer(keep_last as i64));
+        values.extend(scope_values);
+    }
+
+    Ok(conn.execute(&sql, params_from_iter(values))?)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn map_row(row: &Row<'_>) -> rusqlite::Result<QueryHistoryEntry> {
+    Ok(QueryHistoryEntry {
+        id: row.get(0)?,
+        server_id: row.get(1)?,
+        database: row.get(2)?,
+        kind: parse_kind(&row.get::<_, String>(3)?),
+        query: row.get(4)?,
+        started_at: row.get(5)?,
+        duration_ms: row.get(6)?,
+        row_count: row.get(7)?,
+        affected_rows: row.get(8)?,
+        error_code: row.get(9)?,
+        error_message: row.get(10)?,
+        pinned: row.get::<_, i32>(11)? != 0,
+    })
+}
+
+/// Turns free text into an FTS5 query: each word becomes a quoted prefix
+/// term, so operators and punctuation typed by the user (`*`, `"`, `-`,
+/// `:`) are matched literally instead of breaking the MATCH syntax.
+fn fts_query(search: &str) -> Option<String> {
+    let terms: Vec<String> = search
+        .split_whitespace()
+        .map(|word| format!("\"{}\"*", word.replace('"', "\"\"")))
+        .collect();
+
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+fn parse_kind(s: &str) -> HistoryKind {
+    match s {
+        "statement" => HistoryKind::Statement,
+        "transaction" => HistoryKind::Transaction,
+        _ => HistoryKind::Query,
+    }
+}
+
+fn kind_to_string(kind: HistoryKind) -> &'static str {
+    match kind {
+        HistoryKind::Query => "query",
+        HistoryKind::Statement => "statement",
+        HistoryKind::Transaction => "transaction",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{DatabaseType, ServerInput};
+    use crate::storage::database::init_storage;
+    use crate::storage::repositories::servers;
+    use crate::storage::vault;
+
+    fn setup() -> (Mutex<Connection>, i64) {
+        vault::init_for_tests();
+        let storage = Mutex::new(init_storage(":memory:").unwrap());
+        let server = servers::create(
+            &storage,
+            ServerInput {
+                name: "history-test".into(),
+                db_type: DatabaseType::Postgres,
+                host: "localhost".into(),
+                port: 5432,
+                username: "user".into(),
+                password: "pw".into(),
+                default_database: None,
+                tls: None,
+                connection_uri: None,
+                ssh_tunnel: None,
+                read_only: None,
+                confirm_writes: None,
+            },
+        )
+        .unwrap();
+        (storage, server.id.unwrap())
+    }
+
+    fn entry(server_id: i64, query: &str, started_at: i64) -> NewHistoryEntry {
+        NewHistoryEntry {
+            server_id,
+            database: "app".into(),
+            kind: HistoryKind::Query,
+            query: query.into(),
+            started_at,
+            duration_ms: 5,
+            row_count: Some(1),
+            affected_rows: None,
+            error_code: None,
+            error_message: None,
+        }
+    }
+
+    #[test]
+    fn search_matches_words_by_prefix() {
+        let (storage, server_id) = setup();
+        record(
+            &storage,
+            &entry(server_id, "SELECT status FROM orders", 1_000),
+        )
+        .unwrap();
+        record(
+            &storage,
+            &entry(server_id, "SELECT * FROM customers", 2_000),
+        )
+        .unwrap();
+
+        let search = |text: &str| {
+            list(
+                &storage,
+                &HistoryFilter {
+                    search: Some(text.into()),
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+        };
+
+        let found = search("orders stat");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].query, "SELECT status FROM orders");
+
+        // FTS operators typed by the user don't break the query
+        assert_eq!(search("\"*").len(), 0);
+        assert_eq!(search("cust").len(), 1);
+    }
+
+    #[test]
+    fn pinned_entries_survive_purge() {
+        let (storage, server_id) = setup();
+        let keep = record(&storage, &entry(server_id, "SELECT 1", 1_000)).unwrap();
+        record(&storage, &entry(server_id, "SELECT 2", 2_000)).unwrap();
+        record(&storage, &entry(server_id, "SELECT 3", 3_000)).unwrap();
+        set_pinned(&storage, keep, true).unwrap();
+
+        let removed = purge(
+            &storage,
+            &HistoryPurge {
+                keep_last: Some(1),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(removed, 1, "only SELECT 2: pinned and newest are kept");
+
+        let left: Vec<String> = list(&storage, &HistoryFilter::default())
+            .unwrap()
+            .into_iter()
+            .map(|e| e.query)
+            .collect();
+        assert_eq!(left, ["SELECT 3", "SELECT 1"]);
+    }
+
+    #[test]
+    fn retention_drops_old_unpinned_entries() {
+        let (storage, server_id) = setup();
+        let old = record(&storage, &entry(server_id, "SELECT old", 0)).unwrap();
+        record(&storage, &entry(server_id, "SELECT new", MAX_AGE_MS + 1)).unwrap();
+
+        let left = list(&storage, &HistoryFilter::default()).unwrap();
+        assert_eq!(left.len(), 1);
+        assert_ne!(left[0].id, old);
+    }
+}
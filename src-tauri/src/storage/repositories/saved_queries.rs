@@ -0,0 +1,487 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use parking_lot::Mutex;
+use rusqlite::types::Value;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension, Row};
+
+use crate::error::{Error, Result};
+use crate::models::{
+    SavedQuery, SavedQueryExport, SavedQueryFilter, SavedQueryImportSummary, SavedQueryInput,
+    SavedQueryLibrary,
+};
+use crate::storage::repositories::servers::{db_type_to_string, parse_db_type};
+
+const SELECT_COLUMNS: &str = "q.id, q.name, q.folder, q.tags, q.body, q.server_id, q.db_type, \
+                              q.created_at, q.updated_at";
+
+/// Format version written to exported libraries.
+pub const LIBRARY_VERSION: u32 = 1;
+
+/// List snippets ordered by folder and name.
+pub fn list(storage: &Mutex<Connection>, filter: &SavedQueryFilter) -> Result<Vec<SavedQuery>> {
+    let conn = storage.lock();
+
+    let mut conditions: Vec<&str> = Vec::new();
+    let mut values: Vec<Value> = Vec::new();
+
+    if let Some(search) = filter
+        .search
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+    {
+        conditions.push(
+            "(q.name LIKE ? ESCAPE '\\' OR q.body LIKE ? ESCAPE '\\' \
+              OR q.folder LIKE ? ESCAPE '\\' OR q.tags LIKE ? ESCAPE '\\')",
+        );
+        let pattern = format!("%{}%", escape_like(search));
+        values.extend(std::iter::repeat_n(Value::Text(pattern), 4));
+    }
+    if let Some(folder) = filter.folder.as_deref().and_then(normalize_folder) {
+        conditions.push("(q.folder = ? OR q.folder LIKE ? ESCAPE '\\')");
+        values.push(Value::Text(folder.clone()));
+        values.push(Value::Text(format!("{}/%", escape_like(&folder))));
+    }
+    if let Some(tag) = &filter.tag {
+        conditions.push("EXISTS (SELECT 1 FROM json_each(q.tags) WHERE json_each.value = ?)");
+        values.push(Value::Text(tag.trim().to_string()));
+    }
+    if let Some(server_id) = filter.server_id {
+        conditions.push("(q.server_id IS NULL OR q.server_id = ?)");
+        values.push(Value::Integer(server_id));
+    }
+    if let Some(db_type) = &filter.db_type {
+        conditions.push("(q.db_type IS NULL OR q.db_type = ?)");
+        values.push(Value::Text(db_type_to_string(db_type).to_string()));
+    }
+
+    let mut sql = format!("SELECT {SELECT_COLUMNS} FROM saved_queries q");
+    if !conditions.is_empty() {
+        sql.push_str(" WHERE ");
+        sql.push_str(&conditions.join(" AND "));
+    }
+    sql.push_str(" ORDER BY COALESCE(q.folder, '') COLLATE NOCASE, q.name COLLATE NOCASE");
+
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(params_from_iter(values), map_row)?;
+    rows.collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|e| Error::Storage(e.to_string()))
+}
+
+pub fn get_by_id(storage: &Mutex<Connection>, id: i64) -> Result<SavedQuery> {
+    let conn = storage.lock();
+    select_one(&conn, id)
+}
+
+pub fn create(storage: &Mutex<Connection>, input: SavedQueryInput) -> Result<SavedQuery> {
+    let conn = storage.lock();
+    let input = normalize(input)?;
+    let now = now_secs()?;
+
+    conn.execute(
+        "INSERT INTO saved_queries (name, folder, tags, body, server_id, db_type, \
+                                    created_at, updated_at) \
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?7)",
+        params![
+            input.name,
+            input.folder,
+            tags_to_json(&input.tags)?,
+            input.body,
+            input.server_id,
+            input.db_type.as_ref().map(db_type_to_string),
+            now,
+        ],
+    )
+    .map_err(|e| map_write_error(e, &input))?;
+
+    select_one(&conn, conn.last_insert_rowid())
+}
+
+pub fn update(storage: &Mutex<Connection>, id: i64, input: SavedQueryInput) -> Result<SavedQuery> {
+    let conn = storage.lock();
+    let input = normalize(input)?;
+
+    let affected = conn
+        .execute(
+            "UPDATE saved_queries \
+             SET name = ?1, folder = ?2, tags = ?3, body = ?4, server_id = ?5, db_type = ?6, \
+                 updated_at = ?7 \
+             WHERE id = ?8",
+            params![
+                input.name,
+                input.folder,
+                tags_to_json(&input.tags)?,
+                input.body,
+                input.server_id,
+                input.db_type.as_ref().map(db_type_to_string),
+                now_secs()?,
+                id,
+            ],
+        )
+        .map_err(|e| map_write_error(e, &input))?;
+
+    if affected == 0 {
+        return Err(Error::NotFound(format!("Saved query {id} not found")));
+    }
+
+    select_one(&conn, id)
+}
+
+pub fn delete(storage: &Mutex<Connection>, id: i64) -> Result<()> {
+    let conn = storage.lock();
+
+    let affected = conn.execute("DELETE FROM saved_queries WHERE id = ?", [id])?;
+    if affected == 0 {
+        return Err(Error::NotFound(format!("Saved query {id} not found")));
+    }
+
+    Ok(())
+}
+
+/// Build the shareable library: all snippets, or only `ids` when given.
+pub fn export(storage: &Mutex<Connection>, ids: Option<&[i64]>) -> Result<SavedQueryLibrary> {
+    let conn = storage.lock();
+
+    let mut stmt = conn.prepare(
+        "SELECT q.id, q.name, q.folder, q.tags, q.body, q.db_type, s.name \
+         FROM saved_queries q LEFT JOIN servers s ON s.id = q.server_id \
+         ORDER BY COALESCE(q.folder, '') COLLATE NOCASE, q.name COLLATE NOCASE",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        let id: i64 = row.get(0)?;
+        let entry = SavedQueryExport {
+            name: row.get(1)?,
+            folder: row.get(2)?,
+            tags: parse_tags(&row.get::<_, String>(3)?),
+            body: row.get(4)?,
+            db_type: row.get::<_, Option<String>>(5)?.map(parse_db_type),
+            server_name: row.get(6)?,
+        };
+        Ok((id, entry))
+    })?;
+
+    let mut queries = Vec::new();
+    for row in rows {
+        let (id, entry) = row?;
+        if ids.is_none_or(|ids| ids.contains(&id)) {
+            queries.push(entry);
+        }
+    }
+
+    Ok(SavedQueryLibrary {
+        version: LIBRARY_VERSION,
+        queries,
+    })
+}
+
+/// Merge a library into the local one. A snippet with the same folder + name
+/// is replaced; `serverName` binds to the local server of that name, if any.
+/// All or nothing: a bad entry rolls the whole import back.
+pub fn import(
+    storage: &Mutex<Connection>,
+    library: SavedQueryLibrary,
+) -> Result<SavedQueryImportSummary> {
+    if library.version > LIBRARY_VERSION {
+        return Err(Error::InvalidState(format!(
+            "Saved query library version {} is newer than this app supports ({LIBRARY_VERSION})",
+            library.version
+        )));
+    }
+
+    let mut conn = storage.lock();
+    let tx = conn.transaction()?;
+    let now = now_secs()?;
+    let mut summary = SavedQueryImportSummary::default();
+
+    for entry in library.queries {
+        let server_id: Option<i64> = match &entry.server_name {
+            Some(name) => tx
+                .query_row(
+                    "SELECT id FROM servers WHERE name = ? ORDER BY id LIMIT 1",
+                    [name],
+                    |r| r.get(0),
+                )
+                .optional()?,
+            None => None,
+        };
+        let input = normalize(SavedQueryInput {
+            name: entry.name,
+            folder: entry.folder,
+            tags: entry.tags,
+            body: entry.body,
+            server_id,
+            db_type: entry.db_type,
+        })?;
+        let tags = tags_to_json(&input.tags)?;
+        let db_type = input.db_type.as_ref().map(db_type_to_string);
+
+        let existing: Option<i64> = tx
+            .query_row(
+                "SELECT id FROM saved_queries WHERE COALESCE(folder, '') = ?1 AND name = ?2",
+                params![input.folder.as_deref().unwrap_or(""), input.name],
+                |r| r.get(0),
+            )
+            .optional()?;
+
+        match existing {
+            Some(id) => {
+                tx.execute(
+                    "UPDATE saved_queries \
+                     SET tags = ?1, body = ?2, server_id = ?3, db_type = ?4, updated_at = ?5 \
+                     WHERE id = ?6",
+                    params![tags, input.body, input.server_id, db_type, now, id],
+                )?;
+                summary.updated += 1;
+            }
+            None => {
+                tx.execute(
+                    "INSERT INTO saved_queries (name, folder, tags, body, server_id, db_type, \
+                                                created_at, updated_at) \
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?7)",
+                    params![
+                        input.name,
+                        input.folder,
+                        tags,
+                        input.body,
+                        input.server_id,
+                        db_type,
+                        now
+                    ],
+                )?;
+                summary.created += 1;
+            }
+        }
+    }
+
+    tx.commit()?;
+    Ok(summary)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Helpers
+// ─────────────────────────────────────────────────────────────────────────────
+
+fn select_one(conn: &Connection, id: i64) -> Result<SavedQuery> {
+    conn.query_row(
+        &format!("SELECT {SELECT_COLUMNS} FROM saved_queries q WHERE q.id = ?"),
+        [id],
+        map_row,
+    )
+    .map_err(|e| match e {
+        rusqlite::Error::QueryReturnedNoRows => {
+            Error::NotFound(format!("Saved query {id} not found"))
+        }
+        _ => Error::Storage(e.to_string()),
+    })
+}
+
+fn map_row(row: &Row<'_>) -> rusqlite::Result<SavedQuery> {
+    Ok(SavedQuery {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        folder: row.get(2)?,
+        tags: parse_tags(&row.get::<_, String>(3)?),
+        body: row.get(4)?,
+        server_id: row.get(5)?,
+        db_type: row.get::<_, Option<String>>(6)?.map(parse_db_type),
+        created_at: row.get(7)?,
+        updated_at: row.get(8)?,
+    })
+}
+
+/// Trims the name, collapses the folder path (`/a//b/` → `a/b`, empty →
+/// root) and drops blank or repeated tags.
+fn normalize(mut input: SavedQueryInput) -> Result<SavedQueryInput> {
+    input.name = input.name.trim().to_string();
+    if input.name.is_empty() {
+        return Err(Error::InvalidState("Saved query name is required".into()));
+    }
+    if input.body.trim().is_empty() {
+        return Err(Error::InvalidState(format!(
+            "Saved query '{}' has an empty body",
+            input.name
+        )));
+    }
+
+    input.folder = input.folder.as_deref().and_then(normalize_folder);
+
+    let mut tags: Vec<String> = Vec::new();
+    for tag in input.tags.iter().map(|t| t.trim()) {
+        if !tag.is_empty() && !tags.iter().any(|t| t == tag) {
+            tags.push(tag.to_string());
+        }
+    }
+    input.tags = tags;
+
+    Ok(input)
+}
+
+fn normalize_folder(folder: &str) -> Option<String> {
+    let path: Vec<&str> = folder
+        .split('/')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .collect();
+    (!path.is_empty()).then(|| path.join("/"))
+}
+
+fn map_write_error(e: rusqlite::Error, input: &SavedQueryInput) -> Error {
+    match &e {
+        rusqlite::Error::SqliteFailure(err, _)
+            if err.extended_code == rusqlite::ffi::SQLITE_CONSTRAINT_UNIQUE =>
+        {
+            Error::AlreadyExists(format!(
+                "Saved query '{}' already exists in {}",
+                input.name,
+                input.folder.as_deref().unwrap_or("the library root")
+            ))
+        }
+        rusqlite::Error::SqliteFailure(err, _)
+            if err.extended_code == rusqlite::ffi::SQLITE_CONSTRAINT_FOREIGNKEY =>
+        {
+            Error::NotFound(format!(
+                "Server with id {} not found",
+                input.server_id.unwrap_or_default()
+            ))
+        }
+        _ => Error::Storage(e.to_string()),
+    }
+}
+
+fn escape_like(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+fn tags_to_json(tags: &[String]) -> Result<String> {
+    serde_json::to_string(tags).map_err(|e| Error::Storage(e.to_string()))
+}
+
+fn parse_tags(json: &str) -> Vec<String> {
+    serde_json::from_str(json).unwrap_or_default()
+}
+
+fn now_secs() -> Result<i64> {
+    Ok(SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| Error::Storage(e.to_string()))?
+        .as_secs() as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::DatabaseType;
+    use crate::storage::database::init_storage;
+
+    fn input(name: &str, folder: Option<&str>, tags: &[&str]) -> SavedQueryInput {
+        SavedQueryInput {
+            name: name.into(),
+            folder: folder.map(Into::into),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            body: format!("SELECT '{name}'"),
+            server_id: None,
+            db_type: None,
+        }
+    }
+
+    fn names(queries: Vec<SavedQuery>) -> Vec<String> {
+        queries.into_iter().map(|q| q.name).collect()
+    }
+
+    #[test]
+    fn filters_by_folder_tag_search_and_scope() {
+        let storage = Mutex::new(init_storage(":memory:").unwrap());
+        create(
+            &storage,
+            input("locks", Some("/diag//pg/"), &["pg", " pg ", ""]),
+        )
+        .unwrap();
+        create(&storage, input("bloat", Some("diag"), &["size"])).unwrap();
+        create(
+            &storage,
+            SavedQueryInput {
+                db_type: Some(DatabaseType::Mysql),
+                ..input("processlist", None, &[])
+            },
+        )
+        .unwrap();
+
+        let by = |filter: SavedQueryFilter| names(list(&storage, &filter).unwrap());
+
+        let locks = &list(&storage, &SavedQueryFilter::default()).unwrap()[2];
+        assert_eq!(locks.folder.as_deref(), Some("diag/pg"));
+        assert_eq!(locks.tags, ["pg"]);
+
+        assert_eq!(
+            by(SavedQueryFilter {
+                folder: Some("diag".into()),
+                ..Default::default()
+            }),
+            ["bloat", "locks"]
+        );
+        assert_eq!(
+            by(SavedQueryFilter {
+                tag: Some("size".into()),
+                ..Default::default()
+            }),
+            ["bloat"]
+        );
+        assert_eq!(
+            by(SavedQueryFilter {
+                search: Some("PROCESS".into()),
+                ..Default::default()
+            }),
+            ["processlist"]
+        );
+        assert_eq!(
+            by(SavedQueryFilter {
+                db_type: Some(DatabaseType::Postgres),
+                ..Default::default()
+            }),
+            ["bloat", "locks"]
+        );
+    }
+
+    #[test]
+    fn duplicate_path_is_rejected() {
+        let storage = Mutex::new(init_storage(":memory:").unwrap());
+        create(&storage, input("locks", None, &[])).unwrap();
+
+        let err = create(&storage, input(" locks ", Some("/"), &[])).unwrap_err();
+        assert!(matches!(err, Error::AlreadyExists(_)), "got {err:?}");
+
+        // Same name in another folder is fine
+        create(&storage, input("locks", Some("pg"), &[])).unwrap();
+    }
+
+    #[test]
+    fn export_import_round_trip_replaces_by_path() {
+        let source = Mutex::new(init_storage(":memory:").unwrap());
+        create(&source, input("locks", Some("diag"), &["pg"])).unwrap();
+        create(&source, input("sizes", None, &[])).unwrap();
+        let library = export(&source, None).unwrap();
+
+        let json = serde_json::to_string(&library).unwrap();
+        let library: SavedQueryLibrary = serde_json::from_str(&json).unwrap();
+
+        let target = Mutex::new(init_storage(":memory:").unwrap());
+        create(
+            &target,
+            SavedQueryInput {
+                body: "old".into(),
+                ..input("locks", Some("diag"), &[])
+            },
+        )
+        .unwrap();
+
+        let summary = import(&target, library).unwrap();
+        assert_eq!((summary.created, summary.updated), (1, 1));
+
+        let all = list(&target, &SavedQueryFilter::default()).unwrap();
+        assert_eq!(names(all.clone()), ["sizes", "locks"]);
+        assert_eq!(all[1].body, "SELECT 'locks'");
+        assert_eq!(all[1].tags, ["pg"]);
+    }
+}
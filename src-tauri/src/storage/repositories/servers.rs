@@ -4,11 +4,16 @@ use parking_lot::Mutex;
 use rusqlite::{params, Connection, Row};
 
 use crate::error::{Error, Result};
-use crate::models::{DatabaseType, Server, ServerInput};
+use crate::models::{DatabaseType, Server, ServerInput, SshTunnel, TlsConfig, TlsMode};
 use crate::storage::{secrets, vault};
 
 const SELECT_COLUMNS: &str = "id, name, db_type, host, port, username, password, \
-                              default_database, ssl_enabled, connection_uri, created_at";
+                              default_database, ssl_enabled, connection_uri, created_at, \
+                              ssh_host, ssh_port, ssh_username, ssh_password, \
+                              ssh_private_key_path, ssh_passphrase, \
+                              tls_mode, tls_ca_path, tls_client_cert_path, \
+                              tls_client_key_path, tls_key_passphrase, \
+                              read_only, confirm_writes";
 
 /// Get all servers (metadata only). The UI never displays passwords, so the
 /// stored ciphertext is never decrypted nor returned here.
@@ -26,7 +31,7 @@ pub fn get_all(storage: &Mutex<Connection>) -> Result<Vec<Server>> {
 
     // Never expose the stored ciphertext.
     for server in &mut servers {
-        server.password.clear();
+        clear_secrets(server);
     }
 
     Ok(servers)
@@ -38,15 +43,17 @@ pub fn get_by_id(storage: &Mutex<Connection>, id: i64) -> Result<Server> {
     let conn = storage.lock();
     let mut server = select_one(&conn, id)?;
     decrypt_password(&conn, &mut server);
+    decrypt_connection_secrets(&mut server);
     Ok(server)
 }
 
 /// Get a server by ID without decrypting the password — use it for metadata-only
-/// needs (db type, default database). The `password` field comes back empty.
+/// needs (db type, default database). The `password` field (and the SSH
+/// secrets) come back empty.
 pub fn get_by_id_meta(storage: &Mutex<Connection>, id: i64) -> Result<Server> {
     let conn = storage.lock();
     let mut server = select_one(&conn, id)?;
-    server.password.clear();
+    clear_secrets(&mut server);
     Ok(server)
 }
 
@@ -63,8 +70,9 @@ fn select_one(conn: &Connection, id: i64) -> Result<Server> {
     })
 }
 
-/// Create a new server. The password is encrypted into the vault before being
-/// stored — the SQLite column only ever holds ciphertext.
+/// Create a new server. The password (and the SSH password/passphrase and TLS
+/// key passphrase) are encrypted into the vault before being stored — the
+/// SQLite columns only ever hold ciphertext.
 pub fn create(storage: &Mutex<Connection>, input: ServerInput) -> Result<Server> {
     let conn = storage.lock();
 
@@ -74,13 +82,23 @@ pub fn create(storage: &Mutex<Connection>, input: ServerInput) -> Result<Server>
         .as_secs() as i64;
 
     let db_type_str = db_type_to_string(&input.db_type);
-    let ssl_enabled = input.ssl_enabled.unwrap_or(false) as i32;
+    let tls = input.tls.clone().unwrap_or_default();
+    // Legacy column, kept in sync for older app versions
+    let ssl_enabled = tls.mode.is_enabled() as i32;
+    let tls_passphrase = encrypt_opt(&tls.client_key_passphrase)?;
     let encrypted = vault::encrypt(&input.password)?;
+    let ssh = SshColumns::from_input(input.ssh_tunnel.as_ref())?;
 
     let mut stmt = conn.prepare(&format!(
         "INSERT INTO servers (name, db_type, host, port, username, password, \
-                              default_database, ssl_enabled, connection_uri, created_at) \
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10) \
+                              default_database, ssl_enabled, connection_uri, created_at, \
+                              ssh_host, ssh_port, ssh_username, ssh_password, \
+                              ssh_private_key_path, ssh_passphrase, \
+                              tls_mode, tls_ca_path, tls_client_cert_path, \
+                              tls_client_key_path, tls_key_passphrase, \
+                              read_only, confirm_writes) \
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, \
+                 ?17, ?18, ?19, ?20, ?21, ?22, ?23) \
          RETURNING {SELECT_COLUMNS}"
     ))?;
 
@@ -97,6 +115,19 @@ pub fn create(storage: &Mutex<Connection>, input: ServerInput) -> Result<Server>
                 ssl_enabled,
                 input.connection_uri,
                 created_at,
+                ssh.host,
+                ssh.port,
+                ssh.username,
+                ssh.password,
+                ssh.private_key_path,
+                ssh.passphrase,
+                tls.mode.as_str(),
+                tls.ca_cert_path,
+                tls.client_cert_path,
+                tls.client_key_path,
+                tls_passphrase,
+                input.read_only.unwrap_or(false),
+                input.confirm_writes.unwrap_or(false),
             ],
             map_row,
         )
@@ -104,24 +135,44 @@ pub fn create(storage: &Mutex<Connection>, input: ServerInput) -> Result<Server>
 
     // Keep the plaintext in the returned value for immediate use.
     server.password = input.password;
+    server.ssh_tunnel = input.ssh_tunnel;
+    server.tls = tls;
 
     Ok(server)
 }
 
-/// Update an existing server. The password is encrypted into the vault before
-/// being stored.
+/// Update an existing server. The password (and the SSH/TLS secrets) are
+/// encrypted into the vault before being stored. The SSH and TLS key secrets
+/// never come back to the form, so when the input omits one the stored one is
+/// kept; removing the tunnel (or the client certificate) drops them.
 pub fn update(storage: &Mutex<Connection>, id: i64, input: ServerInput) -> Result<Server> {
     let conn = storage.lock();
 
     let db_type_str = db_type_to_string(&input.db_type);
-    let ssl_enabled = input.ssl_enabled.unwrap_or(false) as i32;
+    let tls = input.tls.clone().unwrap_or_default();
+    // Legacy column, kept in sync for older app versions
+    let ssl_enabled = tls.mode.is_enabled() as i32;
+    let tls_passphrase = encrypt_opt(&tls.client_key_passphrase)?;
     let encrypted = vault::encrypt(&input.password)?;
+    let ssh = SshColumns::from_input(input.ssh_tunnel.as_ref())?;
 
     let mut stmt = conn.prepare(&format!(
         "UPDATE servers \
          SET name = ?1, db_type = ?2, host = ?3, port = ?4, username = ?5, \
-             password = ?6, default_database = ?7, ssl_enabled = ?8, connection_uri = ?9 \
-         WHERE id = ?10 \
+             password = ?6, default_database = ?7, ssl_enabled = ?8, connection_uri = ?9, \
+             ssh_host = ?10, ssh_port = ?11, ssh_username = ?12, \
+             ssh_password = CASE WHEN ?10 IS NULL THEN NULL \
+                                 ELSE COALESCE(?13, ssh_password) END, \
+             ssh_private_key_path = ?14, \
+             ssh_passphrase = CASE WHEN ?10 IS NULL THEN NULL \
+                                   ELSE COALESCE(?15, ssh_passphrase) END, \
+             tls_mode = ?16, \
+             tls_ca_path = ?17, tls_client_cert_path = ?18, tls_client_key_path = ?19, \
+             tls_key_passphrase = CASE WHEN ?18 IS NULL AND ?19 IS NULL THEN NULL \
+                                       ELSE COALESCE(?20, tls_key_passphrase) END, \
+             read_only = COALESCE(?21, read_only), \
+             confirm_writes = COALESCE(?22, confirm_writes) \
+         WHERE id = ?23 \
          RETURNING {SELECT_COLUMNS}"
     ))?;
 
@@ -137,6 +188,19 @@ pub fn update(storage: &Mutex<Connection>, id: i64, input: ServerInput) -> Resul
                 input.default_database,
                 ssl_enabled,
                 input.connection_uri,
+                ssh.host,
+                ssh.port,
+                ssh.username,
+                ssh.password,
+                ssh.private_key_path,
+                ssh.passphrase,
+                tls.mode.as_str(),
+                tls.ca_cert_path,
+                tls.client_cert_path,
+                tls.client_key_path,
+                tls_passphrase,
+                input.read_only,
+                input.confirm_writes,
                 id,
             ],
             map_row,
@@ -149,6 +213,8 @@ pub fn update(storage: &Mutex<Connection>, id: i64, input: ServerInput) -> Resul
         })?;
 
     server.password = input.password;
+    server.ssh_tunnel = input.ssh_tunnel;
+    server.tls = tls;
 
     Ok(server)
 }
@@ -183,12 +249,103 @@ fn map_row(row: &Row<'_>) -> rusqlite::Result<Server> {
         username: row.get(5)?,
         password: row.get(6)?,
         default_database: row.get(7)?,
-        ssl_enabled: row.get::<_, i32>(8)? != 0,
+        tls: TlsConfig {
+            mode: match row.get::<_, Option<String>>(17)? {
+                Some(mode) => parse_tls_mode(&mode),
+                // Row from before TLS modes: ssl_enabled meant "require"
+                None if row.get::<_, i32>(8)? != 0 => TlsMode::Require,
+                None => TlsMode::Disable,
+            },
+            ca_cert_path: row.get(18)?,
+            client_cert_path: row.get(19)?,
+            client_key_path: row.get(20)?,
+            client_key_passphrase: row.get(21)?,
+            server_name: None,
+        },
         connection_uri: row.get(9)?,
         created_at: row.get(10)?,
+        // A NULL ssh_host means "no tunnel"; secrets stay encrypted here.
+        ssh_tunnel: match row.get::<_, Option<String>>(11)? {
+            Some(host) => Some(SshTunnel {
+                host,
+                port: row.get::<_, Option<u16>>(12)?.unwrap_or(22),
+                username: row.get::<_, Option<String>>(13)?.unwrap_or_default(),
+                password: row.get(14)?,
+                private_key_path: row.get(15)?,
+                private_key_passphrase: row.get(16)?,
+            }),
+            None => None,
+        },
+        read_only: row.get(22)?,
+        confirm_writes: row.get(23)?,
     })
 }
 
+/// SSH tunnel settings as stored: secrets already encrypted.
+struct SshColumns {
+    host: Option<String>,
+    port: Option<u16>,
+    username: Option<String>,
+    password: Option<String>,
+    private_key_path: Option<String>,
+    passphrase: Option<String>,
+}
+
+impl SshColumns {
+    fn from_input(tunnel: Option<&SshTunnel>) -> Result<Self> {
+        Ok(match tunnel {
+            Some(t) => Self {
+                host: Some(t.host.clone()),
+                port: Some(t.port),
+                username: Some(t.username.clone()),
+                password: encrypt_opt(&t.password)?,
+                private_key_path: t.private_key_path.clone(),
+                passphrase: encrypt_opt(&t.private_key_passphrase)?,
+            },
+            None => Self {
+                host: None,
+                port: None,
+                username: None,
+                password: None,
+                private_key_path: None,
+                passphrase: None,
+            },
+        })
+    }
+}
+
+fn encrypt_opt(secret: &Option<String>) -> Result<Option<String>> {
+    secret.as_deref().map(vault::encrypt).transpose()
+}
+
+fn clear_secrets(server: &mut Server) {
+    server.password.clear();
+    server.tls.client_key_passphrase = None;
+    if let Some(tunnel) = server.ssh_tunnel.as_mut() {
+        tunnel.password = None;
+        tunnel.private_key_passphrase = None;
+    }
+}
+
+/// SSH and TLS secrets were only ever stored as vault envelopes (no legacy
+/// formats); an undecryptable value is dropped rather than used as a password.
+fn decrypt_connection_secrets(server: &mut Server) {
+    server.tls.client_key_passphrase = server
+        .tls
+        .client_key_passphrase
+        .take()
+        .and_then(|s| vault::decrypt(&s));
+
+    let Some(tunnel) = server.ssh_tunnel.as_mut() else {
+        return;
+    };
+    tunnel.password = tunnel.password.take().and_then(|s| vault::decrypt(&s));
+    tunnel.private_key_passphrase = tunnel
+        .private_key_passphrase
+        .take()
+        .and_then(|s| vault::decrypt(&s));
+}
+
 /// Replace `server.password` (the stored value) with the plaintext password.
 ///
 /// - vault envelope → decrypt;
@@ -227,7 +384,7 @@ fn decrypt_password(conn: &Connection, server: &mut Server) {
     server.password = plain;
 }
 
-fn parse_db_type(s: String) -> DatabaseType {
+pub(crate) fn parse_db_type(s: String) -> DatabaseType {
     match s.to_lowercase().as_str() {
         "mysql" => DatabaseType::Mysql,
         "sqlite" => DatabaseType::Sqlite,
@@ -237,7 +394,16 @@ fn parse_db_type(s: String) -> DatabaseType {
     }
 }
 
-fn db_type_to_string(db_type: &DatabaseType) -> &'static str {
+fn parse_tls_mode(s: &str) -> TlsMode {
+    match s {
+        "require" => TlsMode::Require,
+        "verify-ca" => TlsMode::VerifyCa,
+        "verify-full" => TlsMode::VerifyFull,
+        _ => TlsMode::Disable,
+    }
+}
+
+pub(crate) fn db_type_to_string(db_type: &DatabaseType) -> &'static str {
     match db_type {
         DatabaseType::Postgres => "postgres",
         DatabaseType::Mysql => "mysql",
@@ -261,8 +427,11 @@ mod tests {
             username: "user".into(),
             password: "s3cret".into(),
             default_database: None,
-            ssl_enabled: None,
+            tls: None,
             connection_uri: None,
+            ssh_tunnel: None,
+            read_only: None,
+            confirm_writes: None,
         }
     }
 
@@ -294,4 +463,230 @@ mod tests {
         assert_eq!(get_by_id_meta(&storage, id).unwrap().password, "");
         assert_eq!(get_all(&storage).unwrap()[0].password, "");
     }
+
+    #[test]
+    fn ssh_secrets_are_encrypted_at_rest() {
+        vault::init_for_tests();
+        let storage = Mutex::new(init_storage(":memory:").unwrap());
+
+        let input = ServerInput {
+            ssh_tunnel: Some(SshTunnel {
+                host: "bastion".into(),
+                port: 2222,
+                username: "jump".into(),
+                password: Some("ssh-pw".into()),
+                private_key_path: Some("/home/me/.ssh/id_ed25519".into()),
+                private_key_passphrase: Some("key-pp".into()),
+            }),
+            ..sample_input()
+        };
+        let id = create(&storage, input).unwrap().id.unwrap();
+
+        let (pw, pp): (String, String) = storage
+            .lock()
+            .query_row(
+                "SELECT ssh_password, ssh_passphrase FROM servers WHERE id = ?",
+                [id],
+                |r| Ok((r.get(0)?, r.get(1)?)),
+            )
+            .unwrap();
+        assert!(vault::is_envelope(&pw), "ssh password leaked to SQLite");
+        assert!(vault::is_envelope(&pp), "ssh passphrase leaked to SQLite");
+
+        let tunnel = get_by_id(&storage, id).unwrap().ssh_tunnel.unwrap();
+        assert_eq!(tunnel.host, "bastion");
+        assert_eq!(tunnel.port, 2222);
+        assert_eq!(tunnel.password.as_deref(), Some("ssh-pw"));
+        assert_eq!(tunnel.private_key_passphrase.as_deref(), Some("key-pp"));
+
+        let meta = get_by_id_meta(&storage, id).unwrap().ssh_tunnel.unwrap();
+        assert_eq!(meta.password, None);
+        assert_eq!(meta.private_key_passphrase, None);
+        assert_eq!(meta.private_key_path.as_deref(), Some("/home/me/.ssh/id_ed25519"));
+    }
+
+    #[test]
+    fn tls_settings_round_trip() {
+        vault::init_for_tests();
+        let storage = Mutex::new(init_storage(":memory:").unwrap());
+
+        let input = ServerInput {
+            tls: Some(TlsConfig {
+                mode: TlsMode::VerifyFull,
+                ca_cert_path: Some("/etc/ssl/db-ca.pem".into()),
+                client_cert_path: Some("/etc/ssl/client.pem".into()),
+                client_key_path: Some("/etc/ssl/client.key".into()),
+                client_key_passphrase: Some("key-pp".into()),
+                server_name: None,
+            }),
+            ..sample_input()
+        };
+        let id = create(&storage, input).unwrap().id.unwrap();
+
+        let stored: String = storage
+            .lock()
+            .query_row(
+                "SELECT tls_key_passphrase FROM servers WHERE id = ?",
+                [id],
+                |r| r.get(0),
+            )
+            .unwrap();
+        assert!(vault::is_envelope(&stored), "tls passphrase leaked to SQLite");
+
+        let tls = get_by_id(&storage, id).unwrap().tls;
+        assert_eq!(tls.mode, TlsMode::VerifyFull);
+        assert_eq!(tls.ca_cert_path.as_deref(), Some("/etc/ssl/db-ca.pem"));
+        assert_eq!(tls.client_key_passphrase.as_deref(), Some("key-pp"));
+        assert_eq!(get_by_id_meta(&storage, id).unwrap().tls.client_key_passphrase, None);
+    }
+
+    #[test]
+    fn legacy_ssl_enabled_reads_as_require() {
+        vault::init_for_tests();
+        let storage = Mutex::new(init_storage(":memory:").unwrap());
+
+        let id = create(&storage, sample_input()).unwrap().id.unwrap();
+        storage
+            .lock()
+            .execute(
+                "UPDATE servers SET ssl_enabled = 1, tls_mode = NULL WHERE id = ?",
+                [id],
+            )
+            .unwrap();
+
+        assert_eq!(get_by_id_meta(&storage, id).unwrap().tls.mode, TlsMode::Require);
+    }
+
+    #[test]
+    fn safety_flags_round_trip() {
+        vault::init_for_tests();
+        let storage = Mutex::new(init_storage(":memory:").unwrap());
+
+        let id = create(&storage, sample_input()).unwrap().id.unwrap();
+        assert!(!get_by_id_meta(&storage, id).unwrap().read_only);
+
+        let input = ServerInput {
+            read_only: Some(true),
+            confirm_writes: Some(true),
+            ..sample_input()
+        };
+        update(&storage, id, input).unwrap();
+
+        let server = get_by_id_meta(&storage, id).unwrap();
+        assert!(server.read_only);
+        assert!(server.confirm_writes);
+    }
+
+    #[test]
+    fn safety_flags_survive_an_update_that_omits_them() {
+        vault::init_for_tests();
+        let storage = Mutex::new(init_storage(":memory:").unwrap());
+
+        let input = ServerInput {
+            read_only: Some(true),
+            confirm_writes: Some(true),
+            ..sample_input()
+        };
+        let id = create(&storage, input).unwrap().id.unwrap();
+
+        // The server form doesn't send the flags
+        let input = ServerInput {
+            name: "renamed".into(),
+            ..sample_input()
+        };
+        let updated = update(&storage, id, input).unwrap();
+        assert!(updated.read_only);
+        assert!(updated.confirm_writes);
+
+        let input = ServerInput {
+            read_only: Some(false),
+            ..sample_input()
+        };
+        update(&storage, id, input).unwrap();
+        let server = get_by_id_meta(&storage, id).unwrap();
+        assert!(!server.read_only);
+        assert!(server.confirm_writes);
+    }
+
+    #[test]
+    fn ssh_secrets_survive_an_update_that_omits_them() {
+        vault::init_for_tests();
+        let storage = Mutex::new(init_storage(":memory:").unwrap());
+
+        let tunnel = SshTunnel {
+            host: "bastion".into(),
+            port: 22,
+            username: "jump".into(),
+            password: Some("ssh-pw".into()),
+            private_key_path: None,
+            private_key_passphrase: Some("key-pp".into()),
+        };
+        let input = ServerInput {
+            ssh_tunnel: Some(tunnel.clone()),
+            ..sample_input()
+        };
+        let id = create(&storage, input).unwrap().id.unwrap();
+
+        // What the form sends back: the tunnel as listed, without secrets
+        let input = ServerInput {
+            ssh_tunnel: Some(SshTunnel {
+                port: 2222,
+                password: None,
+                private_key_passphrase: None,
+                ..tunnel
+            }),
+            ..sample_input()
+        };
+        update(&storage, id, input).unwrap();
+        let kept = get_by_id(&storage, id).unwrap().ssh_tunnel.unwrap();
+        assert_eq!(kept.port, 2222);
+        assert_eq!(kept.password.as_deref(), Some("ssh-pw"));
+        assert_eq!(kept.private_key_passphrase.as_deref(), Some("key-pp"));
+
+        update(&storage, id, sample_input()).unwrap();
+        assert!(get_by_id(&storage, id).unwrap().ssh_tunnel.is_none());
+        let stored: Option<String> = storage
+            .lock()
+            .query_row("SELECT ssh_password FROM servers WHERE id = ?", [id], |r| {
+                r.get(0)
+            })
+            .unwrap();
+        assert_eq!(stored, None);
+    }
+
+    #[test]
+    fn tls_passphrase_survives_an_update_that_omits_it() {
+        vault::init_for_tests();
+        let storage = Mutex::new(init_storage(":memory:").unwrap());
+
+        let tls = TlsConfig {
+            mode: TlsMode::Require,
+            ca_cert_path: None,
+            client_cert_path: Some("/etc/ssl/client.pem".into()),
+            client_key_path: Some("/etc/ssl/client.key".into()),
+            client_key_passphrase: Some("key-pp".into()),
+            server_name: None,
+        };
+        let input = ServerInput {
+            tls: Some(tls.clone()),
+            ..sample_input()
+        };
+        let id = create(&storage, input).unwrap().id.unwrap();
+
+        let input = ServerInput {
+            tls: Some(TlsConfig {
+                mode: TlsMode::VerifyFull,
+                client_key_passphrase: None,
+                ..tls
+            }),
+            ..sample_input()
+        };
+        update(&storage, id, input).unwrap();
+        let kept = get_by_id(&storage, id).unwrap().tls;
+        assert_eq!(kept.mode, TlsMode::VerifyFull);
+        assert_eq!(kept.client_key_passphrase.as_deref(), Some("key-pp"));
+
+        update(&storage, id, sample_input()).unwrap();
+        assert_eq!(get_by_id(&storage, id).unwrap().tls.client_key_passphrase, None);
+    }
 }
@@ -0,0 +1,171 @@
+use parking_lot::Mutex;
+use rusqlite::{params, Connection, OptionalExtension};
+
+use crate::error::Result;
+
+/// Where a cached payload is stored: the connection plus the object path,
+/// flattened to `kind` / `schema` / `table` (empty when they don't apply).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheSlot {
+    pub server_id: i64,
+    pub database: String,
+    pub kind: String,
+    pub schema: String,
+    pub table: String,
+}
+
+/// The JSON payload of a slot and when it was cached (ms).
+pub fn get(storage: &Mutex<Connection>, slot: &CacheSlot) -> Result<Option<(String, i64)>> {
+    let conn = storage.lock();
+    let entry = conn
+        .query_row(
+            "SELECT payload, cached_at FROM structure_cache \
+             WHERE server_id = ?1 AND database = ?2 AND kind = ?3 \
+               AND schema_name = ?4 AND table_name = ?5",
+            params![
+                slot.server_id,
+                slot.database,
+                slot.kind,
+                slot.schema,
+                slot.table
+            ],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?;
+    Ok(entry)
+}
+
+pub fn put(
+    storage: &Mutex<Connection>,
+    slot: &CacheSlot,
+    payload: &str,
+    cached_at: i64,
+) -> Result<()> {
+    let conn = storage.lock();
+    conn.execute(
+        "INSERT OR REPLACE INTO structure_cache \
+             (server_id, database, kind, schema_name, table_name, payload, cached_at) \
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            slot.server_id,
+            slot.database,
+            slot.kind,
+            slot.schema,
+            slot.table,
+            payload,
+            cached_at
+        ],
+    )?;
+    Ok(())
+}
+
+/// Every slot cached for a server, to pick the ones to invalidate.
+pub fn slots(storage: &Mutex<Connection>, server_id: i64) -> Result<Vec<CacheSlot>> {
+    let conn = storage.lock();
+    let mut stmt = conn.prepare(
+        "SELECT database, kind, schema_name, table_name FROM structure_cache \
+         WHERE server_id = ?1",
+    )?;
+    let slots = stmt
+        .query_map(params![server_id], |row| {
+            Ok(CacheSlot {
+                server_id,
+                database: row.get(0)?,
+                kind: row.get(1)?,
+                schema: row.get(2)?,
+                table: row.get(3)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(slots)
+}
+
+pub fn delete(storage: &Mutex<Connection>, slot: &CacheSlot) -> Result<()> {
+    let conn = storage.lock();
+    conn.execute(
+        "DELETE FROM structure_cache \
+         WHERE server_id = ?1 AND database = ?2 AND kind = ?3 \
+           AND schema_name = ?4 AND table_name = ?5",
+        params![
+            slot.server_id,
+            slot.database,
+            slot.kind,
+            slot.schema,
+            slot.table
+        ],
+    )?;
+    Ok(())
+}
+
+pub fn delete_server(storage: &Mutex<Connection>, server_id: i64) -> Result<()> {
+    let conn = storage.lock();
+    conn.execute(
+        "DELETE FROM structure_cache WHERE server_id = ?1",
+        params![server_id],
+    )?;
+    Ok(())
+}
+
+/// Drop what expired, so entries of servers no longer browsed don't pile up.
+pub fn prune(storage: &Mutex<Connection>, cached_before: i64) -> Result<usize> {
+    let conn = storage.lock();
+    let removed = conn.execute(
+        "DELETE FROM structure_cache WHERE cached_at < ?1",
+        params![cached_before],
+    )?;
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{DatabaseType, ServerInput};
+    use crate::storage::database::init_storage;
+    use crate::storage::repositories::servers;
+    use crate::storage::vault;
+
+    fn setup() -> (Mutex<Connection>, i64) {
+        vault::init_for_tests();
+        let storage = Mutex::new(init_storage(":memory:").unwrap());
+        let server = servers::create(
+            &storage,
+            ServerInput {
+                name: "cache-test".into(),
+                db_type: DatabaseType::Postgres,
+                host: "localhost".into(),
+                port: 5432,
+                username: "user".into(),
+                password: "pw".into(),
+                default_database: None,
+                tls: None,
+                connection_uri: None,
+                ssh_tunnel: None,
+                read_only: None,
+                confirm_writes: None,
+            },
+        )
+        .unwrap();
+        (storage, server.id.unwrap())
+    }
+
+    #[test]
+    fn slots_round_trip_and_go_with_the_server() {
+        let (storage, server_id) = setup();
+        let slot = CacheSlot {
+            server_id,
+            database: "app".into(),
+            kind: "columns".into(),
+            schema: "public".into(),
+            table: "users".into(),
+        };
+
+        put(&storage, &slot, "[]", 10).unwrap();
+        put(&storage, &slot, "[1]", 20).unwrap();
+        assert_eq!(get(&storage, &slot).unwrap(), Some(("[1]".into(), 20)));
+        assert_eq!(slots(&storage, server_id).unwrap(), vec![slot.clone()]);
+
+        assert_eq!(prune(&storage, 20).unwrap(), 0);
+        servers::delete(&storage, server_id).unwrap();
+        assert_eq!(get(&storage, &slot).unwrap(), None);
+    }
+}
@@ -8,8 +8,9 @@ export interface Server {
   port: number;
   username: string;
   defaultDatabase: string | null;
-  sslEnabled: boolean;
+  tls: TlsConfig;
   connectionUri: string | null;
+  sshTunnel: SshTunnel | null;
   /** Epoch in seconds */
   createdAt: number;
 }
@@ -23,8 +24,33 @@ export interface ServerInput {
   /** Stored in the OS keychain by the backend — never returned on Server */
   password: string;
   defaultDatabase?: string | null;
-  sslEnabled?: boolean | null;
+  /** Absent = TLS disabled */
+  tls?: TlsConfig | null;
   connectionUri?: string | null;
+  /** Absent/null = no tunnel */
+  sshTunnel?: SshTunnel | null;
+}
+
+export type TlsMode = 'disable' | 'require' | 'verify-ca' | 'verify-full';
+
+export interface TlsConfig {
+  mode: TlsMode;
+  caCertPath?: string | null;
+  clientCertPath?: string | null;
+  clientKeyPath?: string | null;
+  /** Sent on create/update only — never returned on Server */
+  clientKeyPassphrase?: string | null;
+}
+
+export interface SshTunnel {
+  host: string;
+  port: number;
+  username: string;
+  /** Sent on create/update only — never returned on Server */
+  password?: string | null;
+  privateKeyPath: string | null;
+  /** Sent on create/update only — never returned on Server */
+  privateKeyPassphrase?: string | null;
 }
 
 export interface PoolStats {
@@ -152,8 +152,13 @@ export function ServerForm(props: ServerFormProps) {
               <div className="flex items-center gap-2">
                 <Switch
                   id="ssl-enabled"
-                  checked={!!form.sslEnabled}
-                  onCheckedChange={checked => setField('sslEnabled', checked)}
+                  checked={!!form.tls && form.tls.mode !== 'disable'}
+                  onCheckedChange={checked =>
+                    setField('tls', {
+                      ...form.tls,
+                      mode: checked ? 'require' : 'disable',
+                    })
+                  }
                 />
                 <Label htmlFor="ssl-enabled" className="text-sm">
                   SSL habilitado
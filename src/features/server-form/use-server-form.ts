@@ -17,8 +17,9 @@ const emptyForm: ServerInput = {
   username: 'postgres',
   password: '',
   defaultDatabase: DEFAULT_DATABASES.postgres,
-  sslEnabled: false,
+  tls: null,
   connectionUri: null,
+  sshTunnel: null,
 };
 
 export const useServerForm = ({ open, onClose, server }: ServerFormProps) => {
@@ -44,8 +45,9 @@ export const useServerForm = ({ open, onClose, server }: ServerFormProps) => {
         username: server.username,
         password: '', // never comes back from the backend — must be retyped
         defaultDatabase: server.defaultDatabase,
-        sslEnabled: server.sslEnabled,
+        tls: server.tls,
         connectionUri: server.connectionUri,
+        sshTunnel: server.sshTunnel,
       });
     } else {
       setForm(emptyForm);